@@ -0,0 +1,168 @@
+//! Throughput benchmarks for the digests, and for the `Hasher` read loop
+//! and `Background` threads which feed them.
+//!
+//! Run them with `cargo bench`. To check a change for regressions, save a
+//! baseline before making it, then compare against that baseline after:
+//!
+//! ```text
+//! cargo bench --bench throughput -- --save-baseline before
+//! cargo bench --bench throughput -- --baseline before
+//! ```
+//!
+//! Criterion reports any significant change in throughput from the
+//! baseline. Baselines are kept under `target/criterion`, so they are only
+//! comparable when taken on the same machine, such as a CI runner which
+//! benchmarks both the base and the head of a change. Passing `--test`
+//! instead runs each benchmark just once, to check that they all work.
+
+use std::hint::black_box;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, Throughput,
+};
+use digest::{digest, generator, DigestKind, Hasher};
+
+/// The amount of data digested by each iteration of a benchmark.
+const DATA_LEN: usize = 0x100_0000;
+
+/// The digests computed together to measure the overhead of computing more
+/// than one digest of the same data.
+const MULTIPLE: [DigestKind; 4] = [
+    DigestKind::MD5,
+    DigestKind::SHA256,
+    DigestKind::SHA512,
+    DigestKind::CRC32,
+];
+
+/// Generate `len` bytes of pseudo-random data, which is the same for every
+/// run so that the results are comparable.
+fn data(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+/// The throughput of each kind of digest computed in the current thread,
+/// and in a `Background` thread fed by a `Generator`.
+fn algorithms(c: &mut Criterion) {
+    let data = data(DATA_LEN);
+    let chunks: Vec<Arc<[u8]>> = data
+        .chunks(Hasher::DEFAULT_BUFFER_SIZE)
+        .map(Arc::from)
+        .collect();
+
+    let mut group = c.benchmark_group("algorithm");
+    group.throughput(Throughput::Bytes(DATA_LEN as u64));
+    for kind in DigestKind::all() {
+        if digest(kind, &[]).is_none() {
+            continue;
+        }
+        group.bench_function(BenchmarkId::new("inline", kind.name()), |b| {
+            b.iter(|| digest(kind, black_box(&data)));
+        });
+        let generator = generator(kind).expect("the digest is available");
+        group.bench_function(
+            BenchmarkId::new("background", kind.name()),
+            |b| {
+                b.iter(|| {
+                    for chunk in &chunks {
+                        generator.append(Arc::clone(chunk));
+                    }
+                    generator.result()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The cost of computing several digests of the same data with a `Hasher`,
+/// compared with computing just one, with and without background threads.
+fn multiple(c: &mut Criterion) {
+    let data = data(DATA_LEN);
+    let mut group = c.benchmark_group("multiple");
+    group.throughput(Throughput::Bytes(DATA_LEN as u64));
+    for count in 1..=MULTIPLE.len() {
+        for background in [true, false] {
+            let hasher = Hasher::builder()
+                .algorithms(&MULTIPLE[..count])
+                .background(background)
+                .build()
+                .expect("the digests are available");
+            let mode = if background { "background" } else { "inline" };
+            group.bench_with_input(
+                BenchmarkId::new(mode, count),
+                &hasher,
+                |b, hasher| {
+                    b.iter(|| hasher.hash_reader(black_box(&data[..])));
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+/// The effect of the size of the buffer the `Hasher` reads into on its
+/// throughput.
+fn buffer_size(c: &mut Criterion) {
+    let data = data(DATA_LEN);
+    let mut group = c.benchmark_group("buffer_size");
+    group.throughput(Throughput::Bytes(DATA_LEN as u64));
+    for shift in (12..=22).step_by(2) {
+        let size = 1usize << shift;
+        let hasher = Hasher::builder()
+            .algorithms(&MULTIPLE)
+            .buffer_size(size)
+            .build()
+            .expect("the digests are available");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &hasher,
+            |b, hasher| {
+                b.iter(|| hasher.hash_reader(black_box(&data[..])));
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The overhead of digesting many small files, which is dominated by the
+/// cost of setting up each digest rather than of computing it.
+fn small_inputs(c: &mut Criterion) {
+    let data = data(0x1000);
+    let hasher = Hasher::builder()
+        .algorithms(&MULTIPLE)
+        .build()
+        .expect("the digests are available");
+    let mut group = c.benchmark_group("small_inputs");
+    for len in [0, 0x40, 0x400, 0x1000] {
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(len),
+            &data[..len],
+            |b, data| b.iter(|| hasher.hash_reader(black_box(data))),
+        );
+    }
+    group.bench_function("empty reader", |b| {
+        b.iter(|| hasher.hash_reader(io::empty()));
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3));
+    targets = algorithms, multiple, buffer_size, small_inputs
+}
+criterion_main!(benches);
@@ -0,0 +1,167 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+
+use crate::hash::generators;
+use crate::{DigestData, DigestKind, Generator, Hasher};
+
+/// Compute each of the `kinds` of digest of all the data read from the
+/// async `input`, returning them in the same order. As with
+/// `hash_reader()`, the digests are computed in background threads, so
+/// the task only waits on the `input`.
+///
+/// ## Errors
+///
+/// Any error reading the `input` is returned. An error of kind
+/// `Unsupported` is returned if any of the digests is unavailable.
+pub async fn hash_async_read<R: AsyncRead + Unpin>(
+    mut input: R,
+    kinds: &[DigestKind],
+) -> io::Result<Vec<DigestData>> {
+    let generators = generators(kinds)?;
+    let mut buffer = vec![0u8; Hasher::DEFAULT_BUFFER_SIZE];
+    loop {
+        let count = match input.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let data: Arc<[u8]> = Arc::from(&buffer[..count]);
+        for generator in &generators {
+            generator.try_append(Arc::clone(&data))?;
+        }
+    }
+    let digests = generators
+        .iter()
+        .map(|generator| generator.try_result())
+        .collect::<Result<_, _>>()?;
+    Ok(digests)
+}
+
+/// An async reader which passes on everything read from an inner reader,
+/// while appending the same data to one or more `Generator`s, in the same
+/// way as `HashReader`.
+///
+/// Appending data only blocks if a generator has fallen several reads
+/// behind, so the stream can be polled directly from an async task.
+pub struct HashStream<R> {
+    inner: R,
+    generators: Vec<Box<dyn Generator>>,
+}
+
+impl<R: AsyncRead + Unpin> HashStream<R> {
+    /// Create a new `HashStream`, which reads from `inner` and appends the
+    /// data read to each of the `generators`.
+    pub fn new(inner: R, generators: Vec<Box<dyn Generator>>) -> Self {
+        Self { inner, generators }
+    }
+
+    /// A reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Retrieve the digests of the data read so far, in the same order as
+    /// the generators were given. The generators are reset, so further
+    /// reads start new digests.
+    #[must_use]
+    pub fn result(&self) -> Vec<DigestData> {
+        self.generators
+            .iter()
+            .map(|generator| generator.result())
+            .collect()
+    }
+
+    /// Return the inner reader, along with the digests of all the data
+    /// read from it.
+    #[must_use]
+    pub fn finish(self) -> (R, Vec<DigestData>) {
+        let digests = self.result();
+        (self.inner, digests)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashStream<R> {
+    /// Read into `buf` from the inner reader, and append the data read to
+    /// each of the generators.
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        let read = &buf.filled()[filled..];
+        if !read.is_empty() {
+            let data: Arc<[u8]> = Arc::from(read);
+            for generator in &self.generators {
+                generator.try_append(Arc::clone(&data))?;
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::future::Future;
+    use std::task::{Wake, Waker};
+    use std::thread::{self, Thread};
+
+    /// Wakes the test thread, which is parked while a future is pending.
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Run the `future` to completion on the test thread, as the tests
+    /// don't need a full runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn hash_async() {
+        let kinds = [DigestKind::MD5, DigestKind::CRC32];
+        let input = &fixtures::RANDOM_11171[..];
+        assert_eq!(
+            block_on(hash_async_read(input, &kinds)).unwrap(),
+            [
+                DigestData::MD5(fixtures::md5::RANDOM_11171),
+                DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_stream() {
+        let input = &fixtures::RANDOM_11171[..];
+        let mut stream = HashStream::new(input, vec![crate::sha256()]);
+        let mut data = Vec::new();
+        block_on(stream.read_to_end(&mut data)).unwrap();
+        assert_eq!(data, fixtures::RANDOM_11171);
+        let (rest, digests) = stream.finish();
+        assert!(rest.is_empty());
+        assert_eq!(
+            digests,
+            [DigestData::SHA256(fixtures::sha256::RANDOM_11171)]
+        );
+    }
+}
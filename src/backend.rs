@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::inline::{boxed, inline_generator, InlineGenerator};
+use crate::rust::{md2, md4, md5, sha256};
+use crate::{generator, DigestData, DigestKind, Generator};
+
+/// A source of `Generator`s, which can be selected at run time, such as
+/// with `checksum --backend`. Each backend only implements some kinds of
+/// digest, so `generator_with_fallback()` can be used to fall back to the
+/// other backends for the rest.
+pub trait Backend: fmt::Debug + Send + Sync {
+    /// The name of the backend, as accepted by `backend_named()`.
+    fn name(&self) -> &'static str;
+
+    /// Create a generator for the `kind` of digest, or `None` if the
+    /// backend doesn't implement it, or it is unavailable.
+    fn generator(&self, kind: DigestKind) -> Option<Box<dyn Generator>>;
+
+    /// Create a generator for the `kind` of digest using this backend, or
+    /// else using the first of the other `BACKENDS` which can.
+    fn generator_with_fallback(
+        &self,
+        kind: DigestKind,
+    ) -> Option<Box<dyn Generator>> {
+        self.generator(kind).or_else(|| {
+            BACKENDS.iter().find_map(|backend| backend.generator(kind))
+        })
+    }
+
+    /// Create a generator for the `kind` of digest which is computed in
+    /// the thread which appends the data to it, rather than in a background
+    /// thread, or `None` if the backend doesn't implement it, or it is
+    /// unavailable. By default no digests can be computed inline.
+    fn inline_generator(
+        &self,
+        kind: DigestKind,
+    ) -> Option<Box<dyn Generator>> {
+        let _ = kind;
+        None
+    }
+
+    /// Create an inline generator for the `kind` of digest using this
+    /// backend, or else using the first of the other `BACKENDS` which can.
+    fn inline_generator_with_fallback(
+        &self,
+        kind: DigestKind,
+    ) -> Option<Box<dyn Generator>> {
+        self.inline_generator(kind).or_else(|| {
+            BACKENDS
+                .iter()
+                .find_map(|backend| backend.inline_generator(kind))
+        })
+    }
+}
+
+/// The digests computed by the system's C libraries, which is OpenSSL
+/// along with libgcrypt for Streebog and Tiger, if the `gcrypt` feature is
+/// enabled. CRC32 isn't taken from a C library, and is computed in Rust
+/// as it is by the `Rust` backend.
+#[derive(Debug)]
+pub struct OpenSSL;
+
+impl Backend for OpenSSL {
+    fn name(&self) -> &'static str {
+        "openssl"
+    }
+
+    fn generator(&self, kind: DigestKind) -> Option<Box<dyn Generator>> {
+        match kind {
+            DigestKind::CRC16_CCITT
+            | DigestKind::CRC16_MODBUS
+            | DigestKind::CRC16_XMODEM
+            | DigestKind::CRC64
+            | DigestKind::XXH32
+            | DigestKind::XXH64
+            | DigestKind::RAPIDHASH
+            | DigestKind::KECCAK256
+            | DigestKind::RMD256
+            | DigestKind::RMD320 => None,
+            kind => generator(kind),
+        }
+    }
+
+    fn inline_generator(
+        &self,
+        kind: DigestKind,
+    ) -> Option<Box<dyn Generator>> {
+        // The same digests are computed inline as in the background.
+        match kind {
+            DigestKind::CRC16_CCITT
+            | DigestKind::CRC16_MODBUS
+            | DigestKind::CRC16_XMODEM
+            | DigestKind::CRC64
+            | DigestKind::XXH32
+            | DigestKind::XXH64
+            | DigestKind::RAPIDHASH
+            | DigestKind::KECCAK256
+            | DigestKind::RMD256
+            | DigestKind::RMD320 => None,
+            kind => inline_generator(kind),
+        }
+    }
+}
+
+/// The digests implemented in Rust by this crate, which need no system
+/// libraries.
+#[derive(Debug)]
+pub struct Rust;
+
+impl Backend for Rust {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn generator(&self, kind: DigestKind) -> Option<Box<dyn Generator>> {
+        let generator: Box<dyn Generator> = match kind {
+            DigestKind::MD2 => Box::new(md2::BackgroundMD2::new()),
+            DigestKind::MD4 => Box::new(md4::BackgroundMD4::new()),
+            DigestKind::MD5 => Box::new(md5::BackgroundMD5::new()),
+            DigestKind::SHA256 => Box::new(sha256::BackgroundSHA256::new()),
+            DigestKind::CRC16_CCITT
+            | DigestKind::CRC16_MODBUS
+            | DigestKind::CRC16_XMODEM
+            | DigestKind::CRC64
+            | DigestKind::XXH32
+            | DigestKind::XXH64
+            | DigestKind::RAPIDHASH
+            | DigestKind::KECCAK256
+            | DigestKind::RMD256
+            | DigestKind::RMD320 => return generator(kind),
+            _ => return None,
+        };
+        Some(generator)
+    }
+
+    fn inline_generator(
+        &self,
+        kind: DigestKind,
+    ) -> Option<Box<dyn Generator>> {
+        let digest = match kind {
+            DigestKind::MD2 => boxed(md2::MD2::new(), DigestData::MD2),
+            DigestKind::MD4 => boxed(md4::MD4::new(), DigestData::MD4),
+            DigestKind::MD5 => boxed(md5::MD5::new(), DigestData::MD5),
+            DigestKind::SHA256 => {
+                boxed(sha256::SHA256::new(), DigestData::SHA256)
+            }
+            DigestKind::CRC16_CCITT
+            | DigestKind::CRC16_MODBUS
+            | DigestKind::CRC16_XMODEM
+            | DigestKind::CRC64
+            | DigestKind::XXH32
+            | DigestKind::XXH64
+            | DigestKind::RAPIDHASH
+            | DigestKind::KECCAK256
+            | DigestKind::RMD256
+            | DigestKind::RMD320 => return inline_generator(kind),
+            _ => return None,
+        };
+        Some(InlineGenerator::boxed(kind, digest))
+    }
+}
+
+/// Every backend, with the default first.
+pub static BACKENDS: [&dyn Backend; 2] = [&OpenSSL, &Rust];
+
+/// The backend with the given `name`, as returned by `Backend::name()`.
+#[must_use]
+pub fn backend_named(name: &str) -> Option<&'static dyn Backend> {
+    BACKENDS
+        .iter()
+        .find(|backend| backend.name() == name)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{digest, fixtures, DigestData};
+    use std::sync::Arc;
+
+    #[test]
+    fn backend_names() {
+        for backend in BACKENDS {
+            assert_eq!(
+                backend_named(backend.name()).unwrap().name(),
+                backend.name()
+            );
+        }
+        assert!(backend_named("OpenSSL").is_none());
+    }
+
+    #[test]
+    fn backend_generators() {
+        for kind in DigestKind::all() {
+            let mut implemented = false;
+            for backend in BACKENDS {
+                let Some(generator) = backend.generator(kind) else {
+                    continue;
+                };
+                implemented = true;
+                assert_eq!(generator.name(), kind.name());
+                generator.append(Arc::from(&fixtures::RANDOM_11171[..]));
+                if let Some(expected) = digest(kind, &fixtures::RANDOM_11171)
+                {
+                    assert_eq!(generator.result(), expected);
+                }
+            }
+            // Only the digests which OpenSSL may lack are missing from
+            // both backends.
+            assert!(
+                implemented
+                    || matches!(
+                        kind,
+                        DigestKind::STREEBOG256
+                            | DigestKind::STREEBOG512
+                            | DigestKind::TIGER
+                            | DigestKind::TIGER2
+                    ),
+                "{kind:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn backend_fallback() {
+        let rust = Rust.generator(DigestKind::MD2).unwrap();
+        assert_eq!(rust.result(), DigestData::MD2(fixtures::md2::EMPTY));
+        let md2 = OpenSSL.generator_with_fallback(DigestKind::MD2).unwrap();
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::EMPTY));
+
+        assert!(Rust.generator(DigestKind::SHA512).is_none());
+        let sha512 =
+            Rust.generator_with_fallback(DigestKind::SHA512).unwrap();
+        assert_eq!(
+            sha512.result(),
+            DigestData::SHA512(fixtures::sha512::EMPTY)
+        );
+    }
+
+    #[test]
+    fn backend_inline_generators() {
+        for kind in DigestKind::all() {
+            for backend in BACKENDS {
+                // Each digest is computed inline by the same backends as
+                // in the background.
+                let Some(generator) = backend.inline_generator(kind) else {
+                    assert!(backend.generator(kind).is_none(), "{kind:?}");
+                    continue;
+                };
+                assert!(backend.generator(kind).is_some(), "{kind:?}");
+                assert_eq!(generator.name(), kind.name());
+                generator.append(Arc::from(&fixtures::RANDOM_11171[..]));
+                if let Some(expected) = digest(kind, &fixtures::RANDOM_11171)
+                {
+                    assert_eq!(generator.result(), expected);
+                }
+            }
+        }
+        let md2 = OpenSSL
+            .inline_generator_with_fallback(DigestKind::MD2)
+            .unwrap();
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::EMPTY));
+    }
+}
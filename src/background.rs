@@ -1,7 +1,13 @@
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
 
-use crate::Digest;
+use crate::{Digest, Error, Hasher};
+
+/// How long to wait for the digest thread to respond, once all the data
+/// passed to it has been processed.
+const TIMEOUT: Duration = Duration::from_secs(5);
 
 /// An interface to compute a digest in a background thread.
 ///
@@ -13,18 +19,13 @@ use crate::Digest;
 /// trait, but the `data` passed to `update()` needs to be an `Arc<[u8]>`
 /// in order to safely share it across thread boundaries.
 pub struct Background<const N: usize> {
-    tx_input: mpsc::SyncSender<Message>,
-    rx_result: mpsc::Receiver<[u8; N]>,
+    tx_input: mpsc::SyncSender<Message<N>>,
+    /// The receiver for digests is locked while waiting for one, so that
+    /// a `Background` can be shared between threads.
+    results: Mutex<Results<N>>,
+    thread: Option<thread::JoinHandle<()>>,
 }
 
-/// The `DigestConstructor` type describes a function which can be used to
-/// create an object which implements the `Digest` trait.
-///
-/// Note that the type constraint `Digest<L>` will not actually be applied
-/// at compilation time due to current compiler limitations.
-#[allow(type_alias_bounds)]
-type DigestConstructor<D: Digest<N>, const N: usize> = fn() -> D;
-
 impl<const N: usize> Background<N> {
     /// The length of the digest, in bytes.
     #[must_use]
@@ -33,20 +34,29 @@ impl<const N: usize> Background<N> {
     }
 
     /// Create a new `Background` object. The `digest` function will be
-    /// used to create a new `Digest` implementor in a separate thread.
-    pub fn new<D: Digest<N> + 'static>(
-        digest: DigestConstructor<D, N>,
-    ) -> Self {
-        let (tx_input, rx_input) = mpsc::sync_channel(4);
+    /// used to create a new `Digest` implementor in a separate thread. It
+    /// may be a plain constructor such as `MD5::new`, or a closure which
+    /// captures any parameters the `Digest` needs.
+    pub fn new<D, F>(digest: F) -> Self
+    where
+        D: Digest<N> + Clone + Send + 'static,
+        F: FnOnce() -> D + Send + 'static,
+    {
+        let (tx_input, rx_input) =
+            mpsc::sync_channel(Hasher::DEFAULT_QUEUE_DEPTH);
         let (tx_result, rx_result) = mpsc::channel();
 
-        std::thread::spawn(move || {
+        let thread = thread::spawn(move || {
             Self::background(digest, &rx_input, &tx_result);
         });
 
         Self {
             tx_input,
-            rx_result,
+            results: Mutex::new(Results {
+                rx_result,
+                requested: false,
+            }),
+            thread: Some(thread),
         }
     }
 
@@ -56,10 +66,61 @@ impl<const N: usize> Background<N> {
     /// The channel used to communicate with the digest thread is limited
     /// to 4 entries, so it is possible that this method will block if
     /// that queue becomes saturated.
-    pub fn update(&self, data: Arc<[u8]>) {
-        self.tx_input
-            .send(Message::Append(data))
-            .expect("unexpected error appending to digest");
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest thread has
+    /// unexpectedly exited.
+    pub fn update(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.send(Message::Append(data))
+    }
+
+    /// Discard any data passed to the encapsulated `Digest` object, so
+    /// that the next call to `update()` starts a new digest. Unlike
+    /// `finish()`, this doesn't wait for the digest thread. If the digest
+    /// thread has exited then there is nothing to discard, and the next
+    /// call to `update()` or `finish()` reports the error.
+    pub fn reset(&self) {
+        let _ = self.send(Message::Reset);
+    }
+
+    /// Create a new `Background` object with a copy of the current state
+    /// of the encapsulated `Digest` object, running in its own thread.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest thread has
+    /// unexpectedly exited, or if the copy is not returned within 5
+    /// seconds.
+    pub fn fork(&self) -> Result<Self, Error> {
+        let (tx_fork, rx_fork) = mpsc::channel();
+        self.send(Message::Fork(tx_fork))?;
+        rx_fork
+            .recv_timeout(TIMEOUT)
+            .map_err(|_err| Error::WorkerFailed)
+    }
+
+    /// Create a new `Background` object, with a `Digest` implementor
+    /// which is resumed from the `state` exported by `export_state()`.
+    /// `None` is returned if the `state` can't be imported.
+    #[must_use]
+    pub fn import_state<D>(state: &[u8]) -> Option<Self>
+    where
+        D: Digest<N> + Clone + Send + 'static,
+    {
+        let digest = D::import_state(state)?;
+        Some(Self::new(move || digest))
+    }
+
+    /// Export the current state of the encapsulated `Digest` object, as
+    /// described for `Digest::export_state()`. `None` is also returned if
+    /// the digest thread has unexpectedly exited, or if the state is not
+    /// returned within 5 seconds.
+    #[must_use]
+    pub fn export_state(&self) -> Option<Vec<u8>> {
+        let (tx_state, rx_state) = mpsc::channel();
+        self.send(Message::Export(tx_state)).ok()?;
+        rx_state.recv_timeout(TIMEOUT).ok().flatten()
     }
 
     /// Get digest data back from the encapsulated `Digest` object.
@@ -67,49 +128,160 @@ impl<const N: usize> Background<N> {
     /// Note that the the threads use `std::mpsc` channels to communicate.
     /// Consequently, it is possible that this method will block if work
     /// remains to be perfomed in the thread computing the digest.
-    pub fn finish(&self) -> [u8; N] {
-        use std::time::Duration;
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest thread has
+    /// unexpectedly exited, and `Error::TimedOut` if the digest value is
+    /// not returned within 5 seconds.
+    pub fn finish(&self) -> Result<[u8; N], Error> {
+        self.finish_timeout(TIMEOUT)
+    }
 
-        self.tx_input
-            .send(Message::Finish)
-            .expect("unexpected error finishing digest");
+    /// Get digest data back from the encapsulated `Digest` object, as for
+    /// `finish()`, waiting no longer than `timeout` for it.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest thread has
+    /// unexpectedly exited, and `Error::TimedOut` if the digest value is
+    /// not returned within the `timeout`. The digest is still computed
+    /// after a timeout, and is returned by the next call to `finish()`,
+    /// `finish_timeout()`, or `try_finish()`.
+    pub fn finish_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<[u8; N], Error> {
+        let mut results = self.request_finish()?;
+        match results.rx_result.recv_timeout(timeout) {
+            Ok(digest) => {
+                results.requested = false;
+                Ok(digest)
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::TimedOut),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(Error::WorkerFailed)
+            }
+        }
+    }
+
+    /// Get digest data back from the encapsulated `Digest` object, if the
+    /// thread computing the digest has caught up, without waiting for it.
+    /// If `None` is returned then the digest is still being computed, and
+    /// this can be called again later to poll for it. Any data passed to
+    /// `update()` in the meantime is part of the next digest.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest thread has
+    /// unexpectedly exited.
+    pub fn try_finish(&self) -> Result<Option<[u8; N]>, Error> {
+        let mut results = self.request_finish()?;
+        match results.rx_result.try_recv() {
+            Ok(digest) => {
+                results.requested = false;
+                Ok(Some(digest))
+            }
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(Error::WorkerFailed),
+        }
+    }
+
+    /// Ask the digest thread for the digest value, unless it has already
+    /// been asked for and not yet received, returning the locked results.
+    fn request_finish(&self) -> Result<MutexGuard<'_, Results<N>>, Error> {
+        let mut results =
+            self.results.lock().unwrap_or_else(PoisonError::into_inner);
+        if !results.requested {
+            self.send(Message::Finish)?;
+            results.requested = true;
+        }
+        Ok(results)
+    }
 
-        let timeout = Duration::new(5, 0);
-        self.rx_result
-            .recv_timeout(timeout)
-            .expect("unable to retrieve digest value")
+    /// Send a `message` to the digest thread, which fails if the thread
+    /// has exited.
+    fn send(&self, message: Message<N>) -> Result<(), Error> {
+        self.tx_input
+            .send(message)
+            .map_err(|_err| Error::WorkerFailed)
     }
 
     /// The function to run in a separate thread. It will use the passed
-    /// `DigestConstructor` to create a `Digest` implementor which is then
+    /// `constructor` to create a `Digest` implementor which is then
     /// used to compute a digest with data passed to it using the
     /// `Background::update()` method. The computed data is then sent back
     /// to the calling thread when `Background::finish()` is called.
-    fn background<D: Digest<N>>(
-        constructor: DigestConstructor<D, N>,
-        rx_input: &mpsc::Receiver<Message>,
+    fn background<D: Digest<N> + Clone + Send + 'static>(
+        constructor: impl FnOnce() -> D,
+        rx_input: &mpsc::Receiver<Message<N>>,
+        tx_result: &mpsc::Sender<[u8; N]>,
+    ) {
+        Self::run(constructor(), rx_input, tx_result);
+    }
+
+    /// Compute a digest using the `worker`, as described for
+    /// `background()`. This is kept separate from the `constructor` so
+    /// that a forked `worker` can be run in the same way.
+    fn run<D: Digest<N> + Clone + Send + 'static>(
+        mut worker: D,
+        rx_input: &mpsc::Receiver<Message<N>>,
         tx_result: &mpsc::Sender<[u8; N]>,
     ) {
-        let mut worker = constructor();
         loop {
             let msg = rx_input.recv();
 
             match msg {
                 Ok(Message::Append(data)) => worker.update(&data),
                 Ok(Message::Finish) => {
-                    tx_result.send(worker.finish()).unwrap();
+                    if tx_result.send(worker.finish()).is_err() {
+                        break;
+                    }
                 }
-                Err(_) => break,
+                Ok(Message::Reset) => {
+                    let _ = worker.finish();
+                }
+                Ok(Message::Fork(tx_fork)) => {
+                    let forked = worker.fork();
+                    let _ = tx_fork.send(Background::new(move || forked));
+                }
+                Ok(Message::Export(tx_state)) => {
+                    let _ = tx_state.send(worker.export_state());
+                }
+                Ok(Message::Exit) | Err(_) => break,
             }
         }
     }
 }
 
+impl<const N: usize> Drop for Background<N> {
+    /// Stop the thread computing the digest, and wait for it to exit. This
+    /// ensures that the `Digest` implementor has been cleaned up before
+    /// the `Background` object goes away.
+    fn drop(&mut self) {
+        let _ = self.tx_input.send(Message::Exit);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The receiving end for digest values, along with whether one has been
+/// asked for which hasn't been received yet.
+struct Results<const N: usize> {
+    rx_result: mpsc::Receiver<[u8; N]>,
+    requested: bool,
+}
+
 /// An internal `enum` used to communicate between the caller's thread and
 /// the thread the `Digest` implementor is running in.
-enum Message {
+enum Message<const N: usize> {
     Append(Arc<[u8]>),
     Finish,
+    Reset,
+    Fork(mpsc::Sender<Background<N>>),
+    Export(mpsc::Sender<Option<Vec<u8>>>),
+    Exit,
 }
 
 #[cfg(test)]
@@ -121,31 +293,108 @@ mod test {
     #[test]
     fn background_count_empty() {
         let bg = Background::new(Count::new);
-        assert_eq!(bg.finish(), fixtures::count::EMPTY);
+        assert_eq!(bg.finish().unwrap(), fixtures::count::EMPTY);
     }
 
     #[test]
     fn background_count_zero() {
         let bg = Background::new(Count::new);
-        bg.update(Arc::from([0; 0x4000]));
-        bg.update(Arc::from([0; 0x0d]));
-        assert_eq!(bg.finish(), fixtures::count::ZERO_400D);
+        bg.update(Arc::from([0; 0x4000])).unwrap();
+        bg.update(Arc::from([0; 0x0d])).unwrap();
+        assert_eq!(bg.finish().unwrap(), fixtures::count::ZERO_400D);
     }
 
     #[test]
     fn background_xor_random() {
         let bg = Background::new(XOR::new);
-        bg.update(Arc::from(fixtures::RANDOM_11171));
-        assert_eq!(bg.finish(), fixtures::xor::RANDOM_11171);
+        bg.update(Arc::from(fixtures::RANDOM_11171)).unwrap();
+        assert_eq!(bg.finish().unwrap(), fixtures::xor::RANDOM_11171);
     }
 
     #[test]
     fn background_count_multiple() {
         let bg = Background::new(Count::new);
-        assert_eq!(bg.finish(), fixtures::count::EMPTY);
-        bg.update(Arc::from(fixtures::ZERO_400D));
-        assert_eq!(bg.finish(), fixtures::count::ZERO_400D);
-        bg.update(Arc::from(fixtures::RANDOM_11171));
-        assert_eq!(bg.finish(), fixtures::count::RANDOM_11171);
+        assert_eq!(bg.finish().unwrap(), fixtures::count::EMPTY);
+        bg.update(Arc::from(fixtures::ZERO_400D)).unwrap();
+        assert_eq!(bg.finish().unwrap(), fixtures::count::ZERO_400D);
+        bg.update(Arc::from(fixtures::RANDOM_11171)).unwrap();
+        assert_eq!(bg.finish().unwrap(), fixtures::count::RANDOM_11171);
+    }
+
+    #[test]
+    fn background_count_fork() {
+        let bg = Background::new(Count::new);
+        bg.update(Arc::from(fixtures::ZERO_400D)).unwrap();
+        let forked = bg.fork().unwrap();
+        bg.update(Arc::from([0; 3])).unwrap();
+        forked.update(Arc::from([0; 5])).unwrap();
+        assert_eq!(bg.finish().unwrap(), [0x10]);
+        assert_eq!(forked.finish().unwrap(), [0x12]);
+    }
+
+    #[test]
+    fn background_count_state() {
+        let bg = Background::new(Count::new);
+        bg.update(Arc::from(fixtures::ZERO_400D)).unwrap();
+        let state = bg.export_state().unwrap();
+        assert_eq!(state, fixtures::count::ZERO_400D);
+        let resumed = Background::<1>::import_state::<Count>(&state).unwrap();
+        resumed.update(Arc::from([0; 3])).unwrap();
+        assert_eq!(resumed.finish().unwrap(), [0x10]);
+        assert!(Background::<1>::import_state::<Count>(&[]).is_none());
+
+        let bg = Background::new(XOR::new);
+        assert_eq!(bg.export_state(), None);
+    }
+
+    #[test]
+    fn background_failed() {
+        let bg = Background::<1>::new(|| -> Count {
+            panic!("unable to create digest");
+        });
+        assert!(matches!(bg.finish(), Err(Error::WorkerFailed)));
+        assert!(matches!(bg.try_finish(), Err(Error::WorkerFailed)));
+        assert!(matches!(
+            bg.update(Arc::from([0; 3])),
+            Err(Error::WorkerFailed)
+        ));
+        assert!(matches!(bg.fork(), Err(Error::WorkerFailed)));
+        assert_eq!(bg.export_state(), None);
+    }
+
+    #[test]
+    fn background_try_finish() {
+        let bg = Background::new(|| {
+            thread::sleep(Duration::from_millis(200));
+            Count::new()
+        });
+        bg.update(Arc::from(fixtures::ZERO_400D)).unwrap();
+        assert_eq!(bg.try_finish().unwrap(), None);
+        assert!(matches!(
+            bg.finish_timeout(Duration::from_millis(1)),
+            Err(Error::TimedOut)
+        ));
+        // Data appended while the digest is pending starts the next one.
+        bg.update(Arc::from([0; 3])).unwrap();
+        let digest = loop {
+            if let Some(digest) = bg.try_finish().unwrap() {
+                break digest;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(digest, fixtures::count::ZERO_400D);
+        assert_eq!(bg.finish().unwrap(), [3]);
+    }
+
+    #[test]
+    fn background_xor_reset() {
+        let bg = Background::new(XOR::new);
+        bg.update(Arc::from(fixtures::ZERO_400D)).unwrap();
+        bg.update(Arc::from([0xa5])).unwrap();
+        bg.reset();
+        bg.update(Arc::from(fixtures::RANDOM_11171)).unwrap();
+        assert_eq!(bg.finish().unwrap(), fixtures::xor::RANDOM_11171);
+        bg.reset();
+        assert_eq!(bg.finish().unwrap(), fixtures::xor::EMPTY);
     }
 }
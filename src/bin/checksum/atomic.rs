@@ -0,0 +1,138 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A file which is written to a temporary file alongside it, and only
+/// renamed into place once `commit()` is called. If the `AtomicFile` is
+/// dropped without being committed then the temporary file is removed, and
+/// any existing file is left untouched.
+pub struct AtomicFile {
+    /// The path the file is renamed to when committed.
+    path: PathBuf,
+    /// The path of the temporary file being written.
+    temp: PathBuf,
+    /// The temporary file, until it has been committed.
+    file: Option<io::BufWriter<fs::File>>,
+}
+
+impl AtomicFile {
+    /// Create a new `AtomicFile` which will be renamed to `path` when
+    /// committed.
+    ///
+    /// ## Errors
+    ///
+    /// Any error creating the temporary file is returned.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let temp = temp_path(path);
+        let file = fs::File::create(&temp)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            temp,
+            file: Some(io::BufWriter::new(file)),
+        })
+    }
+
+    /// Flush the temporary file to disk, and rename it into place.
+    ///
+    /// ## Errors
+    ///
+    /// Any error flushing or renaming the temporary file is returned, in
+    /// which case the temporary file is removed.
+    pub fn commit(mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            let file =
+                file.into_inner().map_err(io::IntoInnerError::into_error)?;
+            file.sync_all()?;
+            fs::rename(&self.temp, &self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieve the temporary file, which is only missing once committed.
+    fn file(&mut self) -> &mut io::BufWriter<fs::File> {
+        self.file
+            .as_mut()
+            .expect("the file has already been committed")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    /// Remove the temporary file, unless it has been renamed into place.
+    fn drop(&mut self) {
+        if self.temp.exists() {
+            let _ = fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// The temporary path used while writing `path`. It is placed in the same
+/// directory so that it can be renamed into place.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = format!(".{name}.{}.tmp", std::process::id());
+    path.with_file_name(temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A path in the temporary directory which is unique to the `test`.
+    fn test_path(test: &str) -> PathBuf {
+        let name = format!("checksum-{}-{test}", std::process::id());
+        std::env::temp_dir().join(name)
+    }
+
+    #[test]
+    fn commit() {
+        let path = test_path("commit");
+        fs::write(&path, "old").unwrap();
+
+        let mut file = AtomicFile::create(&path).unwrap();
+        file.write_all(b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        file.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn abandon() {
+        let path = test_path("abandon");
+        fs::write(&path, "old").unwrap();
+
+        let mut file = AtomicFile::create(&path).unwrap();
+        file.write_all(b"new").unwrap();
+        assert!(temp_path(&path).exists());
+        drop(file);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn temp_path_name() {
+        let pid = std::process::id();
+        assert_eq!(
+            temp_path(Path::new("dir/SHA256SUMS")),
+            PathBuf::from(format!("dir/.SHA256SUMS.{pid}.tmp"))
+        );
+        assert_eq!(
+            temp_path(Path::new("SUMS")),
+            PathBuf::from(format!(".SUMS.{pid}.tmp"))
+        );
+    }
+}
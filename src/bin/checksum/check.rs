@@ -0,0 +1,525 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use digest::manifest::{Entry, Format};
+use digest::{DigestData, Encoding};
+
+use crate::output::{quoted_string, timestamp};
+use crate::DigestKind;
+
+/// What is reported while checking a manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Report {
+    /// Report every file, and a summary of any failures.
+    All,
+    /// Only report the files which fail, and a summary of the failures
+    /// (`--quiet`).
+    Failures,
+    /// Report nothing other than errors reading files, relying on the exit
+    /// status (`--status`).
+    Status,
+}
+
+/// The result of verifying a file listed in a manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The file has the expected digest.
+    Ok,
+    /// The file has a different digest.
+    Mismatch,
+    /// The file doesn't exist.
+    Missing,
+    /// The file couldn't be opened or read.
+    ReadError,
+}
+
+impl Status {
+    /// The status as shown in the text report.
+    pub fn text(self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Mismatch => "FAILED",
+            Status::Missing | Status::ReadError => "FAILED open or read",
+        }
+    }
+
+    /// The status as shown in the JSON report.
+    pub fn json(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Mismatch => "mismatch",
+            Status::Missing => "missing",
+            Status::ReadError => "read-error",
+        }
+    }
+
+    /// The least restrictive report which shows a file with this status.
+    pub fn report(self) -> Report {
+        match self {
+            Status::Ok => Report::All,
+            _ => Report::Failures,
+        }
+    }
+}
+
+/// The number of files with each status, and the number of improperly
+/// formatted lines, seen while checking a manifest.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub ok: usize,
+    pub mismatch: usize,
+    pub missing: usize,
+    pub read_error: usize,
+    pub improper: usize,
+}
+
+impl Summary {
+    /// Count a file with the given `status`.
+    pub fn add(&mut self, status: Status) {
+        match status {
+            Status::Ok => self.ok += 1,
+            Status::Mismatch => self.mismatch += 1,
+            Status::Missing => self.missing += 1,
+            Status::ReadError => self.read_error += 1,
+        }
+    }
+
+    /// The number of files which were checked.
+    pub fn checked(&self) -> usize {
+        self.ok + self.mismatch + self.unreadable()
+    }
+
+    /// The number of files which couldn't be opened or read.
+    pub fn unreadable(&self) -> usize {
+        self.missing + self.read_error
+    }
+
+    /// The summary as a JSON object.
+    pub fn json(&self) -> String {
+        format!(
+            "{{\"ok\": {}, \"mismatch\": {}, \"missing\": {}, \
+             \"read-error\": {}, \"improper\": {}}}",
+            self.ok,
+            self.mismatch,
+            self.missing,
+            self.read_error,
+            self.improper
+        )
+    }
+}
+
+/// Format the result of verifying the file listed in `checksum` as a JSON
+/// object, giving its `status` and the `actual` digest computed for it.
+pub fn json_record(
+    checksum: &Checksum,
+    kind: DigestKind,
+    status: Status,
+    actual: Option<&DigestData>,
+) -> String {
+    let path = quoted_string(&checksum.path.to_string_lossy());
+    let name = kind.name();
+    let status = status.json();
+    let expected = Encoding::Hex.encode(&checksum.digest);
+    let actual = match actual {
+        Some(actual) => format!("\"{actual}\""),
+        None => String::from("null"),
+    };
+    format!(
+        "{{\"path\": {path}, \"algorithm\": \"{name}\", \
+         \"status\": \"{status}\", \"expected\": \"{expected}\", \
+         \"actual\": {actual}}}"
+    )
+}
+
+/// Format a JSON report from the `records` of each file, produced by
+/// `json_record()`, and the `summary`.
+pub fn json_report(records: &[String], summary: &Summary) -> String {
+    let mut report = String::from("{\n  \"files\": [");
+    for (index, record) in records.iter().enumerate() {
+        let separator = if index + 1 < records.len() { "," } else { "" };
+        write!(report, "\n    {record}{separator}").unwrap();
+    }
+    if !records.is_empty() {
+        report.push_str("\n  ");
+    }
+    write!(report, "],\n  \"summary\": {}\n}}", summary.json()).unwrap();
+    report
+}
+
+/// A line of a checksum manifest, giving the expected digest of a file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Checksum {
+    /// The kind of digest, if the line names it.
+    pub kind: Option<DigestKind>,
+    /// The expected digest.
+    pub digest: Vec<u8>,
+    /// The path of the file.
+    pub path: PathBuf,
+}
+
+impl Checksum {
+    /// Parse a `line` of a manifest, which is in any of the forms read by
+    /// `digest::manifest::parse()`: a `sha256sum` style line, a BSD style
+    /// line as output by `checksum`, or an SFV line. Any `size=` or
+    /// `mtime=` columns following a BSD style digest are ignored. If the
+    /// line is not in any of these forms then `None` is returned.
+    pub fn parse(line: &str) -> Option<Self> {
+        let entry = Entry::parse(line).ok()?;
+        Some(Checksum {
+            kind: entry.kind,
+            digest: entry.digest,
+            path: entry.path,
+        })
+    }
+
+    /// Check if the line should be verified when the `selected` kind of
+    /// digest is being checked. Lines which name a different kind of
+    /// digest are skipped.
+    pub fn is_selected(&self, selected: Option<DigestKind>) -> bool {
+        match (self.kind, selected) {
+            (Some(kind), Some(selected)) => kind == selected,
+            _ => true,
+        }
+    }
+
+    /// The kind of digest to compute for the file. This is the kind named
+    /// by the line, or otherwise the `selected` kind, or the default kind
+    /// for a digest of its length. If there is no such kind then `None` is
+    /// returned.
+    pub fn kind(&self, selected: Option<DigestKind>) -> Option<DigestKind> {
+        self.kind
+            .or(selected)
+            .or_else(|| default_kind(&self.digest))
+    }
+}
+
+/// A digest which a file is expected to have, given with `--expect`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Expected {
+    /// The kind of digest.
+    pub kind: DigestKind,
+    /// The expected digest.
+    pub digest: Vec<u8>,
+}
+
+/// The kind of digest `sha*sum` or `md5sum` would use for a `digest` of
+/// its length, if any. A 160 bit digest is taken to be RMD160, as it is
+/// the only 160 bit digest which is supported (SHA1 isn't), although a
+/// checksum option may be used to select another kind.
+pub fn default_kind(digest: &[u8]) -> Option<DigestKind> {
+    match digest.len() {
+        16 => Some(DigestKind::MD5),
+        20 => Some(DigestKind::RMD160),
+        28 => Some(DigestKind::SHA224),
+        32 => Some(DigestKind::SHA256),
+        48 => Some(DigestKind::SHA384),
+        64 => Some(DigestKind::SHA512),
+        _ => None,
+    }
+}
+
+/// Rewrite a manifest `line` with a new `digest`, writing it again in the
+/// same `manifest::Format` as it was in. The digest is written in upper
+/// case hexadecimal if the old one was, and the `*` of a `sha256sum` style
+/// line for a file read in binary mode is kept. Any `size=` or `mtime=`
+/// columns following a BSD style digest are rewritten with the `size` and
+/// `mtime` of the file now, or dropped if they aren't known. If the line
+/// isn't in any of the forms read by `Checksum::parse()` then `None` is
+/// returned.
+pub fn update_line(
+    line: &str,
+    digest: &[u8],
+    size: Option<u64>,
+    mtime: Option<SystemTime>,
+) -> Option<String> {
+    let (mut entry, format) = Entry::parse_with_format(line).ok()?;
+    let encoding = if line.contains(&Encoding::Hex.encode(&entry.digest)) {
+        Encoding::Hex
+    } else {
+        Encoding::UpperHex
+    };
+    let binary = format == Format::GNU
+        && line
+            .trim_start_matches('\\')
+            .split_once(' ')
+            .is_some_and(|(_, path)| path.starts_with('*'));
+    entry.digest = digest.to_vec();
+    entry.size = entry.size.and(size);
+    entry.mtime = entry.mtime.and(mtime.map(timestamp));
+    let updated = format.line(&entry, encoding).ok()?;
+    if binary {
+        Some(updated.replacen("  ", " *", 1))
+    } else {
+        Some(updated)
+    }
+}
+
+/// The `kind` of digest with the bytes of the `digest`. If the digest is
+/// the wrong length for the kind then `None` is returned.
+pub fn digest_data(kind: DigestKind, digest: &[u8]) -> Option<DigestData> {
+    DigestData::from_bytes(kind, digest).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use digest::DigestData;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn parse_checksum() {
+        let line = "d41d8cd98f00b204e9800998ecf8427e  foo bar";
+        assert_eq!(
+            Checksum::parse(line),
+            Some(Checksum {
+                kind: None,
+                digest: fixtures::md5::EMPTY.to_vec(),
+                path: PathBuf::from("foo bar"),
+            })
+        );
+        let line = "D41D8CD98F00B204E9800998ECF8427E *foo";
+        assert_eq!(
+            Checksum::parse(line).unwrap().digest,
+            fixtures::md5::EMPTY
+        );
+        let line = "\\d41d8cd98f00b204e9800998ecf8427e  a\\\\b\\nc";
+        assert_eq!(
+            Checksum::parse(line).unwrap().path,
+            PathBuf::from("a\\b\nc")
+        );
+    }
+
+    #[test]
+    fn update_lines() {
+        let md5 = fixtures::md5::EMPTY;
+        let hex = "d41d8cd98f00b204e9800998ecf8427e";
+        let old = "0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            update_line(&format!("{old}  foo bar"), &md5, None, None),
+            Some(format!("{hex}  foo bar"))
+        );
+        assert_eq!(
+            update_line(
+                &format!("{}  foo", old.to_uppercase()),
+                &md5,
+                None,
+                None
+            ),
+            Some(format!("{}  foo", hex.to_uppercase()))
+        );
+        assert_eq!(
+            update_line(&format!("\\{old} *foo\\nbar"), &md5, None, None),
+            Some(format!("\\{hex} *foo\\nbar"))
+        );
+        assert_eq!(
+            update_line(
+                &format!("MD5 (foo) = {old} size=0"),
+                &md5,
+                None,
+                None
+            ),
+            Some(format!("MD5 (foo) = {hex}"))
+        );
+        assert_eq!(update_line("foo", &md5, None, None), None);
+
+        // The size and mtime columns describe the file as it is now, and
+        // are only written if the line had them.
+        let mtime = UNIX_EPOCH + Duration::from_hours(24);
+        let line = format!("MD5 (foo) = {old} size=3 mtime=x");
+        assert_eq!(
+            update_line(&line, &md5, Some(0), Some(mtime)),
+            Some(format!(
+                "MD5 (foo) = {hex} size=0 mtime=1970-01-02T00:00:00Z"
+            ))
+        );
+        let line = format!("MD5 (foo) = {old} mtime=x");
+        assert_eq!(
+            update_line(&line, &md5, Some(0), Some(mtime)),
+            Some(format!("MD5 (foo) = {hex} mtime=1970-01-02T00:00:00Z"))
+        );
+        assert_eq!(
+            update_line(&format!("{old}  foo"), &md5, Some(0), Some(mtime)),
+            Some(format!("{hex}  foo"))
+        );
+
+        // SFV lines keep their layout, with the filename first.
+        let crc32 = fixtures::crc32::RANDOM_11171;
+        let line = "f E6C1C582";
+        let updated = update_line(line, &crc32, None, None).unwrap();
+        assert_eq!(updated, "f FF70A8EE");
+        let checksum = Checksum::parse(&updated).unwrap();
+        assert_eq!(checksum.path, PathBuf::from("f"));
+        assert_eq!(checksum.digest, crc32);
+        assert_eq!(
+            update_line("CRC32 (f) = e6c1c582", &crc32, None, None),
+            Some(String::from("CRC32 (f) = ff70a8ee"))
+        );
+    }
+
+    #[test]
+    fn parse_bsd_checksum() {
+        let line = "MD5 (foo (1)) = d41d8cd98f00b204e9800998ecf8427e";
+        assert_eq!(
+            Checksum::parse(line),
+            Some(Checksum {
+                kind: Some(DigestKind::MD5),
+                digest: fixtures::md5::EMPTY.to_vec(),
+                path: PathBuf::from("foo (1)"),
+            })
+        );
+        let line = "SHA512/224 (foo) = \
+            6ed0dd02806fa89e25de060c19d3ac86cabb87d6a0ddd05c333b84f4";
+        assert_eq!(
+            Checksum::parse(line).unwrap().kind,
+            Some(DigestKind::SHA512_224)
+        );
+        let line = "CRC32 (foo) = 00000000 size=0 mtime=2023-11-14T22:13:20Z";
+        assert_eq!(
+            Checksum::parse(line),
+            Some(Checksum {
+                kind: Some(DigestKind::CRC32),
+                digest: fixtures::crc32::EMPTY.to_vec(),
+                path: PathBuf::from("foo"),
+            })
+        );
+        let line = "\\CRC32 (a\\nb) = 00000000";
+        assert_eq!(
+            Checksum::parse(line).unwrap().path,
+            PathBuf::from("a\nb")
+        );
+    }
+
+    #[test]
+    fn checksum_selected() {
+        let line = "RMD160 (foo) = 0000000000000000000000000000000000000000";
+        let checksum = Checksum::parse(line).unwrap();
+        assert!(checksum.is_selected(None));
+        assert!(checksum.is_selected(Some(DigestKind::RMD160)));
+        assert!(!checksum.is_selected(Some(DigestKind::MD5)));
+        let line = "0000000000000000000000000000000000000000  foo";
+        let checksum = Checksum::parse(line).unwrap();
+        assert!(checksum.is_selected(None));
+        assert!(checksum.is_selected(Some(DigestKind::MD5)));
+    }
+
+    #[test]
+    fn parse_invalid_checksum() {
+        for line in [
+            "",
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "d41d8cd98f00b204e9800998ecf8427e  ",
+            "d41d8cd98f00b204e9800998ecf8427e foo",
+            "d41d8cd98f00b204e9800998ecf8427  foo",
+            "d41d8cd98f00b204e9800998ecf8427x  foo",
+            "  foo",
+            "\\d41d8cd98f00b204e9800998ecf8427e  a\\b",
+            "SHA1 (foo) = da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            "MD5 (foo) d41d8cd98f00b204e9800998ecf8427e",
+            "MD5 (foo) = d41d8cd98f00b204e9800998ecf8427e extra",
+            "MD5 = d41d8cd98f00b204e9800998ecf8427e",
+        ] {
+            assert_eq!(Checksum::parse(line), None, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn checksum_kind() {
+        let kind = |hex: &str, selected| {
+            let line = format!("{hex}  foo");
+            Checksum::parse(&line).unwrap().kind(selected)
+        };
+        assert_eq!(kind(&"00".repeat(16), None), Some(DigestKind::MD5));
+        assert_eq!(kind(&"00".repeat(28), None), Some(DigestKind::SHA224));
+        assert_eq!(kind(&"00".repeat(32), None), Some(DigestKind::SHA256));
+        assert_eq!(kind(&"00".repeat(48), None), Some(DigestKind::SHA384));
+        assert_eq!(kind(&"00".repeat(64), None), Some(DigestKind::SHA512));
+        assert_eq!(kind(&"00".repeat(20), None), Some(DigestKind::RMD160));
+        assert_eq!(kind(&"00".repeat(24), None), None);
+        assert_eq!(
+            kind(&"00".repeat(32), Some(DigestKind::SHA3_256)),
+            Some(DigestKind::SHA3_256)
+        );
+        let line = "RMD160 (foo) = 0000000000000000000000000000000000000000";
+        let checksum = Checksum::parse(line).unwrap();
+        assert_eq!(checksum.kind(None), Some(DigestKind::RMD160));
+        assert_eq!(
+            checksum.kind(Some(DigestKind::RMD160)),
+            Some(DigestKind::RMD160)
+        );
+        assert_eq!(
+            checksum.kind(Some(DigestKind::MD5)),
+            Some(DigestKind::RMD160)
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let mut summary = Summary::default();
+        for status in [
+            Status::Ok,
+            Status::Ok,
+            Status::Mismatch,
+            Status::Missing,
+            Status::ReadError,
+        ] {
+            summary.add(status);
+        }
+        summary.improper = 3;
+        assert_eq!(summary.checked(), 5);
+        assert_eq!(summary.unreadable(), 2);
+        assert_eq!(
+            summary.json(),
+            "{\"ok\": 2, \"mismatch\": 1, \"missing\": 1, \
+             \"read-error\": 1, \"improper\": 3}"
+        );
+    }
+
+    #[test]
+    fn json_records() {
+        let line = "d41d8cd98f00b204e9800998ecf8427e  say \"foo\"";
+        let checksum = Checksum::parse(line).unwrap();
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(
+            json_record(
+                &checksum,
+                DigestKind::MD5,
+                Status::Ok,
+                Some(&digest)
+            ),
+            "{\"path\": \"say \\\"foo\\\"\", \"algorithm\": \"MD5\", \
+             \"status\": \"ok\", \
+             \"expected\": \"d41d8cd98f00b204e9800998ecf8427e\", \
+             \"actual\": \"d41d8cd98f00b204e9800998ecf8427e\"}"
+        );
+        assert_eq!(
+            json_record(&checksum, DigestKind::MD5, Status::Missing, None),
+            "{\"path\": \"say \\\"foo\\\"\", \"algorithm\": \"MD5\", \
+             \"status\": \"missing\", \
+             \"expected\": \"d41d8cd98f00b204e9800998ecf8427e\", \
+             \"actual\": null}"
+        );
+    }
+
+    #[test]
+    fn json_reports() {
+        let summary = Summary::default();
+        assert_eq!(
+            json_report(&[], &summary),
+            format!(
+                "{{\n  \"files\": [],\n  \"summary\": {}\n}}",
+                summary.json()
+            )
+        );
+        let records = [String::from("{}"), String::from("{}")];
+        assert_eq!(
+            json_report(&records, &summary),
+            format!(
+                "{{\n  \"files\": [\n    {{}},\n    {{}}\n  ],\n  \
+                 \"summary\": {}\n}}",
+                summary.json()
+            )
+        );
+    }
+}
@@ -1,136 +1,2442 @@
+use std::collections::VecDeque;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-use crate::{DigestKind as Kind, Error};
+use digest::{backend_named, decode_hex, Backend, Blake2Params, OpenSSL};
+
+use crate::check::{default_kind, Expected};
+use crate::{
+    Columns, DigestKind as Kind, Encoding, Error, Format, Report, Symlinks,
+};
+
+/// A subcommand, given as the first argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Compute the digests of files, as when no subcommand is given.
+    Hash,
+    /// Verify the digests listed in a manifest, or in sidecar files.
+    Check,
+    /// Measure how quickly each digest can be computed.
+    Bench,
+    /// List the digests which can be computed.
+    List,
+    /// Find the files with identical contents.
+    Dupes,
+    /// Compare the files in two trees.
+    Diff,
+}
+
+impl Command {
+    /// Map the `name` of a subcommand to the subcommand.
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "hash" => Some(Command::Hash),
+            "check" => Some(Command::Check),
+            "bench" => Some(Command::Bench),
+            "list" => Some(Command::List),
+            "dupes" => Some(Command::Dupes),
+            "diff" => Some(Command::Diff),
+            _ => None,
+        }
+    }
+
+    /// The name of the subcommand.
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::Hash => "hash",
+            Command::Check => "check",
+            Command::Bench => "bench",
+            Command::List => "list",
+            Command::Dupes => "dupes",
+            Command::Diff => "diff",
+        }
+    }
+
+    /// The usage text for the subcommand.
+    pub fn usage(self) -> &'static str {
+        match self {
+            Command::Hash => include_str!("usage-hash.txt"),
+            Command::Check => include_str!("usage-check.txt"),
+            Command::Bench => include_str!("usage-bench.txt"),
+            Command::List => include_str!("usage-list.txt"),
+            Command::Dupes => include_str!("usage-dupes.txt"),
+            Command::Diff => include_str!("usage-diff.txt"),
+        }
+    }
+}
 
 /// A structure describing command line parameters.
-#[allow(clippy::upper_case_acronyms)]
+#[allow(clippy::upper_case_acronyms, clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub struct CLI {
+    /// The subcommand, if one was given.
+    pub command: Option<Command>,
     /// The `--help` (`-h`) flag was set.
     pub help: bool,
     /// The `--version` (`-V`) flag was set.
     pub version: bool,
     /// The list of the digests to compute.
     pub digests: Vec<Kind>,
+    /// The key, salt, and personalization used by the BLAKE2 digests.
+    pub params: Blake2Params,
+    /// The backend the digests are computed by, as set by `--backend`.
+    pub backend: &'static dyn Backend,
+    /// The file to read the BLAKE2 key from, with `--key-file`.
+    pub key_file: Option<PathBuf>,
+    /// The format to output the digests in.
+    pub format: Format,
+    /// The encoding used for the digests in the output.
+    pub encoding: Encoding,
+    /// The option which set the encoding, `--encoding` or `--base32`, so
+    /// that a conflict can name the option which was actually given.
+    pub encoding_option: &'static str,
+    /// The optional columns included in the output.
+    pub columns: Columns,
+    /// The file to write the output to, rather than stdout.
+    pub output: Option<PathBuf>,
+    /// The manifest listing the digests to verify, if any.
+    pub check: Option<PathBuf>,
+    /// The directory which relative paths in the manifest are resolved
+    /// against, rather than the current directory.
+    pub base_dir: Option<PathBuf>,
+    /// The `--update` flag was set, so the manifest is rewritten with the
+    /// digests of any files which changed, and without any missing files.
+    /// With `--output` only the files which changed since the output was
+    /// written are digested.
+    pub update: bool,
+    /// The `--dry-run` flag was set, so the changes `--update` would make
+    /// are reported, but the manifest is left alone.
+    pub dry_run: bool,
+    /// The directory to verify the sidecar files in, if any.
+    pub check_sidecars: Option<PathBuf>,
+    /// What is reported while verifying a manifest or sidecars.
+    pub report: Report,
+    /// The `--warn` flag was set, so improperly formatted lines in the
+    /// manifest are reported.
+    pub warn: bool,
+    /// The `--strict` flag was set, so improperly formatted lines in the
+    /// manifest cause the check to fail.
+    pub strict: bool,
+    /// The `--json` flag was set, so a JSON report of the check is output.
+    pub json: bool,
+    /// The digests the file is expected to have, if any.
+    pub expect: Vec<Expected>,
+    /// The `--sidecar` flag was set, so each digest is written to a file
+    /// next to the file it was computed for.
+    pub sidecar: bool,
+    /// The `--force` flag was set, so sidecars are always rewritten.
+    pub force: bool,
+    /// The `--sort` flag was set, so the results are output in path order.
+    pub sort: bool,
+    /// The `--combine` flag was set, so the files are digested as though
+    /// they were concatenated, giving a single digest of each kind.
+    pub combine: bool,
+    /// The `--tree-digest` flag was set, so a single digest of each kind
+    /// is computed for the whole tree under each directory.
+    pub tree_digest: bool,
+    /// The `--tee` flag was set, so stdin is copied to stdout while it is
+    /// digested.
+    pub tee: bool,
+    /// The `--progress` flag was set, so the progress through the files is
+    /// shown on stderr.
+    pub progress: bool,
+    /// The `--stats` flag was set, so a summary of the run is printed to
+    /// stderr once it finishes.
+    pub stats: bool,
+    /// How much is logged to stderr, as set by `--verbose` or `-vv`.
+    pub verbose: u8,
+    /// How many files are digested at once, if set by `--jobs`.
+    pub jobs: Option<usize>,
+    /// The number of bytes skipped at the start of each file, with
+    /// `--offset`.
+    pub offset: u64,
+    /// The most bytes digested from each file, if limited by `--size`.
+    pub size: Option<u64>,
+    /// The most bytes read per second, across every file, if limited by
+    /// `--throttle`.
+    pub throttle: Option<u64>,
+    /// The `--direct-io` flag was set, so files are read bypassing the
+    /// page cache.
+    pub direct_io: bool,
+    /// Whether each digest is computed in its own thread, which is unset
+    /// by `--no-threads` so that every digest is computed in the thread
+    /// reading the file.
+    pub threads: bool,
+    /// The `--fail-fast` flag was set (and not overridden by a later
+    /// `--keep-going`), so the run stops at the first file which fails.
+    pub fail_fast: bool,
+    /// The `--background-priority` flag was set, so the CPU and I/O
+    /// priority of the process are lowered.
+    pub background: bool,
     /// The filenames to compute the digests for.
     pub paths: Vec<PathBuf>,
+    /// The glob patterns to expand into more filenames, with `--glob`.
+    pub globs: Vec<String>,
+    /// The patterns for files and directories to skip while walking a
+    /// directory tree, with `--exclude`.
+    pub exclude: Vec<String>,
+    /// The `--recursive` flag was set, so each directory in the paths is
+    /// replaced by the files in the tree below it.
+    pub recursive: bool,
+    /// The `--respect-gitignore` flag was set, so files ignored by git are
+    /// skipped while walking a directory tree.
+    pub gitignore: bool,
+    /// How symbolic links are treated, as set by `--follow-symlinks`,
+    /// `--no-follow-symlinks`, or `--symlink-literal`.
+    pub symlinks: Symlinks,
+    /// How many levels of directories below the root of a tree are walked,
+    /// if limited by `--max-depth`.
+    pub max_depth: Option<usize>,
+}
+
+impl Default for CLI {
+    fn default() -> Self {
+        Self {
+            command: None,
+            help: false,
+            version: false,
+            digests: Vec::new(),
+            params: Blake2Params::default(),
+            backend: &OpenSSL,
+            key_file: None,
+            format: Format::Text,
+            encoding: Encoding::Hex,
+            encoding_option: "--encoding",
+            columns: Columns::default(),
+            output: None,
+            check: None,
+            base_dir: None,
+            update: false,
+            dry_run: false,
+            check_sidecars: None,
+            report: Report::All,
+            warn: false,
+            strict: false,
+            json: false,
+            expect: Vec::new(),
+            sidecar: false,
+            force: false,
+            sort: false,
+            combine: false,
+            tree_digest: false,
+            tee: false,
+            progress: false,
+            stats: false,
+            verbose: 0,
+            jobs: None,
+            offset: 0,
+            size: None,
+            throttle: None,
+            direct_io: false,
+            threads: true,
+            fail_fast: false,
+            background: false,
+            paths: Vec::new(),
+            globs: Vec::new(),
+            exclude: Vec::new(),
+            gitignore: false,
+            symlinks: Symlinks::Files,
+            max_depth: None,
+            recursive: false,
+        }
+    }
+}
+
+impl Drop for CLI {
+    /// Wipe the BLAKE2 key, so it doesn't linger in memory.
+    fn drop(&mut self) {
+        self.params.wipe();
+    }
 }
 
 impl CLI {
     pub const USAGE: &'static str = include_str!("usage.txt");
 
-    /// Parse a set of command line parameters into a `CLI` structure.
-    /// Duplicate options are ignored. If no digest options are provided,
-    /// then a default set of `--md5`, `--sha256`, `--sha512`, and
-    /// `--rmd160` are used.
+    /// The usage text for the subcommand, or for every option if there is
+    /// no subcommand.
+    pub fn usage(&self) -> &'static str {
+        self.command.map_or(Self::USAGE, Command::usage)
+    }
+
+    /// Parse a set of command line parameters into a `CLI` structure. The
+    /// first parameter may be a subcommand, and `check` takes the manifest
+    /// (or stdin) as its file. Duplicate options are ignored. If no digest
+    /// options are provided, then a default set of `--md5`, `--sha256`,
+    /// `--sha512`, and `--rmd160` are used, or just `--crc32` if `--sfv` is
+    /// set. No default is used for `--check`, where the digest depends on
+    /// the manifest, and every digest is used for `bench`.
     ///
     /// ## Errors
     ///
     /// If an unknown option is provided then an `Error::InvalidOption` is
-    /// returned with the offending option.
+    /// returned with the offending option. If an option is missing its
+    /// value, or the value is invalid (such as a `--key` which is not
+    /// hexadecimal), then an `Error::MissingValue` or `Error::InvalidValue`
+    /// is returned. If `--sfv` is used with a digest other than `--crc32`
+    /// then an `Error::SfvDigest` is returned. If the options are
+    /// inconsistent then the error from `check()` is returned.
     pub fn parse<I, A>(args: I) -> Result<Self, Error>
     where
         I: IntoIterator<Item = A>,
         A: Into<OsString>,
     {
-        let args = args.into_iter().map(Into::into);
-        let mut help = false;
-        let mut version = false;
-        let mut digests = Vec::new();
-        let mut paths = Vec::new();
-        for os_arg in args {
+        let mut args = args.into_iter().map(Into::into).peekable();
+        let command =
+            args.peek().and_then(|arg| Command::parse(arg.to_str()?));
+        if command.is_some() {
+            args.next();
+        }
+        let mut args = Args {
+            pending: VecDeque::new(),
+            args,
+        };
+        let mut cli = Self::default();
+        cli.command = command;
+        let mut encoding = "hex".to_string();
+        let mut padding = true;
+        let mut expect = Vec::new();
+        while let Some(os_arg) = args.next() {
             let arg = os_arg.to_string_lossy().to_string();
-            match arg.as_str() {
-                "--help" | "-h" => help = true,
-                "--version" | "-V" => version = true,
-                "--crc32" => {
-                    if !digests.contains(&Kind::CRC32) {
-                        digests.push(Kind::CRC32);
-                    }
+            let (option, value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => {
+                    (option, Some(value.to_string()))
                 }
-                "--md5" => {
-                    if !digests.contains(&Kind::MD5) {
-                        digests.push(Kind::MD5);
-                    }
+                _ => (arg.as_str(), None),
+            };
+            match option {
+                "--base32" => {
+                    encoding = "base32".to_string();
+                    cli.encoding_option = "--base32";
                 }
-                "--sha256" => {
-                    if !digests.contains(&Kind::SHA256) {
-                        digests.push(Kind::SHA256);
-                    }
+                "--encoding" => {
+                    encoding = option_value(option, value, &mut args)?;
+                    cli.encoding_option = "--encoding";
                 }
-                "--sha512" => {
-                    if !digests.contains(&Kind::SHA512) {
-                        digests.push(Kind::SHA512);
-                    }
+                "--no-padding" => padding = false,
+                // Everything after `--` is a file, even if it looks like an
+                // option.
+                "--" => cli.paths.extend(args.rest().map(PathBuf::from)),
+                "--expect" => {
+                    expect.push(option_value(option, value, &mut args)?);
                 }
-                "--rmd160" => {
-                    if !digests.contains(&Kind::RMD160) {
-                        digests.push(Kind::RMD160);
-                    }
+                _ => cli.parse_option(&arg, option, value, &mut args)?,
+            }
+        }
+        cli.encoding =
+            encoding_option(&encoding, padding).ok_or_else(|| {
+                Error::InvalidValue("--encoding".to_string(), encoding)
+            })?;
+
+        // The expected digests use the selected digest if their algorithm
+        // isn't given, and replace the selected digests with their own.
+        if !expect.is_empty() {
+            let selected = match cli.digests.as_slice() {
+                [kind] => Some(*kind),
+                _ => None,
+            };
+            cli.expect = expect
+                .into_iter()
+                .map(|value| {
+                    expect_option(&value, selected).ok_or_else(|| {
+                        Error::InvalidValue("--expect".to_string(), value)
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            cli.digests.clear();
+            for expected in &cli.expect {
+                if !cli.digests.contains(&expected.kind) {
+                    cli.digests.push(expected.kind);
+                }
+            }
+        }
+
+        cli.defaults()?;
+
+        if !cli.help && !cli.version {
+            cli.check()?;
+        }
+        Ok(cli)
+    }
+
+    /// Parse a single `arg`, which is split into the `option` and any
+    /// `value` given as part of it (as in `--option=value`). If the option
+    /// takes a value which wasn't given then it is taken from `args`.
+    fn parse_option(
+        &mut self,
+        arg: &str,
+        option: &str,
+        value: Option<String>,
+        args: &mut Args<impl Iterator<Item = OsString>>,
+    ) -> Result<(), Error> {
+        match option {
+            "--help" | "-h" => self.help = true,
+            "--version" | "-V" => self.version = true,
+            "--sfv" => self.format = Format::SFV,
+            "--csv" => self.format = Format::CSV,
+            "--yaml" => self.format = Format::YAML,
+            "--plain" => self.format = Format::Plain,
+            "--with-size" => self.columns.size = true,
+            "--with-mtime" => self.columns.mtime = true,
+            "--sidecar" => self.sidecar = true,
+            "--force" => self.force = true,
+            "--sort" => self.sort = true,
+            "--combine" => self.combine = true,
+            "--tree-digest" => self.tree_digest = true,
+            "--tee" => self.tee = true,
+            "--progress" => self.progress = true,
+            "--stats" => self.stats = true,
+            "--background-priority" => self.background = true,
+            "--direct-io" => self.direct_io = true,
+            "--no-threads" => self.threads = false,
+            "--keep-going" => self.fail_fast = false,
+            "--fail-fast" => self.fail_fast = true,
+            "--verbose" | "-v" => {
+                self.verbose = self.verbose.saturating_add(1);
+            }
+            "--jobs" | "-j" => {
+                self.jobs = Some(number_value(option, value, args, 1)?);
+            }
+            "--offset" | "--size" | "--throttle" => {
+                self.parse_limit(option, value, args)?;
+            }
+            "--output" | "-o" => {
+                let value = option_value(option, value, args)?;
+                self.output = Some(PathBuf::from(value));
+            }
+            "--check" | "-c" => {
+                let value = option_value(option, value, args)?;
+                self.check = Some(PathBuf::from(value));
+            }
+            "--base-dir" => {
+                let value = option_value(option, value, args)?;
+                self.base_dir = Some(PathBuf::from(value));
+            }
+            "--glob" => {
+                self.globs.push(option_value(option, value, args)?);
+            }
+            "--exclude" => {
+                self.exclude.push(option_value(option, value, args)?);
+            }
+            "--respect-gitignore" => self.gitignore = true,
+            "--recursive" | "-r" => self.recursive = true,
+            "--max-depth" => {
+                self.max_depth = Some(number_value(option, value, args, 1)?);
+            }
+            "--follow-symlinks" | "-L" => self.symlinks = Symlinks::Follow,
+            "--no-follow-symlinks" | "-P" => {
+                self.symlinks = Symlinks::NoFollow;
+            }
+            "--symlink-literal" => self.symlinks = Symlinks::Literal,
+            "--update" => self.update = true,
+            "--dry-run" => self.dry_run = true,
+            "--check-sidecars" => {
+                let value = option_value(option, value, args)?;
+                self.check_sidecars = Some(PathBuf::from(value));
+            }
+            "--quiet" | "-q" => {
+                self.report = self.report.max(Report::Failures);
+            }
+            "--status" => self.report = Report::Status,
+            "--warn" => self.warn = true,
+            "--strict" => self.strict = true,
+            "--json" => self.json = true,
+            "--backend" => {
+                let value = option_value(option, value, args)?;
+                self.backend = backend_named(&value).ok_or_else(|| {
+                    Error::InvalidValue(option.to_string(), value)
+                })?;
+            }
+            "--key-file" => {
+                let value = option_value(option, value, args)?;
+                self.key_file = Some(PathBuf::from(value));
+            }
+            "--key" | "--salt" | "--personal" => {
+                self.parse_param(option, value, args)?;
+            }
+            // A lone `-` is stdin, listed among the files.
+            _ if arg.starts_with('-') && arg != "-" => {
+                let kind = digest_option(arg)
+                    .ok_or_else(|| Error::InvalidOption(arg.to_string()))?;
+                if !self.digests.contains(&kind) {
+                    self.digests.push(kind);
+                }
+            }
+            _ => self.paths.push(arg.into()),
+        }
+        Ok(())
+    }
+
+    /// Parse the value of an `option` which limits how much of each file is
+    /// read, or how quickly, as for `parse_option()`.
+    fn parse_limit(
+        &mut self,
+        option: &str,
+        value: Option<String>,
+        args: &mut Args<impl Iterator<Item = OsString>>,
+    ) -> Result<(), Error> {
+        match option {
+            "--offset" => {
+                self.offset = number_value(option, value, args, 0)?;
+            }
+            "--size" => {
+                self.size = Some(number_value(option, value, args, 0)?);
+            }
+            _ => self.throttle = Some(rate_value(option, value, args)?),
+        }
+        Ok(())
+    }
+
+    /// Parse the value of an `option` which sets one of the BLAKE2
+    /// parameters, given in hexadecimal, as for `parse_option()`.
+    fn parse_param(
+        &mut self,
+        option: &str,
+        value: Option<String>,
+        args: &mut Args<impl Iterator<Item = OsString>>,
+    ) -> Result<(), Error> {
+        let value = option_value(option, value, args)?;
+        let bytes = decode_hex(&value).ok_or_else(|| {
+            Error::InvalidValue(option.to_string(), value.clone())
+        })?;
+        match option {
+            "--key" => self.params.key = bytes,
+            "--salt" => self.params.salt = bytes,
+            _ => self.params.personal = bytes,
+        }
+        Ok(())
+    }
+
+    /// Check that the options which depend on each other are consistent.
+    ///
+    /// ## Errors
+    ///
+    /// If the BLAKE2 parameters are used without a BLAKE2 digest then an
+    /// `Error::UnusedParameters` is returned. If options which can't be
+    /// used together are set then an `Error::ConflictingOptions` is
+    /// returned, and if an option is set without another option it
+    /// requires then an `Error::RequiredOption` is returned. If `--sidecar`
+    /// is used without any files, or with `-` for stdin, then an
+    /// `Error::SidecarStdin` is returned.
+    /// If `--check` is used with more than one digest, or any files, then
+    /// an `Error::CheckDigests` or `Error::CheckPaths` is returned, and if
+    /// `--check-sidecars` is used with any files then an
+    /// `Error::CheckSidecarsPaths` is returned. If `--update` is used with
+    /// a manifest read from stdin then an `Error::UpdateStdin` is returned.
+    /// If `--expect` is used with more than one file, without `--combine`,
+    /// then an `Error::ExpectPaths` is returned, and if `--tee` is used with
+    /// any files then an `Error::TeePaths` is returned.
+    fn check(&self) -> Result<(), Error> {
+        // The BLAKE2 parameters have no effect on any other digest, so
+        // make sure they are not silently ignored.
+        let params = !self.params.is_empty() || self.key_file.is_some();
+        if params
+            && !self.digests.contains(&Kind::BLAKE2B)
+            && !self.digests.contains(&Kind::BLAKE2S)
+        {
+            return Err(Error::UnusedParameters);
+        }
+        if !self.params.key.is_empty() && self.key_file.is_some() {
+            return Err(Error::ConflictingOptions(
+                "--key".to_string(),
+                "--key-file".to_string(),
+            ));
+        }
+        self.check_output()?;
+        // The files are combined into a single digest, which has no file
+        // to verify or to write a sidecar next to.
+        if self.combine {
+            for (set, option) in [
+                (self.check.is_some(), "--check"),
+                (self.check_sidecars.is_some(), "--check-sidecars"),
+                (self.sidecar, "--sidecar"),
+                (self.jobs.is_some(), "--jobs"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--combine".to_string(),
+                        option.to_string(),
+                    ));
                 }
-                arg if arg.starts_with('-') => {
-                    return Err(Error::InvalidOption(arg.to_string()))
+            }
+        }
+        self.check_command()?;
+        self.check_verify()?;
+        self.check_jobs()?;
+        self.check_tree()?;
+        self.check_tee()?;
+        // The options for walking a tree have no effect without one.
+        if self.globs.is_empty()
+            && self.check_sidecars.is_none()
+            && !self.recursive
+            && !self.tree_digest
+            && self.command != Some(Command::Diff)
+        {
+            for (set, option) in [
+                (!self.exclude.is_empty(), "--exclude"),
+                (self.gitignore, "--respect-gitignore"),
+                (self.max_depth.is_some(), "--max-depth"),
+            ] {
+                if set {
+                    return Err(Error::RequiredOption(
+                        option.to_string(),
+                        "--glob".to_string(),
+                    ));
                 }
-                filename => paths.push(filename.into()),
             }
         }
+        if self.dry_run && !self.update {
+            return Err(Error::RequiredOption(
+                "--dry-run".to_string(),
+                "--update".to_string(),
+            ));
+        }
+        if self.force && !self.sidecar {
+            return Err(Error::RequiredOption(
+                "--force".to_string(),
+                "--sidecar".to_string(),
+            ));
+        }
+        // Sidecars are written next to the files, so stdin has nowhere to
+        // put one.
+        let stdin = self.paths.is_empty() && self.globs.is_empty()
+            || self.paths.iter().any(|path| path == Path::new("-"));
+        if self.sidecar && stdin {
+            return Err(Error::SidecarStdin);
+        }
+        Ok(())
+    }
 
+    /// Use the defaults for the manifest used by `check` and for the
+    /// digests, if they weren't set, as described for `parse()`. If `--sfv`
+    /// is used with a digest other than `--crc32` then an
+    /// `Error::SfvDigest` is returned.
+    fn defaults(&mut self) -> Result<(), Error> {
+        // The manifest for `check` is given like a file, or is read from
+        // stdin.
+        if self.command == Some(Command::Check)
+            && self.check.is_none()
+            && self.check_sidecars.is_none()
+        {
+            self.check = Some(if self.paths.is_empty() {
+                PathBuf::from("-")
+            } else {
+                self.paths.remove(0)
+            });
+        }
+        // The directories for `dupes` are searched, starting from the
+        // current directory if none are given.
+        if self.command == Some(Command::Dupes) {
+            self.recursive = true;
+            if self.paths.is_empty() && self.globs.is_empty() {
+                self.paths.push(PathBuf::from("."));
+            }
+        }
         // If no digests were set, use a default set of MD5, SHA256,
-        // SHA512, and RMD160.
-        if digests.is_empty() && !help && !version {
-            digests =
+        // SHA512, and RMD160. SFV output only supports CRC32.
+        if self.format == Format::SFV {
+            if self.digests.iter().any(|kind| *kind != Kind::CRC32) {
+                return Err(Error::SfvDigest);
+            }
+            self.digests = vec![Kind::CRC32];
+        } else if self.digests.is_empty()
+            && self.command == Some(Command::Bench)
+        {
+            // Every digest is measured unless some were selected.
+            self.digests = DIGEST_OPTIONS.map(|(_, kind)| kind).to_vec();
+        } else if self.digests.is_empty()
+            && matches!(self.command, Some(Command::Dupes | Command::Diff))
+        {
+            self.digests = vec![Kind::SHA256];
+        } else if self.digests.is_empty()
+            && self.check.is_none()
+            && self.check_sidecars.is_none()
+            && !self.help
+            && !self.version
+        {
+            self.digests =
                 vec![Kind::MD5, Kind::SHA256, Kind::SHA512, Kind::RMD160];
         }
+        Ok(())
+    }
+
+    /// Check the options for how the digests are computed are consistent.
+    /// If `--progress` or `--jobs` are used with an option which doesn't
+    /// support them then an `Error::ConflictingOptions` is returned.
+    fn check_jobs(&self) -> Result<(), Error> {
+        // Progress is only shown while computing digests, one at a time.
+        if self.progress {
+            for (set, option) in [
+                (self.check.is_some(), "--check"),
+                (self.check_sidecars.is_some(), "--check-sidecars"),
+                (!self.expect.is_empty(), "--expect"),
+                (self.jobs.is_some_and(|jobs| jobs > 1), "--jobs"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--progress".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+        }
+        // Only computing digests, or checking a manifest, uses several jobs.
+        if self.jobs.is_some() {
+            for (set, option) in [
+                (self.check_sidecars.is_some(), "--check-sidecars"),
+                (!self.expect.is_empty(), "--expect"),
+                (self.sidecar, "--sidecar"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--jobs".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the options for the format of the output are consistent. If
+    /// they conflict then an `Error::ConflictingOptions` is returned.
+    fn check_output(&self) -> Result<(), Error> {
+        // SFV files have a fixed format, with no room for other columns,
+        // and plain output is only the digests.
+        let fixed = match self.format {
+            Format::SFV => Some("--sfv"),
+            Format::Plain => Some("--plain"),
+            _ => None,
+        };
+        if let Some(format) = fixed {
+            for (set, option) in [
+                (self.columns.size, "--with-size"),
+                (self.columns.mtime, "--with-mtime"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        option.to_string(),
+                        format.to_string(),
+                    ));
+                }
+            }
+        }
+        // Plain sidecars wouldn't name the file they are for.
+        if self.sidecar && self.format == Format::Plain {
+            return Err(Error::ConflictingOptions(
+                "--sidecar".to_string(),
+                "--plain".to_string(),
+            ));
+        }
+        if self.sidecar && self.output.is_some() {
+            return Err(Error::ConflictingOptions(
+                "--sidecar".to_string(),
+                "--output".to_string(),
+            ));
+        }
+        // The digests already in the output can only be read back from the
+        // text format, and only if they are of the whole of each file. The
+        // manifest doesn't record the BLAKE2 parameters, so digests made
+        // with them can't be reused either.
+        if self.update && self.check.is_none() {
+            let hex =
+                matches!(self.encoding, Encoding::Hex | Encoding::UpperHex);
+            for (set, option) in [
+                (self.format == Format::SFV, "--sfv"),
+                (self.format == Format::CSV, "--csv"),
+                (self.format == Format::YAML, "--yaml"),
+                (self.format == Format::Plain, "--plain"),
+                (!hex, self.encoding_option),
+                (self.combine, "--combine"),
+                (self.tree_digest, "--tree-digest"),
+                (self.offset > 0, "--offset"),
+                (self.size.is_some(), "--size"),
+                (!self.params.key.is_empty(), "--key"),
+                (self.key_file.is_some(), "--key-file"),
+                (!self.params.salt.is_empty(), "--salt"),
+                (!self.params.personal.is_empty(), "--personal"),
+                (self.dry_run, "--dry-run"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--update".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+        }
+        // Manifests are only read back with hexadecimal digests, so a
+        // manifest or sidecar written in any other encoding couldn't be
+        // checked. Text output to stdout is still allowed, as it is often
+        // read by something other than '--check'.
+        let hex = matches!(self.encoding, Encoding::Hex | Encoding::UpperHex);
+        if !hex && self.format == Format::Text {
+            for (set, option) in [
+                (self.output.is_some(), "--output"),
+                (self.sidecar, "--sidecar"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        self.encoding_option.to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the options are consistent with `--tree-digest`, if it is set.
+    /// If it is used with an option which digests individual files then an
+    /// `Error::ConflictingOptions` is returned, and if there are no
+    /// directories, or stdin is listed, then an `Error::TreeDigestStdin` is
+    /// returned.
+    fn check_tree(&self) -> Result<(), Error> {
+        if !self.tree_digest {
+            return Ok(());
+        }
+        for (set, option) in [
+            (self.check.is_some(), "--check"),
+            (self.check_sidecars.is_some(), "--check-sidecars"),
+            (!self.expect.is_empty(), "--expect"),
+            (self.sidecar, "--sidecar"),
+            (self.combine, "--combine"),
+            (self.recursive, "--recursive"),
+            (!self.globs.is_empty(), "--glob"),
+            (self.jobs.is_some(), "--jobs"),
+            (self.progress, "--progress"),
+        ] {
+            if set {
+                return Err(Error::ConflictingOptions(
+                    "--tree-digest".to_string(),
+                    option.to_string(),
+                ));
+            }
+        }
+        if self.paths.is_empty()
+            || self.paths.iter().any(|path| path == Path::new("-"))
+        {
+            return Err(Error::TreeDigestStdin);
+        }
+        Ok(())
+    }
 
-        Ok(Self {
-            help,
-            version,
-            digests,
-            paths,
+    /// Check the options are consistent with `--tee`, if it is set. If it is
+    /// used with an option which doesn't compute digests of stdin then an
+    /// `Error::ConflictingOptions` is returned, and if any files are listed
+    /// then an `Error::TeePaths` is returned.
+    fn check_tee(&self) -> Result<(), Error> {
+        if !self.tee {
+            return Ok(());
+        }
+        if let Some(command) = self.command.filter(|&c| c != Command::Hash) {
+            return Err(Error::ConflictingOptions(
+                "--tee".to_string(),
+                command.name().to_string(),
+            ));
+        }
+        for (set, option) in [
+            (self.check.is_some(), "--check"),
+            (self.check_sidecars.is_some(), "--check-sidecars"),
+            (!self.expect.is_empty(), "--expect"),
+            (self.sidecar, "--sidecar"),
+            (self.combine, "--combine"),
+            (self.tree_digest, "--tree-digest"),
+            (self.recursive, "--recursive"),
+            (self.progress, "--progress"),
+            (self.jobs.is_some(), "--jobs"),
+        ] {
+            if set {
+                return Err(Error::ConflictingOptions(
+                    "--tee".to_string(),
+                    option.to_string(),
+                ));
+            }
+        }
+        if !self.paths.is_empty() || !self.globs.is_empty() {
+            return Err(Error::TeePaths);
+        }
+        Ok(())
+    }
+
+    /// Check the options are consistent with the subcommand, if any. If
+    /// `hash` is used with options to verify digests then an
+    /// `Error::ConflictingOptions` is returned, as it is if `bench` or
+    /// `list` is used with `--stats`, and if `bench` or `list` is used with
+    /// any files then an `Error::CommandPaths` is returned.
+    fn check_command(&self) -> Result<(), Error> {
+        match self.command {
+            Some(Command::Hash) => {
+                for (set, option) in [
+                    (self.check.is_some(), "--check"),
+                    (self.check_sidecars.is_some(), "--check-sidecars"),
+                ] {
+                    if set {
+                        return Err(Error::ConflictingOptions(
+                            "hash".to_string(),
+                            option.to_string(),
+                        ));
+                    }
+                }
+            }
+            // Neither digests any files.
+            Some(command @ (Command::Bench | Command::List))
+                if self.stats =>
+            {
+                return Err(Error::ConflictingOptions(
+                    command.name().to_string(),
+                    "--stats".to_string(),
+                ));
+            }
+            Some(command @ (Command::Bench | Command::List))
+                if !self.paths.is_empty() || !self.globs.is_empty() =>
+            {
+                return Err(Error::CommandPaths(command.name().to_string()));
+            }
+            // Only the groups of files are output, as text or JSON.
+            Some(command @ (Command::Dupes | Command::Diff)) => {
+                // The trees being compared are given as the paths.
+                let diff = command == Command::Diff;
+                for (set, option) in [
+                    (diff && !self.globs.is_empty(), "--glob"),
+                    (diff && self.recursive, "--recursive"),
+                    (diff && self.json, "--json"),
+                    (self.format == Format::SFV, "--sfv"),
+                    (self.format == Format::CSV, "--csv"),
+                    (self.format == Format::YAML, "--yaml"),
+                    (self.format == Format::Plain, "--plain"),
+                    (self.check.is_some(), "--check"),
+                    (self.check_sidecars.is_some(), "--check-sidecars"),
+                    (!self.expect.is_empty(), "--expect"),
+                    (self.sidecar, "--sidecar"),
+                    (self.output.is_some(), "--output"),
+                    (self.combine, "--combine"),
+                    (self.tree_digest, "--tree-digest"),
+                    (self.progress, "--progress"),
+                    (self.fail_fast, "--fail-fast"),
+                    (self.columns.size, "--with-size"),
+                    (self.columns.mtime, "--with-mtime"),
+                ] {
+                    if set {
+                        return Err(Error::ConflictingOptions(
+                            command.name().to_string(),
+                            option.to_string(),
+                        ));
+                    }
+                }
+                if diff && self.paths.len() != 2 {
+                    return Err(Error::DiffPaths);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Check the options used to verify digests, with `--check`,
+    /// `--check-sidecars`, or `--expect`, are consistent. The errors are
+    /// the same as for `check()`.
+    fn check_verify(&self) -> Result<(), Error> {
+        if self.check.is_some() {
+            for (set, option) in [
+                (self.output.is_some(), "--output"),
+                (self.sidecar, "--sidecar"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--check".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+            if self.json && self.report == Report::Status {
+                return Err(Error::ConflictingOptions(
+                    "--json".to_string(),
+                    "--status".to_string(),
+                ));
+            }
+            if self.digests.len() > 1 {
+                return Err(Error::CheckDigests);
+            }
+            if !self.paths.is_empty() || !self.globs.is_empty() {
+                return Err(Error::CheckPaths);
+            }
+            if self.update && self.check.as_deref() == Some(Path::new("-")) {
+                return Err(Error::UpdateStdin);
+            }
+        }
+        if let Some(dir) = &self.check_sidecars {
+            for (set, option) in [
+                (self.output.is_some(), "--output"),
+                (self.check.is_some(), "--check"),
+                (self.sidecar, "--sidecar"),
+                (!self.expect.is_empty(), "--expect"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--check-sidecars".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+            if !self.paths.is_empty() || !self.globs.is_empty() {
+                return Err(Error::CheckSidecarsPaths(dir.clone()));
+            }
+        }
+        if self.check.is_none() {
+            let report = self.check_sidecars.is_none();
+            let dupes = self.command == Some(Command::Dupes);
+            for (set, option) in [
+                (report && self.report == Report::Failures, "--quiet"),
+                (report && self.report == Report::Status, "--status"),
+                (self.warn, "--warn"),
+                (self.strict, "--strict"),
+                (self.json && !dupes, "--json"),
+                (self.base_dir.is_some(), "--base-dir"),
+                (self.update && self.output.is_none(), "--update"),
+            ] {
+                if set {
+                    return Err(Error::RequiredOption(
+                        option.to_string(),
+                        "--check".to_string(),
+                    ));
+                }
+            }
+        }
+        if !self.expect.is_empty() {
+            for (set, option) in [
+                (self.output.is_some(), "--output"),
+                (self.check.is_some(), "--check"),
+                (self.sidecar, "--sidecar"),
+                (!self.globs.is_empty() && !self.combine, "--glob"),
+            ] {
+                if set {
+                    return Err(Error::ConflictingOptions(
+                        "--expect".to_string(),
+                        option.to_string(),
+                    ));
+                }
+            }
+            if self.paths.len() > 1 && !self.combine {
+                return Err(Error::ExpectPaths);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Retrieve the value of an `option`. The `value` is used if it was given
+/// as part of the option (as in `--option=value`), otherwise the next of the
+/// `args` is used.
+fn option_value(
+    option: &str,
+    value: Option<String>,
+    args: &mut Args<impl Iterator<Item = OsString>>,
+) -> Result<String, Error> {
+    value
+        .or_else(|| {
+            let arg = args.next_value()?;
+            Some(arg.to_string_lossy().to_string())
         })
+        .ok_or_else(|| Error::MissingValue(option.to_string()))
+}
+
+/// Fetch the value of an `option` which takes a number, as for
+/// `option_value()`. If the value isn't a number of at least `min` then an
+/// `Error::InvalidValue` is returned.
+fn number_value<T: FromStr + PartialOrd + Copy>(
+    option: &str,
+    value: Option<String>,
+    args: &mut Args<impl Iterator<Item = OsString>>,
+    min: T,
+) -> Result<T, Error> {
+    let value = option_value(option, value, args)?;
+    value
+        .parse()
+        .ok()
+        .filter(|number| *number >= min)
+        .ok_or_else(|| Error::InvalidValue(option.to_string(), value))
+}
+
+/// Fetch the value of an `option` which takes a rate, in bytes per second,
+/// as for `option_value()`. If the value isn't a valid rate, as parsed by
+/// `parse_rate()`, then an `Error::InvalidValue` is returned.
+fn rate_value(
+    option: &str,
+    value: Option<String>,
+    args: &mut Args<impl Iterator<Item = OsString>>,
+) -> Result<u64, Error> {
+    let value = option_value(option, value, args)?;
+    parse_rate(&value)
+        .ok_or_else(|| Error::InvalidValue(option.to_string(), value))
+}
+
+/// Parse a rate, in bytes per second, which may have a `K`, `M`, `G`, or
+/// `T` suffix for thousands, millions, and so on. If the rate isn't a
+/// number of at least one byte then `None` is returned.
+fn parse_rate(rate: &str) -> Option<u64> {
+    let (number, scale) = match rate.char_indices().last()? {
+        (at, 'K') => (&rate[..at], 1_000),
+        (at, 'M') => (&rate[..at], 1_000_000),
+        (at, 'G') => (&rate[..at], 1_000_000_000),
+        (at, 'T') => (&rate[..at], 1_000_000_000_000),
+        _ => (rate, 1),
+    };
+    number
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(scale)
+        .filter(|rate| *rate > 0)
+}
+
+/// The short flags which take a value.
+const SHORT_VALUES: [char; 3] = ['c', 'j', 'o'];
+
+/// The command line parameters still to be parsed, where each cluster of
+/// short flags (such as `-rc`) is split into separate flags.
+struct Args<I> {
+    /// The flags split from a cluster which are still to be parsed.
+    pending: VecDeque<OsString>,
+    args: I,
+}
+
+impl<I: Iterator<Item = OsString>> Args<I> {
+    /// The value of an option, which is taken as it is even if it looks
+    /// like a cluster of flags.
+    fn next_value(&mut self) -> Option<OsString> {
+        self.pending.pop_front().or_else(|| self.args.next())
+    }
+
+    /// The remaining parameters, which are all taken as they are.
+    fn rest(&mut self) -> impl Iterator<Item = OsString> + '_ {
+        std::iter::from_fn(|| self.next_value())
     }
 }
 
+impl<I: Iterator<Item = OsString>> Iterator for Args<I> {
+    type Item = OsString;
+
+    fn next(&mut self) -> Option<OsString> {
+        if let Some(arg) = self.pending.pop_front() {
+            return Some(arg);
+        }
+        let arg = self.args.next()?;
+        match split_cluster(&arg) {
+            Some(flags) => {
+                self.pending.extend(flags);
+                self.pending.pop_front()
+            }
+            None => Some(arg),
+        }
+    }
+}
+
+/// Split a cluster of short flags, such as `-rc`, into separate flags. The
+/// rest of the cluster after a flag which takes a value, as in `-j4`, is
+/// its value. If `arg` isn't a cluster then `None` is returned.
+fn split_cluster(arg: &OsString) -> Option<Vec<OsString>> {
+    let flags = arg.to_str()?.strip_prefix('-').filter(|flags| {
+        !flags.starts_with('-') && flags.chars().count() > 1
+    })?;
+    let mut split = Vec::new();
+    for (i, flag) in flags.char_indices() {
+        split.push(OsString::from(format!("-{flag}")));
+        if SHORT_VALUES.contains(&flag) {
+            let value = &flags[i + flag.len_utf8()..];
+            if !value.is_empty() {
+                split.push(OsString::from(value));
+            }
+            break;
+        }
+    }
+    Some(split)
+}
+
+/// Parse the `value` of an `--expect` option, which is a hexadecimal
+/// digest, optionally prefixed by the name of its algorithm (as used for
+/// its option) and a `:`, such as `sha256:HEXDIGEST`. If the algorithm
+/// isn't named then the `selected` digest is used, or otherwise the
+/// default digest of the same length. If the algorithm is unknown, or the
+/// digest isn't hexadecimal or is the wrong length for the algorithm, then
+/// `None` is returned.
+fn expect_option(value: &str, selected: Option<Kind>) -> Option<Expected> {
+    let (kind, hex) = match value.split_once(':') {
+        Some((name, hex)) => {
+            (Some(digest_option(&format!("--{name}"))?), hex)
+        }
+        None => (None, value),
+    };
+    if hex.is_empty() {
+        return None;
+    }
+    let digest = decode_hex(hex)?;
+    let kind = kind.or(selected).or_else(|| default_kind(&digest))?;
+    if digest.len() != kind.output_len() {
+        return None;
+    }
+    Some(Expected { kind, digest })
+}
+
+/// Map the name of an encoding (such as `base64`) to the `Encoding`,
+/// with the `padding` used for encodings which may be padded. If `name`
+/// is not an encoding then `None` is returned.
+fn encoding_option(name: &str, padding: bool) -> Option<Encoding> {
+    match name {
+        "hex" => Some(Encoding::Hex),
+        "HEX" => Some(Encoding::UpperHex),
+        "base32" => Some(Encoding::Base32 { padding }),
+        "base58btc" => Some(Encoding::Base58Btc),
+        "base64" => Some(Encoding::Base64 { padding }),
+        "base64url" => Some(Encoding::Base64Url { padding }),
+        _ => None,
+    }
+}
+
+/// The option which selects each kind of digest.
+pub const DIGEST_OPTIONS: [(&str, Kind); 31] = [
+    ("--crc16=ccitt", Kind::CRC16_CCITT),
+    ("--crc16=modbus", Kind::CRC16_MODBUS),
+    ("--crc16=xmodem", Kind::CRC16_XMODEM),
+    ("--crc32", Kind::CRC32),
+    ("--crc64", Kind::CRC64),
+    ("--xxh32", Kind::XXH32),
+    ("--xxh64", Kind::XXH64),
+    ("--rapidhash", Kind::RAPIDHASH),
+    ("--md2", Kind::MD2),
+    ("--md4", Kind::MD4),
+    ("--md5", Kind::MD5),
+    ("--sha224", Kind::SHA224),
+    ("--sha256", Kind::SHA256),
+    ("--sha384", Kind::SHA384),
+    ("--sha512", Kind::SHA512),
+    ("--sha512-224", Kind::SHA512_224),
+    ("--sha512-256", Kind::SHA512_256),
+    ("--sha3-224", Kind::SHA3_224),
+    ("--sha3-256", Kind::SHA3_256),
+    ("--sha3-384", Kind::SHA3_384),
+    ("--sha3-512", Kind::SHA3_512),
+    ("--keccak256", Kind::KECCAK256),
+    ("--blake2b", Kind::BLAKE2B),
+    ("--blake2s", Kind::BLAKE2S),
+    ("--streebog256", Kind::STREEBOG256),
+    ("--streebog512", Kind::STREEBOG512),
+    ("--tiger", Kind::TIGER),
+    ("--tiger2", Kind::TIGER2),
+    ("--rmd256", Kind::RMD256),
+    ("--rmd320", Kind::RMD320),
+    ("--rmd160", Kind::RMD160),
+];
+
+/// Map a digest option (such as `--md5`) to the kind of digest it selects.
+/// If `option` does not select a digest then `None` is returned.
+fn digest_option(option: &str) -> Option<Kind> {
+    DIGEST_OPTIONS
+        .iter()
+        .find(|(known, _)| *known == option)
+        .map(|(_, kind)| *kind)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures;
     use std::path::Path;
 
+    #[test]
+    fn parse_command() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.command, None);
+        let cli = CLI::parse(["hash", "--md5", "foo"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Hash));
+        assert_eq!(cli.digests, vec![Kind::MD5]);
+        assert_eq!(cli.paths, vec![Path::new("foo")]);
+        let cli = CLI::parse(["hash"]).unwrap();
+        assert!(cli.paths.is_empty());
+        // A subcommand is only recognized as the first parameter.
+        let cli = CLI::parse(["foo", "hash"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.paths, vec![Path::new("foo"), Path::new("hash")]);
+
+        let cli = CLI::parse(["check", "SUMS"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Check));
+        assert_eq!(cli.check, Some(PathBuf::from("SUMS")));
+        assert!(cli.paths.is_empty());
+        assert!(cli.digests.is_empty());
+        let cli = CLI::parse(["check"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("-")));
+        let cli = CLI::parse(["check", "--check-sidecars", "dir"]).unwrap();
+        assert_eq!(cli.check, None);
+        assert_eq!(cli.check_sidecars, Some(PathBuf::from("dir")));
+        let err = CLI::parse(["check", "SUMS", "foo"]).unwrap_err();
+        assert_eq!(err, Error::CheckPaths);
+
+        let cli = CLI::parse(["bench"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Bench));
+        assert_eq!(cli.digests.len(), DIGEST_OPTIONS.len());
+        let cli = CLI::parse(["bench", "--sha256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA256]);
+        let cli = CLI::parse(["list"]).unwrap();
+        assert_eq!(cli.command, Some(Command::List));
+        let cli = CLI::parse(["list", "--help"]).unwrap();
+        assert!(cli.help);
+        assert!(cli.usage().starts_with("usage: checksum list"));
+
+        let err = CLI::parse(["hash", "-c", "SUMS"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "hash".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err = CLI::parse(["bench", "foo"]).unwrap_err();
+        assert_eq!(err, Error::CommandPaths("bench".to_string()));
+        let err = CLI::parse(["list", "--glob", "*"]).unwrap_err();
+        assert_eq!(err, Error::CommandPaths("list".to_string()));
+
+        let cli = CLI::parse(["dupes", "foo", "bar"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Dupes));
+        assert_eq!(cli.paths, vec![Path::new("foo"), Path::new("bar")]);
+        assert_eq!(cli.digests, vec![Kind::SHA256]);
+        assert!(cli.recursive);
+        let cli =
+            CLI::parse(["dupes", "--md5", "--json", "--exclude", ".git"])
+                .unwrap();
+        assert_eq!(cli.paths, vec![Path::new(".")]);
+        assert_eq!(cli.digests, vec![Kind::MD5]);
+        assert!(cli.json);
+        for option in ["--csv", "--sidecar", "--combine", "--with-size"] {
+            let err = CLI::parse(["dupes", option, "dir"]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "dupes".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+
+        let cli = CLI::parse(["diff", "foo", "bar"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Diff));
+        assert_eq!(cli.paths, vec![Path::new("foo"), Path::new("bar")]);
+        assert_eq!(cli.digests, vec![Kind::SHA256]);
+        for args in
+            [&["diff"][..], &["diff", "foo"], &["diff", "a", "b", "c"]]
+        {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(err, Error::DiffPaths);
+        }
+        for option in ["--glob", "--json", "--recursive"] {
+            let err = CLI::parse(["diff", option, "foo", "bar"]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "diff".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+    }
+
     #[test]
     fn parse_help() {
-        let cli = CLI::parse(&["--help"]).unwrap();
+        let cli = CLI::parse(["--help"]).unwrap();
         assert!(cli.help);
-        let cli = CLI::parse(&["-h"]).unwrap();
+        let cli = CLI::parse(["-h"]).unwrap();
         assert!(cli.help);
     }
 
     #[test]
     fn parse_version() {
-        let cli = CLI::parse(&["--version"]).unwrap();
+        let cli = CLI::parse(["--version"]).unwrap();
         assert!(cli.version);
-        let cli = CLI::parse(&["-V"]).unwrap();
+        let cli = CLI::parse(["-V"]).unwrap();
         assert!(cli.version);
     }
 
     #[test]
     fn parse_digests() {
-        let cli = CLI::parse(&["--crc32"]).unwrap();
+        let cli = CLI::parse(["--crc16=ccitt"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_CCITT]);
+        let cli = CLI::parse(["--crc16=modbus"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_MODBUS]);
+        let cli = CLI::parse(["--crc16=xmodem"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_XMODEM]);
+        let cli = CLI::parse(["--crc32"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::CRC32]);
-        let cli = CLI::parse(&["--md5"]).unwrap();
+        let cli = CLI::parse(["--crc64"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC64]);
+        let cli = CLI::parse(["--xxh32"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::XXH32]);
+        let cli = CLI::parse(["--xxh64"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::XXH64]);
+        let cli = CLI::parse(["--rapidhash"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RAPIDHASH]);
+        let cli = CLI::parse(["--md2"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::MD2]);
+        let cli = CLI::parse(["--md4"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::MD4]);
+        let cli = CLI::parse(["--md5"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::MD5]);
-        let cli = CLI::parse(&["--sha256"]).unwrap();
+        let cli = CLI::parse(["--sha224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA224]);
+        let cli = CLI::parse(["--sha256"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::SHA256]);
-        let cli = CLI::parse(&["--sha512"]).unwrap();
+        let cli = CLI::parse(["--sha384"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA384]);
+        let cli = CLI::parse(["--sha512"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::SHA512]);
-        let cli = CLI::parse(&["--rmd160"]).unwrap();
+        let cli = CLI::parse(["--sha512-224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA512_224]);
+        let cli = CLI::parse(["--sha512-256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA512_256]);
+        let cli = CLI::parse(["--sha3-224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_224]);
+        let cli = CLI::parse(["--sha3-256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_256]);
+        let cli = CLI::parse(["--sha3-384"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_384]);
+        let cli = CLI::parse(["--sha3-512"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_512]);
+        let cli = CLI::parse(["--keccak256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::KECCAK256]);
+        let cli = CLI::parse(["--blake2b"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::BLAKE2B]);
+        let cli = CLI::parse(["--blake2s"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::BLAKE2S]);
+        let cli = CLI::parse(["--streebog256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::STREEBOG256]);
+        let cli = CLI::parse(["--streebog512"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::STREEBOG512]);
+        let cli = CLI::parse(["--tiger"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::TIGER]);
+        let cli = CLI::parse(["--tiger2"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::TIGER2]);
+        let cli = CLI::parse(["--rmd256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RMD256]);
+        let cli = CLI::parse(["--rmd320"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RMD320]);
+        let cli = CLI::parse(["--rmd160"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::RMD160]);
     }
 
+    #[test]
+    fn parse_blake2_params() {
+        let cli = CLI::parse(["--blake2b"]).unwrap();
+        assert_eq!(cli.params, Blake2Params::default());
+        let cli = CLI::parse([
+            "--blake2b",
+            "--key",
+            "6b6579",
+            "--salt=73616c74",
+            "--personal",
+            "0A0b",
+        ])
+        .unwrap();
+        assert_eq!(cli.digests, vec![Kind::BLAKE2B]);
+        assert_eq!(
+            cli.params,
+            Blake2Params {
+                key: b"key".to_vec(),
+                salt: b"salt".to_vec(),
+                personal: vec![0x0a, 0x0b],
+            }
+        );
+        let cli = CLI::parse(["--key=", "--blake2s", "foo"]).unwrap();
+        assert_eq!(cli.params, Blake2Params::default());
+        assert_eq!(cli.paths, vec![Path::new("foo")]);
+        let cli = CLI::parse(["--blake2s", "--key-file", "KEY"]).unwrap();
+        assert_eq!(cli.key_file, Some(PathBuf::from("KEY")));
+        assert_eq!(cli.params, Blake2Params::default());
+    }
+
+    #[test]
+    fn parse_invalid_blake2_params() {
+        let err = CLI::parse(["--blake2b", "--key"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--key".to_string()));
+        for value in ["abc", "xy", "+1", "6b65\u{e9}"] {
+            let err = CLI::parse(["--blake2b", "--salt", value]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue("--salt".to_string(), value.to_string())
+            );
+        }
+        let err = CLI::parse(["--personal=00"]).unwrap_err();
+        assert_eq!(err, Error::UnusedParameters);
+        let err = CLI::parse(["--md5", "--key", "00"]).unwrap_err();
+        assert_eq!(err, Error::UnusedParameters);
+        let err = CLI::parse(["--md5", "--key-file", "KEY"]).unwrap_err();
+        assert_eq!(err, Error::UnusedParameters);
+        let err = CLI::parse(["--blake2b", "--key=00", "--key-file=KEY"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--key".to_string(),
+                "--key-file".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_format() {
+        let cli = CLI::parse(["--md5"]).unwrap();
+        assert_eq!(cli.format, Format::Text);
+        let cli = CLI::parse(["--sfv"]).unwrap();
+        assert_eq!(cli.format, Format::SFV);
+        assert_eq!(cli.digests, vec![Kind::CRC32]);
+        let cli = CLI::parse(["--crc32", "--sfv"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC32]);
+        let cli = CLI::parse(["--csv", "--md5"]).unwrap();
+        assert_eq!(cli.format, Format::CSV);
+        assert_eq!(cli.digests, vec![Kind::MD5]);
+        let cli = CLI::parse(["--yaml"]).unwrap();
+        assert_eq!(cli.format, Format::YAML);
+        assert_eq!(
+            cli.digests,
+            vec![Kind::MD5, Kind::SHA256, Kind::SHA512, Kind::RMD160]
+        );
+        let err = CLI::parse(["--sfv", "--md5"]).unwrap_err();
+        assert_eq!(err, Error::SfvDigest);
+        let cli = CLI::parse(["--plain", "--sha256"]).unwrap();
+        assert_eq!(cli.format, Format::Plain);
+        assert_eq!(cli.digests, vec![Kind::SHA256]);
+        let err = CLI::parse(["--plain", "--with-size"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--with-size".to_string(),
+                "--plain".to_string()
+            )
+        );
+        let err = CLI::parse(["--plain", "--sidecar", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--sidecar".to_string(),
+                "--plain".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_backend() {
+        let cli = CLI::parse(["--md5"]).unwrap();
+        assert_eq!(cli.backend.name(), "openssl");
+        let cli = CLI::parse(["--backend", "rust", "--md5"]).unwrap();
+        assert_eq!(cli.backend.name(), "rust");
+        let cli = CLI::parse(["--backend=openssl", "--md5"]).unwrap();
+        assert_eq!(cli.backend.name(), "openssl");
+        let err = CLI::parse(["--backend", "boringssl"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidValue(
+                "--backend".to_string(),
+                "boringssl".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_encoding() {
+        let cli = CLI::parse(["--md5"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Hex);
+        let cli = CLI::parse(["--base32"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base32 { padding: true });
+        let cli = CLI::parse(["--no-padding", "--base32"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base32 { padding: false });
+        let cli = CLI::parse(["--no-padding"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Hex);
+        let cli = CLI::parse(["--encoding", "HEX"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::UpperHex);
+        let cli = CLI::parse(["--encoding=base32"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base32 { padding: true });
+        let cli = CLI::parse(["--encoding=base58btc"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base58Btc);
+        let cli = CLI::parse(["--encoding=base64", "--no-padding"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base64 { padding: false });
+        let cli = CLI::parse(["--encoding", "base64url"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Base64Url { padding: true });
+        let cli = CLI::parse(["--base32", "--encoding=hex"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::Hex);
+        let err = CLI::parse(["--encoding=base16"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidValue(
+                "--encoding".to_string(),
+                "base16".to_string()
+            )
+        );
+        let err = CLI::parse(["--encoding"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--encoding".to_string()));
+
+        // Manifests must be hexadecimal to be checked.
+        for (args, option) in [
+            (&["-o", "SUMS"][..], "--output"),
+            (&["--sidecar"], "--sidecar"),
+        ] {
+            for encoding in [&["--base32"][..], &["--encoding", "base64"]] {
+                let args = [encoding, &["foo"], args].concat();
+                let err = CLI::parse(args).unwrap_err();
+                assert_eq!(
+                    err,
+                    Error::ConflictingOptions(
+                        encoding[0].to_string(),
+                        option.to_string()
+                    )
+                );
+            }
+        }
+        let cli =
+            CLI::parse(["--encoding=HEX", "-o", "SUMS", "foo"]).unwrap();
+        assert_eq!(cli.encoding, Encoding::UpperHex);
+        let cli = CLI::parse(["--base32", "--csv", "-o", "SUMS", "foo"]);
+        assert!(cli.is_ok());
+    }
+
+    #[test]
+    fn parse_columns() {
+        let cli = CLI::parse(["--md5"]).unwrap();
+        assert_eq!(cli.columns, Columns::default());
+        let cli = CLI::parse(["--with-size"]).unwrap();
+        assert_eq!(
+            cli.columns,
+            Columns {
+                size: true,
+                mtime: false
+            }
+        );
+        let cli = CLI::parse(["--with-mtime", "--with-size"]).unwrap();
+        assert_eq!(
+            cli.columns,
+            Columns {
+                size: true,
+                mtime: true
+            }
+        );
+        let err = CLI::parse(["--sfv", "--with-mtime"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--with-mtime".to_string(),
+                "--sfv".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_output() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.output, None);
+        let cli = CLI::parse(["-o", "SUMS", "foo"]).unwrap();
+        assert_eq!(cli.output, Some(PathBuf::from("SUMS")));
+        assert_eq!(cli.paths, vec![Path::new("foo")]);
+        let cli = CLI::parse(["--output", "SUMS"]).unwrap();
+        assert_eq!(cli.output, Some(PathBuf::from("SUMS")));
+        let cli = CLI::parse(["--output=SUMS"]).unwrap();
+        assert_eq!(cli.output, Some(PathBuf::from("SUMS")));
+        let err = CLI::parse(["-o"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("-o".to_string()));
+    }
+
+    #[test]
+    fn parse_check() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.check, None);
+        let cli = CLI::parse(["-c", "SHA256SUMS"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("SHA256SUMS")));
+        assert!(cli.digests.is_empty());
+        let cli = CLI::parse(["--check=SUMS", "--sha3-256"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("SUMS")));
+        assert_eq!(cli.digests, vec![Kind::SHA3_256]);
+        let err = CLI::parse(["--check"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--check".to_string()));
+        let err = CLI::parse(["-c", "SUMS", "--md5", "--sha1"]).unwrap_err();
+        assert_eq!(err, Error::InvalidOption("--sha1".to_string()));
+        let err =
+            CLI::parse(["-c", "SUMS", "--md5", "--sha256"]).unwrap_err();
+        assert_eq!(err, Error::CheckDigests);
+        let err = CLI::parse(["-c", "SUMS", "foo"]).unwrap_err();
+        assert_eq!(err, Error::CheckPaths);
+        let err = CLI::parse(["-c", "SUMS", "-o", "OUT"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--check".to_string(),
+                "--output".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_base_dir() {
+        let cli = CLI::parse(["-c", "SUMS"]).unwrap();
+        assert_eq!(cli.base_dir, None);
+        let cli = CLI::parse(["-c", "SUMS", "--base-dir", "dir"]).unwrap();
+        assert_eq!(cli.base_dir, Some(PathBuf::from("dir")));
+        let cli = CLI::parse(["--base-dir=/dir", "-c", "SUMS"]).unwrap();
+        assert_eq!(cli.base_dir, Some(PathBuf::from("/dir")));
+
+        let err = CLI::parse(["-c", "SUMS", "--base-dir"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--base-dir".to_string()));
+        let err = CLI::parse(["--base-dir", "dir"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--base-dir".to_string(),
+                "--check".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_glob() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(cli.globs.is_empty());
+        let cli = CLI::parse(["--glob", "**/*.iso", "--glob=*.img", "foo"])
+            .unwrap();
+        assert_eq!(cli.globs, vec!["**/*.iso", "*.img"]);
+        assert_eq!(cli.paths, vec![Path::new("foo")]);
+        let cli = CLI::parse(["--sidecar", "--glob", "*.iso"]).unwrap();
+        assert!(cli.sidecar);
+
+        let err = CLI::parse(["--glob"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--glob".to_string()));
+        let err = CLI::parse(["-c", "SUMS", "--glob", "*"]).unwrap_err();
+        assert_eq!(err, Error::CheckPaths);
+        let err = CLI::parse(["--expect", "crc32:00000000", "--glob", "*"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--expect".to_string(),
+                "--glob".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_exclude() {
+        let cli = CLI::parse(["--glob", "**"]).unwrap();
+        assert!(cli.exclude.is_empty());
+        let cli = CLI::parse([
+            "--glob",
+            "**",
+            "--exclude",
+            ".git",
+            "--exclude=*.tmp",
+        ])
+        .unwrap();
+        assert_eq!(cli.exclude, vec![".git", "*.tmp"]);
+        let cli =
+            CLI::parse(["--check-sidecars", "dir", "--exclude", ".git"])
+                .unwrap();
+        assert_eq!(cli.exclude, vec![".git"]);
+
+        let err = CLI::parse(["--glob", "**", "--exclude"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--exclude".to_string()));
+        let err = CLI::parse(["--exclude", ".git", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--exclude".to_string(),
+                "--glob".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_gitignore() {
+        let cli = CLI::parse(["--glob", "**"]).unwrap();
+        assert!(!cli.gitignore);
+        let cli =
+            CLI::parse(["--glob", "**", "--respect-gitignore"]).unwrap();
+        assert!(cli.gitignore);
+        let err = CLI::parse(["--respect-gitignore"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--respect-gitignore".to_string(),
+                "--glob".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_max_depth() {
+        let cli = CLI::parse(["--glob", "**"]).unwrap();
+        assert_eq!(cli.max_depth, None);
+        let cli = CLI::parse(["--glob", "**", "--max-depth", "1"]).unwrap();
+        assert_eq!(cli.max_depth, Some(1));
+        let cli =
+            CLI::parse(["--check-sidecars=dir", "--max-depth=3"]).unwrap();
+        assert_eq!(cli.max_depth, Some(3));
+
+        for value in ["0", "-1", "x", ""] {
+            let err = CLI::parse(["--glob", "**", "--max-depth", value])
+                .unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue(
+                    "--max-depth".to_string(),
+                    value.to_string()
+                )
+            );
+        }
+        let err = CLI::parse(["--max-depth", "1", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--max-depth".to_string(),
+                "--glob".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_symlinks() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Files);
+        let cli = CLI::parse(["--follow-symlinks", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Follow);
+        let cli = CLI::parse(["-L", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Follow);
+        let cli = CLI::parse(["--no-follow-symlinks", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::NoFollow);
+        let cli = CLI::parse(["-L", "-P", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::NoFollow);
+        let cli = CLI::parse(["-P", "--follow-symlinks", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Follow);
+        let cli = CLI::parse(["--symlink-literal", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Literal);
+        let cli = CLI::parse(["--symlink-literal", "-L", "foo"]).unwrap();
+        assert_eq!(cli.symlinks, Symlinks::Follow);
+    }
+
+    #[test]
+    fn parse_update() {
+        let cli = CLI::parse(["-c", "SUMS"]).unwrap();
+        assert!(!cli.update);
+        assert!(!cli.dry_run);
+        let cli = CLI::parse(["-c", "SUMS", "--update"]).unwrap();
+        assert!(cli.update);
+        assert!(!cli.dry_run);
+        let cli =
+            CLI::parse(["--dry-run", "--update", "-c", "SUMS"]).unwrap();
+        assert!(cli.update);
+        assert!(cli.dry_run);
+
+        let err = CLI::parse(["--update"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--update".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err = CLI::parse(["-c", "SUMS", "--dry-run"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--dry-run".to_string(),
+                "--update".to_string()
+            )
+        );
+        let err = CLI::parse(["-c", "-", "--update"]).unwrap_err();
+        assert_eq!(err, Error::UpdateStdin);
+
+        let cli = CLI::parse(["--update", "-o", "SUMS", "foo"]).unwrap();
+        assert!(cli.update);
+        assert_eq!(cli.output, Some(PathBuf::from("SUMS")));
+        for (args, option) in [
+            (&["--csv"][..], "--csv"),
+            (&["--encoding", "base64"], "--encoding"),
+            (&["--base32"], "--base32"),
+            (&["--combine"], "--combine"),
+            (&["--dry-run"], "--dry-run"),
+            (&["--blake2s", "--key", "aa"], "--key"),
+            (&["--blake2s", "--key-file", "KEY"], "--key-file"),
+            (&["--blake2b", "--salt", "aa"], "--salt"),
+            (&["--blake2b", "--personal", "aa"], "--personal"),
+        ] {
+            let args =
+                [&["--update", "-o", "SUMS", "foo"][..], args].concat();
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "--update".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn parse_check_sidecars() {
+        let cli = CLI::parse(["--check-sidecars", "dir"]).unwrap();
+        assert_eq!(cli.check_sidecars, Some(PathBuf::from("dir")));
+        assert!(cli.digests.is_empty());
+        let cli = CLI::parse(["--check-sidecars=dir", "--sha256", "--md5"])
+            .unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA256, Kind::MD5]);
+        let cli = CLI::parse(["--check-sidecars", "dir", "--quiet"]).unwrap();
+        assert_eq!(cli.report, Report::Failures);
+
+        let err = CLI::parse(["--check-sidecars"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--check-sidecars".to_string()));
+        let err = CLI::parse(["--check-sidecars", "dir", "foo"]).unwrap_err();
+        assert_eq!(err, Error::CheckSidecarsPaths(PathBuf::from("dir")));
+        let err = CLI::parse(["--check-sidecars", "dir", "-c", "SUMS"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--check-sidecars".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err =
+            CLI::parse(["--check-sidecars", "dir", "--json"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--json".to_string(),
+                "--check".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_report() {
+        let cli = CLI::parse(["-c", "SUMS"]).unwrap();
+        assert_eq!(cli.report, Report::All);
+        let cli = CLI::parse(["-c", "SUMS", "--quiet"]).unwrap();
+        assert_eq!(cli.report, Report::Failures);
+        let cli = CLI::parse(["--status", "-c", "SUMS"]).unwrap();
+        assert_eq!(cli.report, Report::Status);
+        let cli = CLI::parse(["--status", "--quiet", "-c", "SUMS"]).unwrap();
+        assert_eq!(cli.report, Report::Status);
+        let err = CLI::parse(["--quiet", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--quiet".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err = CLI::parse(["--status", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--status".to_string(),
+                "--check".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_warn_strict() {
+        let cli = CLI::parse(["-c", "SUMS"]).unwrap();
+        assert!(!cli.warn);
+        assert!(!cli.strict);
+        let cli = CLI::parse(["-c", "SUMS", "--warn", "--strict"]).unwrap();
+        assert!(cli.warn);
+        assert!(cli.strict);
+        let err = CLI::parse(["--warn"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--warn".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err = CLI::parse(["--strict"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--strict".to_string(),
+                "--check".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_json() {
+        let cli = CLI::parse(["-c", "SUMS"]).unwrap();
+        assert!(!cli.json);
+        let cli = CLI::parse(["-c", "SUMS", "--json"]).unwrap();
+        assert!(cli.json);
+        let err = CLI::parse(["--json"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--json".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err =
+            CLI::parse(["-c", "SUMS", "--json", "--status"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--json".to_string(),
+                "--status".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_expect() {
+        let md5 = "d41d8cd98f00b204e9800998ecf8427e";
+        let cli = CLI::parse(["--expect", md5, "foo"]).unwrap();
+        assert_eq!(
+            cli.expect,
+            vec![Expected {
+                kind: Kind::MD5,
+                digest: fixtures::md5::EMPTY.to_vec()
+            }]
+        );
+        assert_eq!(cli.digests, vec![Kind::MD5]);
+        let crc32 = "00000000";
+        let cli = CLI::parse([
+            &format!("--expect=crc32:{crc32}"),
+            "--expect",
+            md5,
+            "--expect",
+            &format!("md5:{md5}"),
+        ])
+        .unwrap();
+        assert_eq!(cli.expect.len(), 3);
+        assert_eq!(cli.expect[0].kind, Kind::CRC32);
+        assert_eq!(cli.digests, vec![Kind::CRC32, Kind::MD5]);
+        let cli =
+            CLI::parse(["--rmd160", "--expect", &"00".repeat(20)]).unwrap();
+        assert_eq!(cli.expect[0].kind, Kind::RMD160);
+        assert_eq!(cli.digests, vec![Kind::RMD160]);
+        let cli = CLI::parse(["--crc16=ccitt", "--expect=crc16=ccitt:ffff"])
+            .unwrap();
+        assert_eq!(cli.expect[0].kind, Kind::CRC16_CCITT);
+
+        for value in ["00", "sha1:00", "md5:", "md5:xy", "md5:764efa", ""] {
+            let err = CLI::parse(["--expect", value]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue(
+                    "--expect".to_string(),
+                    value.to_string()
+                )
+            );
+        }
+        let err = CLI::parse(["--rmd160", "--expect", md5]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidValue("--expect".to_string(), md5.to_string())
+        );
+        let err = CLI::parse(["--expect", md5, "foo", "bar"]).unwrap_err();
+        assert_eq!(err, Error::ExpectPaths);
+        let err = CLI::parse(["--expect", md5, "-c", "SUMS"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--expect".to_string(),
+                "--check".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_sidecar() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.sidecar);
+        assert!(!cli.force);
+        let cli = CLI::parse(["--sidecar", "foo"]).unwrap();
+        assert!(cli.sidecar);
+        assert!(!cli.force);
+        let cli = CLI::parse(["--sidecar", "--force", "foo"]).unwrap();
+        assert!(cli.sidecar);
+        assert!(cli.force);
+        let err = CLI::parse(["--sidecar", "-o", "SUMS", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--sidecar".to_string(),
+                "--output".to_string()
+            )
+        );
+        let err = CLI::parse(["--force", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::RequiredOption(
+                "--force".to_string(),
+                "--sidecar".to_string()
+            )
+        );
+        let err = CLI::parse(["--sidecar"]).unwrap_err();
+        assert_eq!(err, Error::SidecarStdin);
+        let err = CLI::parse(["--sidecar", "foo", "-"]).unwrap_err();
+        assert_eq!(err, Error::SidecarStdin);
+    }
+
+    #[test]
+    fn parse_sort() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.sort);
+        let cli = CLI::parse(["--sort", "foo"]).unwrap();
+        assert!(cli.sort);
+    }
+
+    #[test]
+    fn parse_tree_digest() {
+        let cli = CLI::parse(["dir"]).unwrap();
+        assert!(!cli.tree_digest);
+        let cli = CLI::parse(["--tree-digest", "dir"]).unwrap();
+        assert!(cli.tree_digest);
+        let cli = CLI::parse(["--tree-digest", "--exclude", ".git", "dir"])
+            .unwrap();
+        assert_eq!(cli.exclude, vec![".git"]);
+
+        for args in [&["--tree-digest"][..], &["--tree-digest", "dir", "-"]] {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(err, Error::TreeDigestStdin);
+        }
+        for (args, option) in [
+            (&["--tree-digest", "-c", "SUMS"][..], "--check"),
+            (&["--tree-digest", "--sidecar", "dir"][..], "--sidecar"),
+            (&["--tree-digest", "--combine", "dir"][..], "--combine"),
+            (&["--tree-digest", "-r", "dir"][..], "--recursive"),
+            (&["--tree-digest", "--glob", "*"][..], "--glob"),
+        ] {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "--tree-digest".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn parse_combine() {
+        let cli = CLI::parse(["foo", "bar"]).unwrap();
+        assert!(!cli.combine);
+        let cli = CLI::parse(["--combine", "foo", "bar"]).unwrap();
+        assert!(cli.combine);
+        let md5 = "md5:d41d8cd98f00b204e9800998ecf8427e";
+        let cli =
+            CLI::parse(["--combine", "--expect", md5, "foo", "bar"]).unwrap();
+        assert_eq!(cli.paths, vec![Path::new("foo"), Path::new("bar")]);
+        let cli = CLI::parse(["--combine", "--expect", md5, "--glob", "*"])
+            .unwrap();
+        assert_eq!(cli.globs, vec!["*"]);
+
+        for (args, option) in [
+            (&["--combine", "-c", "SUMS"][..], "--check"),
+            (
+                &["--combine", "--check-sidecars", "dir"][..],
+                "--check-sidecars",
+            ),
+            (&["--combine", "--sidecar", "foo"][..], "--sidecar"),
+            (&["--combine", "-j2", "foo"][..], "--jobs"),
+        ] {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "--combine".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn parse_verbose() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.verbose, 0);
+        let cli = CLI::parse(["-v", "foo"]).unwrap();
+        assert_eq!(cli.verbose, 1);
+        let cli = CLI::parse(["--verbose", "--verbose", "foo"]).unwrap();
+        assert_eq!(cli.verbose, 2);
+        let cli = CLI::parse(["-vv", "foo"]).unwrap();
+        assert_eq!(cli.verbose, 2);
+        let cli = CLI::parse(["-c", "SUMS", "-v"]).unwrap();
+        assert_eq!(cli.verbose, 1);
+    }
+
+    #[test]
+    fn parse_recursive() {
+        let cli = CLI::parse(["dir"]).unwrap();
+        assert!(!cli.recursive);
+        let cli = CLI::parse(["--recursive", "dir"]).unwrap();
+        assert!(cli.recursive);
+        let cli = CLI::parse(["-r", "--exclude", "*.md5", "dir"]).unwrap();
+        assert!(cli.recursive);
+        assert_eq!(cli.exclude, vec!["*.md5"]);
+    }
+
+    #[test]
+    fn parse_clustered() {
+        let cli = CLI::parse(["-rq", "-c", "SUMS"]).unwrap();
+        assert!(cli.recursive);
+        assert_eq!(cli.report, Report::Failures);
+        assert_eq!(cli.check, Some(PathBuf::from("SUMS")));
+        let cli = CLI::parse(["-qc", "SUMS"]).unwrap();
+        assert_eq!(cli.report, Report::Failures);
+        assert_eq!(cli.check, Some(PathBuf::from("SUMS")));
+        let cli = CLI::parse(["-qcSUMS"]).unwrap();
+        assert_eq!(cli.check, Some(PathBuf::from("SUMS")));
+        let cli = CLI::parse(["-j4", "foo"]).unwrap();
+        assert_eq!(cli.jobs, Some(4));
+        let cli = CLI::parse(["-rvj", "2", "dir"]).unwrap();
+        assert!(cli.recursive);
+        assert_eq!(cli.verbose, 1);
+        assert_eq!(cli.jobs, Some(2));
+        let cli = CLI::parse(["-o", "-rq", "foo"]).unwrap();
+        assert_eq!(cli.output, Some(PathBuf::from("-rq")));
+        assert!(!cli.recursive);
+
+        let err = CLI::parse(["-rx", "foo"]).unwrap_err();
+        assert_eq!(err, Error::InvalidOption("-x".to_string()));
+    }
+
+    #[test]
+    fn parse_range() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.offset, 0);
+        assert_eq!(cli.size, None);
+        let cli =
+            CLI::parse(["--offset", "512", "--size=1024", "foo"]).unwrap();
+        assert_eq!(cli.offset, 512);
+        assert_eq!(cli.size, Some(1024));
+        let cli = CLI::parse(["--size", "0", "-c", "SUMS"]).unwrap();
+        assert_eq!(cli.size, Some(0));
+
+        for value in ["-1", "x", "1K", ""] {
+            let err = CLI::parse(["--offset", value, "foo"]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue(
+                    "--offset".to_string(),
+                    value.to_string()
+                )
+            );
+        }
+        let err = CLI::parse(["--size"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("--size".to_string()));
+    }
+
+    #[test]
+    fn parse_jobs() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.jobs, None);
+        let cli = CLI::parse(["--jobs", "4", "foo"]).unwrap();
+        assert_eq!(cli.jobs, Some(4));
+        let cli = CLI::parse(["-j", "2", "-c", "SUMS"]).unwrap();
+        assert_eq!(cli.jobs, Some(2));
+        let cli = CLI::parse(["--jobs=1", "--progress", "foo"]).unwrap();
+        assert_eq!(cli.jobs, Some(1));
+
+        for value in ["0", "-1", "x"] {
+            let err = CLI::parse(["--jobs", value, "foo"]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue("--jobs".to_string(), value.to_string())
+            );
+        }
+        let err = CLI::parse(["-j"]).unwrap_err();
+        assert_eq!(err, Error::MissingValue("-j".to_string()));
+        let err = CLI::parse(["-j", "2", "--progress", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--progress".to_string(),
+                "--jobs".to_string()
+            )
+        );
+        let err = CLI::parse(["-j", "2", "--sidecar", "foo"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--jobs".to_string(),
+                "--sidecar".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_progress() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.progress);
+        let cli = CLI::parse(["--progress", "foo"]).unwrap();
+        assert!(cli.progress);
+        let cli = CLI::parse(["--progress", "--sidecar", "foo"]).unwrap();
+        assert!(cli.progress);
+        let err = CLI::parse(["--progress", "-c", "SUMS"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--progress".to_string(),
+                "--check".to_string()
+            )
+        );
+        let err = CLI::parse(["--progress", "--expect", "crc32:00000000"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "--progress".to_string(),
+                "--expect".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_tee() {
+        let cli = CLI::parse(["--md5"]).unwrap();
+        assert!(!cli.tee);
+        let cli = CLI::parse(["--tee", "--sha256", "-o", "SUMS"]).unwrap();
+        assert!(cli.tee);
+        assert_eq!(cli.output, Some(PathBuf::from("SUMS")));
+        let cli = CLI::parse(["hash", "--tee"]).unwrap();
+        assert!(cli.tee);
+        for args in [&["--tee", "foo"][..], &["--tee", "-"]] {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(err, Error::TeePaths);
+        }
+        for (args, option) in [
+            (&["--tee", "--sidecar"][..], "--sidecar"),
+            (&["--tee", "--combine"], "--combine"),
+            (&["--tee", "-j", "2"], "--jobs"),
+            (&["dupes", "--tee"], "dupes"),
+        ] {
+            let err = CLI::parse(args).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    "--tee".to_string(),
+                    option.to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn parse_throttle() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert_eq!(cli.throttle, None);
+        for (rate, expected) in [
+            ("100", 100),
+            ("50M", 50_000_000),
+            ("2K", 2000),
+            ("1G", 1_000_000_000),
+            ("3T", 3_000_000_000_000),
+        ] {
+            let cli = CLI::parse(["--throttle", rate, "foo"]).unwrap();
+            assert_eq!(cli.throttle, Some(expected));
+        }
+        for rate in ["0", "0M", "M", "", "-1", "5k", "20000000T"] {
+            let err = CLI::parse(["--throttle", rate]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InvalidValue(
+                    "--throttle".to_string(),
+                    rate.to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn parse_direct_io() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.direct_io);
+        let cli = CLI::parse(["--direct-io", "-c", "SUMS"]).unwrap();
+        assert!(cli.direct_io);
+    }
+
+    #[test]
+    fn parse_no_threads() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(cli.threads);
+        let cli = CLI::parse(["--no-threads", "-j", "4", "foo"]).unwrap();
+        assert!(!cli.threads);
+    }
+
+    #[test]
+    fn parse_background() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.background);
+        let cli =
+            CLI::parse(["--background-priority", "-c", "SUMS"]).unwrap();
+        assert!(cli.background);
+    }
+
+    #[test]
+    fn parse_fail_fast() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.fail_fast);
+        let cli = CLI::parse(["--fail-fast", "-c", "SUMS"]).unwrap();
+        assert!(cli.fail_fast);
+        let cli = CLI::parse(["--fail-fast", "--keep-going", "foo"]).unwrap();
+        assert!(!cli.fail_fast);
+        let cli = CLI::parse(["--keep-going", "--fail-fast", "foo"]).unwrap();
+        assert!(cli.fail_fast);
+        let err = CLI::parse(["dupes", "--fail-fast"]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::ConflictingOptions(
+                "dupes".to_string(),
+                "--fail-fast".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_stats() {
+        let cli = CLI::parse(["foo"]).unwrap();
+        assert!(!cli.stats);
+        let cli = CLI::parse(["--stats", "-j", "4", "foo"]).unwrap();
+        assert!(cli.stats);
+        let cli = CLI::parse(["--stats", "-c", "SUMS"]).unwrap();
+        assert!(cli.stats);
+        for command in ["bench", "list"] {
+            let err = CLI::parse([command, "--stats"]).unwrap_err();
+            assert_eq!(
+                err,
+                Error::ConflictingOptions(
+                    command.to_string(),
+                    "--stats".to_string()
+                )
+            );
+        }
+    }
+
     #[test]
     fn parse_paths() {
-        let cli = CLI::parse(&["foo", "bar"]).unwrap();
+        let cli = CLI::parse(["foo", "bar"]).unwrap();
         assert_eq!(cli.paths, vec![Path::new("foo"), Path::new("bar")]);
+        let cli = CLI::parse(["foo", "-", "bar"]).unwrap();
+        assert_eq!(
+            cli.paths,
+            vec![Path::new("foo"), Path::new("-"), Path::new("bar")]
+        );
+        let cli = CLI::parse(["--md5", "foo", "--", "-rf", "--sha1", "--"])
+            .unwrap();
+        assert_eq!(
+            cli.paths,
+            vec![
+                Path::new("foo"),
+                Path::new("-rf"),
+                Path::new("--sha1"),
+                Path::new("--")
+            ]
+        );
+        assert_eq!(cli.digests, vec![Kind::MD5]);
+        let cli = CLI::parse(["--"]).unwrap();
+        assert!(cli.paths.is_empty());
     }
 
     #[test]
@@ -147,32 +2453,185 @@ mod tests {
 
     #[test]
     fn parse_duplicate_digests() {
-        let cli = CLI::parse(&["--crc32", "--crc32"]).unwrap();
+        let cli = CLI::parse(["--crc16=ccitt", "--crc16=ccitt"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_CCITT]);
+        let cli = CLI::parse(["--crc16=modbus", "--crc16=modbus"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_MODBUS]);
+        let cli = CLI::parse(["--crc16=xmodem", "--crc16=xmodem"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC16_XMODEM]);
+        let cli = CLI::parse(["--crc32", "--crc32"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::CRC32]);
-        let cli = CLI::parse(&["--md5", "--md5"]).unwrap();
+        let cli = CLI::parse(["--crc64", "--crc64"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::CRC64]);
+        let cli = CLI::parse(["--xxh32", "--xxh32"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::XXH32]);
+        let cli = CLI::parse(["--xxh64", "--xxh64"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::XXH64]);
+        let cli = CLI::parse(["--rapidhash", "--rapidhash"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RAPIDHASH]);
+        let cli = CLI::parse(["--md2", "--md2"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::MD2]);
+        let cli = CLI::parse(["--md4", "--md4"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::MD4]);
+        let cli = CLI::parse(["--md5", "--md5"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::MD5]);
-        let cli = CLI::parse(&["--sha256", "--sha256"]).unwrap();
+        let cli = CLI::parse(["--sha224", "--sha224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA224]);
+        let cli = CLI::parse(["--sha256", "--sha256"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::SHA256]);
-        let cli = CLI::parse(&["--sha512", "--sha512"]).unwrap();
+        let cli = CLI::parse(["--sha384", "--sha384"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA384]);
+        let cli = CLI::parse(["--sha512", "--sha512"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::SHA512]);
-        let cli = CLI::parse(&["--rmd160", "--rmd160"]).unwrap();
+        let cli = CLI::parse(["--sha512-224", "--sha512-224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA512_224]);
+        let cli = CLI::parse(["--sha512-256", "--sha512-256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA512_256]);
+        let cli = CLI::parse(["--sha3-224", "--sha3-224"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_224]);
+        let cli = CLI::parse(["--sha3-256", "--sha3-256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_256]);
+        let cli = CLI::parse(["--sha3-384", "--sha3-384"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_384]);
+        let cli = CLI::parse(["--sha3-512", "--sha3-512"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::SHA3_512]);
+        let cli = CLI::parse(["--keccak256", "--keccak256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::KECCAK256]);
+        let cli = CLI::parse(["--blake2b", "--blake2b"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::BLAKE2B]);
+        let cli = CLI::parse(["--blake2s", "--blake2s"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::BLAKE2S]);
+        let cli = CLI::parse(["--streebog256", "--streebog256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::STREEBOG256]);
+        let cli = CLI::parse(["--streebog512", "--streebog512"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::STREEBOG512]);
+        let cli = CLI::parse(["--tiger", "--tiger"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::TIGER]);
+        let cli = CLI::parse(["--tiger2", "--tiger2"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::TIGER2]);
+        let cli = CLI::parse(["--rmd256", "--rmd256"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RMD256]);
+        let cli = CLI::parse(["--rmd320", "--rmd320"]).unwrap();
+        assert_eq!(cli.digests, vec![Kind::RMD320]);
+        let cli = CLI::parse(["--rmd160", "--rmd160"]).unwrap();
         assert_eq!(cli.digests, vec![Kind::RMD160]);
     }
 
     #[test]
     fn parse_invalid_option() {
-        let err = CLI::parse(&["--foo"]).unwrap_err();
+        let err = CLI::parse(["--foo"]).unwrap_err();
         assert_eq!(err, Error::InvalidOption("--foo".to_string()));
+        let err = CLI::parse(["--md5=foo"]).unwrap_err();
+        assert_eq!(err, Error::InvalidOption("--md5=foo".to_string()));
+        let err = CLI::parse(["--crc16=foo"]).unwrap_err();
+        assert_eq!(err, Error::InvalidOption("--crc16=foo".to_string()));
     }
 
     #[test]
     fn usage_text() {
         assert!(CLI::USAGE.contains("--help"));
         assert!(CLI::USAGE.contains("--version"));
+        assert!(CLI::USAGE.contains("--crc16=ccitt"));
+        assert!(CLI::USAGE.contains("--crc16=modbus"));
+        assert!(CLI::USAGE.contains("--crc16=xmodem"));
         assert!(CLI::USAGE.contains("--crc32"));
+        assert!(CLI::USAGE.contains("--crc64"));
+        assert!(CLI::USAGE.contains("--xxh32"));
+        assert!(CLI::USAGE.contains("--xxh64"));
+        assert!(CLI::USAGE.contains("--rapidhash"));
+        assert!(CLI::USAGE.contains("--md2"));
+        assert!(CLI::USAGE.contains("--md4"));
         assert!(CLI::USAGE.contains("--md5"));
+        assert!(CLI::USAGE.contains("--sha224"));
         assert!(CLI::USAGE.contains("--sha256"));
+        assert!(CLI::USAGE.contains("--sha384"));
         assert!(CLI::USAGE.contains("--sha512"));
+        assert!(CLI::USAGE.contains("--sha512-224"));
+        assert!(CLI::USAGE.contains("--sha512-256"));
+        assert!(CLI::USAGE.contains("--sha3-224"));
+        assert!(CLI::USAGE.contains("--sha3-256"));
+        assert!(CLI::USAGE.contains("--sha3-384"));
+        assert!(CLI::USAGE.contains("--sha3-512"));
+        assert!(CLI::USAGE.contains("--keccak256"));
+        assert!(CLI::USAGE.contains("--blake2b"));
+        assert!(CLI::USAGE.contains("--blake2s"));
+        assert!(CLI::USAGE.contains("--streebog256"));
+        assert!(CLI::USAGE.contains("--streebog512"));
+        assert!(CLI::USAGE.contains("--tiger"));
+        assert!(CLI::USAGE.contains("--tiger2"));
+        assert!(CLI::USAGE.contains("--rmd256"));
+        assert!(CLI::USAGE.contains("--rmd320"));
         assert!(CLI::USAGE.contains("--rmd160"));
+        assert!(CLI::USAGE.contains("--key"));
+        assert!(CLI::USAGE.contains("--salt"));
+        assert!(CLI::USAGE.contains("--personal"));
+        assert!(CLI::USAGE.contains("--sfv"));
+        assert!(CLI::USAGE.contains("--csv"));
+        assert!(CLI::USAGE.contains("--yaml"));
+        assert!(CLI::USAGE.contains("--plain"));
+        assert!(CLI::USAGE.contains("--encoding"));
+        assert!(CLI::USAGE.contains("--base32"));
+        assert!(CLI::USAGE.contains("--no-padding"));
+        assert!(CLI::USAGE.contains("--with-size"));
+        assert!(CLI::USAGE.contains("--with-mtime"));
+        assert!(CLI::USAGE.contains("--output"));
+        assert!(CLI::USAGE.contains("--check"));
+        assert!(CLI::USAGE.contains("--quiet"));
+        assert!(CLI::USAGE.contains("--status"));
+        assert!(CLI::USAGE.contains("--warn"));
+        assert!(CLI::USAGE.contains("--strict"));
+        assert!(CLI::USAGE.contains("--json"));
+        assert!(CLI::USAGE.contains("--expect"));
+        assert!(CLI::USAGE.contains("--check-sidecars"));
+        assert!(CLI::USAGE.contains("--base-dir"));
+        assert!(CLI::USAGE.contains("--update"));
+        assert!(CLI::USAGE.contains("--dry-run"));
+        assert!(CLI::USAGE.contains("--glob"));
+        assert!(CLI::USAGE.contains("--exclude"));
+        assert!(CLI::USAGE.contains("--respect-gitignore"));
+        assert!(CLI::USAGE.contains("--follow-symlinks"));
+        assert!(CLI::USAGE.contains("--no-follow-symlinks"));
+        assert!(CLI::USAGE.contains("--symlink-literal"));
+        assert!(CLI::USAGE.contains("--max-depth"));
+        assert!(CLI::USAGE.contains("--recursive"));
+        assert!(CLI::USAGE.contains("--key-file"));
+        assert!(CLI::USAGE.contains("--offset"));
+        assert!(CLI::USAGE.contains("--size"));
+        assert!(CLI::USAGE.contains("--combine"));
+        assert!(CLI::USAGE.contains("--tree-digest"));
+        assert!(CLI::USAGE.contains("--sidecar"));
+        assert!(CLI::USAGE.contains("--force"));
+        assert!(CLI::USAGE.contains("--sort"));
+        assert!(CLI::USAGE.contains("--progress"));
+        assert!(CLI::USAGE.contains("--stats"));
+        assert!(CLI::USAGE.contains("--tee"));
+        assert!(CLI::USAGE.contains("--throttle"));
+        assert!(CLI::USAGE.contains("--direct-io"));
+        assert!(CLI::USAGE.contains("--no-threads"));
+        assert!(CLI::USAGE.contains("--background-priority"));
+        assert!(CLI::USAGE.contains("--keep-going"));
+        assert!(CLI::USAGE.contains("--fail-fast"));
+        assert!(CLI::USAGE.contains("--verbose"));
+        assert!(CLI::USAGE.contains("--jobs"));
+    }
+
+    #[test]
+    fn command_usage() {
+        for command in [
+            Command::Hash,
+            Command::Check,
+            Command::Bench,
+            Command::List,
+            Command::Dupes,
+            Command::Diff,
+        ] {
+            let usage = command.usage();
+            let name = command.name();
+            assert!(usage.starts_with(&format!("usage: checksum {name}")));
+            assert_eq!(Command::parse(name), Some(command));
+        }
+        assert!(
+            CLI::USAGE.contains("checksum hash|check|bench|list|dupes|diff")
+        );
     }
 }
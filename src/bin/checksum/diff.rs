@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// A file in either or both of two trees, given by its path relative to
+/// the root of each tree.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Pair<'a> {
+    /// The file is only in the left tree.
+    Left(&'a Path),
+    /// The file is only in the right tree.
+    Right(&'a Path),
+    /// The file is in both trees.
+    Both(&'a Path),
+}
+
+/// The paths of the `files` in the tree under `root`, relative to the
+/// root, sorted using a byte by byte comparison.
+pub fn relative(root: &Path, files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = files
+        .iter()
+        .map(|file| file.strip_prefix(root).unwrap_or(file).to_path_buf())
+        .collect();
+    paths.sort_by(|a, b| bytes(a).cmp(bytes(b)));
+    paths
+}
+
+/// Pair up the files in two trees, where `left` and `right` are the sorted
+/// relative paths from `relative()`. The pairs are in path order.
+pub fn pair<'a>(left: &'a [PathBuf], right: &'a [PathBuf]) -> Vec<Pair<'a>> {
+    let mut pairs = Vec::with_capacity(left.len().max(right.len()));
+    let (mut l, mut r) = (0, 0);
+    while l < left.len() || r < right.len() {
+        let order = match (left.get(l), right.get(r)) {
+            (Some(a), Some(b)) => bytes(a).cmp(bytes(b)),
+            (Some(_), None) => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        match order {
+            Ordering::Less => {
+                pairs.push(Pair::Left(&left[l]));
+                l += 1;
+            }
+            Ordering::Greater => {
+                pairs.push(Pair::Right(&right[r]));
+                r += 1;
+            }
+            Ordering::Equal => {
+                pairs.push(Pair::Both(&left[l]));
+                l += 1;
+                r += 1;
+            }
+        }
+    }
+    pairs
+}
+
+/// The bytes of a `path`, for a comparison which doesn't depend on the
+/// locale.
+fn bytes(path: &Path) -> &[u8] {
+    path.as_os_str().as_encoded_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths() {
+        let files =
+            ["dir/b", "dir/a/z", "dir/a.txt", "dir/a"].map(PathBuf::from);
+        assert_eq!(
+            relative(Path::new("dir"), &files),
+            ["a", "a.txt", "a/z", "b"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn pair_paths() {
+        let left = ["a", "b", "d", "e"].map(PathBuf::from);
+        let right = ["b", "c", "e", "f"].map(PathBuf::from);
+        assert_eq!(
+            pair(&left, &right),
+            [
+                Pair::Left(Path::new("a")),
+                Pair::Both(Path::new("b")),
+                Pair::Right(Path::new("c")),
+                Pair::Left(Path::new("d")),
+                Pair::Both(Path::new("e")),
+                Pair::Right(Path::new("f")),
+            ]
+        );
+        assert_eq!(pair(&left, &[]).len(), 4);
+        assert!(pair(&[], &[]).is_empty());
+    }
+}
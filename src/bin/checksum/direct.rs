@@ -0,0 +1,106 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use digest::Hasher;
+
+/// The alignment of the buffers, and of the offsets, used for direct I/O,
+/// which is enough for the logical block size of any common storage.
+pub const ALIGNMENT: usize = 4096;
+
+/// Open the file at `path` to be read with `--direct-io`, bypassing the
+/// page cache. On Linux the file is opened with `O_DIRECT`, unless the
+/// file system doesn't support it (such as tmpfs), in which case it is
+/// opened normally. Elsewhere the file is always opened normally.
+#[cfg(target_os = "linux")]
+pub fn open(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    match fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+    {
+        Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {
+            fs::File::open(path)
+        }
+        result => result,
+    }
+}
+
+/// Open the file at `path`, which can't bypass the page cache on this
+/// platform.
+#[cfg(not(target_os = "linux"))]
+pub fn open(path: &Path) -> io::Result<fs::File> {
+    fs::File::open(path)
+}
+
+/// A reader which reads its input in whole blocks into a buffer aligned to
+/// `ALIGNMENT`, as `O_DIRECT` requires, with `--direct-io`. The input must
+/// start at an aligned offset.
+pub struct DirectReader<R> {
+    input: R,
+    /// The buffer, which is larger than needed so that an aligned part of
+    /// it can be used.
+    buffer: Vec<u8>,
+    /// The offset of the aligned part of the buffer.
+    start: usize,
+    /// The range of the buffer which has been read but not yet returned.
+    pos: usize,
+    end: usize,
+}
+
+impl<R> DirectReader<R> {
+    pub fn new(input: R) -> Self {
+        let buffer = vec![0u8; Hasher::DEFAULT_BUFFER_SIZE + ALIGNMENT];
+        let start = buffer.as_ptr().align_offset(ALIGNMENT);
+        Self {
+            input,
+            buffer,
+            start,
+            pos: start,
+            end: start,
+        }
+    }
+}
+
+impl<R: Read> Read for DirectReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.end {
+            let block = self.start..self.start + Hasher::DEFAULT_BUFFER_SIZE;
+            let count = self.input.read(&mut self.buffer[block])?;
+            self.pos = self.start;
+            self.end = self.start + count;
+        }
+        let count = buf.len().min(self.end - self.pos);
+        buf[..count]
+            .copy_from_slice(&self.buffer[self.pos..self.pos + count]);
+        self.pos += count;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn direct_read() {
+        let path = Path::new("tests/fixtures/random-11171");
+        let mut reader = DirectReader::new(open(path).unwrap());
+        let aligned = reader.buffer[reader.start..].as_ptr();
+        assert_eq!(aligned as usize % ALIGNMENT, 0);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, fixtures::RANDOM_11171);
+
+        // Reads smaller than the buffer are served from it.
+        let mut reader = DirectReader::new(&fixtures::RANDOM_11171[..]);
+        let mut small = [0u8; 100];
+        assert_eq!(reader.read(&mut small).unwrap(), 100);
+        assert_eq!(small, fixtures::RANDOM_11171[..100]);
+        assert_eq!(reader.read(&mut small).unwrap(), 100);
+        assert_eq!(small, fixtures::RANDOM_11171[100..200]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use digest::{DigestData, Encoding};
+
+use crate::output::quoted_string;
+
+/// A group of files with identical contents.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Group {
+    /// The size of each file.
+    pub size: u64,
+    /// The digests of each file.
+    pub digests: Vec<DigestData>,
+    /// The paths of the files, in the order they were found.
+    pub paths: Vec<PathBuf>,
+}
+
+impl Group {
+    /// The space which would be reclaimed by removing every file in the
+    /// group but one.
+    pub fn reclaimable(&self) -> u64 {
+        self.size * (self.paths.len() as u64 - 1)
+    }
+
+    /// The group as lines of text: a line naming the digests and size,
+    /// followed by an indented line for each file.
+    pub fn lines(&self, encoding: Encoding) -> Vec<String> {
+        let digests: Vec<String> = self
+            .digests
+            .iter()
+            .map(|digest| {
+                format!("{} = {}", digest.name(), digest.encode(encoding))
+            })
+            .collect();
+        let mut lines = vec![format!(
+            "{}, {} files of {} bytes",
+            digests.join(", "),
+            self.paths.len(),
+            self.size
+        )];
+        lines.extend(
+            self.paths
+                .iter()
+                .map(|path| format!("    {}", path.display())),
+        );
+        lines
+    }
+
+    /// The group as a JSON object.
+    pub fn json(&self, encoding: Encoding) -> String {
+        let digests: Vec<String> = self
+            .digests
+            .iter()
+            .map(|digest| {
+                format!(
+                    "\"{}\": \"{}\"",
+                    digest.name(),
+                    digest.encode(encoding)
+                )
+            })
+            .collect();
+        let paths: Vec<String> = self
+            .paths
+            .iter()
+            .map(|path| quoted_string(&path.to_string_lossy()))
+            .collect();
+        format!(
+            "{{\"size\": {}, \"reclaimable\": {}, \"digests\": {{{}}}, \
+             \"paths\": [{}]}}",
+            self.size,
+            self.reclaimable(),
+            digests.join(", "),
+            paths.join(", ")
+        )
+    }
+}
+
+/// The files which might have duplicates, grouped by size, along with the
+/// files which couldn't be read.
+pub type Candidates<'a> = (Vec<(u64, Vec<&'a Path>)>, Vec<&'a Path>);
+
+/// Find the files among `paths` which share their size with another
+/// file, as only those can have a duplicate. The files are grouped by
+/// size, in order of size. Empty files are skipped, as nothing would be
+/// reclaimed by removing them. A file listed more than once, or linked to
+/// more than once, is only included once.
+pub fn candidates(paths: &[PathBuf]) -> Candidates<'_> {
+    let mut seen = HashSet::new();
+    let mut sizes: BTreeMap<u64, Vec<&Path>> = BTreeMap::new();
+    let mut unreadable = Vec::new();
+    for path in paths {
+        let Ok(meta) = fs::metadata(path) else {
+            unreadable.push(path.as_path());
+            continue;
+        };
+        if meta.is_file()
+            && meta.len() > 0
+            && seen.insert(identity(path, &meta))
+        {
+            sizes.entry(meta.len()).or_default().push(path);
+        }
+    }
+    let sizes = sizes
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    (sizes, unreadable)
+}
+
+/// The device and inode of a file, which identify it however it is
+/// reached.
+#[cfg(unix)]
+fn identity(_path: &Path, meta: &fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (meta.dev(), meta.ino())
+}
+
+/// The canonical path of a file, which identifies it however it is
+/// reached.
+#[cfg(not(unix))]
+fn identity(path: &Path, _meta: &fs::Metadata) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Group the `files` of the same `size` by their digests, returning the
+/// groups with more than one file, in the order their first files were
+/// listed.
+pub fn by_digest(
+    size: u64,
+    files: Vec<(&Path, Vec<DigestData>)>,
+) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (path, digests) in files {
+        match groups.iter_mut().find(|group| group.digests == digests) {
+            Some(group) => group.paths.push(path.to_path_buf()),
+            None => groups.push(Group {
+                size,
+                digests,
+                paths: vec![path.to_path_buf()],
+            }),
+        }
+    }
+    groups.retain(|group| group.paths.len() > 1);
+    groups
+}
+
+/// The summary of the duplicate files in the `groups`, giving how many
+/// files could be removed and the space that would reclaim.
+pub fn summary(groups: &[Group]) -> String {
+    let files: usize = groups.iter().map(|group| group.paths.len() - 1).sum();
+    let bytes: u64 = groups.iter().map(Group::reclaimable).sum();
+    match files {
+        1 => format!("1 duplicate file, {bytes} bytes reclaimable"),
+        files => {
+            format!("{files} duplicate files, {bytes} bytes reclaimable")
+        }
+    }
+}
+
+/// Format a JSON report of the `groups`, with the total number of
+/// duplicate files and the space which could be reclaimed.
+pub fn json_report(groups: &[Group], encoding: Encoding) -> String {
+    let mut report = String::from("{\n  \"groups\": [");
+    for (index, group) in groups.iter().enumerate() {
+        let separator = if index + 1 < groups.len() { "," } else { "" };
+        write!(report, "\n    {}{separator}", group.json(encoding)).unwrap();
+    }
+    if !groups.is_empty() {
+        report.push_str("\n  ");
+    }
+    let files: usize = groups.iter().map(|group| group.paths.len() - 1).sum();
+    let bytes: u64 = groups.iter().map(Group::reclaimable).sum();
+    write!(
+        report,
+        "],\n  \"duplicates\": {files},\n  \"reclaimable\": {bytes}\n}}"
+    )
+    .unwrap();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn find_candidates() {
+        let dir = fixtures::ScratchDir::new("dupes");
+        for (file, contents) in [
+            ("a", "abc"),
+            ("b", "xyz"),
+            ("c", "abcd"),
+            ("d", ""),
+            ("e", ""),
+        ] {
+            fs::write(dir.join(file), contents).unwrap();
+        }
+        let paths = ["a", "b", "c", "d", "e", "a", "missing"]
+            .map(|file| dir.join(file));
+        let (sizes, unreadable) = candidates(&paths);
+        assert_eq!(sizes, [(3, vec![paths[0].as_path(), &paths[1]])]);
+        assert_eq!(unreadable, [&paths[6]]);
+    }
+
+    #[test]
+    fn group_by_digest() {
+        let empty = vec![DigestData::MD5(fixtures::md5::EMPTY)];
+        let zero = vec![DigestData::MD5(fixtures::md5::ZERO_400D)];
+        let files = vec![
+            (Path::new("a"), empty.clone()),
+            (Path::new("b"), zero.clone()),
+            (Path::new("c"), empty.clone()),
+            (
+                Path::new("d"),
+                vec![DigestData::MD5(fixtures::md5::RANDOM_11171)],
+            ),
+            (Path::new("e"), empty.clone()),
+        ];
+        let groups = by_digest(100, files);
+        assert_eq!(
+            groups,
+            [Group {
+                size: 100,
+                digests: empty,
+                paths: ["a", "c", "e"].map(PathBuf::from).to_vec(),
+            }]
+        );
+        assert_eq!(groups[0].reclaimable(), 200);
+        assert_eq!(
+            groups[0].lines(Encoding::Hex),
+            [
+                "MD5 = d41d8cd98f00b204e9800998ecf8427e, 3 files of 100 bytes",
+                "    a",
+                "    c",
+                "    e",
+            ]
+        );
+        assert_eq!(
+            summary(&groups),
+            "2 duplicate files, 200 bytes reclaimable"
+        );
+        let group = Group {
+            size: 5,
+            digests: zero,
+            paths: ["x", "say \"y\""].map(PathBuf::from).to_vec(),
+        };
+        assert_eq!(
+            group.json(Encoding::Hex),
+            "{\"size\": 5, \"reclaimable\": 5, \
+             \"digests\": {\"MD5\": \"96f64e179f777e6eda0caa2d879356c9\"}, \
+             \"paths\": [\"x\", \"say \\\"y\\\"\"]}"
+        );
+        assert_eq!(
+            summary(&[group]),
+            "1 duplicate file, 5 bytes reclaimable"
+        );
+    }
+
+    #[test]
+    fn json_reports() {
+        assert_eq!(
+            json_report(&[], Encoding::Hex),
+            "{\n  \"groups\": [],\n  \"duplicates\": 0,\n  \
+             \"reclaimable\": 0\n}"
+        );
+        let group = Group {
+            size: 5,
+            digests: Vec::new(),
+            paths: ["x", "y", "z"].map(PathBuf::from).to_vec(),
+        };
+        assert_eq!(
+            json_report(&[group], Encoding::Hex),
+            "{\n  \"groups\": [\n    {\"size\": 5, \"reclaimable\": 10, \
+             \"digests\": {}, \"paths\": [\"x\", \"y\", \"z\"]}\n  ],\n  \
+             \"duplicates\": 2,\n  \"reclaimable\": 10\n}"
+        );
+    }
+}
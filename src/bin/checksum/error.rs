@@ -4,26 +4,198 @@ use std::path::PathBuf;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     InvalidOption(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnusedParameters,
+    InvalidParameters(String),
+    SfvDigest,
+    ConflictingOptions(String, String),
+    RequiredOption(String, String),
+    SidecarStdin,
+    TreeDigestStdin,
+    TeePaths,
+    CommandPaths(String),
+    DiffPaths,
+    CheckDigests,
+    CheckPaths,
+    CheckSidecarsPaths(PathBuf),
+    UpdateStdin,
+    ExpectPaths,
+    ExpectMismatch,
+    DirRead(PathBuf),
+    NoMatches(String),
+    SymlinkSkipped(PathBuf),
+    PriorityUnchanged,
+    MissingSidecars(usize),
+    NoChecksums(PathBuf),
+    ImproperLine(PathBuf, usize),
+    ImproperLines(usize),
+    UnreadableFiles(usize),
+    ChecksumMismatch(usize),
     FileOpen(PathBuf),
     FileRead(PathBuf),
+    FileCreate(PathBuf),
+    FileWrite(PathBuf),
     StdinRead,
+    OutputWrite,
+    Interrupted(usize),
+    UnavailableDigest(String),
+    DigestFailed(String),
 }
 
 impl fmt::Display for Error {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::InvalidOption(option) => {
-                write!(f, "invalid option '{}'", option)
+                write!(f, "invalid option '{option}'")
+            }
+            Error::MissingValue(option) => {
+                write!(f, "missing value for option '{option}'")
+            }
+            Error::InvalidValue(option, value) => {
+                write!(f, "invalid value '{value}' for option '{option}'")
+            }
+            Error::UnusedParameters => write!(
+                f,
+                "'--key', '--key-file', '--salt', and '--personal' require a \
+                 BLAKE2 digest"
+            ),
+            Error::InvalidParameters(digest) => {
+                write!(f, "the BLAKE2 parameters are too long for {digest}")
+            }
+            Error::SfvDigest => {
+                write!(f, "'--sfv' only supports the CRC32 checksum")
+            }
+            Error::ConflictingOptions(option, other) => {
+                write!(f, "'{option}' can't be used with '{other}'")
+            }
+            Error::RequiredOption(option, required) => {
+                write!(f, "'{option}' requires '{required}'")
+            }
+            Error::SidecarStdin => {
+                write!(f, "'--sidecar' requires at least one file")
+            }
+            Error::TreeDigestStdin => {
+                write!(f, "'--tree-digest' requires at least one directory")
+            }
+            Error::TeePaths => {
+                write!(f, "'--tee' only reads stdin")
+            }
+            Error::CommandPaths(command) => {
+                write!(f, "'{command}' doesn't take any files")
+            }
+            Error::DiffPaths => {
+                write!(f, "'diff' requires exactly two directories")
+            }
+            Error::CheckDigests => {
+                write!(f, "'--check' can only use one checksum option")
+            }
+            Error::CheckPaths => write!(
+                f,
+                "'--check' verifies the files listed in the manifest"
+            ),
+            Error::CheckSidecarsPaths(dir) => {
+                let dirstr = dir.to_str().unwrap();
+                write!(
+                    f,
+                    "'--check-sidecars' verifies the files in '{dirstr}'"
+                )
+            }
+            Error::UpdateStdin => {
+                write!(
+                    f,
+                    "'--update' can't rewrite a manifest read from stdin"
+                )
+            }
+            Error::ExpectPaths => {
+                write!(f, "'--expect' can only check a single file")
+            }
+            Error::ExpectMismatch => {
+                write!(f, "the computed checksum did NOT match")
+            }
+            Error::NoChecksums(path) => {
+                let pathstr = path.to_str().unwrap();
+                write!(
+                    f,
+                    "no properly formatted checksum lines in '{pathstr}'"
+                )
+            }
+            Error::ImproperLine(path, line) => {
+                let pathstr = path.to_str().unwrap();
+                write!(
+                    f,
+                    "'{pathstr}': {line}: improperly formatted checksum line"
+                )
+            }
+            Error::ImproperLines(1) => {
+                write!(f, "WARNING: 1 line is improperly formatted")
+            }
+            Error::ImproperLines(count) => {
+                write!(f, "WARNING: {count} lines are improperly formatted")
+            }
+            Error::UnreadableFiles(1) => {
+                write!(f, "WARNING: 1 listed file could not be read")
+            }
+            Error::UnreadableFiles(count) => {
+                write!(f, "WARNING: {count} listed files could not be read")
+            }
+            Error::MissingSidecars(1) => {
+                write!(f, "WARNING: 1 file has no sidecar")
+            }
+            Error::MissingSidecars(count) => {
+                write!(f, "WARNING: {count} files have no sidecar")
+            }
+            Error::ChecksumMismatch(1) => {
+                write!(f, "WARNING: 1 computed checksum did NOT match")
+            }
+            Error::ChecksumMismatch(count) => {
+                write!(f, "WARNING: {count} computed checksums did NOT match")
             }
             Error::FileOpen(path) => {
                 let pathstr = path.to_str().unwrap();
-                write!(f, "unable to open '{}'", pathstr)
+                write!(f, "unable to open '{pathstr}'")
             }
             Error::FileRead(path) => {
                 let pathstr = path.to_str().unwrap();
-                write!(f, "unable to read from '{}'", pathstr)
+                write!(f, "unable to read from '{pathstr}'")
+            }
+            Error::DirRead(path) => {
+                let pathstr = path.to_str().unwrap();
+                write!(f, "unable to read the directory '{pathstr}'")
+            }
+            Error::NoMatches(pattern) => {
+                write!(f, "no files match '{pattern}'")
+            }
+            Error::SymlinkSkipped(path) => {
+                let pathstr = path.to_str().unwrap();
+                write!(f, "skipping the symbolic link '{pathstr}'")
+            }
+            Error::PriorityUnchanged => {
+                write!(f, "unable to lower the priority, continuing anyway")
+            }
+            Error::FileCreate(path) => {
+                let pathstr = path.to_str().unwrap();
+                write!(f, "unable to create '{pathstr}'")
+            }
+            Error::FileWrite(path) => {
+                let pathstr = path.to_str().unwrap();
+                write!(f, "unable to write to '{pathstr}'")
             }
             Error::StdinRead => write!(f, "unable to read from stdin"),
+            Error::OutputWrite => write!(f, "unable to write the output"),
+            Error::Interrupted(1) => {
+                write!(f, "interrupted, skipping 1 remaining file")
+            }
+            Error::Interrupted(count) => {
+                write!(f, "interrupted, skipping {count} remaining files")
+            }
+            Error::UnavailableDigest(digest) => {
+                write!(f, "the {digest} digest is not available")
+            }
+            Error::DigestFailed(digest) => {
+                write!(f, "the {digest} digest could not be computed")
+            }
         }
     }
 }
@@ -35,12 +207,177 @@ mod tests {
     #[test]
     fn format_error() {
         let error = Error::InvalidOption(String::from("--foo"));
-        assert_eq!(format!("{}", error), "invalid option '--foo'");
+        assert_eq!(format!("{error}"), "invalid option '--foo'");
+        let error = Error::MissingValue(String::from("--key"));
+        assert_eq!(format!("{error}"), "missing value for option '--key'");
+        let error =
+            Error::InvalidValue(String::from("--key"), String::from("xy"));
+        assert_eq!(
+            format!("{error}"),
+            "invalid value 'xy' for option '--key'"
+        );
+        let error = Error::UnusedParameters;
+        assert_eq!(
+            format!("{error}"),
+            "'--key', '--key-file', '--salt', and '--personal' require a \
+             BLAKE2 digest"
+        );
+        let error = Error::InvalidParameters(String::from("BLAKE2s"));
+        assert_eq!(
+            format!("{error}"),
+            "the BLAKE2 parameters are too long for BLAKE2s"
+        );
+        let error = Error::SfvDigest;
+        assert_eq!(
+            format!("{error}"),
+            "'--sfv' only supports the CRC32 checksum"
+        );
+        let error = Error::ConflictingOptions(
+            String::from("--sidecar"),
+            String::from("--output"),
+        );
+        assert_eq!(
+            format!("{error}"),
+            "'--sidecar' can't be used with '--output'"
+        );
+        let error = Error::RequiredOption(
+            String::from("--force"),
+            String::from("--sidecar"),
+        );
+        assert_eq!(format!("{error}"), "'--force' requires '--sidecar'");
+        let error = Error::SidecarStdin;
+        assert_eq!(
+            format!("{error}"),
+            "'--sidecar' requires at least one file"
+        );
+        let error = Error::TreeDigestStdin;
+        assert_eq!(
+            format!("{error}"),
+            "'--tree-digest' requires at least one directory"
+        );
+        let error = Error::TeePaths;
+        assert_eq!(format!("{error}"), "'--tee' only reads stdin");
+        let error = Error::CommandPaths(String::from("list"));
+        assert_eq!(format!("{error}"), "'list' doesn't take any files");
+        let error = Error::DiffPaths;
+        assert_eq!(
+            format!("{error}"),
+            "'diff' requires exactly two directories"
+        );
         let error = Error::FileOpen(PathBuf::from("foo"));
-        assert_eq!(format!("{}", error), "unable to open 'foo'");
+        assert_eq!(format!("{error}"), "unable to open 'foo'");
         let error = Error::FileRead(PathBuf::from("foo"));
-        assert_eq!(format!("{}", error), "unable to read from 'foo'");
+        assert_eq!(format!("{error}"), "unable to read from 'foo'");
+        let error = Error::DirRead(PathBuf::from("dir"));
+        assert_eq!(format!("{error}"), "unable to read the directory 'dir'");
+        let error = Error::NoMatches(String::from("*.iso"));
+        assert_eq!(format!("{error}"), "no files match '*.iso'");
+        let error = Error::SymlinkSkipped(PathBuf::from("foo"));
+        assert_eq!(format!("{error}"), "skipping the symbolic link 'foo'");
+        let error = Error::PriorityUnchanged;
+        assert_eq!(
+            format!("{error}"),
+            "unable to lower the priority, continuing anyway"
+        );
+        let error = Error::FileCreate(PathBuf::from("foo"));
+        assert_eq!(format!("{error}"), "unable to create 'foo'");
+        let error = Error::FileWrite(PathBuf::from("foo"));
+        assert_eq!(format!("{error}"), "unable to write to 'foo'");
         let error = Error::StdinRead;
-        assert_eq!(format!("{}", error), "unable to read from stdin");
+        assert_eq!(format!("{error}"), "unable to read from stdin");
+        let error = Error::OutputWrite;
+        assert_eq!(format!("{error}"), "unable to write the output");
+        let error = Error::Interrupted(1);
+        assert_eq!(
+            format!("{error}"),
+            "interrupted, skipping 1 remaining file"
+        );
+        let error = Error::Interrupted(4);
+        assert_eq!(
+            format!("{error}"),
+            "interrupted, skipping 4 remaining files"
+        );
+        let error = Error::UnavailableDigest(String::from("MD4"));
+        assert_eq!(format!("{error}"), "the MD4 digest is not available");
+        let error = Error::DigestFailed(String::from("MD4"));
+        assert_eq!(
+            format!("{error}"),
+            "the MD4 digest could not be computed"
+        );
+    }
+
+    #[test]
+    fn format_check_error() {
+        let error = Error::CheckDigests;
+        assert_eq!(
+            format!("{error}"),
+            "'--check' can only use one checksum option"
+        );
+        let error = Error::CheckPaths;
+        assert_eq!(
+            format!("{error}"),
+            "'--check' verifies the files listed in the manifest"
+        );
+        let error = Error::CheckSidecarsPaths(PathBuf::from("dir"));
+        assert_eq!(
+            format!("{error}"),
+            "'--check-sidecars' verifies the files in 'dir'"
+        );
+        let error = Error::UpdateStdin;
+        assert_eq!(
+            format!("{error}"),
+            "'--update' can't rewrite a manifest read from stdin"
+        );
+        let error = Error::ExpectPaths;
+        assert_eq!(
+            format!("{error}"),
+            "'--expect' can only check a single file"
+        );
+        let error = Error::ExpectMismatch;
+        assert_eq!(format!("{error}"), "the computed checksum did NOT match");
+        let error = Error::NoChecksums(PathBuf::from("SUMS"));
+        assert_eq!(
+            format!("{error}"),
+            "no properly formatted checksum lines in 'SUMS'"
+        );
+        let error = Error::ImproperLine(PathBuf::from("SUMS"), 3);
+        assert_eq!(
+            format!("{error}"),
+            "'SUMS': 3: improperly formatted checksum line"
+        );
+        let error = Error::ImproperLines(1);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 1 line is improperly formatted"
+        );
+        let error = Error::ImproperLines(2);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 2 lines are improperly formatted"
+        );
+        let error = Error::UnreadableFiles(1);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 1 listed file could not be read"
+        );
+        let error = Error::UnreadableFiles(2);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 2 listed files could not be read"
+        );
+        let error = Error::MissingSidecars(1);
+        assert_eq!(format!("{error}"), "WARNING: 1 file has no sidecar");
+        let error = Error::MissingSidecars(2);
+        assert_eq!(format!("{error}"), "WARNING: 2 files have no sidecar");
+        let error = Error::ChecksumMismatch(1);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 1 computed checksum did NOT match"
+        );
+        let error = Error::ChecksumMismatch(3);
+        assert_eq!(
+            format!("{error}"),
+            "WARNING: 3 computed checksums did NOT match"
+        );
     }
 }
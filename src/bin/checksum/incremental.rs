@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use digest::manifest::Entry;
+use digest::DigestData;
+
+use crate::check;
+use crate::output::timestamp;
+use crate::DigestKind;
+
+/// What a manifest records about a file.
+#[derive(Debug, Default)]
+struct Recorded {
+    /// The digests of the file, with their kinds.
+    digests: Vec<(DigestKind, DigestData)>,
+    /// The size of the file, if it was recorded with `--with-size`.
+    size: Option<u64>,
+    /// The modification time of the file, if it was recorded with
+    /// `--with-mtime`.
+    mtime: Option<String>,
+}
+
+/// The digests recorded in a manifest which is being updated with
+/// `--update`, so that the files which haven't changed since it was
+/// written don't need to be read again.
+#[derive(Debug, Default)]
+pub struct Previous {
+    /// What the manifest records about each file.
+    files: HashMap<PathBuf, Recorded>,
+    /// When the manifest was written, if known.
+    written: Option<SystemTime>,
+    /// Whether symbolic links are digested literally, with
+    /// `--symlink-literal`, so a link's own size and modification time
+    /// are recorded rather than those of its target.
+    literal: bool,
+}
+
+impl Previous {
+    /// Parse the `lines` of a manifest which was `written` at the given
+    /// time. Lines which aren't properly formatted are skipped, and the
+    /// digests on lines which don't name their kind are taken to be the
+    /// `selected` kind, or the default kind for their length. If links
+    /// are digested `literal`ly then they aren't followed when checking
+    /// whether a file has changed.
+    pub fn parse<'a>(
+        lines: impl IntoIterator<Item = &'a str>,
+        written: Option<SystemTime>,
+        selected: Option<DigestKind>,
+        literal: bool,
+    ) -> Self {
+        let mut files: HashMap<PathBuf, Recorded> = HashMap::new();
+        for line in lines {
+            let line = line.trim_end_matches('\r');
+            let Ok(entry) = Entry::parse(line) else {
+                continue;
+            };
+            let Some((kind, digest)) = entry
+                .kind
+                .or(selected)
+                .or_else(|| check::default_kind(&entry.digest))
+                .and_then(|kind| {
+                    let digest = check::digest_data(kind, &entry.digest)?;
+                    Some((kind, digest))
+                })
+            else {
+                continue;
+            };
+            let recorded = files.entry(entry.path).or_default();
+            recorded.digests.push((kind, digest));
+            recorded.size = recorded.size.or(entry.size);
+            if entry.mtime.is_some() {
+                recorded.mtime = entry.mtime;
+            }
+        }
+        Self {
+            files,
+            written,
+            literal,
+        }
+    }
+
+    /// The digests of each of the `kinds` recorded for the file at `path`,
+    /// along with its size and modification time, if the file hasn't
+    /// changed. A file has changed if its size or modification time
+    /// differs from the one recorded, or, if no modification time was
+    /// recorded, it was modified after the manifest was written. As the
+    /// modification time is only recorded to the second, a file modified
+    /// in the second the manifest was written may have changed since, so
+    /// is taken to have changed. If the file has changed, or any of the
+    /// digests weren't recorded, then `None` is returned.
+    pub fn digests(
+        &self,
+        path: &Path,
+        kinds: &[DigestKind],
+    ) -> Option<(Vec<DigestData>, u64, Option<SystemTime>)> {
+        let recorded = self.files.get(path)?;
+        let meta = if self.literal {
+            fs::symlink_metadata(path)
+        } else {
+            fs::metadata(path)
+        };
+        let meta = meta
+            .ok()
+            .filter(|meta| meta.is_file() || meta.is_symlink())?;
+        let mtime = meta.modified().ok()?;
+        if recorded.size.is_some_and(|size| size != meta.len()) {
+            return None;
+        }
+        let unchanged = match &recorded.mtime {
+            Some(recorded) => {
+                *recorded == timestamp(mtime)
+                    && end_of_second(mtime)
+                        .zip(self.written)
+                        .is_some_and(|(end, written)| end <= written)
+            }
+            None => self.written.is_some_and(|written| mtime < written),
+        };
+        if !unchanged {
+            return None;
+        }
+        let digests = kinds
+            .iter()
+            .map(|kind| {
+                recorded
+                    .digests
+                    .iter()
+                    .find(|(recorded, _)| recorded == kind)
+                    .map(|(_, digest)| *digest)
+            })
+            .collect::<Option<_>>()?;
+        Some((digests, meta.len(), Some(mtime)))
+    }
+}
+
+/// The end of the second `time` is in, which is the earliest time a file
+/// modified within the second recorded by `timestamp()` can be known not to
+/// have been modified since. Times before the epoch aren't handled.
+fn end_of_second(time: SystemTime) -> Option<SystemTime> {
+    let since = time.duration_since(UNIX_EPOCH).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(since.as_secs() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn parse_manifest() {
+        let lines = [
+            "MD5 (foo) = d41d8cd98f00b204e9800998ecf8427e size=0",
+            "CRC32 (foo) = 00000000 mtime=2023-11-14T22:13:20Z",
+            "this line is ignored",
+            "96f64e179f777e6eda0caa2d879356c9  bar",
+        ];
+        let previous = Previous::parse(lines, None, None, false);
+        assert_eq!(previous.files.len(), 2);
+        let foo = &previous.files[Path::new("foo")];
+        assert_eq!(
+            foo.digests,
+            [
+                (DigestKind::MD5, DigestData::MD5(fixtures::md5::EMPTY)),
+                (DigestKind::CRC32, DigestData::CRC32([0; 4])),
+            ]
+        );
+        assert_eq!(foo.size, Some(0));
+        assert_eq!(foo.mtime.as_deref(), Some("2023-11-14T22:13:20Z"));
+        let bar = &previous.files[Path::new("bar")];
+        assert_eq!(
+            bar.digests,
+            [(DigestKind::MD5, DigestData::MD5(fixtures::md5::ZERO_400D))]
+        );
+        assert_eq!(bar.size, None);
+        assert_eq!(bar.mtime, None);
+    }
+
+    #[test]
+    fn reuse_digests() {
+        let dir = fixtures::ScratchDir::new("incremental");
+        let path = dir.join("file");
+        fs::write(&path, "").unwrap();
+        let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        let md5 = DigestData::MD5(fixtures::md5::EMPTY);
+        let line = |columns: &str| {
+            format!(
+                "MD5 ({}) = d41d8cd98f00b204e9800998ecf8427e{columns}",
+                path.display()
+            )
+        };
+        let digests = |line: &str, written| {
+            Previous::parse([line], written, None, false)
+                .digests(&path, &[DigestKind::MD5])
+        };
+
+        // Without a modification time, the file mustn't have been modified
+        // after the manifest was written.
+        let later = Some(mtime + Duration::from_secs(1));
+        assert_eq!(
+            digests(&line(""), later),
+            Some((vec![md5], 0, Some(mtime)))
+        );
+        assert_eq!(digests(&line(""), Some(mtime)), None);
+        assert_eq!(digests(&line(""), None), None);
+        assert_eq!(digests(&line(" size=1"), later), None);
+
+        // With a modification time, the manifest must have been written
+        // after the end of the second the file was modified in.
+        let listed = format!(" size=0 mtime={}", timestamp(mtime));
+        assert_eq!(
+            digests(&line(&listed), later),
+            Some((vec![md5], 0, Some(mtime)))
+        );
+        assert_eq!(digests(&line(&listed), Some(mtime)), None);
+        assert_eq!(digests(&line(&listed), None), None);
+        let earlier = timestamp(mtime - Duration::from_secs(1));
+        assert_eq!(digests(&line(&format!(" mtime={earlier}")), later), None);
+
+        // Every kind of digest must be listed.
+        let previous =
+            Previous::parse([line("").as_str()], later, None, false);
+        let kinds = [DigestKind::MD5, DigestKind::CRC32];
+        assert_eq!(previous.digests(&path, &kinds), None);
+        assert_eq!(previous.digests(&dir.join("missing"), &kinds), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reuse_literal_links() {
+        let dir = fixtures::ScratchDir::new("incremental-links");
+        let link = dir.join("link");
+        std::os::unix::fs::symlink("target", &link).unwrap();
+        let meta = fs::symlink_metadata(&link).unwrap();
+        let mtime = meta.modified().unwrap();
+        let later = Some(mtime + Duration::from_secs(1));
+        let listed = format!(
+            "MD5 ({}) = 00000000000000000000000000000000 size={} mtime={}",
+            link.display(),
+            meta.len(),
+            timestamp(mtime)
+        );
+        let digests = |literal| {
+            Previous::parse([listed.as_str()], later, None, literal)
+                .digests(&link, &[DigestKind::MD5])
+        };
+
+        // The link's own size and modification time are compared, even
+        // though its target doesn't exist.
+        let md5 = DigestData::MD5([0; 16]);
+        assert_eq!(digests(true), Some((vec![md5], meta.len(), Some(mtime))));
+        assert_eq!(digests(false), None);
+    }
+}
@@ -1,70 +1,488 @@
 #![warn(clippy::all, clippy::pedantic)]
 
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::io;
+use std::io::{self, BufRead, Read, Seek, Write};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Instant, SystemTime};
 
-use digest::{crc32, md5, rmd160, sha256, sha512, DigestData, Generator};
+use digest::walk::{self, Skip, Symlinks};
+use digest::{
+    blake2b_with, blake2s_with, glob, inline_blake2b_with,
+    inline_blake2s_with, Backend, Blake2Params, DigestData, Encoding,
+    Generator, Hasher,
+};
+
+pub use digest::DigestKind;
 
 mod error;
 use error::Error;
 
 mod cli;
-use cli::CLI;
+use cli::{Command, CLI};
+
+mod output;
+use output::{Columns, Entry, Format};
+
+mod atomic;
+use atomic::AtomicFile;
+
+mod sidecar;
+
+mod check;
+mod diff;
+mod direct;
+mod dupes;
+mod incremental;
+mod pool;
+mod priority;
+mod progress;
+mod signal;
+mod stats;
+mod throttle;
+use check::{Checksum, Report, Status, Summary};
+use incremental::Previous;
+use progress::Progress;
+use throttle::Throttled;
 
 /// The version number of the program.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum DigestKind {
-    CRC32,
-    MD5,
-    SHA256,
-    SHA512,
-    RMD160,
-}
-
 fn main() {
     let mut args = std::env::args_os();
     let _program = args.next();
-    let cli = match CLI::parse(args) {
+    let mut cli = match CLI::parse(args) {
         Ok(cli) => cli,
         Err(error) => {
-            eprintln!("{}", error);
+            eprintln!("{error}");
             std::process::exit(1)
         }
     };
+    let start = Instant::now();
+    let result = run(&mut cli);
+    if cli.stats {
+        let stats = stats::Stats::current();
+        eprintln!("{}", stats.line(start.elapsed()));
+    }
+    // The config is dropped before exiting, so the key is wiped.
+    drop(cli);
+    // An interrupted run exits as though it was killed by the signal.
+    if let Some(signal) = signal::received() {
+        std::process::exit(128 + signal);
+    }
+    if result.is_err() {
+        std::process::exit(1);
+    }
+}
+
+/// Run the command selected by the config. Any errors have already been
+/// printed when an error is returned.
+fn run(cli: &mut CLI) -> Result<(), ()> {
+    if cli.background && priority::lower().is_err() {
+        print_error(&Error::PriorityUnchanged);
+    }
+    skip_symlinks(cli);
+    expand_globs(cli)?;
+    expand_dirs(cli)?;
+    read_key(cli)?;
 
     if cli.help {
-        show_usage();
+        show_usage(cli);
+        Ok(())
     } else if cli.version {
         show_version();
-    } else if cli.paths.is_empty() {
-        digest_stdin(&cli.digests)
-            .unwrap_or_else(|_err| std::process::exit(1));
+        Ok(())
+    } else if cli.command == Some(Command::List) {
+        list(cli)
+    } else if cli.command == Some(Command::Bench) {
+        bench(cli)
+    } else if cli.command == Some(Command::Dupes) {
+        dupes(cli)
+    } else if cli.command == Some(Command::Diff) {
+        diff(cli)
+    } else if let Some(manifest) = &cli.check {
+        check(cli, manifest)
+    } else if let Some(dir) = &cli.check_sidecars {
+        check_sidecars(cli, dir)
+    } else if !cli.expect.is_empty() {
+        expect(cli)
+    } else if cli.tee {
+        tee(cli)
+    } else if cli.sidecar {
+        digest_sidecars(cli)
+    } else if let Some(path) = &cli.output {
+        digest_to_file(cli, path)
     } else {
-        digest_files(&cli.digests, &cli.paths)
-            .unwrap_or_else(|_err| std::process::exit(1));
+        digest(cli, &mut io::stdout().lock())
     }
 }
 
-/// Show the usage text.
-fn show_usage() {
-    print!("{}", CLI::USAGE);
+/// Remove any symbolic links from the paths in the config, if links
+/// aren't followed, printing a warning for each one which is skipped.
+fn skip_symlinks(cli: &mut CLI) {
+    let symlinks = cli.symlinks;
+    cli.paths.retain(|path| {
+        let skipped = walk::is_unfollowed(path, symlinks);
+        if skipped {
+            print_error(&Error::SymlinkSkipped(path.clone()));
+        }
+        !skipped
+    });
+}
+
+/// Expand the `--glob` patterns in the config, adding the files which
+/// match them to the paths. Any files which are excluded, or ignored by
+/// git with `--respect-gitignore`, are skipped. A pattern which doesn't
+/// match any files is an error, as the shell would leave it unexpanded.
+fn expand_globs(cli: &mut CLI) -> Result<(), ()> {
+    let mut paths = Vec::new();
+    for pattern in &cli.globs {
+        let expanded = glob::expand(pattern, walk_options(cli))
+            .map_err(|dir| print_error(&Error::DirRead(dir)))?;
+        if expanded.is_empty() {
+            print_error(&Error::NoMatches(pattern.clone()));
+            return Err(());
+        }
+        paths.extend(expanded);
+    }
+    cli.paths.extend(paths);
+    Ok(())
+}
+
+/// Replace each directory in the paths with the files in the tree below
+/// it, with `--recursive`. The tree is walked as it would be for a
+/// `--glob` pattern.
+fn expand_dirs(cli: &mut CLI) -> Result<(), ()> {
+    if !cli.recursive {
+        return Ok(());
+    }
+    let mut paths = Vec::new();
+    for path in &cli.paths {
+        if path.is_dir() {
+            let files = walk::files(path, walk_options(cli))
+                .map_err(|dir| print_error(&Error::DirRead(dir)))?;
+            paths.extend(files);
+        } else {
+            paths.push(path.clone());
+        }
+    }
+    cli.paths = paths;
+    Ok(())
+}
+
+/// Read the BLAKE2 key from the file given by `--key-file`, if any. No
+/// more than one byte past the longest key is read, so a longer key is
+/// still rejected without leaving copies of it behind.
+fn read_key(cli: &mut CLI) -> Result<(), ()> {
+    const LIMIT: usize = 65;
+    let Some(path) = &cli.key_file else {
+        return Ok(());
+    };
+    let file = fs::File::open(path)
+        .map_err(|_| print_error(&Error::FileOpen(path.clone())))?;
+    let mut key = Vec::with_capacity(LIMIT);
+    file.take(LIMIT as u64)
+        .read_to_end(&mut key)
+        .map_err(|_| print_error(&Error::FileRead(path.clone())))?;
+    cli.params.key = key;
+    Ok(())
+}
+
+/// The options for walking a directory tree, as set in the config.
+fn walk_options(cli: &CLI) -> walk::Options<'_> {
+    walk::Options {
+        exclude: &cli.exclude,
+        gitignore: cli.gitignore,
+        symlinks: cli.symlinks,
+        max_depth: cli.max_depth,
+        log: (cli.verbose > 0).then_some(log_skipped),
+    }
+}
+
+/// Log a file or directory which was skipped while walking a tree, and
+/// why, with `--verbose`.
+fn log_skipped(path: &Path, skip: Skip) {
+    eprintln!("skipping '{}': {skip}", path.display());
+}
+
+/// Show the usage text, for the subcommand if there is one.
+fn show_usage(cli: &CLI) {
+    print!("{}", cli.usage());
 }
 
 /// Show the program version.
 fn show_version() {
-    println!("checksum {}", VERSION);
+    println!("checksum {VERSION}");
 }
 
-fn digest_stdin(digests: &[DigestKind]) -> Result<(), ()> {
-    // Create the generators based on the digests listed in the config.
-    let generators = create_generators(digests);
+/// List each kind of digest, with the option which selects it, its name,
+/// and its length, or whether it isn't available.
+fn list(cli: &CLI) -> Result<(), ()> {
+    let mut output = io::stdout().lock();
+    for (option, kind) in cli::DIGEST_OPTIONS {
+        let name = kind.name();
+        let params = Blake2Params::default();
+        // Only whether the digest is available matters, so no threads are
+        // started for it.
+        let length =
+            match create_generators(&[kind], &params, cli.backend, false) {
+                Ok(_) => format!("{} bits", kind.output_len() * 8),
+                Err(_err) => String::from("unavailable"),
+            };
+        writeln!(output, "{option:<16}{name:<14}{length}")
+            .map_err(|_err| print_write_error())?;
+    }
+    Ok(())
+}
+
+/// The number of bytes each digest is computed over by `bench`.
+const BENCH_SIZE: u64 = 0x400_0000;
+
+/// Measure how quickly each of the digests listed in the config can be
+/// computed, printing the throughput of each one.
+fn bench(cli: &CLI) -> Result<(), ()> {
+    let mut output = io::stdout().lock();
+    for kind in &cli.digests {
+        let name = kind.name();
+        let Ok(generators) = create_generators(
+            &[*kind],
+            &cli.params,
+            cli.backend,
+            cli.threads,
+        ) else {
+            writeln!(output, "{name:<14}unavailable")
+                .map_err(|_err| print_write_error())?;
+            continue;
+        };
+        let data = io::repeat(0x5a).take(BENCH_SIZE);
+        let start = Instant::now();
+        let Ok((_digests, size)) = digest_file(data, &generators) else {
+            print_error(&Error::DigestFailed(name.to_string()));
+            return Err(());
+        };
+        let millis = start.elapsed().as_millis().max(1);
+        let rate = u128::from(size) * 1000 / millis;
+        let rate = progress::bytes(u64::try_from(rate).unwrap_or(u64::MAX));
+        writeln!(output, "{name:<14}{rate}/s")
+            .map_err(|_err| print_write_error())?;
+    }
+    Ok(())
+}
+
+/// Find the groups of identical files among the files listed in the
+/// config, with the `dupes` subcommand, printing each group and the space
+/// which could be reclaimed, or a JSON report with `--json`. Only files
+/// which share their size with another file are digested, using up to
+/// `--jobs` workers.
+fn dupes(cli: &CLI) -> Result<(), ()> {
+    let (sizes, unreadable) = dupes::candidates(&cli.paths);
+    let mut error = !unreadable.is_empty();
+    for path in unreadable {
+        print_error(&Error::FileOpen(path.to_path_buf()));
+    }
+
+    let items: Vec<(u64, &Path)> = sizes
+        .iter()
+        .flat_map(|(size, paths)| paths.iter().map(|path| (*size, *path)))
+        .collect();
+    let mut digested: BTreeMap<u64, Vec<_>> = BTreeMap::new();
+    pool::map_largest_first(
+        &items,
+        cli.jobs.unwrap_or(1),
+        |(size, _path)| *size,
+        || {
+            create_generators(
+                &cli.digests,
+                &cli.params,
+                cli.backend,
+                cli.threads,
+            )
+        },
+        |generators, (_size, path)| {
+            let generators = generators.as_ref().map_err(Clone::clone)?;
+            digest_path(path, generators, cli, None)
+        },
+        |(size, path), result| {
+            match result {
+                Ok((digests, _size, _mtime)) => {
+                    digested.entry(*size).or_default().push((*path, digests));
+                }
+                Err(err) => {
+                    print_error(&err);
+                    error = true;
+                }
+            }
+            Ok::<(), ()>(())
+        },
+    )?;
+    let groups: Vec<dupes::Group> = digested
+        .into_iter()
+        .flat_map(|(size, files)| dupes::by_digest(size, files))
+        .collect();
+
+    let mut output = io::stdout().lock();
+    let written = if cli.json {
+        writeln!(output, "{}", dupes::json_report(&groups, cli.encoding))
+    } else {
+        groups
+            .iter()
+            .try_for_each(|group| {
+                for line in group.lines(cli.encoding) {
+                    writeln!(output, "{line}")?;
+                }
+                writeln!(output)
+            })
+            .and_then(|()| writeln!(output, "{}", dupes::summary(&groups)))
+    };
+    written.map_err(|_err| print_write_error())?;
+
+    if error {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Compare the trees under the two directories listed in the config, with
+/// the `diff` subcommand, printing each file which is only in one of them,
+/// and each file in both which differs. Files are compared by size, and
+/// only digested if their sizes match, using up to `--jobs` workers. An
+/// error is returned if there are any differences.
+fn diff(cli: &CLI) -> Result<(), ()> {
+    let (left, right) = (&cli.paths[0], &cli.paths[1]);
+    let tree = |dir: &Path| {
+        walk::files(dir, walk_options(cli))
+            .map(|files| diff::relative(dir, &files))
+            .map_err(|dir| print_error(&Error::DirRead(dir)))
+    };
+    let (left_files, right_files) = (tree(left)?, tree(right)?);
+    let pairs = diff::pair(&left_files, &right_files);
+
+    let mut output = io::stdout().lock();
+    let mut differs = false;
+    let mut error = false;
+    pool::map_largest_first(
+        &pairs,
+        cli.jobs.unwrap_or(1),
+        |pair| match pair {
+            diff::Pair::Both(path) => file_size(&left.join(path)),
+            diff::Pair::Left(_) | diff::Pair::Right(_) => 0,
+        },
+        || {
+            create_generators(
+                &cli.digests,
+                &cli.params,
+                cli.backend,
+                cli.threads,
+            )
+        },
+        |generators, pair| match pair {
+            diff::Pair::Both(path) => {
+                let generators = generators.as_ref().map_err(Clone::clone)?;
+                same_contents(
+                    &left.join(path),
+                    &right.join(path),
+                    generators,
+                    cli,
+                )
+            }
+            _ => Ok(false),
+        },
+        |pair, same| {
+            let line = match (pair, same) {
+                (diff::Pair::Left(path), _) => {
+                    format!("only in {}: {}", left.display(), path.display())
+                }
+                (diff::Pair::Right(path), _) => {
+                    format!("only in {}: {}", right.display(), path.display())
+                }
+                (diff::Pair::Both(path), Ok(false)) => {
+                    format!("differs: {}", path.display())
+                }
+                (diff::Pair::Both(_), Ok(true)) => return Ok(()),
+                (diff::Pair::Both(_), Err(err)) => {
+                    print_error(&err);
+                    error = true;
+                    return Ok(());
+                }
+            };
+            differs = true;
+            writeln!(output, "{line}").map_err(|_err| print_write_error())
+        },
+    )?;
+
+    if differs || error {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check whether the files at `left` and `right` have the same contents.
+/// Files of different sizes can't, so they are only digested, using the
+/// `generators`, if their sizes match.
+fn same_contents(
+    left: &Path,
+    right: &Path,
+    generators: &Generators,
+    cli: &CLI,
+) -> Result<bool, Error> {
+    let size = |path: &Path| {
+        fs::metadata(path)
+            .map(|meta| meta.len())
+            .map_err(|_err| Error::FileOpen(path.to_path_buf()))
+    };
+    if size(left)? != size(right)? {
+        return Ok(false);
+    }
+    let (left, _size, _mtime) = digest_path(left, generators, cli, None)?;
+    let (right, _size, _mtime) = digest_path(right, generators, cli, None)?;
+    Ok(left == right)
+}
+
+/// Compute the digests of the files (or stdin) listed in the config,
+/// writing the results to `output`.
+fn digest(cli: &CLI, output: &mut dyn io::Write) -> Result<(), ()> {
+    if cli.paths.is_empty() {
+        digest_stdin(cli, output)
+    } else if cli.combine {
+        digest_combined(cli, output)
+    } else if cli.tree_digest {
+        digest_trees(cli, output)
+    } else {
+        digest_files(cli, output)
+    }
+}
+
+/// Compute the digests, writing the results to the file at `path`. The
+/// file is only replaced once every digest has been computed and written,
+/// so an interrupted or failed run never leaves a truncated file behind.
+fn digest_to_file(cli: &CLI, path: &Path) -> Result<(), ()> {
+    let Ok(mut file) = AtomicFile::create(path) else {
+        print_error(&Error::FileCreate(path.to_path_buf()));
+        return Err(());
+    };
+    digest(cli, &mut file)?;
+    file.commit()
+        .map_err(|_err| print_error(&Error::FileWrite(path.to_path_buf())))
+}
+
+fn digest_stdin(cli: &CLI, output: &mut dyn io::Write) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    print_header(output, cli.format, cli.columns)
+        .map_err(|_err| print_write_error())?;
 
     let input = io::stdin();
-    if let Ok(digests) = digest_file(input, &generators) {
-        print_digests(&digests, None);
+    if let Ok((digests, size)) = digest_file(input, &generators) {
+        let entry = Entry {
+            path: None,
+            size,
+            mtime: None,
+        };
+        print_digests(output, cli, &digests, &entry)
+            .map_err(|_err| print_write_error())?;
     } else {
         print_error(&Error::StdinRead);
         return Err(());
@@ -72,25 +490,893 @@ fn digest_stdin(digests: &[DigestKind]) -> Result<(), ()> {
     Ok(())
 }
 
-fn digest_files(digests: &[DigestKind], paths: &[PathBuf]) -> Result<(), ()> {
-    // Create the generators based on the digests listed in the config.
-    let generators = create_generators(digests);
+/// Copy stdin to stdout unchanged, with `--tee`, computing the digests of
+/// it as it passes through. Once stdin is finished the digests are written
+/// to the file set by `--output`, or to stderr so they don't mix with the
+/// data.
+fn tee(cli: &CLI) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    // The output file is created first, so there is no point copying
+    // everything if it can't be.
+    let file = match &cli.output {
+        Some(path) => match AtomicFile::create(path) {
+            Ok(file) => Some((file, path)),
+            Err(_err) => {
+                print_error(&Error::FileCreate(path.clone()));
+                return Err(());
+            }
+        },
+        None => None,
+    };
+
+    let mut input = io::stdin().lock();
+    let mut passed = io::stdout().lock();
+    let mut buffer = vec![0u8; Hasher::DEFAULT_BUFFER_SIZE];
+    let mut size = 0;
+    loop {
+        let Ok(count) = input.read(&mut buffer) else {
+            print_error(&Error::StdinRead);
+            return Err(());
+        };
+        if count == 0 {
+            break;
+        }
+        update_digests(&generators, &buffer[0..count]);
+        passed
+            .write_all(&buffer[0..count])
+            .map_err(|_err| print_write_error())?;
+        size += count as u64;
+    }
+    passed.flush().map_err(|_err| print_write_error())?;
+    stats::add_file(size);
+
+    let digests: Vec<DigestData> = generators
+        .iter()
+        .map(|generator| generator.result())
+        .collect();
+    let entry = Entry {
+        path: None,
+        size,
+        mtime: None,
+    };
+    let write = |output: &mut dyn io::Write| {
+        print_header(output, cli.format, cli.columns)?;
+        print_digests(output, cli, &digests, &entry)
+    };
+    match file {
+        Some((mut file, path)) => write(&mut file)
+            .and_then(|()| file.commit())
+            .map_err(|_err| print_error(&Error::FileWrite(path.clone()))),
+        None => write(&mut io::stderr().lock())
+            .map_err(|_err| print_write_error()),
+    }
+}
+
+/// Compute a single set of digests of the files listed in the config, as
+/// though they were concatenated, with `--combine`. The result is written
+/// to `output` without a filename, as it would be for stdin.
+fn digest_combined(cli: &CLI, output: &mut dyn io::Write) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    print_header(output, cli.format, cli.columns)
+        .map_err(|_err| print_write_error())?;
+
+    let (digests, size) = combine_files(cli, &generators)?;
+    let entry = Entry {
+        path: None,
+        size,
+        mtime: None,
+    };
+    print_digests(output, cli, &digests, &entry)
+        .map_err(|_err| print_write_error())
+}
+
+/// Compute a single set of digests for the whole tree under each of the
+/// directories listed in the config, with `--tree-digest`, writing the
+/// results to `output` in the order the directories were listed, or sorted
+/// with `--sort`. The size is the total size of the files in the tree.
+fn digest_trees(cli: &CLI, output: &mut dyn io::Write) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    print_header(output, cli.format, cli.columns)
+        .map_err(|_err| print_write_error())?;
+    let mut error = false;
+
+    let mut dirs: Vec<&Path> =
+        cli.paths.iter().map(PathBuf::as_path).collect();
+    if cli.sort {
+        sort_paths(&mut dirs);
+    }
+    for dir in dirs {
+        if error && cli.fail_fast {
+            break;
+        }
+        match digest_tree(dir, &generators, cli) {
+            Ok((digests, size)) => {
+                let entry = Entry {
+                    path: Some(dir),
+                    size,
+                    mtime: None,
+                };
+                print_digests(output, cli, &digests, &entry)
+                    .map_err(|_err| print_write_error())?;
+            }
+            Err(err) => {
+                print_error(&err);
+                error = true;
+            }
+        }
+    }
+
+    if error {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Compute the digests of the tree under `dir`, along with the total size
+/// of the files in it. Each file in the tree is digested, and then for
+/// each kind of digest a record of the path of every file, relative to
+/// `dir` and separated by `/`, followed by a NUL byte and the digest of the
+/// file, is digested in path order. The result only depends on the names
+/// and contents of the files, so the same tree gives the same digest
+/// wherever it is.
+fn digest_tree(
+    dir: &Path,
+    generators: &Generators,
+    cli: &CLI,
+) -> Result<(Vec<DigestData>, u64), Error> {
+    let files =
+        walk::files(dir, walk_options(cli)).map_err(Error::DirRead)?;
+    let mut records = Vec::new();
+    let mut size = 0;
+    for file in &files {
+        let (digests, file_size, _mtime) =
+            digest_path(file, generators, cli, None)?;
+        size += file_size;
+        let name: Vec<&[u8]> = file
+            .strip_prefix(dir)
+            .unwrap_or(file)
+            .iter()
+            .map(std::ffi::OsStr::as_encoded_bytes)
+            .collect();
+        records.push((name.join(&b'/'), digests));
+    }
+    records.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (name, digests) in &records {
+        for (generator, digest) in generators.iter().zip(digests) {
+            let mut record = name.clone();
+            record.push(0);
+            record.extend_from_slice(digest.as_bytes());
+            generator.append(std::sync::Arc::from(record));
+        }
+    }
+    let digests = generators
+        .iter()
+        .map(|generator| generator.result())
+        .collect();
+    Ok((digests, size))
+}
+
+/// Feed each of the files listed in the config through the `generators`
+/// in turn, in the order they were listed or sorted with `--sort`, and
+/// return the digests of them all along with their total size. If any file
+/// can't be read then the digests would be meaningless, so the error is
+/// printed and no digests are returned.
+fn combine_files(
+    cli: &CLI,
+    generators: &Generators,
+) -> Result<(Vec<DigestData>, u64), ()> {
+    let mut paths: Vec<&Path> =
+        cli.paths.iter().map(PathBuf::as_path).collect();
+    if cli.sort {
+        sort_paths(&mut paths);
+    }
+    let mut progress = cli.progress.then(|| Progress::new(&paths));
+    let mut size = 0;
+    for path in &paths {
+        let Ok((file, _mtime)) = open_file(path, cli) else {
+            print_error(&Error::FileOpen(path.to_path_buf()));
+            return Err(());
+        };
+        let file = track(progress.as_mut(), path, file);
+        size += append_file(file, generators).map_err(|_err| {
+            if *path == Path::new("-") {
+                print_error(&Error::StdinRead);
+            } else {
+                print_error(&Error::FileRead(path.to_path_buf()));
+            }
+        })?;
+    }
+    let digests = generators
+        .iter()
+        .map(|generator| generator.result())
+        .collect();
+    Ok((digests, size))
+}
+
+/// Compute the digests of the files listed in the config, writing the
+/// results to `output` in the order the files were listed, or sorted with
+/// `--sort`. With `--jobs` several files are digested at once, starting
+/// with the largest, but the output for each file is held until every
+/// earlier file has been written, so the output is the same as for a
+/// single job. With `--update` the digests listed in the `--output` file
+/// are reused for the files which haven't changed since it was written. If
+/// the run is interrupted by `SIGINT` or `SIGTERM` then the files which
+/// were already digested are written, and the number which were skipped is
+/// reported.
+fn digest_files(cli: &CLI, output: &mut dyn io::Write) -> Result<(), ()> {
+    signal::trap();
+    let generators = generators_for(cli)?;
+    print_header(output, cli.format, cli.columns)
+        .map_err(|_err| print_write_error())?;
     let mut error = false;
 
-    for path in paths {
-        let file = if let Ok(file) = fs::File::open(&path) {
-            file
+    let mut paths: Vec<&Path> =
+        cli.paths.iter().map(PathBuf::as_path).collect();
+    if cli.sort {
+        sort_paths(&mut paths);
+    }
+    let previous = match &cli.output {
+        Some(manifest) if cli.update => previous_digests(cli, manifest)?,
+        _ => Previous::default(),
+    };
+    let reuse = |path: &Path| previous.digests(path, &cli.digests).map(Ok);
+    // Write the digests computed for each file, or report why they
+    // couldn't be computed. Once the run is interrupted no more files are
+    // reported.
+    let mut finished = 0;
+    let mut report = |path: &Path, digested: Result<Digested, Error>| {
+        let interrupted = signal::received().is_some();
+        match digested {
+            Ok((digests, size, mtime)) => {
+                finished += 1;
+                let entry = Entry {
+                    path: Some(path),
+                    size,
+                    mtime,
+                };
+                print_digests(output, cli, &digests, &entry)
+                    .map_err(|_err| print_write_error())?;
+            }
+            // The file was cut short by the interruption.
+            Err(_err) if interrupted => {}
+            Err(err) => {
+                finished += 1;
+                print_error(&err);
+                error = true;
+                // With `--fail-fast` no more files are reported.
+                if cli.fail_fast {
+                    return Err(());
+                }
+            }
+        }
+        if interrupted {
+            Err(())
+        } else {
+            Ok(())
+        }
+    };
+    let jobs = cli.jobs.unwrap_or(1);
+    let reported = if jobs > 1 {
+        // Each worker needs its own generators, as they hold the state of
+        // the file being digested.
+        pool::map_largest_first(
+            &paths,
+            jobs,
+            |path| file_size(path),
+            || {
+                create_generators(
+                    &cli.digests,
+                    &cli.params,
+                    cli.backend,
+                    cli.threads,
+                )
+            },
+            |generators, path| {
+                if let Some(reused) = reuse(path) {
+                    return reused;
+                }
+                let generators = generators.as_ref().map_err(Clone::clone)?;
+                digest_path(path, generators, cli, None)
+            },
+            |path, digested| report(path, digested),
+        )
+    } else {
+        let mut progress = cli.progress.then(|| Progress::new(&paths));
+        paths.iter().try_for_each(|path| {
+            let digested = reuse(path).unwrap_or_else(|| {
+                digest_path(path, &generators, cli, progress.as_mut())
+            });
+            report(path, digested)
+        })
+    };
+
+    if signal::received().is_some() {
+        print_error(&Error::Interrupted(paths.len() - finished));
+        return Err(());
+    }
+    reported?;
+    if error {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Read the digests listed in the `manifest` which is being updated with
+/// `--update`, so they can be reused for the files which haven't changed.
+/// If the manifest doesn't exist yet then there are no digests to reuse.
+fn previous_digests(cli: &CLI, manifest: &Path) -> Result<Previous, ()> {
+    let contents = match fs::read_to_string(manifest) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(Previous::default());
+        }
+        Err(_err) => {
+            print_error(&Error::FileRead(manifest.to_path_buf()));
+            return Err(());
+        }
+    };
+    let written =
+        fs::metadata(manifest).and_then(|meta| meta.modified()).ok();
+    let selected = cli.digests.first().copied();
+    let literal = cli.symlinks == Symlinks::Literal;
+    Ok(Previous::parse(
+        contents.lines(),
+        written,
+        selected,
+        literal,
+    ))
+}
+
+/// Verify the digests listed in the `manifest`, printing whether each file
+/// matches. A summary of any failures is printed once every file has been
+/// checked. The files and summary which are printed are limited by the
+/// `--quiet` and `--status` flags. Improperly formatted lines are skipped,
+/// but reported with `--warn`, and cause a failure with `--strict`. With
+/// `--json` a JSON report is printed once every file has been checked.
+/// Relative paths in the manifest are resolved against `--base-dir`, if it
+/// was set. The files are verified in parallel, using a worker for each
+/// CPU (or as set by `--jobs`), starting with the largest, but are always
+/// reported in the order they are listed. With `--update` the manifest is
+/// then rewritten to match the files. If the run is interrupted by
+/// `SIGINT` or `SIGTERM` then the files which were already verified are
+/// reported, and the number which were skipped, but the manifest isn't
+/// rewritten.
+fn check(cli: &CLI, manifest: &Path) -> Result<(), ()> {
+    signal::trap();
+    let lines = read_manifest(manifest)?;
+    let mut output = io::stdout().lock();
+    let mut summary = Summary::default();
+    let mut records = Vec::new();
+
+    let checksums = manifest_checksums(cli, manifest, &lines, &mut summary);
+
+    // The lines which `--update` would change, with the digest to replace
+    // the listed one, or `None` if the line is to be removed.
+    let mut changes = Vec::new();
+    // The files are verified in parallel, but reported in the order they
+    // are listed in the manifest.
+    let jobs = cli.jobs.unwrap_or_else(|| {
+        thread::available_parallelism().map_or(1, NonZeroUsize::get)
+    });
+    let checked = pool::map_largest_first(
+        &checksums,
+        jobs,
+        |(_, checksum, _)| file_size(&checksum.path),
+        HashMap::new,
+        |generators, (_, checksum, kind)| {
+            let generators = cached_generators(generators, *kind, cli)?;
+            Ok(verify(checksum, generators, cli))
+        },
+        |(number, checksum, kind), verified| {
+            let interrupted = signal::received().is_some();
+            // With `--fail-fast` no more files are verified once one fails.
+            let stop = |status| {
+                if interrupted || (cli.fail_fast && status != Status::Ok) {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            };
+            let (status, actual) =
+                verified.map_err(|error| print_error(&error))?;
+            // The file was cut short by the interruption.
+            if interrupted && status == Status::ReadError {
+                return Err(());
+            }
+            if let Err(error) = &actual {
+                print_error(error);
+            }
+            summary.add(status);
+            match (status, &actual) {
+                (Status::Mismatch, Ok(actual)) => {
+                    changes.push((*number, &checksum.path, Some(*actual)));
+                }
+                (Status::Missing, _) => {
+                    changes.push((*number, &checksum.path, None));
+                }
+                _ => (),
+            }
+            if cli.report > status.report() {
+                return stop(status);
+            }
+            if cli.json {
+                let actual = actual.as_ref().ok();
+                let record =
+                    check::json_record(checksum, *kind, status, actual);
+                records.push(record);
+            } else {
+                let path = checksum.path.display();
+                writeln!(output, "{path}: {}", status.text())
+                    .map_err(|_err| print_write_error())?;
+            }
+            stop(status)
+        },
+    );
+
+    if signal::received().is_some() {
+        let skipped = checksums.len() - summary.checked();
+        print_error(&Error::Interrupted(skipped));
+        return Err(());
+    }
+    checked?;
+
+    if summary.checked() == 0 {
+        print_error(&Error::NoChecksums(manifest.to_path_buf()));
+        return Err(());
+    }
+    if cli.update {
+        update_manifest(cli, manifest, &lines, &changes, &mut output)?;
+    }
+    if cli.json {
+        writeln!(output, "{}", check::json_report(&records, &summary))
+            .map_err(|_err| print_write_error())?;
+    }
+    if cli.report != Report::Status {
+        if summary.improper > 0 && (cli.warn || cli.strict) {
+            print_error(&Error::ImproperLines(summary.improper));
+        }
+        if summary.unreadable() > 0 {
+            print_error(&Error::UnreadableFiles(summary.unreadable()));
+        }
+        if summary.mismatch > 0 {
+            print_error(&Error::ChecksumMismatch(summary.mismatch));
+        }
+    }
+    if summary.checked() > summary.ok || (cli.strict && summary.improper > 0)
+    {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse the `lines` of the `manifest` into the checksums to verify, along
+/// with the number of the line each is on and the kind of digest to
+/// compute. Improperly formatted lines are counted in the `summary`, and
+/// reported with `--warn`.
+fn manifest_checksums(
+    cli: &CLI,
+    manifest: &Path,
+    lines: &[String],
+    summary: &mut Summary,
+) -> Vec<(usize, Checksum, DigestKind)> {
+    let selected = cli.digests.first().copied();
+    let mut checksums = Vec::new();
+    for (number, line) in lines.iter().enumerate() {
+        // Blank lines and comments are skipped, rather than being reported
+        // as improperly formatted.
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        let checksum = Checksum::parse(line);
+        if checksum
+            .as_ref()
+            .is_some_and(|checksum| !checksum.is_selected(selected))
+        {
+            continue;
+        }
+        let Some((mut checksum, kind)) = checksum.and_then(|checksum| {
+            let kind = checksum.kind(selected)?;
+            Some((checksum, kind))
+        }) else {
+            if cli.warn {
+                let manifest = manifest.to_path_buf();
+                print_error(&Error::ImproperLine(manifest, number + 1));
+            }
+            summary.improper += 1;
+            continue;
+        };
+        if let Some(base_dir) = &cli.base_dir {
+            checksum.path = base_dir.join(&checksum.path);
+        }
+        checksums.push((number, checksum, kind));
+    }
+    checksums
+}
+
+/// Rewrite the `manifest` after it has been checked, applying the
+/// `changes` to its `lines`. Each change replaces the digest on a line with
+/// the digest computed for the file, along with its size and mtime if the
+/// line lists them, or removes the line if the file is missing. The
+/// changes are reported to `output` (unless `--json` or `--status` were
+/// used), and with `--dry-run` the manifest is left alone.
+fn update_manifest(
+    cli: &CLI,
+    manifest: &Path,
+    lines: &[String],
+    changes: &[(usize, &PathBuf, Option<DigestData>)],
+    output: &mut dyn io::Write,
+) -> Result<(), ()> {
+    if !cli.json && cli.report != Report::Status {
+        for (_, path, digest) in changes {
+            let change = match (digest.is_some(), cli.dry_run) {
+                (true, false) => "UPDATED",
+                (false, false) => "REMOVED",
+                (true, true) => "WOULD UPDATE",
+                (false, true) => "WOULD REMOVE",
+            };
+            writeln!(output, "{}: {change}", path.display())
+                .map_err(|_err| print_write_error())?;
+        }
+    }
+    if cli.dry_run || changes.is_empty() {
+        return Ok(());
+    }
+
+    let changes: HashMap<_, _> = changes
+        .iter()
+        .map(|(number, path, digest)| (*number, (*path, *digest)))
+        .collect();
+    let Ok(mut file) = AtomicFile::create(manifest) else {
+        print_error(&Error::FileCreate(manifest.to_path_buf()));
+        return Err(());
+    };
+    let written = lines.iter().enumerate().try_for_each(|(number, line)| {
+        match changes.get(&number) {
+            Some((_, None)) => Ok(()),
+            Some((path, Some(digest))) => {
+                // Keep the line ending the line had.
+                let (line, ending) = match line.strip_suffix('\r') {
+                    Some(line) => (line, "\r"),
+                    None => (line.as_str(), ""),
+                };
+                let meta = if cli.symlinks == Symlinks::Literal {
+                    fs::symlink_metadata(path)
+                } else {
+                    fs::metadata(path)
+                };
+                let meta = meta.ok();
+                let size = meta.as_ref().map(fs::Metadata::len);
+                let mtime = meta.and_then(|meta| meta.modified().ok());
+                let updated =
+                    check::update_line(line, digest.as_bytes(), size, mtime)
+                        .unwrap_or_else(|| line.to_string());
+                writeln!(file, "{updated}{ending}")
+            }
+            None => writeln!(file, "{line}"),
+        }
+    });
+    written.and_then(|()| file.commit()).map_err(|_err| {
+        print_error(&Error::FileWrite(manifest.to_path_buf()));
+    })
+}
+
+/// The generators for a `kind` of digest, which are created when first
+/// needed and then kept in the `cache`.
+fn cached_generators<'a>(
+    cache: &'a mut HashMap<DigestKind, Generators>,
+    kind: DigestKind,
+    cli: &CLI,
+) -> Result<&'a Generators, Error> {
+    match cache.entry(kind) {
+        hash_map::Entry::Occupied(entry) => Ok(entry.into_mut()),
+        hash_map::Entry::Vacant(entry) => {
+            let created = create_generators(
+                &[kind],
+                &cli.params,
+                cli.backend,
+                cli.threads,
+            )?;
+            Ok(entry.insert(created))
+        }
+    }
+}
+
+/// Verify the file listed in the `checksum` has the expected digest, using
+/// the `generators` for the kind of digest listed, and opening the file as
+/// set in the config. The digest computed for the file is returned with the
+/// status, or the error if it couldn't be read.
+fn verify(
+    checksum: &Checksum,
+    generators: &Generators,
+    cli: &CLI,
+) -> (Status, Result<DigestData, Error>) {
+    let path = &checksum.path;
+    let file = match open_file(path, cli) {
+        Ok((file, _mtime)) => file,
+        Err(err) => {
+            let status = if err.kind() == io::ErrorKind::NotFound {
+                Status::Missing
+            } else {
+                Status::ReadError
+            };
+            return (status, Err(Error::FileOpen(path.clone())));
+        }
+    };
+    if let Ok((digests, _size)) = digest_file(file, generators) {
+        let digest = digests[0];
+        let status = if digest.as_bytes() == checksum.digest {
+            Status::Ok
         } else {
+            Status::Mismatch
+        };
+        (status, Ok(digest))
+    } else {
+        (Status::ReadError, Err(Error::FileRead(path.clone())))
+    }
+}
+
+/// Verify the sidecar files in the tree under `dir`, checking that the file
+/// next to each sidecar has the digest it lists, and printing whether each
+/// one matches. Only the sidecars for the digests listed in the config are
+/// checked, or every sidecar if none were listed. Files without any
+/// sidecar are reported separately, but don't cause a failure. Files which
+/// are excluded, or ignored by git with `--respect-gitignore`, are
+/// skipped. The files and summary which are printed are limited by the
+/// `--quiet` and `--status` flags.
+fn check_sidecars(cli: &CLI, dir: &Path) -> Result<(), ()> {
+    let files = walk::files(dir, walk_options(cli))
+        .map_err(|path| print_error(&Error::DirRead(path)))?;
+    let mut output = io::stdout().lock();
+    let mut generators = HashMap::new();
+    let mut summary = Summary::default();
+    let selected =
+        |kind| cli.digests.is_empty() || cli.digests.contains(&kind);
+
+    // Every file which has a sidecar for a selected digest, so that the
+    // files without one can be reported.
+    let covered: HashSet<_> = files
+        .iter()
+        .filter_map(|file| sidecar::parse(file))
+        .filter(|(_, kind)| selected(*kind))
+        .map(|(path, _)| path)
+        .collect();
+    let mut uncovered = 0;
+
+    for file in &files {
+        if cli.fail_fast && summary.checked() > summary.ok {
+            break;
+        }
+        let Some((path, kind)) = sidecar::parse(file) else {
+            if !covered.contains(file) {
+                uncovered += 1;
+                if cli.report <= Report::Failures {
+                    writeln!(output, "{}: NO SIDECAR", file.display())
+                        .map_err(|_err| print_write_error())?;
+                }
+            }
+            continue;
+        };
+        if !selected(kind) {
+            continue;
+        }
+        let Some(checksum) = read_sidecar(file, path, kind) else {
+            summary.improper += 1;
+            continue;
+        };
+        let generators = cached_generators(&mut generators, kind, cli)
+            .map_err(|error| print_error(&error))?;
+        let (status, actual) = verify(&checksum, generators, cli);
+        if let Err(error) = actual {
+            print_error(&error);
+        }
+        summary.add(status);
+        if cli.report > status.report() {
+            continue;
+        }
+        let path = checksum.path.display();
+        let name = kind.name();
+        writeln!(output, "{path} ({name}): {}", status.text())
+            .map_err(|_err| print_write_error())?;
+    }
+
+    if cli.report != Report::Status {
+        if uncovered > 0 {
+            print_error(&Error::MissingSidecars(uncovered));
+        }
+        if summary.improper > 0 {
+            print_error(&Error::ImproperLines(summary.improper));
+        }
+        if summary.unreadable() > 0 {
+            print_error(&Error::UnreadableFiles(summary.unreadable()));
+        }
+        if summary.mismatch > 0 {
+            print_error(&Error::ChecksumMismatch(summary.mismatch));
+        }
+    }
+    if summary.checked() > summary.ok || summary.improper > 0 {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Read the checksum held in a `sidecar` file, which holds the `kind`
+/// digest of the file at `path`. The sidecar may be in the format output
+/// by `checksum` or `sha256sum`, and the first line which isn't blank or a
+/// comment is used. If the sidecar can't be read, or the line is
+/// improperly formatted, then an error is printed and `None` returned.
+fn read_sidecar(
+    sidecar: &Path,
+    path: PathBuf,
+    kind: DigestKind,
+) -> Option<Checksum> {
+    let Ok(contents) = fs::read_to_string(sidecar) else {
+        print_error(&Error::FileRead(sidecar.to_path_buf()));
+        return None;
+    };
+    let (number, line) = contents
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .enumerate()
+        .find(|(_, line)| !line.is_empty() && !line.starts_with(['#', ';']))
+        .unwrap_or((0, ""));
+    match Checksum::parse(line) {
+        Some(checksum) if checksum.is_selected(Some(kind)) => {
+            Some(Checksum {
+                kind: Some(kind),
+                digest: checksum.digest,
+                path,
+            })
+        }
+        _ => {
+            let sidecar = sidecar.to_path_buf();
+            print_error(&Error::ImproperLine(sidecar, number + 1));
+            None
+        }
+    }
+}
+
+/// Read the lines of the `manifest`, which is read from stdin if it is
+/// `-`.
+fn read_manifest(manifest: &Path) -> Result<Vec<String>, ()> {
+    let lines = if manifest == Path::new("-") {
+        io::stdin().lock().lines().collect::<io::Result<_>>()
+    } else {
+        let Ok(file) = fs::File::open(manifest) else {
+            print_error(&Error::FileOpen(manifest.to_path_buf()));
+            return Err(());
+        };
+        io::BufReader::new(file).lines().collect()
+    };
+    lines
+        .map_err(|_err| print_error(&Error::FileRead(manifest.to_path_buf())))
+}
+
+/// Check the file (or stdin) listed in the config has each of the
+/// expected digests, printing a PASS or FAIL line for each of them. If any
+/// digest doesn't match then a warning is printed and an error returned.
+fn expect(cli: &CLI) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    // A combined digest isn't for any one file.
+    let path = cli.paths.first().filter(|_| !cli.combine);
+    let digests = if cli.combine {
+        combine_files(cli, &generators)?.0
+    } else if let Some(path) = path {
+        let Ok((file, _mtime)) = open_file(path, cli) else {
             print_error(&Error::FileOpen(path.clone()));
-            error = true;
+            return Err(());
+        };
+        digest_file(file, &generators)
+            .map_err(|_err| print_error(&Error::FileRead(path.clone())))?
+            .0
+    } else {
+        digest_file(io::stdin(), &generators)
+            .map_err(|_err| print_error(&Error::StdinRead))?
+            .0
+    };
+
+    let mut output = io::stdout().lock();
+    let mut failed = false;
+    for expected in &cli.expect {
+        let Some(index) =
+            cli.digests.iter().position(|kind| *kind == expected.kind)
+        else {
             continue;
         };
-        if let Ok(digests) = digest_file(file, &generators) {
-            print_digests(&digests, Some(path));
+        let digest = &digests[index];
+        let name = digest.name();
+        let actual = digest.encode(cli.encoding);
+        let subject = match path {
+            Some(path) => format!("{name} ({})", path.display()),
+            None => name.to_string(),
+        };
+        let written = if digest.as_bytes() == expected.digest {
+            writeln!(output, "PASS: {subject} = {actual}")
         } else {
+            failed = true;
+            let expected = cli.encoding.encode(&expected.digest);
+            writeln!(
+                output,
+                "FAIL: {subject} = {actual}, expected {expected}"
+            )
+        };
+        written.map_err(|_err| print_write_error())?;
+    }
+
+    if failed {
+        print_error(&Error::ExpectMismatch);
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Compute the digests of the files listed in the config, writing each
+/// digest to a sidecar file next to the file it was computed for. Sidecars
+/// which are already current are left alone, unless `--force` was set, and
+/// files with only current sidecars aren't read at all.
+fn digest_sidecars(cli: &CLI) -> Result<(), ()> {
+    let generators = generators_for(cli)?;
+    let mut error = false;
+    let mut progress = cli.progress.then(|| {
+        let paths: Vec<&Path> =
+            cli.paths.iter().map(PathBuf::as_path).collect();
+        Progress::new(&paths)
+    });
+
+    for path in &cli.paths {
+        if error && cli.fail_fast {
+            break;
+        }
+        let sidecars: Vec<_> = cli
+            .digests
+            .iter()
+            .map(|kind| sidecar::path(path, *kind))
+            .collect();
+        if !cli.force
+            && sidecars
+                .iter()
+                .all(|sidecar| sidecar::is_current(path, sidecar))
+        {
+            continue;
+        }
+
+        let Ok((file, mtime)) = open_file(path, cli) else {
+            print_error(&Error::FileOpen(path.clone()));
+            error = true;
+            continue;
+        };
+        let file = track(progress.as_mut(), path, file);
+        let Ok((digests, size)) = digest_file(file, &generators) else {
             print_error(&Error::FileRead(path.clone()));
             error = true;
             continue;
+        };
+
+        // The sidecar lives next to the file, so only refers to its name.
+        let entry = Entry {
+            path: path.file_name().map(Path::new),
+            size,
+            mtime,
+        };
+        for (digest, sidecar) in digests.iter().zip(&sidecars) {
+            if !cli.force && sidecar::is_current(path, sidecar) {
+                continue;
+            }
+            let written = AtomicFile::create(sidecar).and_then(|mut file| {
+                print_header(&mut file, cli.format, cli.columns)?;
+                print_digests(&mut file, cli, &[*digest], &entry)?;
+                file.commit()
+            });
+            if written.is_err() {
+                print_error(&Error::FileWrite(sidecar.clone()));
+                error = true;
+            }
         }
     }
 
@@ -101,79 +1387,364 @@ fn digest_files(digests: &[DigestKind], paths: &[PathBuf]) -> Result<(), ()> {
     }
 }
 
+/// Sort `paths` by their bytes, so that the order is the same on every run
+/// regardless of the locale.
+fn sort_paths(paths: &mut [&Path]) {
+    paths.sort_by(|a, b| {
+        a.as_os_str()
+            .as_encoded_bytes()
+            .cmp(b.as_os_str().as_encoded_bytes())
+    });
+}
+
+/// Print the `error` to stderr. Failures to read or write a file are
+/// counted for `--stats`.
 fn print_error(error: &Error) {
-    eprintln!("{}", error);
+    if matches!(
+        error,
+        Error::DirRead(_)
+            | Error::FileOpen(_)
+            | Error::FileRead(_)
+            | Error::FileCreate(_)
+            | Error::FileWrite(_)
+            | Error::StdinRead
+    ) {
+        stats::add_error();
+    }
+    eprintln!("{error}");
+}
+
+/// Report a failure writing the output. There is no point continuing once
+/// the output can't be written, so this is always fatal.
+fn print_write_error() {
+    print_error(&Error::OutputWrite);
 }
 
-fn print_digests(digests: &[DigestData], path: Option<&Path>) {
-    for digest in digests {
-        print_digest(digest, path);
+fn print_header(
+    output: &mut dyn io::Write,
+    format: Format,
+    columns: Columns,
+) -> io::Result<()> {
+    for line in format.header(VERSION, columns) {
+        writeln!(output, "{line}")?;
     }
+    Ok(())
 }
 
-fn print_digest(digest: &DigestData, path: Option<&Path>) {
-    let digest_name = match digest {
-        DigestData::CRC32(_) => "CRC32",
-        DigestData::MD5(_) => "MD5",
-        DigestData::SHA256(_) => "SHA256",
-        DigestData::SHA512(_) => "SHA512",
-        DigestData::RMD160(_) => "RMD160",
-    };
+fn print_digests(
+    output: &mut dyn io::Write,
+    cli: &CLI,
+    digests: &[DigestData],
+    entry: &Entry,
+) -> io::Result<()> {
+    let lines = cli.format.lines(cli.encoding, cli.columns, digests, entry);
+    for line in lines {
+        writeln!(output, "{line}")?;
+    }
+    Ok(())
+}
 
-    match path {
-        Some(path) => {
-            let pathstr = path.to_str().unwrap();
-            println!("{} ({}) = {}", digest_name, pathstr, digest);
-        }
-        None => {
-            println!("{} = {}", digest_name, digest);
+/// The largest input which is digested in the thread reading it, rather
+/// than in the background threads, as the round trips to the background
+/// threads would take longer than computing the digests.
+const INLINE_LIMIT: u64 = 0x10_0000;
+
+/// The generators for the digests listed in the config, which compute the
+/// digests in background threads. Inputs no larger than `INLINE_LIMIT` are
+/// digested by the `inline` generators instead, in the thread reading them.
+/// With `--no-threads` every generator is inline, and `inline` is `None`.
+struct Generators {
+    background: Vec<Box<dyn Generator>>,
+    inline: Option<Vec<Box<dyn Generator>>>,
+}
+
+impl From<Vec<Box<dyn Generator>>> for Generators {
+    fn from(background: Vec<Box<dyn Generator>>) -> Self {
+        Self {
+            background,
+            inline: None,
         }
-    };
+    }
 }
 
-type Generators = Vec<Box<dyn Generator>>;
+impl std::ops::Deref for Generators {
+    type Target = [Box<dyn Generator>];
 
-fn create_generators(digests: &[DigestKind]) -> Generators {
+    fn deref(&self) -> &Self::Target {
+        &self.background
+    }
+}
+
+/// Create the generators for the digests listed in the config, printing
+/// the error if they can't be created.
+fn generators_for(cli: &CLI) -> Result<Generators, ()> {
+    create_generators(&cli.digests, &cli.params, cli.backend, cli.threads)
+        .map_err(|error| print_error(&error))
+}
+
+/// Create a generator for each of the `digests` using the `backend`, or
+/// another backend for the digests it doesn't implement. The BLAKE2
+/// digests are customized using `params`, if any are set. Unless `threads`
+/// is set every digest is computed in the thread reading the input.
+fn create_generators(
+    digests: &[DigestKind],
+    params: &Blake2Params,
+    backend: &dyn Backend,
+    threads: bool,
+) -> Result<Generators, Error> {
+    let inline = generator_set(digests, params, backend, true)?;
+    if !threads {
+        return Ok(Generators::from(inline));
+    }
+    Ok(Generators {
+        background: generator_set(digests, params, backend, false)?,
+        inline: Some(inline),
+    })
+}
+
+/// Create the generators for `create_generators()`, computing the digests
+/// in the calling thread if `inline` is set, or else in background threads.
+fn generator_set(
+    digests: &[DigestKind],
+    params: &Blake2Params,
+    backend: &dyn Backend,
+    inline: bool,
+) -> Result<Vec<Box<dyn Generator>>, Error> {
+    let custom = !params.is_empty();
     digests
         .iter()
-        .map(|digest| match digest {
-            DigestKind::CRC32 => crc32(),
-            DigestKind::MD5 => md5(),
-            DigestKind::SHA256 => sha256(),
-            DigestKind::SHA512 => sha512(),
-            DigestKind::RMD160 => rmd160(),
+        .map(|&kind| {
+            let generator = match (kind, inline) {
+                (DigestKind::BLAKE2B, false) if custom => {
+                    blake2b_with(params)
+                }
+                (DigestKind::BLAKE2B, true) if custom => {
+                    inline_blake2b_with(params)
+                }
+                (DigestKind::BLAKE2S, false) if custom => {
+                    blake2s_with(params)
+                }
+                (DigestKind::BLAKE2S, true) if custom => {
+                    inline_blake2s_with(params)
+                }
+                (kind, false) => backend.generator_with_fallback(kind),
+                (kind, true) => backend.inline_generator_with_fallback(kind),
+            };
+            generator.ok_or_else(|| match kind {
+                DigestKind::BLAKE2B | DigestKind::BLAKE2S if custom => {
+                    Error::InvalidParameters(kind.name().to_string())
+                }
+                kind => Error::UnavailableDigest(kind.name().to_string()),
+            })
         })
         .collect()
 }
 
-type DigestResult = Result<Vec<DigestData>, io::Error>;
+/// The digests computed for a file, along with the size of the file and its
+/// modification time.
+type Digested = (Vec<DigestData>, u64, Option<SystemTime>);
+
+/// Compute the digests of the file at `path` using the `generators`,
+/// tracking the progress of reading it with `progress`, if set.
+fn digest_path(
+    path: &Path,
+    generators: &Generators,
+    cli: &CLI,
+    progress: Option<&mut Progress>,
+) -> Result<Digested, Error> {
+    let (file, mtime) = open_file(path, cli)
+        .map_err(|_err| Error::FileOpen(path.to_path_buf()))?;
+    let file = track(progress, path, file);
+    match digest_file(file, generators) {
+        Ok((digests, size)) => Ok((digests, size, mtime)),
+        Err(_err) if path == Path::new("-") => Err(Error::StdinRead),
+        Err(_err) => Err(Error::FileRead(path.to_path_buf())),
+    }
+}
+
+/// A file opened to be digested, along with its modification time.
+type OpenFile = (Box<dyn io::Read>, Option<SystemTime>);
+
+/// Open the file at `path` to be digested, where `-` is stdin. If links
+/// are taken literally with `--symlink-literal` and the path is a symbolic
+/// link then the link isn't followed, and the path it points to is read in
+/// place of the contents of its target. Only the range of bytes selected
+/// by `--offset` and `--size` is read, no faster than set by `--throttle`,
+/// and bypassing the page cache with `--direct-io`. Each file is logged as
+/// it is opened with `-vv`.
+fn open_file(path: &Path, cli: &CLI) -> io::Result<OpenFile> {
+    let (file, mtime) = open_range(path, cli)?;
+    match cli.throttle {
+        Some(rate) => Ok((Box::new(Throttled::new(file, rate)), mtime)),
+        None => Ok((file, mtime)),
+    }
+}
+
+/// Open the file at `path` as for `open_file()`, without any throttling.
+fn open_range(path: &Path, cli: &CLI) -> io::Result<OpenFile> {
+    if cli.verbose > 1 {
+        eprintln!("opening '{}'", path.display());
+    }
+    if path == Path::new("-") {
+        let stdin = select_range(Box::new(io::stdin()), cli.offset, cli)?;
+        return Ok((stdin, None));
+    }
+    if cli.symlinks == Symlinks::Literal {
+        let meta = fs::symlink_metadata(path)?;
+        if meta.is_symlink() {
+            let target = fs::read_link(path)?.into_os_string();
+            let contents = io::Cursor::new(target.into_encoded_bytes());
+            let contents = select_range(Box::new(contents), cli.offset, cli)?;
+            return Ok((contents, meta.modified().ok()));
+        }
+    }
+    let mut file = if cli.direct_io {
+        direct::open(path)?
+    } else {
+        fs::File::open(path)?
+    };
+    let meta = file.metadata().ok();
+    let mtime = meta.as_ref().and_then(|meta| meta.modified().ok());
+    // A regular file can seek straight to the offset, rather than reading
+    // every byte before it. Direct I/O can only seek to an aligned offset,
+    // so the rest of the way is read and discarded.
+    let mut skip = cli.offset;
+    if skip > 0 && meta.is_some_and(|meta| meta.is_file()) {
+        let start = if cli.direct_io {
+            skip - skip % direct::ALIGNMENT as u64
+        } else {
+            skip
+        };
+        file.seek(io::SeekFrom::Start(start))?;
+        skip -= start;
+    }
+    let file: Box<dyn io::Read> = if cli.direct_io {
+        Box::new(direct::DirectReader::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok((select_range(file, skip, cli)?, mtime))
+}
+
+/// Skip the first `skip` bytes of the `input`, and limit the rest to the
+/// number of bytes set by `--size`, if any. The skipped bytes are read and
+/// discarded, so this works for input which can't seek, such as a pipe.
+fn select_range(
+    mut input: Box<dyn io::Read>,
+    skip: u64,
+    cli: &CLI,
+) -> io::Result<Box<dyn io::Read>> {
+    if skip > 0 {
+        io::copy(&mut input.by_ref().take(skip), &mut io::sink())?;
+    }
+    Ok(match cli.size {
+        Some(size) => Box::new(input.take(size)),
+        None => input,
+    })
+}
+
+/// The size of the file at `path`, used to start the largest files first
+/// with `--jobs`, or 0 if it can't be found.
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |meta| meta.len())
+}
+
+/// Track the progress of reading the `file` at `path`, if `--progress` was
+/// set.
+fn track<'a>(
+    progress: Option<&'a mut Progress>,
+    path: &Path,
+    file: Box<dyn io::Read>,
+) -> Box<dyn io::Read + 'a> {
+    match progress {
+        Some(progress) => Box::new(progress.track(path, file)),
+        None => file,
+    }
+}
+
+/// The digests computed for a file, along with the size of the file.
+type DigestResult = Result<(Vec<DigestData>, u64), io::Error>;
 
 fn digest_file<R: io::Read>(
     mut input: R,
     generators: &Generators,
 ) -> DigestResult {
-    let mut buffer = [0u8; 0x4_0000];
+    // A small input is read in full first, so that it can be digested
+    // inline.
+    let mut head = Vec::new();
+    let mut used: &[Box<dyn Generator>] = generators;
+    if let Some(inline) = &generators.inline {
+        head = read_head(&mut input)?;
+        if head.len() as u64 <= INLINE_LIMIT {
+            used = inline;
+        }
+    }
+    let size = append_file(head.as_slice().chain(input), used).inspect_err(
+        |_err| {
+            // Drop the part of the file which was read, so that it isn't
+            // included in the digests of the next file.
+            for generator in used {
+                generator.reset();
+            }
+        },
+    )?;
+    let digests = used.iter().map(|generator| generator.result()).collect();
+
+    Ok((digests, size))
+}
+
+/// Read the start of the `input`, until it finishes or more than
+/// `INLINE_LIMIT` bytes have been read. The reads are as large as those
+/// made by `append_file()`, so a small file is read all at once.
+fn read_head<R: io::Read>(input: &mut R) -> io::Result<Vec<u8>> {
+    let limit = usize::try_from(INLINE_LIMIT).unwrap_or(usize::MAX);
+    let mut head = vec![0u8; Hasher::DEFAULT_BUFFER_SIZE];
+    let mut filled = 0;
+    while filled <= limit {
+        if filled == head.len() {
+            head.resize((filled * 2).min(limit + 1), 0);
+        }
+        let count = input.read(&mut head[filled..])?;
+        if count == 0 {
+            break;
+        }
+        filled += count;
+    }
+    head.truncate(filled);
+    Ok(head)
+}
+
+/// Feed all of the `input` through the `generators`, without finishing
+/// the digests, returning the number of bytes read. The file is counted
+/// for `--stats` once it has all been read.
+fn append_file<R: io::Read>(
+    mut input: R,
+    generators: &[Box<dyn Generator>],
+) -> io::Result<u64> {
+    let mut buffer = vec![0u8; Hasher::DEFAULT_BUFFER_SIZE];
+    let mut size = 0;
 
     loop {
+        // Stop part way through the file if the run was interrupted.
+        if signal::received().is_some() {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
         let count = input.read(&mut buffer)?;
         if count > 0 {
             update_digests(generators, &buffer[0..count]);
+            size += count as u64;
         } else {
             break;
         }
     }
 
-    let digests = generators
-        .iter()
-        .map(|generator| generator.result())
-        .collect();
-
-    Ok(digests)
+    stats::add_file(size);
+    Ok(size)
 }
 
 fn update_digests(generators: &[Box<dyn Generator>], data: &[u8]) {
     let data: std::sync::Arc<[u8]> = std::sync::Arc::from(data);
-    for generator in generators.iter() {
+    for generator in generators {
         generator.append(data.clone());
     }
 }
@@ -185,6 +1756,7 @@ pub mod fixtures;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use digest::{crc32, md5, md5_digest, rmd160, sha256, sha512, OpenSSL};
     use std::process;
 
     #[test]
@@ -195,31 +1767,112 @@ mod tests {
             DigestKind::SHA512,
             DigestKind::RMD160,
             DigestKind::CRC32,
+            DigestKind::SHA224,
+            DigestKind::SHA384,
+            DigestKind::SHA3_224,
+            DigestKind::SHA3_256,
+            DigestKind::SHA3_384,
+            DigestKind::SHA3_512,
+            DigestKind::BLAKE2B,
+            DigestKind::BLAKE2S,
+            DigestKind::XXH32,
+            DigestKind::XXH64,
+            DigestKind::CRC64,
+            DigestKind::RMD256,
+            DigestKind::RMD320,
+            DigestKind::KECCAK256,
+            DigestKind::SHA512_224,
+            DigestKind::SHA512_256,
+            DigestKind::RAPIDHASH,
+            DigestKind::CRC16_CCITT,
+            DigestKind::CRC16_MODBUS,
+            DigestKind::CRC16_XMODEM,
         ];
-        let generators = super::create_generators(&digests);
-        assert_eq!(generators.len(), 5);
-        let digest = &generators[0];
-        assert_eq!(digest.result(), DigestData::MD5(fixtures::md5::EMPTY));
-        let digest = &generators[1];
-        assert_eq!(
-            digest.result(),
-            DigestData::SHA256(fixtures::sha256::EMPTY)
-        );
-        let digest = &generators[2];
+        let expected = [
+            DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::SHA256(fixtures::sha256::EMPTY),
+            DigestData::SHA512(fixtures::sha512::EMPTY),
+            DigestData::RMD160(fixtures::rmd160::EMPTY),
+            DigestData::CRC32(fixtures::crc32::EMPTY),
+            DigestData::SHA224(fixtures::sha224::EMPTY),
+            DigestData::SHA384(fixtures::sha384::EMPTY),
+            DigestData::SHA3_224(fixtures::sha3_224::EMPTY),
+            DigestData::SHA3_256(fixtures::sha3_256::EMPTY),
+            DigestData::SHA3_384(fixtures::sha3_384::EMPTY),
+            DigestData::SHA3_512(fixtures::sha3_512::EMPTY),
+            DigestData::BLAKE2B(fixtures::blake2b::EMPTY),
+            DigestData::BLAKE2S(fixtures::blake2s::EMPTY),
+            DigestData::XXH32(fixtures::xxh32::EMPTY),
+            DigestData::XXH64(fixtures::xxh64::EMPTY),
+            DigestData::CRC64(fixtures::crc64::EMPTY),
+            DigestData::RMD256(fixtures::rmd256::EMPTY),
+            DigestData::RMD320(fixtures::rmd320::EMPTY),
+            DigestData::KECCAK256(fixtures::keccak256::EMPTY),
+            DigestData::SHA512_224(fixtures::sha512_224::EMPTY),
+            DigestData::SHA512_256(fixtures::sha512_256::EMPTY),
+            DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY),
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::EMPTY),
+            DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::EMPTY),
+        ];
+        let generators = super::create_generators(
+            &digests,
+            &Blake2Params::default(),
+            &OpenSSL,
+            true,
+        )
+        .unwrap();
+        assert_eq!(generators.len(), expected.len());
+        for (generator, expected) in generators.iter().zip(expected) {
+            assert_eq!(generator.result(), expected);
+        }
+    }
+
+    #[test]
+    fn create_blake2_generators() {
+        let digests = vec![DigestKind::BLAKE2B, DigestKind::BLAKE2S];
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let generators =
+            super::create_generators(&digests, &params, &OpenSSL, true)
+                .unwrap();
+        assert_eq!(generators.len(), 2);
         assert_eq!(
-            digest.result(),
-            DigestData::SHA512(fixtures::sha512::EMPTY)
+            generators[0].result(),
+            blake2b_with(&params).unwrap().result()
         );
-        let digest = &generators[3];
         assert_eq!(
-            digest.result(),
-            DigestData::RMD160(fixtures::rmd160::EMPTY)
+            generators[1].result(),
+            blake2s_with(&params).unwrap().result()
         );
-        let digest = &generators[4];
-        assert_eq!(
-            digest.result(),
-            DigestData::CRC32(fixtures::crc32::EMPTY)
+        assert_ne!(
+            generators[0].result(),
+            DigestData::BLAKE2B(fixtures::blake2b::EMPTY)
         );
+
+        // A 16 byte salt is valid for BLAKE2b, but not for BLAKE2s.
+        let params = Blake2Params {
+            salt: vec![0; 16],
+            ..Blake2Params::default()
+        };
+        assert!(super::create_generators(
+            &[DigestKind::BLAKE2B],
+            &params,
+            &OpenSSL,
+            true
+        )
+        .is_ok());
+        let error = super::create_generators(
+            &[DigestKind::BLAKE2S],
+            &params,
+            &OpenSSL,
+            true,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error, Error::InvalidParameters(String::from("BLAKE2s")));
     }
 
     #[test]
@@ -258,7 +1911,9 @@ mod tests {
             child.stdout.expect("unable to retrieve child stdout");
         let generators = generators();
 
-        let digests = digest_file(child_stdout, &generators).unwrap();
+        let (digests, size) = digest_file(child_stdout, &generators).unwrap();
+
+        assert_eq!(size, 0x400d);
 
         assert_eq!(
             digests,
@@ -272,12 +1927,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn digest_inline() {
+        let digests = [DigestKind::MD5, DigestKind::BLAKE2B];
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let large = usize::try_from(INLINE_LIMIT).unwrap() + 1;
+        let expected = |data: &[u8]| {
+            let blake2b = blake2b_with(&params).unwrap();
+            blake2b.append(std::sync::Arc::from(data));
+            vec![md5_digest(data), blake2b.result()]
+        };
+        for threads in [true, false] {
+            let generators = super::create_generators(
+                &digests, &params, &OpenSSL, threads,
+            )
+            .unwrap();
+            assert_eq!(generators.inline.is_some(), threads);
+            // Small inputs are digested inline, and larger ones in the
+            // background, but the digests are the same either way.
+            for data in [vec![], vec![0x5a; 0x400d], vec![0x5a; large]] {
+                let (digests, size) =
+                    digest_file(data.as_slice(), &generators).unwrap();
+                assert_eq!(size, data.len() as u64);
+                assert_eq!(digests, expected(&data));
+            }
+        }
+    }
+
+    #[test]
+    fn digest_after_read_error() {
+        /// A reader which always fails.
+        struct Failing;
+
+        impl io::Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("read failed"))
+            }
+        }
+
+        let generators = generators();
+        let failing = fixtures::ZERO_400D.chain(Failing);
+        assert!(digest_file(failing, &generators).is_err());
+
+        let empty = fs::File::open(fixture_data("empty")).unwrap();
+        let (digests, _size) = digest_file(empty, &generators).unwrap();
+        assert_eq!(digests[1], DigestData::MD5(fixtures::md5::EMPTY));
+    }
+
     #[test]
     fn digest_empty() {
         let empty = fs::File::open(fixture_data("empty")).unwrap();
         let generators = generators();
 
-        let digests = digest_file(empty, &generators).unwrap();
+        let (digests, size) = digest_file(empty, &generators).unwrap();
+
+        assert_eq!(size, 0);
 
         assert_eq!(
             digests,
@@ -296,7 +2003,9 @@ mod tests {
         let zero = fs::File::open(fixture_data("zero-400d")).unwrap();
         let generators = generators();
 
-        let digests = digest_file(zero, &generators).unwrap();
+        let (digests, size) = digest_file(zero, &generators).unwrap();
+
+        assert_eq!(size, 0x400d);
 
         assert_eq!(
             digests,
@@ -315,7 +2024,9 @@ mod tests {
         let random = fs::File::open(fixture_data("random-11171")).unwrap();
         let generators = generators();
 
-        let digests = digest_file(random, &generators).unwrap();
+        let (digests, size) = digest_file(random, &generators).unwrap();
+
+        assert_eq!(size, 0x11171);
 
         assert_eq!(
             digests,
@@ -329,8 +2040,15 @@ mod tests {
         );
     }
 
-    fn generators() -> Vec<Box<dyn Generator>> {
-        vec![crc32(), md5(), sha256(), sha512(), rmd160()]
+    #[test]
+    fn sorted_paths() {
+        let mut paths = ["b", "a/b", "a-b", "B", "a"].map(Path::new);
+        sort_paths(&mut paths);
+        assert_eq!(paths, ["B", "a", "a-b", "a/b", "b"].map(Path::new));
+    }
+
+    fn generators() -> Generators {
+        Generators::from(vec![crc32(), md5(), sha256(), sha512(), rmd160()])
     }
 
     fn fixture_data(filename: &str) -> PathBuf {
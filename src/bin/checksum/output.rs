@@ -0,0 +1,485 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use digest::{manifest, DigestData, DigestKind, Encoding};
+
+/// The formats which the computed digests may be output in.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The BSD style `ALGORITHM (filename) = HEXDIGEST` format.
+    Text,
+    /// The Simple File Verification (SFV) `filename CRC32` format, used to
+    /// verify CRC32 checksums.
+    SFV,
+    /// Comma separated values, with a `path,algorithm,digest,size` header.
+    CSV,
+    /// A YAML mapping from each path to a mapping of its digests.
+    YAML,
+    /// Only the digests, one per line, for use in scripts.
+    Plain,
+}
+
+/// The optional columns included in each line of output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Columns {
+    /// Include the size of the file.
+    pub size: bool,
+    /// Include the modification time of the file.
+    pub mtime: bool,
+}
+
+/// A file (or stdin) that digests were computed for.
+#[derive(Clone, Copy, Debug)]
+pub struct Entry<'a> {
+    /// The path of the file, or `None` for stdin.
+    pub path: Option<&'a Path>,
+    /// The number of bytes read from the file.
+    pub size: u64,
+    /// The modification time of the file, if known.
+    pub mtime: Option<SystemTime>,
+}
+
+impl Format {
+    /// The lines output before any digests, if any. The CSV header names
+    /// the `mtime` column if it is included in the `columns`.
+    pub fn header(self, version: &str, columns: Columns) -> Vec<String> {
+        match self {
+            Format::Text | Format::YAML | Format::Plain => Vec::new(),
+            Format::SFV => vec![format!("; Generated by checksum {version}")],
+            Format::CSV if columns.mtime => {
+                vec![String::from("path,algorithm,digest,size,mtime")]
+            }
+            Format::CSV => vec![String::from("path,algorithm,digest,size")],
+        }
+    }
+
+    /// Format the `digests` computed for the `entry` as lines of output,
+    /// using the given `encoding`. The size and modification time of the
+    /// entry are included as set in `columns`, although CSV output always
+    /// includes the size, and SFV output never includes either.
+    pub fn lines(
+        self,
+        encoding: Encoding,
+        columns: Columns,
+        digests: &[DigestData],
+        entry: &Entry,
+    ) -> Vec<String> {
+        let pathstr = entry.path.map(|path| path.to_str().unwrap());
+        let size = entry.size;
+        let mtime = entry.mtime.filter(|_| columns.mtime).map(timestamp);
+        let mut lines = Vec::new();
+        if self == Format::YAML {
+            lines.push(format!("{}:", quoted_string(pathstr.unwrap_or("-"))));
+        }
+        lines.extend(digests.iter().map(|digest| {
+            let name = digest.name();
+            let encoded = digest.encode(encoding);
+            match (self, pathstr) {
+                // Lines naming a file are written as for a manifest, so
+                // that the filename is escaped if necessary.
+                (Format::Text, Some(pathstr)) => {
+                    let entry = manifest::Entry {
+                        path: PathBuf::from(pathstr),
+                        kind: DigestKind::from_name(name),
+                        digest: digest.as_bytes().to_vec(),
+                        size: columns.size.then_some(size),
+                        mtime: mtime.clone(),
+                    };
+                    manifest::Format::BSD.line(&entry, encoding).unwrap()
+                }
+                (Format::Text, None) => {
+                    let mut line = format!("{name} = {encoded}");
+                    if columns.size {
+                        write!(line, " size={size}").unwrap();
+                    }
+                    if let Some(mtime) = &mtime {
+                        write!(line, " mtime={mtime}").unwrap();
+                    }
+                    line
+                }
+                // SFV files always use upper case hexadecimal, and stdin is
+                // named `-`.
+                (Format::SFV, pathstr) => {
+                    let pathstr = pathstr.unwrap_or("-");
+                    let digest = digest.to_string().to_uppercase();
+                    format!("{pathstr} {digest}")
+                }
+                (Format::CSV, pathstr) => {
+                    let pathstr = csv_field(pathstr.unwrap_or("-"));
+                    let mut line =
+                        format!("{pathstr},{name},{encoded},{size}");
+                    if columns.mtime {
+                        write!(line, ",{}", mtime.as_deref().unwrap_or(""))
+                            .unwrap();
+                    }
+                    line
+                }
+                // The digest is quoted so that it is never read as a number.
+                (Format::YAML, _) => format!("  {name}: \"{encoded}\""),
+                (Format::Plain, _) => encoded.clone(),
+            }
+        }));
+        if self == Format::YAML {
+            if columns.size {
+                lines.push(format!("  size: {size}"));
+            }
+            if let Some(mtime) = mtime {
+                lines.push(format!("  mtime: \"{mtime}\""));
+            }
+        }
+        lines
+    }
+}
+
+/// Quote a `field` for CSV output if it contains a comma, quote, or line
+/// break, following RFC 4180.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Quote a `string` as a double quoted string, which may be used in both
+/// YAML and JSON output.
+pub fn quoted_string(string: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => {
+                write!(quoted, "\\u{:04x}", u32::from(c)).unwrap();
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Format `time` as an RFC 3339 timestamp in UTC, to the nearest second
+/// (rounding down).
+pub fn timestamp(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => i64::try_from(since.as_secs()).unwrap_or(i64::MAX),
+        Err(err) => {
+            let before = err.duration();
+            let seconds = i64::try_from(before.as_secs()).unwrap_or(i64::MAX);
+            -seconds - i64::from(before.subsec_nanos() > 0)
+        }
+    };
+    let (days, time) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+    let (hour, minute, second) = (time / 3600, time / 60 % 60, time % 60);
+
+    // Convert the days since the epoch to a date in the proleptic
+    // Gregorian calendar, working in 400 year eras starting on March 1st.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year = day_of_era
+        - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::time::Duration;
+
+    /// An `Entry` for the given `path` and `size`, with no modification
+    /// time.
+    fn entry(path: Option<&str>, size: u64) -> Entry<'_> {
+        Entry {
+            path: path.map(Path::new),
+            size,
+            mtime: None,
+        }
+    }
+
+    #[test]
+    fn text_lines() {
+        let digests = [
+            DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+        ];
+        assert_eq!(
+            Format::Text.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(Some("foo"), 0)
+            ),
+            [
+                "MD5 (foo) = d41d8cd98f00b204e9800998ecf8427e",
+                "CRC32 (foo) = ff70a8ee",
+            ]
+        );
+        assert_eq!(
+            Format::Text.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(None, 0)
+            ),
+            ["MD5 = d41d8cd98f00b204e9800998ecf8427e", "CRC32 = ff70a8ee"]
+        );
+        assert_eq!(
+            Format::Text.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests[1..],
+                &entry(Some("a\\b"), 0)
+            ),
+            ["\\CRC32 (a\\\\b) = ff70a8ee"]
+        );
+        assert!(Format::Text.header("1.0", Columns::default()).is_empty());
+    }
+
+    #[test]
+    fn sfv_lines() {
+        let digests = [DigestData::CRC32(fixtures::crc32::RANDOM_11171)];
+        assert_eq!(
+            Format::SFV.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(Some("foo bar"), 0)
+            ),
+            ["foo bar FF70A8EE"]
+        );
+        assert_eq!(
+            Format::SFV.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(None, 0)
+            ),
+            ["- FF70A8EE"]
+        );
+        assert_eq!(
+            Format::SFV.header("1.0", Columns::default()),
+            ["; Generated by checksum 1.0"]
+        );
+    }
+
+    #[test]
+    fn csv_lines() {
+        let digests = [
+            DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::CRC32(fixtures::crc32::EMPTY),
+        ];
+        assert_eq!(
+            Format::CSV.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(Some("foo"), 0)
+            ),
+            [
+                "foo,MD5,d41d8cd98f00b204e9800998ecf8427e,0",
+                "foo,CRC32,00000000,0",
+            ]
+        );
+        let digests = [DigestData::CRC32(fixtures::crc32::RANDOM_11171)];
+        assert_eq!(
+            Format::CSV.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(None, 0x11171)
+            ),
+            ["-,CRC32,ff70a8ee,70001"]
+        );
+        assert_eq!(
+            Format::CSV.header("1.0", Columns::default()),
+            ["path,algorithm,digest,size"]
+        );
+    }
+
+    #[test]
+    fn plain_lines() {
+        let digests = [
+            DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+        ];
+        assert_eq!(
+            Format::Plain.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(Some("foo"), 0)
+            ),
+            ["d41d8cd98f00b204e9800998ecf8427e", "ff70a8ee"]
+        );
+        assert_eq!(
+            Format::Plain.lines(
+                Encoding::UpperHex,
+                Columns::default(),
+                &digests[1..],
+                &entry(None, 0)
+            ),
+            ["FF70A8EE"]
+        );
+        assert!(Format::Plain.header("1.0", Columns::default()).is_empty());
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field("foo bar"), "foo bar");
+        assert_eq!(csv_field("foo,bar"), "\"foo,bar\"");
+        assert_eq!(csv_field("say \"foo\""), "\"say \"\"foo\"\"\"");
+        assert_eq!(csv_field("foo\nbar"), "\"foo\nbar\"");
+    }
+
+    #[test]
+    fn yaml_lines() {
+        let digests = [
+            DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::CRC32(fixtures::crc32::EMPTY),
+        ];
+        assert_eq!(
+            Format::YAML.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests,
+                &entry(Some("foo"), 0)
+            ),
+            [
+                "\"foo\":",
+                "  MD5: \"d41d8cd98f00b204e9800998ecf8427e\"",
+                "  CRC32: \"00000000\"",
+            ]
+        );
+        assert_eq!(
+            Format::YAML.lines(
+                Encoding::Hex,
+                Columns::default(),
+                &digests[1..],
+                &entry(None, 0)
+            ),
+            ["\"-\":", "  CRC32: \"00000000\""]
+        );
+        assert!(Format::YAML.header("1.0", Columns::default()).is_empty());
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quoted_string("foo: bar #1"), "\"foo: bar #1\"");
+        assert_eq!(quoted_string("say \"foo\""), "\"say \\\"foo\\\"\"");
+        assert_eq!(quoted_string("a\\b"), "\"a\\\\b\"");
+        assert_eq!(quoted_string("a\tb\nc\u{7}"), "\"a\\tb\\nc\\u0007\"");
+    }
+
+    #[test]
+    fn base32_lines() {
+        let digests = [DigestData::MD5(fixtures::md5::EMPTY)];
+        let padded = Encoding::Base32 { padding: true };
+        let unpadded = Encoding::Base32 { padding: false };
+        assert_eq!(
+            Format::Text.lines(
+                padded,
+                Columns::default(),
+                &digests,
+                &entry(None, 0)
+            ),
+            ["MD5 = 2QOYZWMPACZAJ2MABGMOZ6CCPY======"]
+        );
+        assert_eq!(
+            Format::CSV.lines(
+                unpadded,
+                Columns::default(),
+                &digests,
+                &entry(None, 0)
+            ),
+            ["-,MD5,2QOYZWMPACZAJ2MABGMOZ6CCPY,0"]
+        );
+        // SFV output is always hexadecimal.
+        let digests = [DigestData::CRC32(fixtures::crc32::RANDOM_11171)];
+        assert_eq!(
+            Format::SFV.lines(
+                padded,
+                Columns::default(),
+                &digests,
+                &entry(None, 0)
+            ),
+            ["- FF70A8EE"]
+        );
+    }
+
+    #[test]
+    fn column_lines() {
+        let digests = [DigestData::CRC32(fixtures::crc32::EMPTY)];
+        let both = Columns {
+            size: true,
+            mtime: true,
+        };
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let file = Entry {
+            path: Some(Path::new("foo")),
+            size: 0x400d,
+            mtime: Some(mtime),
+        };
+        let size = Columns {
+            size: true,
+            mtime: false,
+        };
+        assert_eq!(
+            Format::Text.lines(Encoding::Hex, size, &digests, &file),
+            ["CRC32 (foo) = 00000000 size=16397"]
+        );
+        assert_eq!(
+            Format::Text.lines(Encoding::Hex, both, &digests, &file),
+            ["CRC32 (foo) = 00000000 size=16397 mtime=2023-11-14T22:13:20Z"]
+        );
+        assert_eq!(
+            Format::CSV.lines(Encoding::Hex, both, &digests, &file),
+            ["foo,CRC32,00000000,16397,2023-11-14T22:13:20Z"]
+        );
+        assert_eq!(
+            Format::CSV.lines(Encoding::Hex, both, &digests, &entry(None, 0)),
+            ["-,CRC32,00000000,0,"]
+        );
+        assert_eq!(
+            Format::CSV.header("1.0", both),
+            ["path,algorithm,digest,size,mtime"]
+        );
+        assert_eq!(
+            Format::YAML.lines(Encoding::Hex, both, &digests, &file),
+            [
+                "\"foo\":",
+                "  CRC32: \"00000000\"",
+                "  size: 16397",
+                "  mtime: \"2023-11-14T22:13:20Z\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(timestamp(at(951_782_400)), "2000-02-29T00:00:00Z");
+        assert_eq!(timestamp(at(1_700_000_000)), "2023-11-14T22:13:20Z");
+        assert_eq!(timestamp(at(4_107_542_399)), "2100-02-28T23:59:59Z");
+        let before = UNIX_EPOCH - Duration::from_millis(500);
+        assert_eq!(timestamp(before), "1969-12-31T23:59:59Z");
+    }
+}
@@ -0,0 +1,184 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Run `work` on each of the `items` using a pool of up to `jobs` worker
+/// threads, passing each item and its result to `done` in the same order
+/// as the items.
+/// Each worker has its own `state`, created when the worker starts, which
+/// is passed to `work` along with the item. The items with the largest
+/// `size` are started first (and items of the same size in order), so
+/// that a few large items, such as giant files, don't leave the other
+/// workers idle at the end of the run while they finish. Results are held
+/// until every earlier result has been passed to `done`, so the order
+/// never depends on how long each item takes. If `done` returns an error
+/// then no more items are started, and the error is returned once the
+/// workers finish.
+pub fn map_largest_first<'a, T, S, R, E>(
+    items: &'a [T],
+    jobs: usize,
+    size: impl Fn(&T) -> u64,
+    state: impl Fn() -> S + Sync,
+    work: impl Fn(&mut S, &T) -> R + Sync,
+    mut done: impl FnMut(&'a T, R) -> Result<(), E>,
+) -> Result<(), E>
+where
+    T: Sync,
+    R: Send,
+{
+    let schedule = largest_first(items, jobs, size);
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, stop, state, work) = (&next, &stop, &state, &work);
+            let schedule = &schedule;
+            scope.spawn(move || {
+                let mut state = state();
+                while !stop.load(Ordering::Relaxed) {
+                    let position = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&index) = schedule.get(position) else {
+                        break;
+                    };
+                    let result = work(&mut state, &items[index]);
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // The results which arrived before an earlier result.
+        let mut pending = HashMap::new();
+        let mut expected = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&expected) {
+                expected += 1;
+                if let Err(error) = done(&items[expected - 1], result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The indexes of the `items` in the order `map_largest_first()` starts
+/// them, by decreasing `size`. With a single job the order the items are
+/// started in can't make the run any shorter, so they are left in order,
+/// and each result is passed on as soon as it is ready.
+fn largest_first<T>(
+    items: &[T],
+    jobs: usize,
+    size: impl Fn(&T) -> u64,
+) -> Vec<usize> {
+    let mut schedule: Vec<usize> = (0..items.len()).collect();
+    if jobs > 1 {
+        schedule.sort_by_cached_key(|&index| Reverse(size(&items[index])));
+    }
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn ordered_results() {
+        let items: Vec<u64> = (0..32).collect();
+        let mut results = Vec::new();
+        let outcome: Result<(), ()> = map_largest_first(
+            &items,
+            4,
+            |_| 0,
+            || 0,
+            |count, item| {
+                // Later items finish first, so they arrive out of order.
+                thread::sleep(Duration::from_millis(32 - item));
+                *count += 1;
+                item * 2
+            },
+            |item, result| {
+                assert_eq!(result, item * 2);
+                results.push(result);
+                Ok(())
+            },
+        );
+        assert_eq!(outcome, Ok(()));
+        assert_eq!(results, (0..32).map(|item| item * 2).collect::<Vec<_>>());
+
+        let outcome: Result<(), ()> = map_largest_first(
+            &[] as &[u64],
+            4,
+            |_| 0,
+            || (),
+            |(), item| *item,
+            |_, _| Ok(()),
+        );
+        assert_eq!(outcome, Ok(()));
+    }
+
+    #[test]
+    fn largest_first_order() {
+        let items: Vec<u64> = vec![1, 5, 2, 8, 5, 3];
+        let size = |item: &u64| *item;
+        assert_eq!(largest_first(&items, 2, size), [3, 1, 4, 5, 2, 0]);
+        assert_eq!(largest_first(&items, 1, size), [0, 1, 2, 3, 4, 5]);
+
+        let mut results = Vec::new();
+        let outcome: Result<(), ()> = map_largest_first(
+            &items,
+            2,
+            size,
+            || (),
+            |(), item| {
+                thread::sleep(Duration::from_millis(*item));
+                item * 2
+            },
+            |item, result| {
+                assert_eq!(result, item * 2);
+                results.push(result);
+                Ok(())
+            },
+        );
+        assert_eq!(outcome, Ok(()));
+        assert_eq!(results, [2, 10, 4, 16, 10, 6]);
+    }
+
+    #[test]
+    fn stop_on_error() {
+        let items: Vec<usize> = (0..1000).collect();
+        let started = AtomicUsize::new(0);
+        let mut results = Vec::new();
+        let outcome = map_largest_first(
+            &items,
+            2,
+            |_| 0,
+            || (),
+            |(), item| {
+                started.fetch_add(1, Ordering::Relaxed);
+                thread::sleep(Duration::from_millis(1));
+                *item
+            },
+            |_, result| {
+                results.push(result);
+                if result == 3 {
+                    Err("stopped")
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        assert_eq!(outcome, Err("stopped"));
+        assert_eq!(results, [0, 1, 2, 3]);
+        assert!(started.load(Ordering::Relaxed) < items.len());
+    }
+}
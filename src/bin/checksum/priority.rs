@@ -0,0 +1,60 @@
+use std::io;
+
+/// The niceness used with `--background-priority`, which is the lowest
+/// CPU priority.
+#[cfg(unix)]
+const NICENESS: libc::c_int = 19;
+
+/// Lower the CPU and I/O priority of the whole process, with
+/// `--background-priority`, so that it only uses what other processes
+/// leave idle. On Linux the I/O priority is set to the idle class, as
+/// `ionice -c 3` would, while elsewhere only the CPU priority is lowered,
+/// which most schedulers also take into account for I/O. Any threads
+/// started afterwards share the lowered priority.
+#[cfg(unix)]
+pub fn lower() -> io::Result<()> {
+    // SAFETY: `setpriority` only changes the priority of this process.
+    let result =
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, NICENESS) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    lower_io()
+}
+
+/// Lower the priority of the process, which isn't supported on this
+/// platform.
+#[cfg(not(unix))]
+pub fn lower() -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// Put the process in the idle I/O scheduling class, so its reads are
+/// only served when the disk would otherwise be idle.
+#[cfg(target_os = "linux")]
+fn lower_io() -> io::Result<()> {
+    /// The `which` argument to `ioprio_set` for a process.
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    /// The idle class, shifted into place for `ioprio_set`.
+    const IOPRIO_CLASS_IDLE: libc::c_long = 3 << 13;
+    // SAFETY: `ioprio_set` only changes the I/O priority of this process.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// There is no separate I/O priority to lower on this platform.
+#[cfg(all(unix, not(target_os = "linux")))]
+#[allow(clippy::unnecessary_wraps)]
+fn lower_io() -> io::Result<()> {
+    Ok(())
+}
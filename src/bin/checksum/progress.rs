@@ -0,0 +1,227 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn.
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// The progress through the files being digested, shown on stderr as a
+/// single line which is redrawn as the files are read. The sizes of the
+/// files are taken from their metadata before any are read, so the
+/// throughput and the time remaining can be estimated.
+pub struct Progress {
+    /// The total size of every file, as listed in their metadata.
+    total: u64,
+    /// The number of bytes read from every file so far.
+    read: u64,
+    /// When the first file was started.
+    start: Instant,
+    /// When the progress line was last drawn, if it has been.
+    drawn: Option<Instant>,
+    /// The progress line is on stderr, and needs to be cleared.
+    shown: bool,
+    /// The path of the file being read.
+    path: String,
+    /// The size of the file being read, if it is known.
+    size: Option<u64>,
+    /// The number of bytes read from the file so far.
+    file_read: u64,
+}
+
+impl Progress {
+    /// Create the progress through the files at `paths`. Files which can't
+    /// be read, or which have no size (such as stdin), don't count towards
+    /// the total.
+    pub fn new(paths: &[&Path]) -> Self {
+        let total = paths
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .filter(fs::Metadata::is_file)
+            .map(|meta| meta.len())
+            .sum();
+        Self {
+            total,
+            read: 0,
+            start: Instant::now(),
+            drawn: None,
+            shown: false,
+            path: String::new(),
+            size: None,
+            file_read: 0,
+        }
+    }
+
+    /// Track the progress of reading the file at `path` through the
+    /// `input` it was opened as. The progress line is cleared once the
+    /// returned reader is dropped, so that nothing else printed to stderr
+    /// is mixed up with it.
+    pub fn track<R: io::Read>(
+        &mut self,
+        path: &Path,
+        input: R,
+    ) -> Tracked<'_, R> {
+        self.path = path.display().to_string();
+        self.size = fs::metadata(path)
+            .ok()
+            .filter(fs::Metadata::is_file)
+            .map(|meta| meta.len());
+        self.file_read = 0;
+        Tracked {
+            input,
+            progress: self,
+        }
+    }
+
+    /// Count `count` more bytes read from the file, redrawing the progress
+    /// line if it hasn't been drawn recently.
+    fn update(&mut self, count: usize) {
+        self.read += count as u64;
+        self.file_read += count as u64;
+        let now = Instant::now();
+        if self.drawn.is_none_or(|drawn| now - drawn >= INTERVAL) {
+            self.drawn = Some(now);
+            self.shown = true;
+            let line = self.line(now - self.start);
+            eprint!("\r{line}\x1b[K");
+            let _ = io::stderr().flush();
+        }
+    }
+
+    /// Clear the progress line, if it is shown.
+    fn clear(&mut self) {
+        if std::mem::take(&mut self.shown) {
+            eprint!("\r\x1b[K");
+            let _ = io::stderr().flush();
+        }
+    }
+
+    /// The progress line, after `elapsed` time since the first file was
+    /// started.
+    fn line(&self, elapsed: Duration) -> String {
+        let file = match self.size {
+            Some(size) => percent(self.file_read, size),
+            None => bytes(self.file_read),
+        };
+        // The throughput in bytes per second.
+        let millis = elapsed.as_millis().max(1);
+        let rate = u128::from(self.read) * 1000 / millis;
+        let rate = u64::try_from(rate).unwrap_or(u64::MAX);
+        let remaining = self.total.saturating_sub(self.read);
+        let eta = remaining
+            .checked_div(rate)
+            .map_or_else(|| String::from("--:--:--"), duration);
+        format!(
+            "{}: {file} | {} of {} ({}), {}/s, ETA {eta}",
+            self.path,
+            bytes(self.read),
+            bytes(self.total),
+            percent(self.read, self.total),
+            bytes(rate),
+        )
+    }
+}
+
+/// A reader which tracks the progress of reading a file.
+pub struct Tracked<'a, R> {
+    input: R,
+    progress: &'a mut Progress,
+}
+
+impl<R: io::Read> io::Read for Tracked<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.input.read(buf)?;
+        self.progress.update(count);
+        Ok(count)
+    }
+}
+
+impl<R> Drop for Tracked<'_, R> {
+    fn drop(&mut self) {
+        self.progress.clear();
+    }
+}
+
+/// Format `part` as a percentage of `whole`, where a `whole` of nothing
+/// is always complete.
+fn percent(part: u64, whole: u64) -> String {
+    if whole == 0 {
+        return String::from("100%");
+    }
+    format!(
+        "{}%",
+        (u128::from(part.min(whole)) * 100 / u128::from(whole))
+    )
+}
+
+/// Format a number of bytes using decimal units, to a tenth of a unit.
+pub fn bytes(count: u64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if count < 1000 {
+        return format!("{count} B");
+    }
+    let mut unit = 0;
+    let mut scale = 1000;
+    while count / scale >= 1000 && unit < UNITS.len() - 1 {
+        scale *= 1000;
+        unit += 1;
+    }
+    let tenths = u128::from(count) * 10 / u128::from(scale);
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+/// Format a number of `seconds` as hours, minutes, and seconds.
+fn duration(seconds: u64) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+    format!("{hours}:{minutes:02}:{:02}", seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_units() {
+        assert_eq!(percent(0, 200), "0%");
+        assert_eq!(percent(50, 200), "25%");
+        assert_eq!(percent(200, 200), "100%");
+        assert_eq!(percent(300, 200), "100%");
+        assert_eq!(percent(0, 0), "100%");
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(999), "999 B");
+        assert_eq!(bytes(1000), "1.0 KB");
+        assert_eq!(bytes(350_200_000), "350.2 MB");
+        assert_eq!(bytes(500_000_000_000), "500.0 GB");
+        assert_eq!(bytes(u64::MAX), "18446.7 PB");
+        assert_eq!(duration(0), "0:00:00");
+        assert_eq!(duration(60), "0:01:00");
+        assert_eq!(duration(3723), "1:02:03");
+    }
+
+    #[test]
+    fn progress_line() {
+        let mut progress = Progress::new(&[]);
+        progress.total = 4_000_000;
+        progress.read = 1_000_000;
+        progress.path = String::from("foo.iso");
+        progress.size = Some(2_000_000);
+        progress.file_read = 500_000;
+        assert_eq!(
+            progress.line(Duration::from_secs(2)),
+            "foo.iso: 25% | 1.0 MB of 4.0 MB (25%), 500.0 KB/s, ETA 0:00:06"
+        );
+
+        progress.path = String::from("-");
+        progress.size = None;
+        assert_eq!(
+            progress.line(Duration::from_secs(2)),
+            "-: 500.0 KB | 1.0 MB of 4.0 MB (25%), 500.0 KB/s, ETA 0:00:06"
+        );
+
+        progress.read = 0;
+        assert_eq!(
+            progress.line(Duration::from_secs(2)),
+            "-: 500.0 KB | 0 B of 4.0 MB (0%), 0 B/s, ETA --:--:--"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::DigestKind;
+
+/// The path of the sidecar file holding the `kind` digest of the file at
+/// `path`. The sidecar is placed next to the file, with the extension of
+/// the digest appended to its name (so `foo.txt` has a `foo.txt.sha256`
+/// sidecar for its SHA256 digest).
+pub fn path(path: &Path, kind: DigestKind) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(extension(kind));
+    PathBuf::from(name)
+}
+
+/// Check if the `sidecar` for the file at `path` is current, which is the
+/// case if it was modified no earlier than the file itself. If either
+/// modification time can't be read then the sidecar is not current.
+pub fn is_current(path: &Path, sidecar: &Path) -> bool {
+    let modified = |path| fs::metadata(path).and_then(|meta| meta.modified());
+    match (modified(path), modified(sidecar)) {
+        (Ok(file), Ok(sidecar)) => sidecar >= file,
+        _ => false,
+    }
+}
+
+/// Split the path of a `sidecar` file into the path of the file it is for
+/// and the kind of digest it holds. If the extension of the sidecar isn't
+/// used for any kind of digest then `None` is returned.
+pub fn parse(sidecar: &Path) -> Option<(PathBuf, DigestKind)> {
+    let kind = extension_kind(sidecar.extension()?.to_str()?)?;
+    Some((sidecar.with_extension(""), kind))
+}
+
+/// The file extension used for sidecar files holding a `kind` digest.
+pub fn extension(kind: DigestKind) -> &'static str {
+    match kind {
+        DigestKind::CRC16_CCITT => "crc16-ccitt",
+        DigestKind::CRC16_MODBUS => "crc16-modbus",
+        DigestKind::CRC16_XMODEM => "crc16-xmodem",
+        DigestKind::CRC32 => "crc32",
+        DigestKind::CRC64 => "crc64",
+        DigestKind::XXH32 => "xxh32",
+        DigestKind::XXH64 => "xxh64",
+        DigestKind::RAPIDHASH => "rapidhash",
+        DigestKind::MD2 => "md2",
+        DigestKind::MD4 => "md4",
+        DigestKind::MD5 => "md5",
+        DigestKind::SHA224 => "sha224",
+        DigestKind::SHA256 => "sha256",
+        DigestKind::SHA384 => "sha384",
+        DigestKind::SHA512 => "sha512",
+        DigestKind::SHA512_224 => "sha512-224",
+        DigestKind::SHA512_256 => "sha512-256",
+        DigestKind::SHA3_224 => "sha3-224",
+        DigestKind::SHA3_256 => "sha3-256",
+        DigestKind::SHA3_384 => "sha3-384",
+        DigestKind::SHA3_512 => "sha3-512",
+        DigestKind::KECCAK256 => "keccak256",
+        DigestKind::BLAKE2B => "blake2b",
+        DigestKind::BLAKE2S => "blake2s",
+        DigestKind::STREEBOG256 => "streebog256",
+        DigestKind::STREEBOG512 => "streebog512",
+        DigestKind::TIGER => "tiger",
+        DigestKind::TIGER2 => "tiger2",
+        DigestKind::RMD160 => "rmd160",
+        DigestKind::RMD256 => "rmd256",
+        DigestKind::RMD320 => "rmd320",
+    }
+}
+
+/// The kind of digest held by sidecar files with the extension `ext`.
+fn extension_kind(ext: &str) -> Option<DigestKind> {
+    match ext {
+        "crc16-ccitt" => Some(DigestKind::CRC16_CCITT),
+        "crc16-modbus" => Some(DigestKind::CRC16_MODBUS),
+        "crc16-xmodem" => Some(DigestKind::CRC16_XMODEM),
+        "crc32" => Some(DigestKind::CRC32),
+        "crc64" => Some(DigestKind::CRC64),
+        "xxh32" => Some(DigestKind::XXH32),
+        "xxh64" => Some(DigestKind::XXH64),
+        "rapidhash" => Some(DigestKind::RAPIDHASH),
+        "md2" => Some(DigestKind::MD2),
+        "md4" => Some(DigestKind::MD4),
+        "md5" => Some(DigestKind::MD5),
+        "sha224" => Some(DigestKind::SHA224),
+        "sha256" => Some(DigestKind::SHA256),
+        "sha384" => Some(DigestKind::SHA384),
+        "sha512" => Some(DigestKind::SHA512),
+        "sha512-224" => Some(DigestKind::SHA512_224),
+        "sha512-256" => Some(DigestKind::SHA512_256),
+        "sha3-224" => Some(DigestKind::SHA3_224),
+        "sha3-256" => Some(DigestKind::SHA3_256),
+        "sha3-384" => Some(DigestKind::SHA3_384),
+        "sha3-512" => Some(DigestKind::SHA3_512),
+        "keccak256" => Some(DigestKind::KECCAK256),
+        "blake2b" => Some(DigestKind::BLAKE2B),
+        "blake2s" => Some(DigestKind::BLAKE2S),
+        "streebog256" => Some(DigestKind::STREEBOG256),
+        "streebog512" => Some(DigestKind::STREEBOG512),
+        "tiger" => Some(DigestKind::TIGER),
+        "tiger2" => Some(DigestKind::TIGER2),
+        "rmd160" => Some(DigestKind::RMD160),
+        "rmd256" => Some(DigestKind::RMD256),
+        "rmd320" => Some(DigestKind::RMD320),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn sidecar_path() {
+        assert_eq!(
+            path(Path::new("dir/foo.txt"), DigestKind::SHA256),
+            PathBuf::from("dir/foo.txt.sha256")
+        );
+        assert_eq!(
+            path(Path::new("foo"), DigestKind::SHA3_256),
+            PathBuf::from("foo.sha3-256")
+        );
+        assert_eq!(
+            path(Path::new("foo"), DigestKind::CRC16_CCITT),
+            PathBuf::from("foo.crc16-ccitt")
+        );
+    }
+
+    #[test]
+    fn parse_sidecar() {
+        assert_eq!(
+            parse(Path::new("dir/foo.txt.sha256")),
+            Some((PathBuf::from("dir/foo.txt"), DigestKind::SHA256))
+        );
+        assert_eq!(
+            parse(Path::new("foo.crc16-ccitt")),
+            Some((PathBuf::from("foo"), DigestKind::CRC16_CCITT))
+        );
+        assert_eq!(parse(Path::new("foo.txt")), None);
+        assert_eq!(parse(Path::new("foo")), None);
+    }
+
+    #[test]
+    fn current_sidecar() {
+        let dir = std::env::temp_dir();
+        let file = dir.join(format!("checksum-{}-file", std::process::id()));
+        let sidecar = path(&file, DigestKind::MD5);
+        assert!(!is_current(&file, &sidecar));
+
+        fs::write(&file, "").unwrap();
+        assert!(!is_current(&file, &sidecar));
+        fs::write(&sidecar, "").unwrap();
+        assert!(is_current(&file, &sidecar));
+
+        // Modifying the file after the sidecar makes the sidecar stale.
+        let later = SystemTime::now() + Duration::from_mins(1);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(!is_current(&file, &sidecar));
+
+        fs::remove_file(&file).unwrap();
+        fs::remove_file(&sidecar).unwrap();
+    }
+}
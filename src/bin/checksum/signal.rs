@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// The signal which interrupted the run, or zero if there wasn't one.
+static RECEIVED: AtomicI32 = AtomicI32::new(0);
+
+/// Record the `signal` which interrupted the run. Only an atomic store is
+/// made, as little else is safe in a signal handler.
+#[cfg(unix)]
+extern "C" fn handle(signal: libc::c_int) {
+    RECEIVED.store(signal, Ordering::Relaxed);
+}
+
+/// Trap `SIGINT` and `SIGTERM`, so that the run can stop cleanly after the
+/// files which are already digested, rather than dying part way through a
+/// line of output. A second signal isn't trapped, so it ends the run at
+/// once.
+#[cfg(unix)]
+pub fn trap() {
+    let handler = handle as extern "C" fn(libc::c_int);
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the action is fully initialized before it is installed,
+        // and the handler is async-signal-safe.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handler as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART | libc::SA_RESETHAND;
+            libc::sigemptyset(&raw mut action.sa_mask);
+            libc::sigaction(signal, &raw const action, std::ptr::null_mut());
+        }
+    }
+}
+
+/// Signals aren't trapped on this platform.
+#[cfg(not(unix))]
+pub fn trap() {}
+
+/// The signal which interrupted the run, if any.
+pub fn received() -> Option<i32> {
+    Some(RECEIVED.load(Ordering::Relaxed)).filter(|signal| *signal != 0)
+}
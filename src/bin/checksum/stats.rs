@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::progress::bytes;
+
+/// The number of files digested so far, by every worker.
+static FILES: AtomicU64 = AtomicU64::new(0);
+/// The number of bytes digested so far, by every worker.
+static BYTES: AtomicU64 = AtomicU64::new(0);
+/// The number of files which couldn't be read or written so far.
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+/// Count a file of `size` bytes as digested.
+pub fn add_file(size: u64) {
+    FILES.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+}
+
+/// Count a file which couldn't be read or written.
+pub fn add_error() {
+    ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The statistics for a run, printed to stderr with `--stats`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of files digested.
+    pub files: u64,
+    /// The number of bytes digested.
+    pub bytes: u64,
+    /// The number of files which couldn't be read or written.
+    pub errors: u64,
+}
+
+impl Stats {
+    /// The statistics counted so far.
+    pub fn current() -> Self {
+        Self {
+            files: FILES.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            errors: ERRORS.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The statistics as a single line, for a run which took `elapsed`
+    /// time, so that they are easy to find in a log.
+    pub fn line(&self, elapsed: Duration) -> String {
+        // The throughput in bytes per second.
+        let millis = elapsed.as_millis().max(1);
+        let rate = u128::from(self.bytes) * 1000 / millis;
+        let rate = u64::try_from(rate).unwrap_or(u64::MAX);
+        format!(
+            "{} {}, {} bytes ({}) in {}.{:03}s, {}/s, {} {}",
+            self.files,
+            if self.files == 1 { "file" } else { "files" },
+            self.bytes,
+            bytes(self.bytes),
+            elapsed.as_secs(),
+            elapsed.subsec_millis(),
+            bytes(rate),
+            self.errors,
+            if self.errors == 1 { "error" } else { "errors" },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_line() {
+        let stats = Stats {
+            files: 12,
+            bytes: 350_200_000,
+            errors: 0,
+        };
+        assert_eq!(
+            stats.line(Duration::from_millis(2500)),
+            "12 files, 350200000 bytes (350.2 MB) in 2.500s, 140.0 MB/s, \
+             0 errors"
+        );
+        let stats = Stats {
+            files: 1,
+            bytes: 0,
+            errors: 1,
+        };
+        assert_eq!(
+            stats.line(Duration::ZERO),
+            "1 file, 0 bytes (0 B) in 0.000s, 0 B/s, 1 error"
+        );
+    }
+}
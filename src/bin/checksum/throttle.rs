@@ -0,0 +1,88 @@
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The bytes read so far by every throttled reader, which share the same
+/// bandwidth however many files are read at once.
+static BUDGET: Mutex<Budget> = Mutex::new(Budget {
+    start: None,
+    read: 0,
+});
+
+/// The bandwidth used by the throttled readers.
+struct Budget {
+    /// When the first bytes were read.
+    start: Option<Instant>,
+    /// The number of bytes read since then.
+    read: u64,
+}
+
+impl Budget {
+    /// Count `count` more bytes read `now`, returning how long to wait
+    /// before reading any more to keep to `rate` bytes per second.
+    fn spend(&mut self, count: usize, rate: u64, now: Instant) -> Duration {
+        let start = *self.start.get_or_insert(now);
+        self.read += count as u64;
+        delay(self.read, rate, now - start)
+    }
+}
+
+/// How long to wait after reading `read` bytes in `elapsed` time, so that
+/// no more than `rate` bytes are read per second.
+fn delay(read: u64, rate: u64, elapsed: Duration) -> Duration {
+    let due = u128::from(read) * 1_000_000_000 / u128::from(rate.max(1));
+    let due = Duration::from_nanos(u64::try_from(due).unwrap_or(u64::MAX));
+    due.saturating_sub(elapsed)
+}
+
+/// A reader which is limited to `rate` bytes per second, shared with
+/// every other throttled reader, with `--throttle`.
+pub struct Throttled<R> {
+    input: R,
+    rate: u64,
+}
+
+impl<R> Throttled<R> {
+    pub fn new(input: R, rate: u64) -> Self {
+        Self { input, rate }
+    }
+}
+
+impl<R: io::Read> io::Read for Throttled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.input.read(buf)?;
+        let wait = BUDGET
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .spend(count, self.rate, Instant::now());
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_delay() {
+        let second = Duration::from_secs(1);
+        assert_eq!(delay(0, 1000, Duration::ZERO), Duration::ZERO);
+        assert_eq!(delay(1000, 1000, Duration::ZERO), second);
+        assert_eq!(delay(1000, 1000, second), Duration::ZERO);
+        assert_eq!(delay(1000, 1000, second * 2), Duration::ZERO);
+        assert_eq!(delay(500, 1000, Duration::ZERO), second / 2);
+
+        let now = Instant::now();
+        let mut budget = Budget {
+            start: None,
+            read: 0,
+        };
+        assert_eq!(budget.spend(1000, 1000, now), second);
+        assert_eq!(budget.spend(1000, 1000, now + second), second);
+        assert_eq!(budget.spend(0, 1000, now + second * 3), Duration::ZERO);
+    }
+}
@@ -0,0 +1,89 @@
+//! Support shared by the customized BLAKE2 digests. OpenSSL does not
+//! provide access to the BLAKE2 key, salt, and personalization
+//! parameters through its digest interface, so digests using them are
+//! computed directly.
+
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// Parameters used to customize a BLAKE2 digest. Any of the parameters
+/// may be left empty. The salt and personalization are padded with zeros
+/// to their full length.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2Params {
+    /// The key used to compute a keyed digest (a MAC).
+    pub key: Vec<u8>,
+    /// The salt used to randomize the digest.
+    pub salt: Vec<u8>,
+    /// The personalization used to make the digest application specific.
+    pub personal: Vec<u8>,
+}
+
+impl Blake2Params {
+    /// Check whether the parameters are the defaults, in which case the
+    /// digest is the same as the plain BLAKE2 digest.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.key.is_empty()
+            && self.salt.is_empty()
+            && self.personal.is_empty()
+    }
+
+    /// Check whether the parameters fit a BLAKE2 variant with a maximum
+    /// key length of `key` bytes, and a salt and personalization of
+    /// `extra` bytes.
+    /// Overwrite the key with zeros and clear it, so it doesn't linger in
+    /// memory once it is no longer needed.
+    pub fn wipe(&mut self) {
+        wipe(&mut self.key);
+        self.key.clear();
+    }
+
+    pub(crate) fn fits(&self, key: usize, extra: usize) -> bool {
+        self.key.len() <= key
+            && self.salt.len() <= extra
+            && self.personal.len() <= extra
+    }
+}
+
+/// The message word permutation used in each round of BLAKE2.
+pub const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The state indexes mixed by each application of the G function in a
+/// round: four columns followed by four diagonals.
+pub const MIX: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Overwrite `bytes` with zeros. The writes are volatile, so they aren't
+/// optimized away even though the bytes are never read again.
+pub fn wipe(bytes: &mut [u8]) {
+    for byte in bytes {
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Copy `bytes` into a zero padded array of `N` bytes.
+pub fn pad<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut padded = [0; N];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    padded
+}
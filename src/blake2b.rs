@@ -0,0 +1,518 @@
+use std::sync::Arc;
+
+use openssl_sys::EVP_MD;
+
+use crate::blake2::{pad, wipe, MIX, SIGMA};
+use crate::{
+    evp, Background, Blake2Params, Digest, DigestData, DigestKind, Error,
+    Generator,
+};
+
+// The BLAKE2 digests are not exposed by `openssl_sys`, so we need to
+// declare the function we use ourselves.
+extern "C" {
+    fn EVP_blake2b512() -> *const EVP_MD;
+}
+
+/// A structure used to generate a BLAKE2B digest.
+#[derive(Clone)]
+pub struct BLAKE2B {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl BLAKE2B {
+    /// The length of the BLAKE2B digest, in bytes.
+    pub const LENGTH: usize = 64;
+
+    /// Create a new BLAKE2B structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the OpenSSL API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_blake2b512() }),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for BLAKE2B {
+    /// Update the BLAKE2B digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the BLAKE2B digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the BLAKE2B digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_blake2b512() }, data)
+    }
+}
+
+impl Default for BLAKE2B {
+    /// Create a default BLAKE2B structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of bytes processed by each compression of a BLAKE2B digest.
+const BLOCK: usize = 128;
+
+/// The initial state of the BLAKE2B digest, before the parameters are
+/// applied.
+const IV: [u64; 8] = [
+    0x6a09_e667_f3bc_c908,
+    0xbb67_ae85_84ca_a73b,
+    0x3c6e_f372_fe94_f82b,
+    0xa54f_f53a_5f1d_36f1,
+    0x510e_527f_ade6_82d1,
+    0x9b05_688c_2b3e_6c1f,
+    0x1f83_d9ab_fb41_bd6b,
+    0x5be0_cd19_137e_2179,
+];
+
+/// A structure used to generate a BLAKE2B digest customized with a key,
+/// salt, or personalization.
+#[derive(Clone)]
+pub struct CustomBLAKE2B {
+    /// The state after the parameters have been applied.
+    initial: [u64; 8],
+    /// The key, padded to a full block, if one is used.
+    key: Option<[u8; BLOCK]>,
+    /// The chaining state.
+    state: [u64; 8],
+    /// Input which has not yet been compressed. The last block is only
+    /// compressed by `finish()`.
+    buffer: [u8; BLOCK],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes compressed.
+    total: u128,
+}
+
+impl CustomBLAKE2B {
+    /// The maximum length of the key, in bytes.
+    pub const KEY_LENGTH: usize = 64;
+    /// The maximum length of the salt and personalization, in bytes.
+    pub const EXTRA_LENGTH: usize = 16;
+
+    /// Create a new `CustomBLAKE2B` structure to generate a digest using
+    /// the given `params`. If the parameters are too long for BLAKE2B,
+    /// `None` is returned.
+    #[must_use]
+    pub fn new(params: &Blake2Params) -> Option<Self> {
+        if !params.fits(Self::KEY_LENGTH, Self::EXTRA_LENGTH) {
+            return None;
+        }
+        let mut initial = IV;
+        initial[0] ^= 0x0101_0000
+            ^ ((params.key.len() as u64) << 8)
+            ^ BLAKE2B::LENGTH as u64;
+        let extra = [
+            pad::<{ Self::EXTRA_LENGTH }>(&params.salt),
+            pad::<{ Self::EXTRA_LENGTH }>(&params.personal),
+        ];
+        for (word, bytes) in
+            initial[4..].iter_mut().zip(extra.concat().chunks_exact(8))
+        {
+            *word ^= u64::from_le_bytes(pad(bytes));
+        }
+        let key = (!params.key.is_empty()).then(|| pad(&params.key));
+        let mut this = Self {
+            initial,
+            key,
+            state: initial,
+            buffer: [0; BLOCK],
+            buffered: 0,
+            total: 0,
+        };
+        this.reset();
+        Some(this)
+    }
+
+    /// Re-initialize the `CustomBLAKE2B` structure. A key is processed as
+    /// the first block of the message.
+    fn reset(&mut self) {
+        self.state = self.initial;
+        self.total = 0;
+        if let Some(key) = self.key {
+            self.buffer = key;
+            self.buffered = BLOCK;
+        } else {
+            self.buffered = 0;
+        }
+    }
+
+    /// Compress the buffered block into the state.
+    fn compress(&mut self, last: bool) {
+        self.total += self.buffered as u128;
+        let mut block = [0u64; 16];
+        for (word, bytes) in block.iter_mut().zip(self.buffer.chunks_exact(8))
+        {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.state);
+        v[8..].copy_from_slice(&IV);
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            v[12] ^= self.total as u64;
+            v[13] ^= (self.total >> 64) as u64;
+        }
+        if last {
+            v[14] = !v[14];
+        }
+
+        // The first two rounds are repeated after the tenth.
+        for sigma in SIGMA.iter().cycle().take(12) {
+            for (i, [a, b, c, d]) in MIX.into_iter().enumerate() {
+                let x = block[sigma[2 * i]];
+                let y = block[sigma[2 * i + 1]];
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(32);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(24);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(16);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(63);
+            }
+        }
+
+        for (i, word) in self.state.iter_mut().enumerate() {
+            *word ^= v[i] ^ v[i + 8];
+        }
+    }
+}
+
+impl Drop for CustomBLAKE2B {
+    /// Wipe the key, and any of it left in the buffer.
+    fn drop(&mut self) {
+        if let Some(key) = &mut self.key {
+            wipe(key);
+        }
+        wipe(&mut self.buffer);
+    }
+}
+
+impl Digest<{ BLAKE2B::LENGTH }> for CustomBLAKE2B {
+    /// Update the BLAKE2B digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            // A full block is only compressed once we know more input
+            // follows it, since the last block is flagged by `finish()`.
+            if self.buffered == BLOCK {
+                self.compress(false);
+                self.buffered = 0;
+            }
+            let count = (BLOCK - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+        }
+    }
+
+    /// Finalize the BLAKE2B digest computation and return the result. The
+    /// `CustomBLAKE2B` structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; BLAKE2B::LENGTH] {
+        self.buffer[self.buffered..].fill(0);
+        self.compress(true);
+        let mut digest = [0u8; BLAKE2B::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(8).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+}
+
+/// Structure used to compute a BLAKE2B digest in a separate thread.
+pub struct BackgroundBLAKE2B {
+    worker: Background<{ BLAKE2B::LENGTH }>,
+}
+
+impl BackgroundBLAKE2B {
+    /// Create a new `BackgroundBLAKE2B` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(BLAKE2B::new),
+        }
+    }
+}
+
+impl Generator for BackgroundBLAKE2B {
+    /// Add the given `data` to the BLAKE2B digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the BLAKE2B digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::BLAKE2B(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the BLAKE2B digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the BLAKE2B digest.
+    fn name(&self) -> &'static str {
+        DigestKind::BLAKE2B.name()
+    }
+
+    /// The length of the BLAKE2B digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::BLAKE2B.output_len()
+    }
+
+    /// Copy the current state of the BLAKE2B digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the BLAKE2B digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+/// Structure used to compute a customized BLAKE2B digest in a separate
+/// thread.
+pub struct BackgroundCustomBLAKE2B {
+    worker: Background<{ BLAKE2B::LENGTH }>,
+}
+
+impl BackgroundCustomBLAKE2B {
+    /// Create a new `BackgroundCustomBLAKE2B` structure using the given
+    /// `params`. If the parameters are too long for BLAKE2B, `None` is
+    /// returned.
+    pub fn new(params: &Blake2Params) -> Option<Self> {
+        let blake2b = CustomBLAKE2B::new(params)?;
+        Some(Self {
+            worker: Background::new(move || blake2b),
+        })
+    }
+}
+
+impl Generator for BackgroundCustomBLAKE2B {
+    /// Add the given `data` to the BLAKE2B digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the BLAKE2B digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::BLAKE2B(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the BLAKE2B digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the BLAKE2B digest.
+    fn name(&self) -> &'static str {
+        DigestKind::BLAKE2B.name()
+    }
+
+    /// The length of the BLAKE2B digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::BLAKE2B.output_len()
+    }
+
+    /// Copy the current state of the BLAKE2B digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// The state of a keyed BLAKE2B digest isn't exported, as it would
+    /// include the key.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut blake2b = BLAKE2B::new();
+        assert_eq!(blake2b.finish(), fixtures::blake2b::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut blake2b = BLAKE2B::new();
+        blake2b.update(&[0; 0x4000]);
+        blake2b.update(&[0; 0x0d]);
+        assert_eq!(blake2b.finish(), fixtures::blake2b::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut blake2b = BLAKE2B::new();
+        blake2b.update(&fixtures::RANDOM_11171);
+        assert_eq!(blake2b.finish(), fixtures::blake2b::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut blake2b = BLAKE2B::new();
+        assert_eq!(blake2b.finish(), fixtures::blake2b::EMPTY);
+        blake2b.update(&fixtures::ZERO_400D);
+        assert_eq!(blake2b.finish(), fixtures::blake2b::ZERO_400D);
+        blake2b.update(&fixtures::RANDOM_11171);
+        assert_eq!(blake2b.finish(), fixtures::blake2b::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let blake2b = BackgroundBLAKE2B::new();
+        assert_eq!(
+            blake2b.result(),
+            DigestData::BLAKE2B(fixtures::blake2b::EMPTY)
+        );
+        blake2b.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            blake2b.result(),
+            DigestData::BLAKE2B(fixtures::blake2b::ZERO_400D)
+        );
+        blake2b.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            blake2b.result(),
+            DigestData::BLAKE2B(fixtures::blake2b::RANDOM_11171)
+        );
+    }
+
+    #[test]
+    fn custom() {
+        // Without any parameters, the digest is the plain BLAKE2B digest.
+        let mut blake2b = CustomBLAKE2B::new(&Blake2Params::default())
+            .expect("default parameters are valid");
+        assert_eq!(blake2b.finish(), fixtures::blake2b::EMPTY);
+        blake2b.update(&fixtures::ZERO_400D);
+        assert_eq!(blake2b.finish(), fixtures::blake2b::ZERO_400D);
+        for chunk in fixtures::RANDOM_11171.chunks(127) {
+            blake2b.update(chunk);
+        }
+        assert_eq!(blake2b.finish(), fixtures::blake2b::RANDOM_11171);
+    }
+
+    #[test]
+    fn keyed() {
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let mut blake2b = CustomBLAKE2B::new(&params).unwrap();
+        assert_eq!(
+            DigestData::BLAKE2B(blake2b.finish()).to_string(),
+            "5b3cfd8f422b490b764b55eceb330b500c79cbefa9a928ad00202b8b3c5dd778\
+             a81122570434a2e3b8bfd028d105dfefd0a9576e88ed66de742ca9fbb5f8d2b6"
+        );
+        blake2b.update(b"abc");
+        assert_eq!(
+            DigestData::BLAKE2B(blake2b.finish()).to_string(),
+            "5c6a9a4ae911c02fb7e71a991eb9aea371ae993d4842d206e6020d46f5e41358\
+             c6d5c277c110ef86c959ed63e6ecaaaceaaff38019a43264ae06acf73b9550b1"
+        );
+
+        let params = Blake2Params {
+            key: (0..64).collect(),
+            ..Blake2Params::default()
+        };
+        let mut blake2b = CustomBLAKE2B::new(&params).unwrap();
+        blake2b.update(&fixtures::RANDOM_11171);
+        assert_eq!(
+            DigestData::BLAKE2B(blake2b.finish()).to_string(),
+            "400d926a6a50d35195dba05193f429850ed5d2313e4093b023f8fa5637c8b512\
+             6734a1e470b9356195eb7705498ed7248540b6b5aa2dea972160a5faa66c517f"
+        );
+    }
+
+    #[test]
+    fn salted() {
+        let params = Blake2Params {
+            salt: b"salt".to_vec(),
+            personal: b"personal".to_vec(),
+            ..Blake2Params::default()
+        };
+        let mut blake2b = CustomBLAKE2B::new(&params).unwrap();
+        blake2b.update(&fixtures::RANDOM_11171);
+        assert_eq!(
+            DigestData::BLAKE2B(blake2b.finish()).to_string(),
+            "31c9d28f8276f0165088c44ed998e08f4f1d8ddf8e4d1669172736749627eee6\
+             9ae6361df991460096f3aa0a7990e6d8e2a192020a966e163acddf872365cc62"
+        );
+
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..params
+        };
+        let mut blake2b = CustomBLAKE2B::new(&params).unwrap();
+        blake2b.update(b"abc");
+        assert_eq!(
+            DigestData::BLAKE2B(blake2b.finish()).to_string(),
+            "a91d719bc76c890bb3f04becf896f68eb5f296583e1d772c1327f8e49dab8306\
+             2c268e6a71563cb322144784db9cb134918066140ad37b3757ef64e493ae66a6"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for params in [
+            Blake2Params {
+                key: vec![0; 65],
+                ..Blake2Params::default()
+            },
+            Blake2Params {
+                salt: vec![0; 17],
+                ..Blake2Params::default()
+            },
+            Blake2Params {
+                personal: vec![0; 17],
+                ..Blake2Params::default()
+            },
+        ] {
+            assert!(CustomBLAKE2B::new(&params).is_none());
+        }
+    }
+
+    #[test]
+    fn custom_background() {
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let blake2b = BackgroundCustomBLAKE2B::new(&params).unwrap();
+        blake2b.append(Arc::from(&b"abc"[..]));
+        assert_eq!(
+            blake2b.result().to_string(),
+            "5c6a9a4ae911c02fb7e71a991eb9aea371ae993d4842d206e6020d46f5e41358\
+             c6d5c277c110ef86c959ed63e6ecaaaceaaff38019a43264ae06acf73b9550b1"
+        );
+    }
+}
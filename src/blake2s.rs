@@ -0,0 +1,512 @@
+use std::sync::Arc;
+
+use openssl_sys::EVP_MD;
+
+use crate::blake2::{pad, wipe, MIX, SIGMA};
+use crate::{
+    evp, Background, Blake2Params, Digest, DigestData, DigestKind, Error,
+    Generator,
+};
+
+// The BLAKE2 digests are not exposed by `openssl_sys`, so we need to
+// declare the function we use ourselves.
+extern "C" {
+    fn EVP_blake2s256() -> *const EVP_MD;
+}
+
+/// A structure used to generate a BLAKE2S digest.
+#[derive(Clone)]
+pub struct BLAKE2S {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl BLAKE2S {
+    /// The length of the BLAKE2S digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new BLAKE2S structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the OpenSSL API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_blake2s256() }),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for BLAKE2S {
+    /// Update the BLAKE2S digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the BLAKE2S digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the BLAKE2S digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_blake2s256() }, data)
+    }
+}
+
+impl Default for BLAKE2S {
+    /// Create a default BLAKE2S structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of bytes processed by each compression of a BLAKE2S digest.
+const BLOCK: usize = 64;
+
+/// The initial state of the BLAKE2S digest, before the parameters are
+/// applied.
+const IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// A structure used to generate a BLAKE2S digest customized with a key,
+/// salt, or personalization.
+#[derive(Clone)]
+pub struct CustomBLAKE2S {
+    /// The state after the parameters have been applied.
+    initial: [u32; 8],
+    /// The key, padded to a full block, if one is used.
+    key: Option<[u8; BLOCK]>,
+    /// The chaining state.
+    state: [u32; 8],
+    /// Input which has not yet been compressed. The last block is only
+    /// compressed by `finish()`.
+    buffer: [u8; BLOCK],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes compressed.
+    total: u64,
+}
+
+impl CustomBLAKE2S {
+    /// The maximum length of the key, in bytes.
+    pub const KEY_LENGTH: usize = 32;
+    /// The maximum length of the salt and personalization, in bytes.
+    pub const EXTRA_LENGTH: usize = 8;
+
+    /// Create a new `CustomBLAKE2S` structure to generate a digest using
+    /// the given `params`. If the parameters are too long for BLAKE2S,
+    /// `None` is returned.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(params: &Blake2Params) -> Option<Self> {
+        if !params.fits(Self::KEY_LENGTH, Self::EXTRA_LENGTH) {
+            return None;
+        }
+        let mut initial = IV;
+        initial[0] ^= 0x0101_0000
+            ^ ((params.key.len() as u32) << 8)
+            ^ BLAKE2S::LENGTH as u32;
+        let extra = [
+            pad::<{ Self::EXTRA_LENGTH }>(&params.salt),
+            pad::<{ Self::EXTRA_LENGTH }>(&params.personal),
+        ];
+        for (word, bytes) in
+            initial[4..].iter_mut().zip(extra.concat().chunks_exact(4))
+        {
+            *word ^= u32::from_le_bytes(pad(bytes));
+        }
+        let key = (!params.key.is_empty()).then(|| pad(&params.key));
+        let mut this = Self {
+            initial,
+            key,
+            state: initial,
+            buffer: [0; BLOCK],
+            buffered: 0,
+            total: 0,
+        };
+        this.reset();
+        Some(this)
+    }
+
+    /// Re-initialize the `CustomBLAKE2S` structure. A key is processed as
+    /// the first block of the message.
+    fn reset(&mut self) {
+        self.state = self.initial;
+        self.total = 0;
+        if let Some(key) = self.key {
+            self.buffer = key;
+            self.buffered = BLOCK;
+        } else {
+            self.buffered = 0;
+        }
+    }
+
+    /// Compress the buffered block into the state.
+    fn compress(&mut self, last: bool) {
+        self.total += self.buffered as u64;
+        let mut block = [0u32; 16];
+        for (word, bytes) in block.iter_mut().zip(self.buffer.chunks_exact(4))
+        {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        let mut v = [0u32; 16];
+        v[..8].copy_from_slice(&self.state);
+        v[8..].copy_from_slice(&IV);
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            v[12] ^= self.total as u32;
+            v[13] ^= (self.total >> 32) as u32;
+        }
+        if last {
+            v[14] = !v[14];
+        }
+
+        for sigma in &SIGMA {
+            for (i, [a, b, c, d]) in MIX.into_iter().enumerate() {
+                let x = block[sigma[2 * i]];
+                let y = block[sigma[2 * i + 1]];
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(16);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(12);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(8);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(7);
+            }
+        }
+
+        for (i, word) in self.state.iter_mut().enumerate() {
+            *word ^= v[i] ^ v[i + 8];
+        }
+    }
+}
+
+impl Drop for CustomBLAKE2S {
+    /// Wipe the key, and any of it left in the buffer.
+    fn drop(&mut self) {
+        if let Some(key) = &mut self.key {
+            wipe(key);
+        }
+        wipe(&mut self.buffer);
+    }
+}
+
+impl Digest<{ BLAKE2S::LENGTH }> for CustomBLAKE2S {
+    /// Update the BLAKE2S digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            // A full block is only compressed once we know more input
+            // follows it, since the last block is flagged by `finish()`.
+            if self.buffered == BLOCK {
+                self.compress(false);
+                self.buffered = 0;
+            }
+            let count = (BLOCK - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+        }
+    }
+
+    /// Finalize the BLAKE2S digest computation and return the result. The
+    /// `CustomBLAKE2S` structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; BLAKE2S::LENGTH] {
+        self.buffer[self.buffered..].fill(0);
+        self.compress(true);
+        let mut digest = [0u8; BLAKE2S::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+}
+
+/// Structure used to compute a BLAKE2S digest in a separate thread.
+pub struct BackgroundBLAKE2S {
+    worker: Background<{ BLAKE2S::LENGTH }>,
+}
+
+impl BackgroundBLAKE2S {
+    /// Create a new `BackgroundBLAKE2S` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(BLAKE2S::new),
+        }
+    }
+}
+
+impl Generator for BackgroundBLAKE2S {
+    /// Add the given `data` to the BLAKE2S digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the BLAKE2S digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::BLAKE2S(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the BLAKE2S digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the BLAKE2S digest.
+    fn name(&self) -> &'static str {
+        DigestKind::BLAKE2S.name()
+    }
+
+    /// The length of the BLAKE2S digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::BLAKE2S.output_len()
+    }
+
+    /// Copy the current state of the BLAKE2S digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the BLAKE2S digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+/// Structure used to compute a customized BLAKE2S digest in a separate
+/// thread.
+pub struct BackgroundCustomBLAKE2S {
+    worker: Background<{ BLAKE2S::LENGTH }>,
+}
+
+impl BackgroundCustomBLAKE2S {
+    /// Create a new `BackgroundCustomBLAKE2S` structure using the given
+    /// `params`. If the parameters are too long for BLAKE2S, `None` is
+    /// returned.
+    pub fn new(params: &Blake2Params) -> Option<Self> {
+        let blake2s = CustomBLAKE2S::new(params)?;
+        Some(Self {
+            worker: Background::new(move || blake2s),
+        })
+    }
+}
+
+impl Generator for BackgroundCustomBLAKE2S {
+    /// Add the given `data` to the BLAKE2S digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the BLAKE2S digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::BLAKE2S(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the BLAKE2S digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the BLAKE2S digest.
+    fn name(&self) -> &'static str {
+        DigestKind::BLAKE2S.name()
+    }
+
+    /// The length of the BLAKE2S digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::BLAKE2S.output_len()
+    }
+
+    /// Copy the current state of the BLAKE2S digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// The state of a keyed BLAKE2S digest isn't exported, as it would
+    /// include the key.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut blake2s = BLAKE2S::new();
+        assert_eq!(blake2s.finish(), fixtures::blake2s::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut blake2s = BLAKE2S::new();
+        blake2s.update(&[0; 0x4000]);
+        blake2s.update(&[0; 0x0d]);
+        assert_eq!(blake2s.finish(), fixtures::blake2s::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut blake2s = BLAKE2S::new();
+        blake2s.update(&fixtures::RANDOM_11171);
+        assert_eq!(blake2s.finish(), fixtures::blake2s::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut blake2s = BLAKE2S::new();
+        assert_eq!(blake2s.finish(), fixtures::blake2s::EMPTY);
+        blake2s.update(&fixtures::ZERO_400D);
+        assert_eq!(blake2s.finish(), fixtures::blake2s::ZERO_400D);
+        blake2s.update(&fixtures::RANDOM_11171);
+        assert_eq!(blake2s.finish(), fixtures::blake2s::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let blake2s = BackgroundBLAKE2S::new();
+        assert_eq!(
+            blake2s.result(),
+            DigestData::BLAKE2S(fixtures::blake2s::EMPTY)
+        );
+        blake2s.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            blake2s.result(),
+            DigestData::BLAKE2S(fixtures::blake2s::ZERO_400D)
+        );
+        blake2s.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            blake2s.result(),
+            DigestData::BLAKE2S(fixtures::blake2s::RANDOM_11171)
+        );
+    }
+
+    #[test]
+    fn custom() {
+        // Without any parameters, the digest is the plain BLAKE2S digest.
+        let mut blake2s = CustomBLAKE2S::new(&Blake2Params::default())
+            .expect("default parameters are valid");
+        assert_eq!(blake2s.finish(), fixtures::blake2s::EMPTY);
+        blake2s.update(&fixtures::ZERO_400D);
+        assert_eq!(blake2s.finish(), fixtures::blake2s::ZERO_400D);
+        for chunk in fixtures::RANDOM_11171.chunks(63) {
+            blake2s.update(chunk);
+        }
+        assert_eq!(blake2s.finish(), fixtures::blake2s::RANDOM_11171);
+    }
+
+    #[test]
+    fn keyed() {
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let mut blake2s = CustomBLAKE2S::new(&params).unwrap();
+        assert_eq!(
+            DigestData::BLAKE2S(blake2s.finish()).to_string(),
+            "a65f92611fdc3722a305edf1ed575947aa86209290344f817e45c3a4edfddad9"
+        );
+        blake2s.update(b"abc");
+        assert_eq!(
+            DigestData::BLAKE2S(blake2s.finish()).to_string(),
+            "3f9723437b033bf0c1f4df43cafd0776068cb0a95912de13f3b2952a3aba764d"
+        );
+
+        let params = Blake2Params {
+            key: (0..32).collect(),
+            ..Blake2Params::default()
+        };
+        let mut blake2s = CustomBLAKE2S::new(&params).unwrap();
+        blake2s.update(&fixtures::RANDOM_11171);
+        assert_eq!(
+            DigestData::BLAKE2S(blake2s.finish()).to_string(),
+            "7273d5cd2978bd004cae628ce071ac72a58b8c95cce40ad2b19f7598ec2f3b3d"
+        );
+    }
+
+    #[test]
+    fn salted() {
+        let params = Blake2Params {
+            salt: b"salt".to_vec(),
+            personal: b"personal".to_vec(),
+            ..Blake2Params::default()
+        };
+        let mut blake2s = CustomBLAKE2S::new(&params).unwrap();
+        blake2s.update(&fixtures::RANDOM_11171);
+        assert_eq!(
+            DigestData::BLAKE2S(blake2s.finish()).to_string(),
+            "12f18a1f1d6ffde98da3761513f7e7113533573f24f691ca8cecdc50e58c35ab"
+        );
+
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..params
+        };
+        let mut blake2s = CustomBLAKE2S::new(&params).unwrap();
+        blake2s.update(b"abc");
+        assert_eq!(
+            DigestData::BLAKE2S(blake2s.finish()).to_string(),
+            "b80783649ddab48ee120fa77e134fb1b48d0fcbc48b235906bb74fa3b1c0710b"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for params in [
+            Blake2Params {
+                key: vec![0; 33],
+                ..Blake2Params::default()
+            },
+            Blake2Params {
+                salt: vec![0; 9],
+                ..Blake2Params::default()
+            },
+            Blake2Params {
+                personal: vec![0; 9],
+                ..Blake2Params::default()
+            },
+        ] {
+            assert!(CustomBLAKE2S::new(&params).is_none());
+        }
+    }
+
+    #[test]
+    fn custom_background() {
+        let params = Blake2Params {
+            key: b"key".to_vec(),
+            ..Blake2Params::default()
+        };
+        let blake2s = BackgroundCustomBLAKE2S::new(&params).unwrap();
+        blake2s.append(Arc::from(&b"abc"[..]));
+        assert_eq!(
+            blake2s.result().to_string(),
+            "3f9723437b033bf0c1f4df43cafd0776068cb0a95912de13f3b2952a3aba764d"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::walk::{Options, Walker};
+use crate::{decode_hex, DigestData, DigestKind, Error, Hasher};
+
+/// What a `Cache` records about a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cached {
+    /// The size of the file, in bytes.
+    pub size: u64,
+    /// The modification time of the file.
+    pub mtime: SystemTime,
+    /// The digests of the file, in the order of the kinds of the `Hasher`.
+    pub digests: Vec<DigestData>,
+}
+
+/// A cache of the digests of the files in one or more trees, which records
+/// the size and modification time of each file alongside its digests, so
+/// that only the files which have changed need to be read again when the
+/// trees are scanned.
+///
+/// The cache is saved as a text file, with a line for each file giving its
+/// size, its modification time in nanoseconds since the epoch, its digests
+/// as `kind=hex` pairs, and its path, separated by tabs. Files with paths
+/// which aren't UTF-8, or which contain a newline, aren't saved, so are
+/// read again after the cache is loaded.
+#[derive(Debug)]
+pub struct Cache {
+    /// The hasher used to compute the digests of the files.
+    hasher: Hasher,
+    /// What is recorded about each file, by path.
+    files: BTreeMap<PathBuf, Cached>,
+}
+
+impl Cache {
+    /// Create a new, empty `Cache`, which uses `hasher` to compute the
+    /// digests of the files.
+    #[must_use]
+    pub fn new(hasher: Hasher) -> Self {
+        Self {
+            hasher,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache saved at `path` by `save()`, using `hasher` to
+    /// compute the digests of the files. If there is no file at `path`
+    /// then the cache is empty. Lines which aren't properly formatted, or
+    /// which don't hold a digest of each of the hasher's kinds, are
+    /// skipped.
+    ///
+    /// ## Errors
+    ///
+    /// Any error reading the file is returned.
+    pub fn load(
+        path: impl AsRef<Path>,
+        hasher: Hasher,
+    ) -> Result<Self, Error> {
+        let mut cache = Self::new(hasher);
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(cache);
+            }
+            Err(err) => return Err(err.into()),
+        };
+        for line in text.lines() {
+            if let Some((path, cached)) = parse_line(line, cache.kinds()) {
+                cache.files.insert(path, cached);
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Save the cache to `path`, replacing any existing file once the
+    /// cache has been written in full.
+    ///
+    /// ## Errors
+    ///
+    /// Any error writing the file is returned.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        for (file, cached) in &self.files {
+            let Some(file) =
+                file.to_str().filter(|file| !file.contains('\n'))
+            else {
+                continue;
+            };
+            let mtime = cached
+                .mtime
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_nanos());
+            let _ = write!(text, "{}\t{mtime}\t", cached.size);
+            for (i, digest) in cached.digests.iter().enumerate() {
+                let space = if i == 0 { "" } else { " " };
+                let _ =
+                    write!(text, "{space}{}={digest}", digest.kind().name());
+            }
+            let _ = writeln!(text, "\t{file}");
+        }
+
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        fs::write(&temp, text)?;
+        fs::rename(&temp, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })?;
+        Ok(())
+    }
+
+    /// The kinds of digest held by the cache.
+    #[must_use]
+    pub fn kinds(&self) -> &[DigestKind] {
+        self.hasher.kinds()
+    }
+
+    /// What is recorded about the file at `path`, if it is in the cache.
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<&Cached> {
+        self.files.get(path.as_ref())
+    }
+
+    /// The number of files in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Bring the cache up to date with the files in the tree under `dir`,
+    /// found as for `walk::Walker` with the default options. A file is
+    /// only read if it isn't in the cache, or its size or modification
+    /// time differs from the one recorded. Files under `dir` which no
+    /// longer exist are removed from the cache.
+    ///
+    /// The new digests of the files which were read are returned, by path.
+    ///
+    /// ## Errors
+    ///
+    /// Any error reading a directory or file in the tree is returned, in
+    /// which case the cache holds the files read before the error.
+    pub fn refresh(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> Result<BTreeMap<PathBuf, Vec<DigestData>>, Error> {
+        let dir = dir.as_ref();
+        let mut found = Vec::new();
+        let mut changed = BTreeMap::new();
+        for path in Walker::new(dir, Options::default()) {
+            let path = path.map_err(|dir| {
+                io::Error::other(format!(
+                    "unable to read directory '{}'",
+                    dir.display()
+                ))
+            })?;
+            let metadata = fs::metadata(&path)?;
+            let size = metadata.len();
+            let mtime = metadata.modified()?;
+            let unchanged = self.files.get(&path).is_some_and(|cached| {
+                cached.size == size && cached.mtime == mtime
+            });
+            if !unchanged {
+                let digests = self.hasher.hash_file(&path)?;
+                changed.insert(path.clone(), digests.clone());
+                self.files.insert(
+                    path.clone(),
+                    Cached {
+                        size,
+                        mtime,
+                        digests,
+                    },
+                );
+            }
+            found.push(path);
+        }
+
+        found.sort();
+        self.files.retain(|path, _| {
+            !path.starts_with(dir) || found.binary_search(path).is_ok()
+        });
+        Ok(changed)
+    }
+}
+
+/// Parse a `line` of a saved cache, which must hold a digest of each of the
+/// `kinds`, in order.
+fn parse_line(line: &str, kinds: &[DigestKind]) -> Option<(PathBuf, Cached)> {
+    let mut fields = line.splitn(4, '\t');
+    let size = fields.next()?.parse().ok()?;
+    let nanos: u128 = fields.next()?.parse().ok()?;
+    let digests = fields.next()?;
+    let path = fields.next().filter(|path| !path.is_empty())?;
+
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    let nanos = u32::try_from(nanos % 1_000_000_000).ok()?;
+    let mtime = UNIX_EPOCH.checked_add(Duration::new(secs, nanos))?;
+
+    let mut parsed = Vec::with_capacity(kinds.len());
+    let mut digests = digests.split(' ');
+    for &kind in kinds {
+        let (name, hex) = digests.next()?.split_once('=')?;
+        if name != kind.name() {
+            return None;
+        }
+        parsed.push(DigestData::from_bytes(kind, &decode_hex(hex)?).ok()?);
+    }
+    if digests.next().is_some() {
+        return None;
+    }
+
+    Some((
+        PathBuf::from(path),
+        Cached {
+            size,
+            mtime,
+            digests: parsed,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{digest, fixtures};
+
+    #[test]
+    fn cache_refresh() {
+        let dir = fixtures::ScratchDir::new("cache");
+        let tree = dir.join("tree");
+        fs::create_dir_all(tree.join("sub")).unwrap();
+        fs::write(tree.join("a"), "abc").unwrap();
+        fs::write(tree.join("sub/b"), "").unwrap();
+
+        let kinds = [DigestKind::MD5, DigestKind::CRC32];
+        let hasher = || Hasher::builder().algorithms(&kinds).build().unwrap();
+        let expected = |data: &[u8]| -> Vec<DigestData> {
+            kinds
+                .iter()
+                .map(|&kind| digest(kind, data).unwrap())
+                .collect()
+        };
+
+        let mut cache = Cache::new(hasher());
+        let changed = cache.refresh(&tree).unwrap();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[&tree.join("a")], expected(b"abc"));
+        assert_eq!(changed[&tree.join("sub/b")], expected(b""));
+        assert!(cache.refresh(&tree).unwrap().is_empty());
+
+        // The cache survives being saved and loaded.
+        let saved = dir.join("cache");
+        cache.save(&saved).unwrap();
+        let mut cache = Cache::load(&saved, hasher()).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.get(tree.join("a")).unwrap().digests,
+            expected(b"abc")
+        );
+        assert!(cache.refresh(&tree).unwrap().is_empty());
+
+        // Only the changed file is read again, and removed files are
+        // dropped from the cache.
+        fs::write(tree.join("a"), "abcd").unwrap();
+        fs::remove_file(tree.join("sub/b")).unwrap();
+        let changed = cache.refresh(&tree).unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[&tree.join("a")], expected(b"abcd"));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(tree.join("sub/b")).is_none());
+
+        // A cache saved with other kinds of digest is discarded.
+        cache.save(&saved).unwrap();
+        let sha256 = Hasher::builder()
+            .algorithms(&[DigestKind::SHA256])
+            .build()
+            .unwrap();
+        assert!(Cache::load(&saved, sha256).unwrap().is_empty());
+        assert!(Cache::load(dir.join("missing"), hasher())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn cache_lines() {
+        let kinds = [DigestKind::CRC32];
+        let line = "3\t1500000000\tCRC32=352441c2\tdir/a file";
+        let (path, cached) = parse_line(line, &kinds).unwrap();
+        assert_eq!(path, Path::new("dir/a file"));
+        assert_eq!(cached.size, 3);
+        assert_eq!(cached.mtime, UNIX_EPOCH + Duration::from_millis(1500));
+        assert_eq!(
+            cached.digests,
+            [digest(DigestKind::CRC32, b"abc").unwrap()]
+        );
+
+        for line in [
+            "x\t0\tCRC32=352441c2\ta",
+            "3\t0\tCRC32=352441c\ta",
+            "3\t0\tMD5=352441c2\ta",
+            "3\t0\tCRC32=352441c2 CRC32=352441c2\ta",
+            "3\t0\tCRC32=352441c2\t",
+            "3\t0\tCRC32=352441c2",
+        ] {
+            assert_eq!(parse_line(line, &kinds), None, "{line}");
+        }
+    }
+}
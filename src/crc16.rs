@@ -0,0 +1,374 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The lookup table for the CRC16/CCITT and CRC16/XMODEM checksums, which
+/// share the polynomial 0x1021.
+const CCITT_TABLE: [u16; 256] = table(0x1021);
+
+/// The lookup table for the CRC16/MODBUS checksum, which uses the
+/// polynomial 0x8005 with the bits of each byte reflected.
+const MODBUS_TABLE: [u16; 256] = reflected_table(0xa001);
+
+/// The CRC16 variants which may be computed. Each variant has its own
+/// `DigestData` variant.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// The CRC-16/CCITT-FALSE checksum, with an initial value of 0xffff.
+    CCITT,
+    /// The CRC-16/MODBUS checksum, as used by the Modbus protocol.
+    MODBUS,
+    /// The CRC-16/XMODEM checksum, with an initial value of 0.
+    XMODEM,
+}
+
+impl Variant {
+    /// The kind of digest computed for the variant.
+    fn kind(self) -> DigestKind {
+        match self {
+            Variant::CCITT => DigestKind::CRC16_CCITT,
+            Variant::MODBUS => DigestKind::CRC16_MODBUS,
+            Variant::XMODEM => DigestKind::CRC16_XMODEM,
+        }
+    }
+
+    /// The identifier used for the variant in exported state.
+    fn id(self) -> u8 {
+        match self {
+            Variant::CCITT => 0,
+            Variant::MODBUS => 1,
+            Variant::XMODEM => 2,
+        }
+    }
+
+    /// The variant with the given `id`, as returned by `id()`.
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Variant::CCITT),
+            1 => Some(Variant::MODBUS),
+            2 => Some(Variant::XMODEM),
+            _ => None,
+        }
+    }
+
+    /// The initial value of the checksum.
+    fn initial(self) -> u16 {
+        match self {
+            Variant::CCITT | Variant::MODBUS => 0xffff,
+            Variant::XMODEM => 0x0000,
+        }
+    }
+}
+
+/// A structure used to generate a CRC16 checksum.
+#[derive(Clone)]
+pub struct CRC16 {
+    /// The CRC16 variant being computed.
+    variant: Variant,
+    /// The current CRC16 checksum.
+    crc: u16,
+}
+
+impl CRC16 {
+    /// The length of the CRC16 checksum, in bytes.
+    pub const LENGTH: usize = 2;
+
+    /// Create a new CRC16 structure to generate a checksum using the given
+    /// `variant`.
+    #[must_use]
+    pub fn new(variant: Variant) -> Self {
+        Self {
+            variant,
+            crc: variant.initial(),
+        }
+    }
+
+    /// Re-initialize the CRC16 structure.
+    fn reset(&mut self) {
+        self.crc = self.variant.initial();
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for CRC16 {
+    /// Update the CRC16 checksum using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut crc = self.crc;
+        match self.variant {
+            Variant::CCITT | Variant::XMODEM => {
+                for &byte in data {
+                    let index = (crc >> 8) as u8 ^ byte;
+                    crc = (crc << 8) ^ CCITT_TABLE[usize::from(index)];
+                }
+            }
+            Variant::MODBUS => {
+                for &byte in data {
+                    let index = crc.to_le_bytes()[0] ^ byte;
+                    crc = (crc >> 8) ^ MODBUS_TABLE[usize::from(index)];
+                }
+            }
+        }
+        self.crc = crc;
+    }
+
+    /// Return the CRC16 checksum. The CRC16 checksum is reset so that it
+    /// can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let crc = self.crc.to_be_bytes();
+        self.reset();
+        crc
+    }
+
+    /// Export the current state of the CRC16 checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u8(self.variant.id());
+        state.u16(self.crc);
+        Some(state.finish())
+    }
+
+    /// Create a CRC16 checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let variant = Variant::from_id(state.u8()?)?;
+        let crc = state.u16()?;
+        state.finish()?;
+        Some(Self { variant, crc })
+    }
+}
+
+/// Build the lookup table for a CRC16 with the given `polynomial`,
+/// processing the most significant bit first.
+const fn table(polynomial: u16) -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i: u16 = 0;
+    while i < 256 {
+        let mut crc = i << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ polynomial
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Build the lookup table for a CRC16 with the given (reflected)
+/// `polynomial`, processing the least significant bit first.
+const fn reflected_table(polynomial: u16) -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i: u16 = 0;
+    while i < 256 {
+        let mut crc = i;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x0001 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ polynomial
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Structure used to compute a CRC16 checksum in a separate thread.
+pub struct BackgroundCRC16 {
+    variant: Variant,
+    worker: Background<{ CRC16::LENGTH }>,
+}
+
+impl BackgroundCRC16 {
+    /// Create a new `BackgroundCRC16` structure using the given `variant`.
+    pub fn new(variant: Variant) -> Self {
+        Self {
+            variant,
+            worker: Background::new(move || CRC16::new(variant)),
+        }
+    }
+
+    /// Create a `BackgroundCRC16` structure which resumes the state
+    /// returned by `export_state()`, which must be for the given
+    /// `variant`.
+    pub fn import(variant: Variant, state: &[u8]) -> Option<Self> {
+        let crc16 = CRC16::import_state(state)?;
+        if crc16.variant != variant {
+            return None;
+        }
+        Some(Self {
+            variant,
+            worker: Background::new(move || crc16),
+        })
+    }
+}
+
+impl Generator for BackgroundCRC16 {
+    /// Add the given `data` to the CRC16 checksum.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the CRC16 checksum, and reset the checksum computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        let crc = self.worker.finish()?;
+        Ok(match self.variant {
+            Variant::CCITT => DigestData::CRC16_CCITT(crc),
+            Variant::MODBUS => DigestData::CRC16_MODBUS(crc),
+            Variant::XMODEM => DigestData::CRC16_XMODEM(crc),
+        })
+    }
+
+    /// Discard the data added to the CRC16 checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the CRC16 checksum.
+    fn name(&self) -> &'static str {
+        self.variant.kind().name()
+    }
+
+    /// The length of the CRC16 checksum, in bytes.
+    fn output_len(&self) -> usize {
+        self.variant.kind().output_len()
+    }
+
+    /// Copy the current state of the CRC16 checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            variant: self.variant,
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the CRC16 checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// The fixtures for each variant, as empty, zero, and random data.
+    const FIXTURES: [(Variant, [[u8; CRC16::LENGTH]; 3]); 3] = [
+        (
+            Variant::CCITT,
+            [
+                fixtures::crc16_ccitt::EMPTY,
+                fixtures::crc16_ccitt::ZERO_400D,
+                fixtures::crc16_ccitt::RANDOM_11171,
+            ],
+        ),
+        (
+            Variant::MODBUS,
+            [
+                fixtures::crc16_modbus::EMPTY,
+                fixtures::crc16_modbus::ZERO_400D,
+                fixtures::crc16_modbus::RANDOM_11171,
+            ],
+        ),
+        (
+            Variant::XMODEM,
+            [
+                fixtures::crc16_xmodem::EMPTY,
+                fixtures::crc16_xmodem::ZERO_400D,
+                fixtures::crc16_xmodem::RANDOM_11171,
+            ],
+        ),
+    ];
+
+    #[test]
+    fn empty() {
+        for (variant, [empty, _, _]) in FIXTURES {
+            let mut crc16 = CRC16::new(variant);
+            assert_eq!(crc16.finish(), empty, "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn zero() {
+        for (variant, [_, zero, _]) in FIXTURES {
+            let mut crc16 = CRC16::new(variant);
+            crc16.update(&[0; 0x4000]);
+            crc16.update(&[0; 0x0d]);
+            assert_eq!(crc16.finish(), zero, "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn random() {
+        for (variant, [_, _, random]) in FIXTURES {
+            let mut crc16 = CRC16::new(variant);
+            crc16.update(&fixtures::RANDOM_11171);
+            assert_eq!(crc16.finish(), random, "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn check() {
+        // The standard check values for each variant, from the catalogue
+        // of parametrised CRC algorithms.
+        for (variant, expected) in [
+            (Variant::CCITT, 0x29b1u16),
+            (Variant::MODBUS, 0x4b37),
+            (Variant::XMODEM, 0x31c3),
+        ] {
+            let mut crc16 = CRC16::new(variant);
+            crc16.update(b"123456789");
+            assert_eq!(crc16.finish(), expected.to_be_bytes(), "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn multiple() {
+        for (variant, [empty, zero, random]) in FIXTURES {
+            let mut crc16 = CRC16::new(variant);
+            assert_eq!(crc16.finish(), empty, "{variant:?}");
+            crc16.update(&fixtures::ZERO_400D);
+            assert_eq!(crc16.finish(), zero, "{variant:?}");
+            crc16.update(&fixtures::RANDOM_11171);
+            assert_eq!(crc16.finish(), random, "{variant:?}");
+        }
+    }
+
+    #[test]
+    fn background() {
+        let crc16 = BackgroundCRC16::new(Variant::CCITT);
+        assert_eq!(
+            crc16.result(),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY)
+        );
+        crc16.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            crc16.result(),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::ZERO_400D)
+        );
+        let crc16 = BackgroundCRC16::new(Variant::MODBUS);
+        crc16.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            crc16.result(),
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::RANDOM_11171)
+        );
+        let crc16 = BackgroundCRC16::new(Variant::XMODEM);
+        crc16.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            crc16.result(),
+            DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::RANDOM_11171)
+        );
+    }
+}
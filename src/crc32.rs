@@ -1,10 +1,195 @@
 use std::sync::Arc;
 
-use libz_sys::crc32;
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
 
-use crate::{Background, Digest, DigestData, Generator};
+/// The reflected form of the CRC32 polynomial, as used by zlib.
+const POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// Lookup tables used to process the checksum eight bytes at a time. The
+/// first table processes a single byte, and each of the others processes a
+/// byte which is one further from the end of the eight.
+const TABLE: [[u32; 256]; 8] = table();
+
+/// Compute the lookup tables for `POLYNOMIAL`.
+const fn table() -> [[u32; 256]; 8] {
+    let mut table = [[0; 256]; 8];
+    let mut i = 0;
+    while i < 256 {
+        // The index is less than 256, so always fits.
+        #[allow(clippy::cast_possible_truncation)]
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[0][i] = crc;
+        i += 1;
+    }
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let crc = table[k - 1][i];
+            table[k][i] = (crc >> 8) ^ table[0][(crc & 0xff) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    table
+}
+
+/// Update the CRC32 checksum `crc` with `data`, using the fastest
+/// implementation supported by the CPU, which is checked at runtime.
+fn update(crc: u32, data: &[u8]) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    if data.len() >= pclmul::MINIMUM && pclmul::detected() {
+        // SAFETY: The CPU supports the features `pclmul::update()` uses.
+        return unsafe { pclmul::update(crc, data) };
+    }
+    portable(crc, data)
+}
+
+/// Update the CRC32 checksum `crc` with `data`, using the lookup tables to
+/// process eight bytes at a time.
+fn portable(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let word =
+            u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let [b0, b1, b2, b3] = (crc ^ word).to_le_bytes();
+        crc = TABLE[7][usize::from(b0)]
+            ^ TABLE[6][usize::from(b1)]
+            ^ TABLE[5][usize::from(b2)]
+            ^ TABLE[4][usize::from(b3)]
+            ^ TABLE[3][usize::from(chunk[4])]
+            ^ TABLE[2][usize::from(chunk[5])]
+            ^ TABLE[1][usize::from(chunk[6])]
+            ^ TABLE[0][usize::from(chunk[7])];
+    }
+    for &byte in chunks.remainder() {
+        let [low, ..] = crc.to_le_bytes();
+        crc = TABLE[0][usize::from(low ^ byte)] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// The CRC32 checksum computed by folding the data with carry-less
+/// multiplication, following Intel's "Fast CRC Computation for Generic
+/// Polynomials Using PCLMULQDQ Instruction".
+#[cfg(target_arch = "x86_64")]
+mod pclmul {
+    use std::arch::x86_64::{
+        __m128i, _mm_and_si128, _mm_clmulepi64_si128, _mm_cvtsi32_si128,
+        _mm_extract_epi32, _mm_loadu_si128, _mm_set_epi32, _mm_set_epi64x,
+        _mm_srli_si128, _mm_xor_si128,
+    };
+
+    /// The shortest data for which folding is worthwhile. Shorter data is
+    /// processed using the lookup tables.
+    pub const MINIMUM: usize = 128;
+
+    /// The folding constants for the reflected CRC32 polynomial: x^(4*128+32)
+    /// and x^(4*128-32) mod P for folding by four, x^(128+32) and x^(128-32)
+    /// mod P for folding by one, and x^64 mod P for the reduction to 64 bits.
+    const K1: i64 = 0x1_5444_2bd4;
+    const K2: i64 = 0x1_c6e4_1596;
+    const K3: i64 = 0x1_7519_97d0;
+    const K4: i64 = 0x0_ccaa_009e;
+    const K5: i64 = 0x1_63cd_6124;
+    /// The polynomial, and its Barrett reduction constant.
+    const P_X: i64 = 0x1_db71_0641;
+    const U_PRIME: i64 = 0x1_f701_1641;
+
+    /// Whether the CPU supports the features `update()` uses.
+    pub fn detected() -> bool {
+        is_x86_feature_detected!("pclmulqdq")
+            && is_x86_feature_detected!("sse4.1")
+    }
+
+    /// Update the CRC32 checksum `crc` with `data`, which must be at least
+    /// `MINIMUM` bytes long.
+    #[target_feature(enable = "pclmulqdq,sse4.1")]
+    pub fn update(crc: u32, mut data: &[u8]) -> u32 {
+        debug_assert!(data.len() >= MINIMUM);
+
+        // Fold four blocks of 128 bits at a time, starting with the initial
+        // value folded into the first block.
+        let mut x3 = load(&mut data);
+        let mut x2 = load(&mut data);
+        let mut x1 = load(&mut data);
+        let mut x0 = load(&mut data);
+        x3 = _mm_xor_si128(x3, _mm_cvtsi32_si128((!crc).cast_signed()));
+        let k1k2 = _mm_set_epi64x(K2, K1);
+        while data.len() >= 64 {
+            x3 = fold(x3, load(&mut data), k1k2);
+            x2 = fold(x2, load(&mut data), k1k2);
+            x1 = fold(x1, load(&mut data), k1k2);
+            x0 = fold(x0, load(&mut data), k1k2);
+        }
+
+        // Fold the four blocks into one, then fold in any further whole
+        // blocks.
+        let k3k4 = _mm_set_epi64x(K4, K3);
+        let mut x = fold(x3, x2, k3k4);
+        x = fold(x, x1, k3k4);
+        x = fold(x, x0, k3k4);
+        while data.len() >= 16 {
+            x = fold(x, load(&mut data), k3k4);
+        }
+
+        // Reduce the 128 bits to 64 bits.
+        let low = _mm_set_epi32(0, 0, 0, !0);
+        let x = _mm_xor_si128(
+            _mm_clmulepi64_si128(x, k3k4, 0x10),
+            _mm_srli_si128(x, 8),
+        );
+        let x = _mm_xor_si128(
+            _mm_clmulepi64_si128(
+                _mm_and_si128(x, low),
+                _mm_set_epi64x(0, K5),
+                0x00,
+            ),
+            _mm_srli_si128(x, 4),
+        );
+
+        // Barrett reduction of the 64 bits to the 32 bit checksum.
+        let pu = _mm_set_epi64x(U_PRIME, P_X);
+        let t1 = _mm_clmulepi64_si128(_mm_and_si128(x, low), pu, 0x10);
+        let t2 = _mm_clmulepi64_si128(_mm_and_si128(t1, low), pu, 0x00);
+        let crc = !_mm_extract_epi32(_mm_xor_si128(x, t2), 1).cast_unsigned();
+
+        // Any bytes after the last whole block use the lookup tables.
+        super::portable(crc, data)
+    }
+
+    /// Fold the 128 bit block `a` into the block `b`, using the pair of
+    /// constants in `keys`.
+    #[target_feature(enable = "pclmulqdq,sse4.1")]
+    fn fold(a: __m128i, b: __m128i, keys: __m128i) -> __m128i {
+        let t1 = _mm_clmulepi64_si128(a, keys, 0x00);
+        let t2 = _mm_clmulepi64_si128(a, keys, 0x11);
+        _mm_xor_si128(_mm_xor_si128(b, t1), t2)
+    }
+
+    /// Load a 128 bit block from the start of `data`, and advance past it.
+    #[target_feature(enable = "pclmulqdq,sse4.1")]
+    fn load(data: &mut &[u8]) -> __m128i {
+        let (block, rest) = data.split_at(16);
+        *data = rest;
+        // SAFETY: `block` is 16 bytes long, and the load is unaligned.
+        unsafe { _mm_loadu_si128(block.as_ptr().cast()) }
+    }
+}
 
 /// A structure used to generated a CRC32 checksum.
+#[derive(Clone)]
 pub struct CRC32 {
     /// The current CRC32 checksum.
     crc: u32,
@@ -28,18 +213,8 @@ impl CRC32 {
 
 impl Digest<{ Self::LENGTH }> for CRC32 {
     /// Update the CRC32 checksum using the given `data`.
-    ///
-    /// ## Panics
-    ///
-    /// Passing a data block with more than `u32::MAX` bytes will cause a
-    /// panic.
     fn update(&mut self, data: &[u8]) {
-        let len = data
-            .len()
-            .try_into()
-            .expect("data block is too large for CRC32 processing");
-        let crc = unsafe { crc32(self.crc.into(), data.as_ptr(), len) };
-        self.crc = crc.try_into().expect("unexpected CRC32 value > u32::MAX");
+        self.crc = update(self.crc, data);
     }
 
     /// Return the CRC32 checksum. The CRC32 checksum is reset so that it
@@ -49,6 +224,22 @@ impl Digest<{ Self::LENGTH }> for CRC32 {
         self.reset();
         crc
     }
+
+    /// Export the current state of the CRC32 checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u32(self.crc);
+        Some(state.finish())
+    }
+
+    /// Create a CRC32 checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let crc = state.u32()?;
+        state.finish()?;
+        Some(Self { crc })
+    }
 }
 
 impl Default for CRC32 {
@@ -70,17 +261,53 @@ impl BackgroundCRC32 {
             worker: Background::new(CRC32::new),
         }
     }
+
+    /// Create a `BackgroundCRC32` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<CRC32>(state)?,
+        })
+    }
 }
 
 impl Generator for BackgroundCRC32 {
     /// Add the given `data` to the CRC32 checksum.
-    fn append(&self, data: Arc<[u8]>) {
-        self.worker.update(data);
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
     }
 
     /// Retrieve the CRC32 checksum, and reset the checksum computation.
-    fn result(&self) -> DigestData {
-        DigestData::CRC32(self.worker.finish())
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::CRC32(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the CRC32 checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the CRC32 checksum.
+    fn name(&self) -> &'static str {
+        DigestKind::CRC32.name()
+    }
+
+    /// The length of the CRC32 checksum, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::CRC32.output_len()
+    }
+
+    /// Copy the current state of the CRC32 checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the CRC32 checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
     }
 }
 
@@ -120,6 +347,34 @@ mod tests {
         assert_eq!(crc32.finish(), fixtures::crc32::RANDOM_11171);
     }
 
+    #[test]
+    fn portable_tables() {
+        assert_eq!(
+            portable(0, &fixtures::RANDOM_11171).to_be_bytes(),
+            fixtures::crc32::RANDOM_11171
+        );
+        assert_eq!(
+            portable(0, &fixtures::ZERO_400D).to_be_bytes(),
+            fixtures::crc32::ZERO_400D
+        );
+    }
+
+    #[test]
+    fn unaligned_lengths() {
+        // The accelerated implementation, where it is available, matches
+        // the lookup tables for data of any length and alignment.
+        for start in 0..16 {
+            for len in (0..300).chain([0x1000, 0x2b00]) {
+                let data = &fixtures::RANDOM_11171[start..start + len];
+                assert_eq!(
+                    update(0x1234_5678, data),
+                    portable(0x1234_5678, data),
+                    "{start} {len}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn background() {
         let crc32 = BackgroundCRC32::new();
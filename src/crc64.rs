@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The reflected form of the ECMA-182 polynomial, as used by xz and 7-zip.
+const POLYNOMIAL: u64 = 0xc96c_5795_d787_0f42;
+
+/// A lookup table used to process the checksum a byte at a time.
+const TABLE: [u64; 256] = table();
+
+/// Compute the lookup table for `POLYNOMIAL`.
+const fn table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// A structure used to generate a CRC64 checksum.
+#[derive(Clone)]
+pub struct CRC64 {
+    /// The current CRC64 checksum.
+    crc: u64,
+}
+
+impl CRC64 {
+    /// The length of the CRC64 checksum, in bytes.
+    pub const LENGTH: usize = 8;
+
+    /// Create a new CRC64 structure generate a checksum.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { crc: 0 }
+    }
+
+    /// Re-initialize the CRC64 structure.
+    fn reset(&mut self) {
+        self.crc = 0;
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for CRC64 {
+    /// Update the CRC64 checksum using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut crc = !self.crc;
+        for &byte in data {
+            // Only the low byte of the CRC is used to index the table.
+            #[allow(clippy::cast_possible_truncation)]
+            let index = (crc as u8 ^ byte) as usize;
+            crc = TABLE[index] ^ (crc >> 8);
+        }
+        self.crc = !crc;
+    }
+
+    /// Return the CRC64 checksum. The CRC64 checksum is reset so that it
+    /// can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let crc = self.crc.to_be_bytes();
+        self.reset();
+        crc
+    }
+
+    /// Export the current state of the CRC64 checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u64(self.crc);
+        Some(state.finish())
+    }
+
+    /// Create a CRC64 checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let crc = state.u64()?;
+        state.finish()?;
+        Some(Self { crc })
+    }
+}
+
+impl Default for CRC64 {
+    /// Create a default CRC64 structure to generate a checksum.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute an CRC64 checksum in a separate thread.
+pub struct BackgroundCRC64 {
+    worker: Background<{ CRC64::LENGTH }>,
+}
+
+impl BackgroundCRC64 {
+    /// Create a new `BackgroundCRC64` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(CRC64::new),
+        }
+    }
+
+    /// Create a `BackgroundCRC64` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<CRC64>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundCRC64 {
+    /// Add the given `data` to the CRC64 checksum.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the CRC64 checksum, and reset the checksum computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::CRC64(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the CRC64 checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the CRC64 checksum.
+    fn name(&self) -> &'static str {
+        DigestKind::CRC64.name()
+    }
+
+    /// The length of the CRC64 checksum, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::CRC64.output_len()
+    }
+
+    /// Copy the current state of the CRC64 checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the CRC64 checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut crc64 = CRC64::new();
+        assert_eq!(crc64.finish(), fixtures::crc64::EMPTY);
+    }
+
+    #[test]
+    fn check() {
+        let mut crc64 = CRC64::new();
+        crc64.update(b"123456789");
+        assert_eq!(crc64.finish(), 0x995d_c9bb_df19_39fa_u64.to_be_bytes());
+    }
+
+    #[test]
+    fn zero() {
+        let mut crc64 = CRC64::new();
+        crc64.update(&[0; 0x4000]);
+        crc64.update(&[0; 0x0d]);
+        assert_eq!(crc64.finish(), fixtures::crc64::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut crc64 = CRC64::new();
+        crc64.update(&fixtures::RANDOM_11171);
+        assert_eq!(crc64.finish(), fixtures::crc64::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut crc64 = CRC64::new();
+        assert_eq!(crc64.finish(), fixtures::crc64::EMPTY);
+        crc64.update(&fixtures::ZERO_400D);
+        assert_eq!(crc64.finish(), fixtures::crc64::ZERO_400D);
+        crc64.update(&fixtures::RANDOM_11171);
+        assert_eq!(crc64.finish(), fixtures::crc64::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let crc64 = BackgroundCRC64::new();
+        assert_eq!(crc64.result(), DigestData::CRC64(fixtures::crc64::EMPTY));
+        crc64.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            crc64.result(),
+            DigestData::CRC64(fixtures::crc64::ZERO_400D)
+        );
+        crc64.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            crc64.result(),
+            DigestData::CRC64(fixtures::crc64::RANDOM_11171)
+        );
+    }
+}
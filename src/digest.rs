@@ -16,6 +16,49 @@ pub trait Digest<const N: usize> {
     /// after calling this method, so that the next call to `update()`
     /// will work as though no data had been received.
     fn finish(&mut self) -> [u8; N];
+
+    /// Copy the current state of the `Digest`, so that the digest of the
+    /// data so far can be computed by one copy while the other continues
+    /// to be updated.
+    #[must_use]
+    fn fork(&self) -> Self
+    where
+        Self: Clone + Sized,
+    {
+        self.clone()
+    }
+
+    /// Export the current state of the `Digest`, so that it can be saved
+    /// and the digest resumed later with `import_state()`, such as after
+    /// a restart part way through a very large file. `None` is returned
+    /// if the underlying implementation doesn't expose its state.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Create a `Digest` with the `state` returned by `export_state()`.
+    /// `None` is returned if the `state` isn't valid for this kind of
+    /// digest, or its state can't be imported.
+    #[must_use]
+    fn import_state(state: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = state;
+        None
+    }
+
+    /// Compute the digest of `data` using a new `Digest`, which is dropped
+    /// afterwards.
+    #[must_use]
+    fn digest(data: &[u8]) -> [u8; N]
+    where
+        Self: Default + Sized,
+    {
+        let mut digest = Self::default();
+        digest.update(data);
+        digest.finish()
+    }
 }
 
 #[cfg(test)]
@@ -24,6 +67,7 @@ pub mod count {
 
     /// A trivial digest algorithm which just computes a count of the passed
     /// bytes (mod 256). This is intended to be used for testing purposes.
+    #[derive(Clone)]
     pub struct Count {
         c: u8,
     }
@@ -48,6 +92,17 @@ pub mod count {
             self.c = 0;
             [c]
         }
+
+        /// Export the count of bytes.
+        fn export_state(&self) -> Option<Vec<u8>> {
+            Some(vec![self.c])
+        }
+
+        /// Import the count of bytes.
+        fn import_state(state: &[u8]) -> Option<Self> {
+            let [c] = state.try_into().ok()?;
+            Some(Self { c })
+        }
     }
 
     #[cfg(test)]
@@ -94,6 +149,7 @@ pub mod xor {
 
     /// A trivial digest algorithm which just computes a running XOR of the
     /// bytes. This is intended to be used for testing purposes.
+    #[derive(Clone)]
     #[allow(clippy::upper_case_acronyms)]
     pub struct XOR {
         d: u8,
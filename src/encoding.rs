@@ -0,0 +1,228 @@
+/// The encodings which a digest may be formatted with. The names follow
+/// the multibase table, although no multibase prefix is added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lower case hexadecimal.
+    Hex,
+    /// Upper case hexadecimal.
+    UpperHex,
+    /// RFC 4648 base32, optionally padded with `=`.
+    Base32 { padding: bool },
+    /// Base58, using the Bitcoin alphabet.
+    Base58Btc,
+    /// RFC 4648 base64, optionally padded with `=`.
+    Base64 { padding: bool },
+    /// RFC 4648 URL and filename safe base64, optionally padded with `=`.
+    Base64Url { padding: bool },
+}
+
+impl Encoding {
+    /// Encode `bytes` as a string.
+    #[must_use]
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => hex(bytes, HEX),
+            Encoding::UpperHex => hex(bytes, UPPER_HEX),
+            Encoding::Base32 { padding } => base32(bytes, padding),
+            Encoding::Base58Btc => base58(bytes),
+            Encoding::Base64 { padding } => base64(bytes, BASE64, padding),
+            Encoding::Base64Url { padding } => {
+                base64(bytes, BASE64_URL, padding)
+            }
+        }
+    }
+}
+
+/// Decode a string of hexadecimal digits, in either case, into bytes. If
+/// `hex` is not an even number of hexadecimal digits then `None` is
+/// returned.
+#[must_use]
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|digits| {
+            let high = char::from(digits[0]).to_digit(16)?;
+            let low = char::from(digits[1]).to_digit(16)?;
+            u8::try_from(high << 4 | low).ok()
+        })
+        .collect()
+}
+
+/// The lower case hexadecimal alphabet.
+const HEX: &[u8; 16] = b"0123456789abcdef";
+/// The upper case hexadecimal alphabet.
+const UPPER_HEX: &[u8; 16] = b"0123456789ABCDEF";
+/// The RFC 4648 base32 alphabet.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// The Bitcoin base58 alphabet, which omits `0`, `O`, `I`, and `l`.
+const BASE58: &[u8; 58] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// The RFC 4648 base64 alphabet.
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// The RFC 4648 URL and filename safe base64 alphabet.
+const BASE64_URL: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode `bytes` as hexadecimal, using the given `alphabet`.
+fn hex(bytes: &[u8], alphabet: &[u8; 16]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        encoded.push(char::from(alphabet[usize::from(byte >> 4)]));
+        encoded.push(char::from(alphabet[usize::from(byte & 0xf)]));
+    }
+    encoded
+}
+
+/// Encode `bytes` as base32, optionally `padding` the result with `=`.
+pub(crate) fn base32(bytes: &[u8], padding: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    // Each group of 5 bytes is encoded as 8 characters of 5 bits each.
+    for group in bytes.chunks(5) {
+        let mut bits = [0u8; 8];
+        bits[3..3 + group.len()].copy_from_slice(group);
+        let bits = u64::from_be_bytes(bits);
+        let chars = (group.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < chars {
+                let index = (bits >> (35 - 5 * i)) & 0x1f;
+                encoded.push(char::from(BASE32[index as usize]));
+            } else if padding {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Encode `bytes` as base58. Each leading zero byte is encoded as a `1`,
+/// and the remaining bytes are treated as a big endian number.
+fn base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    // The base58 digits, least significant first.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let ones = std::iter::repeat_n('1', zeros);
+    let digits = digits
+        .iter()
+        .rev()
+        .map(|digit| char::from(BASE58[usize::from(*digit)]));
+    ones.chain(digits).collect()
+}
+
+/// Encode `bytes` as base64 using the given `alphabet`, optionally
+/// `padding` the result with `=`.
+fn base64(bytes: &[u8], alphabet: &[u8; 64], padding: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    // Each group of 3 bytes is encoded as 4 characters of 6 bits each.
+    for group in bytes.chunks(3) {
+        let mut bits = [0u8; 4];
+        bits[1..=group.len()].copy_from_slice(group);
+        let bits = u32::from_be_bytes(bits);
+        let chars = (group.len() * 8).div_ceil(6);
+        for i in 0..4 {
+            if i < chars {
+                let index = (bits >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(alphabet[index as usize]));
+            } else if padding {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_encoding() {
+        let bytes = [0x00, 0x1f, 0xa0, 0xff];
+        assert_eq!(Encoding::Hex.encode(&bytes), "001fa0ff");
+        assert_eq!(Encoding::UpperHex.encode(&bytes), "001FA0FF");
+        assert_eq!(Encoding::Hex.encode(&[]), "");
+    }
+
+    #[test]
+    fn hex_decoding() {
+        let bytes = [0x00, 0x1f, 0xa0, 0xff];
+        assert_eq!(decode_hex("001fa0ff"), Some(bytes.to_vec()));
+        assert_eq!(decode_hex("001FA0FF"), Some(bytes.to_vec()));
+        assert_eq!(decode_hex(""), Some(Vec::new()));
+        for hex in ["0", "0g", "+f", "-1", " 1", "\u{e9}"] {
+            assert_eq!(decode_hex(hex), None);
+        }
+    }
+
+    #[test]
+    fn base32_encoding() {
+        // The test vectors from RFC 4648.
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (data, expected) in vectors {
+            assert_eq!(base32(data.as_bytes(), true), expected);
+            assert_eq!(
+                base32(data.as_bytes(), false),
+                expected.trim_end_matches('=')
+            );
+        }
+    }
+
+    #[test]
+    fn base58_encoding() {
+        assert_eq!(base58(b""), "");
+        assert_eq!(base58(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(base58(&[0x00, 0x00, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+        assert_eq!(base58(&[0xfb, 0xff]), "LBG");
+        assert_eq!(base58(&[0x00]), "1");
+    }
+
+    #[test]
+    fn base64_encoding() {
+        // The test vectors from RFC 4648.
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (data, expected) in vectors {
+            assert_eq!(base64(data.as_bytes(), BASE64, true), expected);
+            assert_eq!(
+                base64(data.as_bytes(), BASE64, false),
+                expected.trim_end_matches('=')
+            );
+        }
+        let bytes = [0xfb, 0xff, 0xfe];
+        assert_eq!(Encoding::Base64 { padding: true }.encode(&bytes), "+//+");
+        assert_eq!(
+            Encoding::Base64Url { padding: true }.encode(&bytes),
+            "-__-"
+        );
+    }
+}
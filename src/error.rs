@@ -0,0 +1,110 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::DigestKind;
+
+/// The errors which may occur while computing digests.
+#[derive(Debug)]
+pub enum Error {
+    /// An error reading the data to digest.
+    Io(io::Error),
+    /// The background thread computing a digest has exited, or didn't
+    /// respond in time.
+    WorkerFailed,
+    /// The background thread computing a digest didn't respond within the
+    /// time allowed, but may still do so.
+    TimedOut,
+    /// The operation was cancelled using a `CancellationToken`.
+    Cancelled,
+    /// The kind of digest is unavailable, either because it wasn't built
+    /// in or because the system libraries don't provide it.
+    Unsupported(DigestKind),
+    /// A digest was the wrong length for its kind.
+    InvalidDigest {
+        /// The kind of digest.
+        kind: DigestKind,
+        /// The length of the digest which was given, in bytes.
+        len: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{err}"),
+            Error::WorkerFailed => {
+                write!(f, "the thread computing the digest has failed")
+            }
+            Error::TimedOut => {
+                write!(f, "timed out waiting for the digest to be computed")
+            }
+            Error::Cancelled => write!(f, "the operation was cancelled"),
+            Error::Unsupported(kind) => {
+                write!(f, "the {} digest is unavailable", kind.name())
+            }
+            Error::InvalidDigest { kind, len } => write!(
+                f,
+                "a {} digest is {} bytes, not {len}",
+                kind.name(),
+                kind.output_len()
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    /// Convert the error for the I/O traits, such as when a `HashReader`
+    /// is read. An unavailable digest has the kind `Unsupported`.
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io(err) => err,
+            Error::Unsupported(_) => {
+                io::Error::new(io::ErrorKind::Unsupported, err)
+            }
+            Error::TimedOut => io::Error::new(io::ErrorKind::TimedOut, err),
+            err => io::Error::other(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_errors() {
+        assert_eq!(
+            Error::Unsupported(DigestKind::MD2).to_string(),
+            "the MD2 digest is unavailable"
+        );
+        let invalid = Error::InvalidDigest {
+            kind: DigestKind::MD5,
+            len: 4,
+        };
+        assert_eq!(invalid.to_string(), "a MD5 digest is 16 bytes, not 4");
+        let err = io::Error::from(Error::Unsupported(DigestKind::MD2));
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        let err = io::Error::from(Error::TimedOut);
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        let err = io::Error::from(Error::WorkerFailed);
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+        assert!(error::Error::source(&err).is_some());
+    }
+}
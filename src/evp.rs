@@ -0,0 +1,156 @@
+//! The OpenSSL one-shot digest function, used to compute the digest of
+//! data which is all in memory without creating a context to reuse.
+
+use std::os::raw::{c_int, c_uchar, c_uint, c_void};
+use std::ptr;
+
+use openssl_sys::{
+    EVP_DigestFinal_ex, EVP_DigestInit_ex, EVP_DigestUpdate,
+    EVP_MD_CTX_copy_ex, EVP_MD_CTX_free, EVP_MD_CTX_new, ENGINE,
+    EVP_MAX_MD_SIZE, EVP_MD, EVP_MD_CTX,
+};
+
+// The one-shot digest function is not exposed by `openssl_sys`, so we need
+// to declare it ourselves.
+extern "C" {
+    fn EVP_Digest(
+        data: *const c_void,
+        count: usize,
+        md: *mut c_uchar,
+        size: *mut c_uint,
+        kind: *const EVP_MD,
+        engine: *mut ENGINE,
+    ) -> c_int;
+}
+
+/// An OpenSSL digest context, which is the only owner of the raw context
+/// it wraps. Copying a `Context` duplicates the raw context, so the two may
+/// be updated independently.
+pub struct Context(*mut EVP_MD_CTX);
+
+impl Context {
+    /// Create a context to compute a digest using the OpenSSL digest
+    /// algorithm `md`. The context keeps its own reference to `md`, so one
+    /// which was fetched may be freed once the context is created.
+    ///
+    /// ## Panics
+    ///
+    /// A panic occurs if the context can't be created, or can't be
+    /// initialized to compute `md`.
+    pub fn new(md: *const EVP_MD) -> Self {
+        assert!(!md.is_null());
+        let ctx = unsafe { EVP_MD_CTX_new() };
+        assert!(!ctx.is_null());
+        let context = Self(ctx);
+        let done = unsafe { EVP_DigestInit_ex(ctx, md, ptr::null_mut()) };
+        assert!(done == 1);
+        context
+    }
+
+    /// Update the digest using the given `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        unsafe { EVP_DigestUpdate(self.0, data.as_ptr().cast(), data.len()) };
+    }
+
+    /// Finish computing the digest and return it. The context is then
+    /// initialized again, so that it can be reused to compute the same
+    /// digest of new data.
+    ///
+    /// ## Panics
+    ///
+    /// A panic occurs if OpenSSL fails to compute the digest, or the digest
+    /// isn't `N` bytes long. This should not occur unless the OpenSSL API
+    /// has fallen out of sync.
+    pub fn finish<const N: usize>(&mut self) -> [u8; N] {
+        let mut len = 0;
+        let mut buffer = [0u8; EVP_MAX_MD_SIZE as usize];
+        let done = unsafe {
+            EVP_DigestFinal_ex(self.0, buffer.as_mut_ptr(), &raw mut len)
+        };
+        assert!(done == 1);
+        assert!(N == len as usize);
+        // Without an algorithm the one the context was using is reused.
+        let done = unsafe {
+            EVP_DigestInit_ex(self.0, ptr::null(), ptr::null_mut())
+        };
+        assert!(done == 1);
+        buffer[..N].try_into().unwrap()
+    }
+}
+
+impl Clone for Context {
+    /// Copy the context, along with the state of the digest so far.
+    fn clone(&self) -> Self {
+        let ctx = unsafe { EVP_MD_CTX_new() };
+        assert!(!ctx.is_null());
+        let context = Self(ctx);
+        let copied = unsafe { EVP_MD_CTX_copy_ex(ctx, self.0) };
+        assert!(copied == 1);
+        context
+    }
+}
+
+impl Drop for Context {
+    /// Free the raw context.
+    fn drop(&mut self) {
+        unsafe { EVP_MD_CTX_free(self.0) };
+    }
+}
+
+// SAFETY: a raw context isn't tied to the thread which created it, and it
+// is only ever used through the `Context` which owns it, so the `Context`
+// may be moved to another thread.
+unsafe impl Send for Context {}
+
+/// Compute the digest of `data` using the OpenSSL digest algorithm `md`,
+/// in a single call.
+///
+/// ## Panics
+///
+/// A panic occurs if OpenSSL fails to compute the digest, or the digest
+/// isn't `N` bytes long. This should not occur unless the OpenSSL API has
+/// fallen out of sync.
+pub fn digest<const N: usize>(md: *const EVP_MD, data: &[u8]) -> [u8; N] {
+    let mut len = 0;
+    let mut buffer = [0u8; EVP_MAX_MD_SIZE as usize];
+    let done = unsafe {
+        EVP_Digest(
+            data.as_ptr().cast(),
+            data.len(),
+            buffer.as_mut_ptr(),
+            &raw mut len,
+            md,
+            ptr::null_mut(),
+        )
+    };
+    assert!(done == 1);
+    assert!(N == len as usize);
+    buffer[..N].try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn evp_digest() {
+        let md5 = unsafe { openssl_sys::EVP_md5() };
+        assert_eq!(digest(md5, &[]), fixtures::md5::EMPTY);
+        assert_eq!(
+            digest(md5, &fixtures::RANDOM_11171),
+            fixtures::md5::RANDOM_11171
+        );
+    }
+
+    #[test]
+    fn context_reuse() {
+        let md5 = unsafe { openssl_sys::EVP_md5() };
+        let mut context = Context::new(md5);
+        context.update(&fixtures::ZERO_400D);
+        let mut copy = context.clone();
+        assert_eq!(context.finish(), fixtures::md5::ZERO_400D);
+        assert_eq!(context.finish(), fixtures::md5::EMPTY);
+        assert_eq!(copy.finish(), fixtures::md5::ZERO_400D);
+    }
+}
@@ -0,0 +1,116 @@
+//! A minimal binding to the parts of libgcrypt used to compute digests
+//! which OpenSSL does not provide.
+
+use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_void};
+use std::ptr;
+use std::sync::Once;
+
+/// An opaque libgcrypt digest context.
+#[allow(non_camel_case_types)]
+enum gcry_md_handle {}
+
+/// A handle to a libgcrypt digest context.
+#[allow(non_camel_case_types)]
+type gcry_md_hd_t = *mut gcry_md_handle;
+
+pub const GCRY_MD_TIGER1: c_int = 306;
+pub const GCRY_MD_TIGER2: c_int = 307;
+pub const GCRY_MD_STRIBOG256: c_int = 309;
+pub const GCRY_MD_STRIBOG512: c_int = 310;
+
+const GCRYCTL_INITIALIZATION_FINISHED: c_int = 38;
+
+#[link(name = "gcrypt")]
+extern "C" {
+    fn gcry_check_version(req_version: *const c_char) -> *const c_char;
+    fn gcry_control(cmd: c_int, ...) -> c_uint;
+    fn gcry_md_open(
+        h: *mut gcry_md_hd_t,
+        algo: c_int,
+        flags: c_uint,
+    ) -> c_uint;
+    fn gcry_md_close(h: gcry_md_hd_t);
+    fn gcry_md_copy(bhd: *mut gcry_md_hd_t, ahd: gcry_md_hd_t) -> c_uint;
+    fn gcry_md_reset(h: gcry_md_hd_t);
+    fn gcry_md_write(h: gcry_md_hd_t, buffer: *const c_void, len: usize);
+    fn gcry_md_read(h: gcry_md_hd_t, algo: c_int) -> *mut c_uchar;
+}
+
+/// Initialize libgcrypt. This must be called before any other libgcrypt
+/// function is used, but only has an effect the first time it is called.
+fn initialize() {
+    static INITIALIZE: Once = Once::new();
+    INITIALIZE.call_once(|| unsafe {
+        gcry_check_version(ptr::null());
+        gcry_control(GCRYCTL_INITIALIZATION_FINISHED, 0);
+    });
+}
+
+/// A libgcrypt digest context, which is the only owner of the handle it
+/// wraps. Copying a `Context` duplicates the handle, so the two may be
+/// updated independently.
+pub struct Context(gcry_md_hd_t);
+
+impl Context {
+    /// Create a context to compute the libgcrypt digest `algo`, such as
+    /// `GCRY_MD_TIGER1`. libgcrypt is initialized first if necessary.
+    ///
+    /// ## Panics
+    ///
+    /// A panic occurs if the context can't be created. This should not
+    /// occur unless the libgcrypt API has fallen out of sync.
+    pub fn new(algo: c_int) -> Self {
+        initialize();
+        let mut hd = ptr::null_mut();
+        let err = unsafe { gcry_md_open(&raw mut hd, algo, 0) };
+        assert!(err == 0 && !hd.is_null());
+        Self(hd)
+    }
+
+    /// Update the digest using the given `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        unsafe { gcry_md_write(self.0, data.as_ptr().cast(), data.len()) };
+    }
+
+    /// Finish computing the digest and return it. The context is then
+    /// reset, so that it can be reused to compute the same digest of new
+    /// data.
+    ///
+    /// ## Panics
+    ///
+    /// A panic occurs if libgcrypt fails to compute the digest.
+    pub fn finish<const N: usize>(&mut self) -> [u8; N] {
+        let mut digest = [0u8; N];
+        unsafe {
+            // The context computes a single digest, so the algorithm
+            // doesn't need to be named.
+            let result = gcry_md_read(self.0, 0);
+            assert!(!result.is_null());
+            ptr::copy_nonoverlapping(result, digest.as_mut_ptr(), N);
+            gcry_md_reset(self.0);
+        }
+        digest
+    }
+}
+
+impl Clone for Context {
+    /// Copy the context, along with the state of the digest so far.
+    fn clone(&self) -> Self {
+        let mut hd = ptr::null_mut();
+        let err = unsafe { gcry_md_copy(&raw mut hd, self.0) };
+        assert!(err == 0 && !hd.is_null());
+        Self(hd)
+    }
+}
+
+impl Drop for Context {
+    /// Close the handle.
+    fn drop(&mut self) {
+        unsafe { gcry_md_close(self.0) };
+    }
+}
+
+// SAFETY: a handle isn't tied to the thread which opened it, and it is
+// only ever used through the `Context` which owns it, so the `Context` may
+// be moved to another thread.
+unsafe impl Send for Context {}
@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use crate::walk;
+
+/// Expand a glob `pattern` into the paths of the regular files which match
+/// it, sorted by path. The pattern is matched against each component of
+/// the path, where `*` matches any run of characters, `?` matches a single
+/// character, and `[...]` matches one of a set of characters (such as
+/// `[abc]`, `[a-z]`, or `[!abc]`). A `**` component matches any number of
+/// directories, so `**/*.iso` matches every ISO image in the tree. The
+/// tree is walked using the `options`, as for `walk::files()`.
+///
+/// ## Errors
+///
+/// The path of the directory holding the files is returned if it can't be
+/// read.
+pub fn expand(
+    pattern: &str,
+    options: walk::Options,
+) -> Result<Vec<PathBuf>, PathBuf> {
+    // Only the directory named by the leading components without any
+    // wildcards needs to be searched.
+    let literal: Vec<&str> = pattern
+        .split('/')
+        .take_while(|component| !is_wildcard(component))
+        .collect();
+    let base = match literal.join("/") {
+        base if !base.is_empty() => PathBuf::from(base),
+        _ if literal.is_empty() => PathBuf::from("."),
+        _ => PathBuf::from("/"),
+    };
+    if literal.len() == pattern.split('/').count() {
+        return Ok(if base.is_file() {
+            vec![base]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let files = walk::files(&base, options)?;
+    let paths = files
+        .into_iter()
+        .map(|file| {
+            if literal.is_empty() {
+                file.strip_prefix(".")
+                    .map(Path::to_path_buf)
+                    .unwrap_or(file)
+            } else {
+                file
+            }
+        })
+        .filter(|file| {
+            file.to_str().is_some_and(|path| matches(pattern, path))
+        })
+        .collect();
+    Ok(paths)
+}
+
+/// Check if a `pattern` component contains any wildcards.
+fn is_wildcard(component: &str) -> bool {
+    component.contains(['*', '?', '['])
+}
+
+/// Check if the `path` matches the glob `pattern`, as described for
+/// `expand()`.
+#[must_use]
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    match_components(&pattern, &path)
+}
+
+/// Match the components of a `pattern` against the components of a
+/// `path`, where a `**` component matches any number of components.
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skip| match_components(rest, &path[skip..]))
+        }
+        Some((component, rest)) => {
+            path.split_first().is_some_and(|(name, path)| {
+                let pattern: Vec<char> = component.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                match_name(&pattern, &name) && match_components(rest, path)
+            })
+        }
+    }
+}
+
+/// Match a single component of a `pattern` against a `name`.
+fn match_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => {
+            (0..=name.len()).any(|skip| match_name(rest, &name[skip..]))
+        }
+        Some(('?', rest)) => !name.is_empty() && match_name(rest, &name[1..]),
+        Some(('[', rest)) => {
+            let Some((&c, name)) = name.split_first() else {
+                return false;
+            };
+            match match_class(rest, c) {
+                Some((matched, rest)) => matched && match_name(rest, name),
+                // An unterminated class is matched literally.
+                None => c == '[' && match_name(rest, name),
+            }
+        }
+        Some((p, rest)) => {
+            name.first() == Some(p) && match_name(rest, &name[1..])
+        }
+    }
+}
+
+/// Match the character `c` against the character class at the start of
+/// `pattern`, just after the `[`. Returns whether it matched and the rest
+/// of the pattern after the closing `]`, or `None` if there is no `]`.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negated, pattern) = match pattern.split_first() {
+        Some(('!' | '^', rest)) => (true, rest),
+        _ => (false, pattern),
+    };
+    // A `]` straight after the `[` is part of the class.
+    let end = pattern
+        .iter()
+        .skip(1)
+        .position(|p| *p == ']')
+        .map(|end| end + 1)?;
+    let class = &pattern[..end];
+    let mut matched = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            matched |= (class[i]..=class[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            matched |= class[i] == c;
+            i += 1;
+        }
+    }
+    Some((matched != negated, &pattern[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::fs;
+
+    #[test]
+    fn match_patterns() {
+        assert!(matches("*.iso", "foo.iso"));
+        assert!(!matches("*.iso", "dir/foo.iso"));
+        assert!(matches("**/*.iso", "foo.iso"));
+        assert!(matches("**/*.iso", "dir/sub/foo.iso"));
+        assert!(!matches("**/*.iso", "dir/foo.img"));
+        assert!(matches("dir/**", "dir/sub/foo"));
+        assert!(matches("dir/**/foo", "dir/foo"));
+        assert!(!matches("dir/**/foo", "other/foo"));
+        assert!(matches("f?o", "foo"));
+        assert!(!matches("f?o", "fo"));
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("a*b*c", "aXbYbZ"));
+        assert!(matches("[abc].txt", "b.txt"));
+        assert!(!matches("[abc].txt", "d.txt"));
+        assert!(matches("[a-c]x", "cx"));
+        assert!(matches("[!a-c]x", "dx"));
+        assert!(!matches("[!a-c]x", "ax"));
+        assert!(matches("[]]", "]"));
+        assert!(matches("[ab", "[ab"));
+        assert!(matches("café", "café"));
+    }
+
+    #[test]
+    fn expand_patterns() {
+        let dir = fixtures::ScratchDir::new("glob");
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in ["a.iso", "b.img", "sub/c.iso", "sub/deeper/d.iso"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let dirstr = dir.to_str().unwrap();
+        assert_eq!(
+            expand(&format!("{dirstr}/**/*.iso"), walk::Options::default())
+                .unwrap(),
+            ["a.iso", "sub/c.iso", "sub/deeper/d.iso"]
+                .map(|file| dir.join(file))
+        );
+        assert_eq!(
+            expand(&format!("{dirstr}/*.is?"), walk::Options::default())
+                .unwrap(),
+            [dir.join("a.iso")]
+        );
+        assert_eq!(
+            expand(&format!("{dirstr}/b.img"), walk::Options::default())
+                .unwrap(),
+            [dir.join("b.img")]
+        );
+        assert!(expand(&format!("{dirstr}/*.zip"), walk::Options::default())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            expand(&format!("{dirstr}/missing/*"), walk::Options::default()),
+            Err(dir.join("missing"))
+        );
+        let exclude = [String::from("deeper")];
+        let options = walk::Options {
+            exclude: &exclude,
+            ..walk::Options::default()
+        };
+        assert_eq!(
+            expand(&format!("{dirstr}/**/*.iso"), options).unwrap(),
+            ["a.iso", "sub/c.iso"].map(|file| dir.join(file))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "async")]
+use crate::{generator, Error, Generator};
+use crate::{DigestData, DigestKind, Hasher};
+
+/// Compute each of the `kinds` of digest of the file at `path`, returning
+/// them in the same order.
+///
+/// ## Errors
+///
+/// Any error opening or reading the file is returned. An error of kind
+/// `Unsupported` is returned if any of the digests is unavailable.
+pub fn hash_file(
+    path: impl AsRef<Path>,
+    kinds: &[DigestKind],
+) -> io::Result<Vec<DigestData>> {
+    let file = File::open(path)?;
+    hash_reader(file, kinds)
+}
+
+/// Compute each of the `kinds` of digest of all the data read from
+/// `input`, returning them in the same order. The digests are computed in
+/// background threads, so that they are computed at the same time, as
+/// for a `Hasher` with the default settings. A `Hasher` can also be given
+/// a `CancellationToken`, so that hashing can be stopped part way through.
+///
+/// ## Errors
+///
+/// Any error reading the `input` is returned. An error of kind
+/// `Unsupported` is returned if any of the digests is unavailable.
+pub fn hash_reader<R: io::Read>(
+    input: R,
+    kinds: &[DigestKind],
+) -> io::Result<Vec<DigestData>> {
+    let hasher = Hasher::builder().algorithms(kinds).build()?;
+    Ok(hasher.hash_reader(input)?)
+}
+
+/// Create a generator for each of the `kinds` of digest, returning an
+/// error of kind `Unsupported` if any of them is unavailable.
+#[cfg(feature = "async")]
+pub(crate) fn generators(
+    kinds: &[DigestKind],
+) -> io::Result<Vec<Box<dyn Generator>>> {
+    kinds
+        .iter()
+        .map(|kind| {
+            generator(*kind)
+                .ok_or_else(|| io::Error::from(Error::Unsupported(*kind)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn hash_fixture_file() {
+        let path = Path::new("tests/fixtures/random-11171");
+        let kinds = [DigestKind::MD5, DigestKind::SHA512, DigestKind::CRC32];
+        assert_eq!(
+            hash_file(path, &kinds).unwrap(),
+            [
+                DigestData::MD5(fixtures::md5::RANDOM_11171),
+                DigestData::SHA512(fixtures::sha512::RANDOM_11171),
+                DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+            ]
+        );
+        let missing = Path::new("tests/fixtures/missing");
+        let error = hash_file(missing, &kinds).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn hash_fixture_reader() {
+        let input = &fixtures::ZERO_400D[..];
+        assert_eq!(
+            hash_reader(input, &[DigestKind::RMD160]).unwrap(),
+            [DigestData::RMD160(fixtures::rmd160::ZERO_400D)]
+        );
+        assert_eq!(hash_reader(input, &[]).unwrap(), []);
+    }
+
+    #[cfg(not(feature = "md2"))]
+    #[test]
+    fn hash_unavailable() {
+        let error = hash_reader(io::empty(), &[DigestKind::MD2]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,553 @@
+use std::fs::File;
+use std::io;
+use std::iter;
+use std::path::Path;
+use std::sync::{mpsc, PoisonError, RwLock};
+use std::thread;
+
+use crate::inline::{inline_digest, InlineDigest};
+use crate::{digest, CancellationToken, DigestData, DigestKind, Error};
+
+/// Computes a set of digests of files or other input, configured using a
+/// `HasherBuilder`. Unlike `hash_file()` and `hash_reader()`, the size of
+/// the buffer data is read into and the number of threads used can be
+/// chosen, and the digests can be computed without starting any threads.
+#[derive(Clone, Debug)]
+pub struct Hasher {
+    kinds: Vec<DigestKind>,
+    buffer_size: usize,
+    parallelism: Option<usize>,
+    queue_depth: usize,
+    background: bool,
+    cancel: Option<CancellationToken>,
+}
+
+impl Hasher {
+    /// The default size of the buffer data is read into, in bytes.
+    pub const DEFAULT_BUFFER_SIZE: usize = 0x4_0000;
+
+    /// The default number of buffers which may be queued for each thread
+    /// computing digests, before reading waits for them to catch up.
+    pub const DEFAULT_QUEUE_DEPTH: usize = 4;
+
+    /// Create a builder to configure a new `Hasher`.
+    #[must_use]
+    pub fn builder() -> HasherBuilder {
+        HasherBuilder::default()
+    }
+
+    /// The kinds of digest computed, in the order they are returned.
+    #[must_use]
+    pub fn kinds(&self) -> &[DigestKind] {
+        &self.kinds
+    }
+
+    /// Compute each of the digests of the file at `path`, returning them
+    /// in the same order as the kinds of digest were given.
+    ///
+    /// ## Errors
+    ///
+    /// Any error opening or reading the file is returned as `Error::Io`,
+    /// and `Error::WorkerFailed` is returned if a thread computing the
+    /// digests fails. `Error::Cancelled` is returned if the cancellation
+    /// token is cancelled before all of the file is read.
+    pub fn hash_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<DigestData>, Error> {
+        let file = File::open(path)?;
+        self.hash_reader(file)
+    }
+
+    /// Compute each of the digests of all the data read from `input`, as
+    /// for `hash_file()`. Input which fits in the buffer is digested in the
+    /// current thread, without starting any threads.
+    ///
+    /// ## Errors
+    ///
+    /// Any error reading the `input` is returned as `Error::Io`, and
+    /// `Error::WorkerFailed` is returned if a thread computing the digests
+    /// fails. `Error::Cancelled` is returned if the cancellation token is
+    /// cancelled before all of the `input` is read.
+    pub fn hash_reader<R: io::Read>(
+        &self,
+        mut input: R,
+    ) -> Result<Vec<DigestData>, Error> {
+        let mut buffer = vec![0u8; self.buffer_size];
+        let count = self.fill_chunk(&mut input, &mut buffer)?;
+        if count < buffer.len() {
+            return self.hash_chunk(&buffer[..count]);
+        }
+
+        let digests = self.digests()?;
+        if self.background && !digests.is_empty() {
+            self.hash_background(input, buffer, digests)
+        } else {
+            self.hash_inline(input, buffer, digests)
+        }
+    }
+
+    /// Compute each of the digests of `data`, which is all of the input,
+    /// in the current thread. Each digest is computed in one go, which
+    /// avoids the cost of starting threads and passing them the data, as
+    /// well as reusing contexts, which dominates for small files.
+    fn hash_chunk(&self, data: &[u8]) -> Result<Vec<DigestData>, Error> {
+        self.kinds
+            .iter()
+            .map(|&kind| digest(kind, data).ok_or(Error::Unsupported(kind)))
+            .collect()
+    }
+
+    /// Create each of the kinds of digest, returning
+    /// `Error::Unsupported` if any of them is unavailable.
+    fn digests(&self) -> Result<Vec<Box<dyn InlineDigest>>, Error> {
+        self.kinds
+            .iter()
+            .map(|kind| inline_digest(*kind).ok_or(Error::Unsupported(*kind)))
+            .collect()
+    }
+
+    /// Compute the `digests` of the `input` in the current thread, which
+    /// starts with the full `buffer` already read.
+    fn hash_inline<R: io::Read>(
+        &self,
+        mut input: R,
+        mut buffer: Vec<u8>,
+        mut digests: Vec<Box<dyn InlineDigest>>,
+    ) -> Result<Vec<DigestData>, Error> {
+        let mut count = buffer.len();
+        loop {
+            for digest in &mut digests {
+                digest.update(&buffer[..count]);
+            }
+            match self.read_chunk(&mut input, &mut buffer)? {
+                Some(read) => count = read,
+                None => break,
+            }
+        }
+        Ok(digests.iter_mut().map(|digest| digest.finish()).collect())
+    }
+
+    /// Compute the `digests` of the `input`, sharing them between at most
+    /// `parallelism` threads, each of which computes its digests of every
+    /// buffer in turn. The `input` starts with the full `buffer` already
+    /// read.
+    ///
+    /// Rather than each buffer being copied for the threads, they borrow
+    /// it from a ring of `queue_depth + 1` buffers, and a buffer is only
+    /// filled again once every thread has finished with it.
+    fn hash_background<R: io::Read>(
+        &self,
+        input: R,
+        buffer: Vec<u8>,
+        digests: Vec<Box<dyn InlineDigest>>,
+    ) -> Result<Vec<DigestData>, Error> {
+        let threads = self
+            .parallelism
+            .unwrap_or(digests.len())
+            .clamp(1, digests.len());
+        let mut groups: Vec<Vec<(usize, Box<dyn InlineDigest>)>> =
+            (0..threads).map(|_| Vec::new()).collect();
+        for (index, digest) in digests.into_iter().enumerate() {
+            groups[index % threads].push((index, digest));
+        }
+
+        let buffers: Vec<RwLock<Vec<u8>>> = iter::once(buffer)
+            .chain((0..self.queue_depth).map(|_| vec![0u8; self.buffer_size]))
+            .map(RwLock::new)
+            .collect();
+        let buffers = &buffers;
+
+        thread::scope(|scope| {
+            let mut senders = Vec::with_capacity(threads);
+            let mut finished = Vec::with_capacity(threads);
+            let mut workers = Vec::with_capacity(threads);
+            for mut group in groups {
+                let (tx, rx) = mpsc::channel::<(usize, usize)>();
+                let (done_tx, done_rx) = mpsc::channel::<()>();
+                senders.push(tx);
+                finished.push(done_rx);
+                workers.push(scope.spawn(move || {
+                    for (index, count) in rx {
+                        let data = buffers[index]
+                            .read()
+                            .unwrap_or_else(PoisonError::into_inner);
+                        for (_, digest) in &mut group {
+                            digest.update(&data[..count]);
+                        }
+                        drop(data);
+                        let _ = done_tx.send(());
+                    }
+                    group
+                        .into_iter()
+                        .map(|(index, mut digest)| (index, digest.finish()))
+                        .collect::<Vec<_>>()
+                }));
+            }
+
+            self.share_buffers(input, buffers, &senders, &finished)?;
+            drop(senders);
+
+            let mut results = Vec::with_capacity(self.kinds.len());
+            for worker in workers {
+                let group =
+                    worker.join().map_err(|_err| Error::WorkerFailed)?;
+                results.extend(group);
+            }
+            results.sort_by_key(|(index, _)| *index);
+            Ok(results.into_iter().map(|(_, digest)| digest).collect())
+        })
+    }
+
+    /// Pass each buffer of the `input` to the threads computing the
+    /// digests, by its index in `buffers`, using their `senders`. The first
+    /// of the `buffers` is already full. Before a buffer is filled again,
+    /// each thread reports that it is finished with it using `finished`.
+    fn share_buffers<R: io::Read>(
+        &self,
+        mut input: R,
+        buffers: &[RwLock<Vec<u8>>],
+        senders: &[mpsc::Sender<(usize, usize)>],
+        finished: &[mpsc::Receiver<()>],
+    ) -> Result<(), Error> {
+        let mut index = 0;
+        let mut count = self.buffer_size;
+        let mut pending = 0;
+        loop {
+            for tx in senders {
+                tx.send((index, count))
+                    .map_err(|_err| Error::WorkerFailed)?;
+            }
+            pending += 1;
+            index = (index + 1) % buffers.len();
+            if pending == buffers.len() {
+                for done in finished {
+                    done.recv().map_err(|_err| Error::WorkerFailed)?;
+                }
+                pending -= 1;
+            }
+
+            let mut buffer = buffers[index]
+                .write()
+                .unwrap_or_else(PoisonError::into_inner);
+            match self.read_chunk(&mut input, &mut buffer)? {
+                Some(read) => count = read,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Fill `buffer` from `input`, returning the number of bytes read,
+    /// which is only less than the length of the `buffer` at the end of
+    /// the input.
+    fn fill_chunk<R: io::Read>(
+        &self,
+        input: &mut R,
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.read_chunk(input, &mut buffer[filled..])? {
+                Some(count) => filled += count,
+                None => break,
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Read the next chunk of `input` into `buffer`, returning the number
+    /// of bytes read, or `None` at the end of the input. The cancellation
+    /// token, if any, is checked before each read.
+    fn read_chunk<R: io::Read>(
+        &self,
+        input: &mut R,
+        buffer: &mut [u8],
+    ) -> Result<Option<usize>, Error> {
+        loop {
+            if self
+                .cancel
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled)
+            {
+                return Err(Error::Cancelled);
+            }
+            match input.read(buffer) {
+                Ok(0) => return Ok(None),
+                Ok(count) => return Ok(Some(count)),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(Error::Io(err)),
+            }
+        }
+    }
+}
+
+/// A builder for a `Hasher`, created by `Hasher::builder()`.
+#[derive(Clone, Debug)]
+pub struct HasherBuilder {
+    kinds: Vec<DigestKind>,
+    buffer_size: usize,
+    parallelism: Option<usize>,
+    queue_depth: usize,
+    background: bool,
+    cancel: Option<CancellationToken>,
+}
+
+impl Default for HasherBuilder {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            buffer_size: Hasher::DEFAULT_BUFFER_SIZE,
+            parallelism: None,
+            queue_depth: Hasher::DEFAULT_QUEUE_DEPTH,
+            background: true,
+            cancel: None,
+        }
+    }
+}
+
+impl HasherBuilder {
+    /// Set the `kinds` of digest to compute, which are returned in the
+    /// same order.
+    #[must_use]
+    pub fn algorithms(mut self, kinds: &[DigestKind]) -> Self {
+        self.kinds = kinds.to_vec();
+        self
+    }
+
+    /// Set the size of the buffer data is read into, in bytes. The default
+    /// is `Hasher::DEFAULT_BUFFER_SIZE`, and at least one byte is used.
+    #[must_use]
+    pub fn buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = size.max(1);
+        self
+    }
+
+    /// Set the largest number of threads used to compute the digests. By
+    /// default each digest is computed in its own thread, and at least one
+    /// thread is used.
+    #[must_use]
+    pub fn parallelism(mut self, threads: usize) -> Self {
+        self.parallelism = Some(threads.max(1));
+        self
+    }
+
+    /// Set the number of buffers which may be queued for each thread
+    /// computing digests. The buffers are shared by the threads, so one
+    /// more than this many buffers is used in all. The default is
+    /// `Hasher::DEFAULT_QUEUE_DEPTH`.
+    #[must_use]
+    pub fn queue_depth(mut self, depth: usize) -> Self {
+        self.queue_depth = depth;
+        self
+    }
+
+    /// Set whether the digests are computed in background threads, which
+    /// is the default. Otherwise they are computed in the thread which
+    /// reads the data, which avoids the cost of starting threads for small
+    /// inputs.
+    #[must_use]
+    pub fn background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Set a `token` which can be used to cancel hashing from another
+    /// thread, such as when the client a file is being hashed for has
+    /// disconnected. The token is checked before each buffer is read.
+    #[must_use]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Create the `Hasher`.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::Unsupported` is returned if any of the kinds of digest is
+    /// unavailable.
+    pub fn build(self) -> Result<Hasher, Error> {
+        if let Some(kind) = self
+            .kinds
+            .iter()
+            .find(|kind| inline_digest(**kind).is_none())
+        {
+            return Err(Error::Unsupported(*kind));
+        }
+        Ok(Hasher {
+            kinds: self.kinds,
+            buffer_size: self.buffer_size,
+            parallelism: self.parallelism,
+            queue_depth: self.queue_depth,
+            background: self.background,
+            cancel: self.cancel,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    const KINDS: [DigestKind; 4] = [
+        DigestKind::MD5,
+        DigestKind::SHA512,
+        DigestKind::CRC32,
+        DigestKind::RMD320,
+    ];
+
+    fn expected() -> [DigestData; 4] {
+        [
+            DigestData::MD5(fixtures::md5::RANDOM_11171),
+            DigestData::SHA512(fixtures::sha512::RANDOM_11171),
+            DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+            DigestData::RMD320(fixtures::rmd320::RANDOM_11171),
+        ]
+    }
+
+    #[test]
+    fn hasher_default() {
+        let hasher = Hasher::builder().algorithms(&KINDS).build().unwrap();
+        assert_eq!(hasher.kinds(), KINDS);
+        let digests =
+            hasher.hash_file("tests/fixtures/random-11171").unwrap();
+        assert_eq!(digests, expected());
+    }
+
+    #[test]
+    fn hasher_configured() {
+        let builders = [
+            Hasher::builder().background(false).buffer_size(0x100),
+            Hasher::builder().parallelism(1).queue_depth(0),
+            Hasher::builder().parallelism(3).buffer_size(7),
+        ];
+        for builder in builders {
+            let hasher = builder.algorithms(&KINDS).build().unwrap();
+            let digests = hasher.hash_reader(&fixtures::RANDOM_11171[..]);
+            assert_eq!(digests.unwrap(), expected());
+        }
+    }
+
+    #[test]
+    fn hasher_single_chunk() {
+        // Input which fits in the buffer is digested in one go, and input
+        // which exactly fills it is digested as usual.
+        let len = fixtures::RANDOM_11171.len();
+        for size in [len + 1, len, len - 1] {
+            for background in [true, false] {
+                let hasher = Hasher::builder()
+                    .algorithms(&KINDS)
+                    .buffer_size(size)
+                    .background(background)
+                    .build()
+                    .unwrap();
+                let digests = hasher.hash_reader(&fixtures::RANDOM_11171[..]);
+                assert_eq!(digests.unwrap(), expected());
+            }
+        }
+    }
+
+    #[test]
+    fn hasher_shared_buffers() {
+        // The threads share the ring of buffers correctly, however short
+        // the reads and however many buffers there are.
+        for depth in 0..4 {
+            for threads in 1..=KINDS.len() {
+                let hasher = Hasher::builder()
+                    .algorithms(&KINDS)
+                    .buffer_size(0x100)
+                    .queue_depth(depth)
+                    .parallelism(threads)
+                    .build()
+                    .unwrap();
+                let reader = ShortReads(&fixtures::RANDOM_11171[..], 0);
+                assert_eq!(hasher.hash_reader(reader).unwrap(), expected());
+            }
+        }
+    }
+
+    /// A reader which returns reads of varying lengths.
+    struct ShortReads<'a>(&'a [u8], usize);
+
+    impl io::Read for ShortReads<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1 = (self.1 + 1) % 7;
+            let len = buf.len().min(self.1 * 0x31 + 1);
+            io::Read::read(&mut self.0, &mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn hasher_empty() {
+        let hasher = Hasher::builder().build().unwrap();
+        assert!(hasher
+            .hash_reader(&fixtures::ZERO_400D[..])
+            .unwrap()
+            .is_empty());
+        let hasher = Hasher::builder()
+            .algorithms(&[DigestKind::SHA256])
+            .build()
+            .unwrap();
+        assert_eq!(
+            hasher.hash_reader(io::empty()).unwrap(),
+            [DigestData::SHA256(fixtures::sha256::EMPTY)]
+        );
+    }
+
+    #[test]
+    fn hasher_cancelled() {
+        for background in [true, false] {
+            let token = CancellationToken::new();
+            let hasher = Hasher::builder()
+                .algorithms(&KINDS)
+                .background(background)
+                .buffer_size(0x1000)
+                .cancellation(token.clone())
+                .build()
+                .unwrap();
+            let digests = hasher.hash_reader(&fixtures::RANDOM_11171[..]);
+            assert_eq!(digests.unwrap(), expected());
+
+            // Cancel part way through reading the input.
+            let reader = io::Read::chain(
+                &fixtures::RANDOM_11171[..0x2000],
+                CancelOnRead(token.clone()),
+            );
+            assert!(matches!(
+                hasher.hash_reader(reader),
+                Err(Error::Cancelled)
+            ));
+            assert!(matches!(
+                hasher.hash_file("tests/fixtures/random-11171"),
+                Err(Error::Cancelled)
+            ));
+        }
+    }
+
+    /// A reader which cancels a token when it is read, as though the
+    /// operation had been cancelled by another thread.
+    struct CancelOnRead(CancellationToken);
+
+    impl io::Read for CancelOnRead {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.cancel();
+            let count = buf.len().min(0x10);
+            buf[..count].fill(0);
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn hasher_errors() {
+        let hasher = Hasher::builder().algorithms(&KINDS).build().unwrap();
+        let err = hasher.hash_file("tests/fixtures/missing").unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+
+        #[cfg(not(feature = "gcrypt"))]
+        assert!(matches!(
+            Hasher::builder().algorithms(&[DigestKind::TIGER]).build(),
+            Err(Error::Unsupported(DigestKind::TIGER))
+        ));
+    }
+}
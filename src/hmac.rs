@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use crate::blake2::{pad, wipe};
+use crate::inline::{inline_digest, InlineDigest};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The length of the bytes held by `DigestData::HMAC`, which is enough for
+/// the longest kind of digest.
+pub const HMAC_LENGTH: usize = 64;
+
+/// The size of the blocks the `kind` of digest consumes its input in, which
+/// is the size the HMAC key is padded to, or `None` if the `kind` is a
+/// checksum rather than a cryptographic digest.
+fn block_size(kind: DigestKind) -> Option<usize> {
+    match kind {
+        DigestKind::CRC16_CCITT
+        | DigestKind::CRC16_MODBUS
+        | DigestKind::CRC16_XMODEM
+        | DigestKind::CRC32
+        | DigestKind::CRC64
+        | DigestKind::XXH32
+        | DigestKind::XXH64
+        | DigestKind::RAPIDHASH => None,
+        DigestKind::MD2 => Some(16),
+        DigestKind::SHA3_384 => Some(104),
+        DigestKind::SHA3_512 => Some(72),
+        DigestKind::SHA384
+        | DigestKind::SHA512
+        | DigestKind::SHA512_224
+        | DigestKind::SHA512_256
+        | DigestKind::BLAKE2B => Some(128),
+        DigestKind::SHA3_256 | DigestKind::KECCAK256 => Some(136),
+        DigestKind::SHA3_224 => Some(144),
+        DigestKind::MD4
+        | DigestKind::MD5
+        | DigestKind::SHA224
+        | DigestKind::SHA256
+        | DigestKind::BLAKE2S
+        | DigestKind::STREEBOG256
+        | DigestKind::STREEBOG512
+        | DigestKind::TIGER
+        | DigestKind::TIGER2
+        | DigestKind::RMD160
+        | DigestKind::RMD256
+        | DigestKind::RMD320 => Some(64),
+    }
+}
+
+/// The name of the HMAC of the `kind` of digest, as output by `checksum`.
+pub(crate) const fn name(kind: DigestKind) -> &'static str {
+    match kind {
+        DigestKind::CRC16_CCITT
+        | DigestKind::CRC16_MODBUS
+        | DigestKind::CRC16_XMODEM
+        | DigestKind::CRC32
+        | DigestKind::CRC64
+        | DigestKind::XXH32
+        | DigestKind::XXH64
+        | DigestKind::RAPIDHASH => "HMAC",
+        DigestKind::MD2 => "HMAC-MD2",
+        DigestKind::MD4 => "HMAC-MD4",
+        DigestKind::MD5 => "HMAC-MD5",
+        DigestKind::SHA224 => "HMAC-SHA224",
+        DigestKind::SHA256 => "HMAC-SHA256",
+        DigestKind::SHA384 => "HMAC-SHA384",
+        DigestKind::SHA512 => "HMAC-SHA512",
+        DigestKind::SHA512_224 => "HMAC-SHA512/224",
+        DigestKind::SHA512_256 => "HMAC-SHA512/256",
+        DigestKind::SHA3_224 => "HMAC-SHA3-224",
+        DigestKind::SHA3_256 => "HMAC-SHA3-256",
+        DigestKind::SHA3_384 => "HMAC-SHA3-384",
+        DigestKind::SHA3_512 => "HMAC-SHA3-512",
+        DigestKind::KECCAK256 => "HMAC-Keccak-256",
+        DigestKind::BLAKE2B => "HMAC-BLAKE2b",
+        DigestKind::BLAKE2S => "HMAC-BLAKE2s",
+        DigestKind::STREEBOG256 => "HMAC-STREEBOG256",
+        DigestKind::STREEBOG512 => "HMAC-STREEBOG512",
+        DigestKind::TIGER => "HMAC-TIGER",
+        DigestKind::TIGER2 => "HMAC-TIGER2",
+        DigestKind::RMD160 => "HMAC-RMD160",
+        DigestKind::RMD256 => "HMAC-RMD256",
+        DigestKind::RMD320 => "HMAC-RMD320",
+    }
+}
+
+/// A structure used to compute an HMAC (RFC 2104) using one of the
+/// cryptographic digests. The digests are zero padded to `HMAC_LENGTH`
+/// bytes, so that each kind of digest can share the same `Background`.
+#[allow(clippy::upper_case_acronyms)]
+pub struct HMAC {
+    /// The inner digest, which has been updated with the padded key.
+    start: Box<dyn InlineDigest>,
+    /// The inner digest of the data so far.
+    inner: Box<dyn InlineDigest>,
+    /// The outer digest, which has been updated with the padded key.
+    outer: Box<dyn InlineDigest>,
+}
+
+impl HMAC {
+    /// Create a new HMAC structure for the `kind` of digest, using `key`.
+    /// `None` is returned if the `kind` is not a cryptographic digest, or
+    /// is unavailable.
+    pub fn new(kind: DigestKind, key: &[u8]) -> Option<Self> {
+        let block = block_size(kind)?;
+        let mut start = inline_digest(kind)?;
+        let mut outer = inline_digest(kind)?;
+
+        // Keys longer than a block are replaced by their digest.
+        let mut padded = vec![0u8; block];
+        if key.len() > block {
+            let mut digest = inline_digest(kind)?;
+            digest.update(key);
+            let digested = digest.finish();
+            let bytes = digested.as_bytes();
+            padded[..bytes.len()].copy_from_slice(bytes);
+        } else {
+            padded[..key.len()].copy_from_slice(key);
+        }
+
+        for byte in &mut padded {
+            *byte ^= 0x36;
+        }
+        start.update(&padded);
+        for byte in &mut padded {
+            *byte ^= 0x36 ^ 0x5c;
+        }
+        outer.update(&padded);
+        wipe(&mut padded);
+
+        let inner = start.fork();
+        Some(Self {
+            start,
+            inner,
+            outer,
+        })
+    }
+}
+
+impl Digest<HMAC_LENGTH> for HMAC {
+    /// Update the HMAC using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finalize the HMAC computation and return the result, zero padded.
+    /// The HMAC is reset so that it can be reused with the same key.
+    fn finish(&mut self) -> [u8; HMAC_LENGTH] {
+        let inner = self.inner.finish();
+        self.inner = self.start.fork();
+        let mut outer = self.outer.fork();
+        outer.update(inner.as_bytes());
+        pad(outer.finish().as_bytes())
+    }
+}
+
+impl Clone for HMAC {
+    /// Copy the current state of the HMAC.
+    fn clone(&self) -> Self {
+        Self {
+            start: self.start.fork(),
+            inner: self.inner.fork(),
+            outer: self.outer.fork(),
+        }
+    }
+}
+
+/// Structure used to compute an HMAC in a separate thread.
+pub struct BackgroundHMAC {
+    kind: DigestKind,
+    worker: Background<HMAC_LENGTH>,
+}
+
+impl BackgroundHMAC {
+    /// Create a new `BackgroundHMAC` structure, as for `HMAC::new()`.
+    pub fn new(kind: DigestKind, key: &[u8]) -> Option<Self> {
+        let hmac = HMAC::new(kind, key)?;
+        Some(Self {
+            kind,
+            worker: Background::new(move || hmac),
+        })
+    }
+}
+
+impl Generator for BackgroundHMAC {
+    /// Add the given `data` to the HMAC.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the HMAC, and reset the HMAC computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::HMAC {
+            kind: self.kind,
+            bytes: self.worker.finish()?,
+        })
+    }
+
+    /// Discard the data added to the HMAC so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the HMAC, such as `HMAC-SHA256`.
+    fn name(&self) -> &'static str {
+        name(self.kind)
+    }
+
+    /// The length of the HMAC, in bytes.
+    fn output_len(&self) -> usize {
+        self.kind.output_len()
+    }
+
+    /// Copy the current state of the HMAC into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            kind: self.kind,
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// The state of an HMAC can't be exported, as it would expose the key.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The data from the second test case of RFC 2104 and RFC 4231.
+    const DATA: &[u8] = b"what do ya want for nothing?";
+
+    /// Compute the `kind` of HMAC of `data` with `key`, as a hex string.
+    fn hmac_hex(kind: DigestKind, key: &[u8], data: &[u8]) -> String {
+        let hmac = BackgroundHMAC::new(kind, key).unwrap();
+        hmac.append(Arc::from(data));
+        hmac.result().to_string()
+    }
+
+    #[test]
+    fn hmac_rfc_vectors() {
+        assert_eq!(
+            hmac_hex(DigestKind::MD5, b"Jefe", DATA),
+            "750c783e6ab0b503eaa86e310a5db738"
+        );
+        assert_eq!(
+            hmac_hex(DigestKind::SHA256, b"Jefe", DATA),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // A key longer than the block size is digested first.
+        assert_eq!(
+            hmac_hex(
+                DigestKind::SHA256,
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn hmac_generator() {
+        let hmac = BackgroundHMAC::new(DigestKind::SHA256, b"Jefe").unwrap();
+        assert_eq!(hmac.name(), "HMAC-SHA256");
+        assert_eq!(hmac.output_len(), 32);
+        hmac.append(Arc::from(&DATA[..10]));
+        let forked = hmac.fork();
+        hmac.append(Arc::from(&DATA[10..]));
+        forked.append(Arc::from(&DATA[10..]));
+        let digest = hmac.result();
+        assert_eq!(digest.len(), 32);
+        assert_eq!(forked.result(), digest);
+
+        // The generator is reset to start a new HMAC with the same key.
+        hmac.append(Arc::from(DATA));
+        assert_eq!(hmac.result(), digest);
+        assert_eq!(hmac.export_state(), None);
+    }
+
+    #[test]
+    fn hmac_checksums() {
+        assert!(BackgroundHMAC::new(DigestKind::CRC32, b"key").is_none());
+        assert!(BackgroundHMAC::new(DigestKind::XXH64, b"key").is_none());
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[cfg(feature = "md2")]
+use crate::md2;
+use crate::{
+    blake2b, blake2s, crc16, crc32, crc64, keccak256, md4, md5, rapidhash,
+    rmd160, rmd256, rmd320, sha224, sha256, sha384, sha3_224, sha3_256,
+    sha3_384, sha3_512, sha512, sha512_224, sha512_256, xxh32, xxh64,
+};
+#[cfg(feature = "gcrypt")]
+use crate::{streebog256, streebog512, tiger, tiger2};
+use crate::{Blake2Params, Digest, DigestData, DigestKind, Error, Generator};
+
+/// A digest which is computed in the thread which updates it, with the
+/// length of the digest hidden behind `DigestData`, so that several kinds
+/// of digest can be kept together.
+pub(crate) trait InlineDigest: Send {
+    /// Update the digest with additional `data`.
+    fn update(&mut self, data: &[u8]);
+
+    /// Finish computing the digest and return it, resetting the digest so
+    /// that it can be reused.
+    fn finish(&mut self) -> DigestData;
+
+    /// Copy the current state of the digest.
+    fn fork(&self) -> Box<dyn InlineDigest>;
+}
+
+/// A `Digest` implementor, along with the `DigestData` variant used to wrap
+/// the digests it computes.
+struct Wrapped<D, const N: usize> {
+    digest: D,
+    wrap: fn([u8; N]) -> DigestData,
+}
+
+impl<D, const N: usize> InlineDigest for Wrapped<D, N>
+where
+    D: Digest<N> + Clone + Send + 'static,
+{
+    fn update(&mut self, data: &[u8]) {
+        self.digest.update(data);
+    }
+
+    fn finish(&mut self) -> DigestData {
+        (self.wrap)(self.digest.finish())
+    }
+
+    fn fork(&self) -> Box<dyn InlineDigest> {
+        boxed(self.digest.fork(), self.wrap)
+    }
+}
+
+/// Box the `digest`, wrapping the digests it computes with `wrap`.
+pub(crate) fn boxed<D, const N: usize>(
+    digest: D,
+    wrap: fn([u8; N]) -> DigestData,
+) -> Box<dyn InlineDigest>
+where
+    D: Digest<N> + Clone + Send + 'static,
+{
+    Box::new(Wrapped { digest, wrap })
+}
+
+/// Create the `kind` of digest to be computed in the current thread, or
+/// `None` if it is unavailable, as described for `md2()`, `md4()`,
+/// `streebog256()`, and so on.
+pub(crate) fn inline_digest(
+    kind: DigestKind,
+) -> Option<Box<dyn InlineDigest>> {
+    let digest = match kind {
+        DigestKind::CRC16_CCITT => boxed(
+            crc16::CRC16::new(crc16::Variant::CCITT),
+            DigestData::CRC16_CCITT,
+        ),
+        DigestKind::CRC16_MODBUS => boxed(
+            crc16::CRC16::new(crc16::Variant::MODBUS),
+            DigestData::CRC16_MODBUS,
+        ),
+        DigestKind::CRC16_XMODEM => boxed(
+            crc16::CRC16::new(crc16::Variant::XMODEM),
+            DigestData::CRC16_XMODEM,
+        ),
+        DigestKind::CRC32 => boxed(crc32::CRC32::new(), DigestData::CRC32),
+        DigestKind::CRC64 => boxed(crc64::CRC64::new(), DigestData::CRC64),
+        DigestKind::XXH32 => boxed(xxh32::XXH32::new(), DigestData::XXH32),
+        DigestKind::XXH64 => boxed(xxh64::XXH64::new(), DigestData::XXH64),
+        DigestKind::RAPIDHASH => {
+            boxed(rapidhash::RAPIDHASH::new(), DigestData::RAPIDHASH)
+        }
+        DigestKind::MD2 => return inline_md2(),
+        DigestKind::MD4 => {
+            if !md4::MD4::available() {
+                return None;
+            }
+            boxed(md4::MD4::new(), DigestData::MD4)
+        }
+        DigestKind::MD5 => boxed(md5::MD5::new(), DigestData::MD5),
+        DigestKind::SHA224 => {
+            boxed(sha224::SHA224::new(), DigestData::SHA224)
+        }
+        DigestKind::SHA256 => {
+            boxed(sha256::SHA256::new(), DigestData::SHA256)
+        }
+        DigestKind::SHA384 => {
+            boxed(sha384::SHA384::new(), DigestData::SHA384)
+        }
+        DigestKind::SHA512 => {
+            boxed(sha512::SHA512::new(), DigestData::SHA512)
+        }
+        DigestKind::SHA512_224 => {
+            boxed(sha512_224::SHA512_224::new(), DigestData::SHA512_224)
+        }
+        DigestKind::SHA512_256 => {
+            boxed(sha512_256::SHA512_256::new(), DigestData::SHA512_256)
+        }
+        DigestKind::SHA3_224 => {
+            boxed(sha3_224::SHA3_224::new(), DigestData::SHA3_224)
+        }
+        DigestKind::SHA3_256 => {
+            boxed(sha3_256::SHA3_256::new(), DigestData::SHA3_256)
+        }
+        DigestKind::SHA3_384 => {
+            boxed(sha3_384::SHA3_384::new(), DigestData::SHA3_384)
+        }
+        DigestKind::SHA3_512 => {
+            boxed(sha3_512::SHA3_512::new(), DigestData::SHA3_512)
+        }
+        DigestKind::KECCAK256 => {
+            boxed(keccak256::KECCAK256::new(), DigestData::KECCAK256)
+        }
+        DigestKind::BLAKE2B => {
+            boxed(blake2b::BLAKE2B::new(), DigestData::BLAKE2B)
+        }
+        DigestKind::BLAKE2S => {
+            boxed(blake2s::BLAKE2S::new(), DigestData::BLAKE2S)
+        }
+        DigestKind::STREEBOG256
+        | DigestKind::STREEBOG512
+        | DigestKind::TIGER
+        | DigestKind::TIGER2 => return inline_gcrypt(kind),
+        DigestKind::RMD160 => {
+            boxed(rmd160::RMD160::new(), DigestData::RMD160)
+        }
+        DigestKind::RMD256 => {
+            boxed(rmd256::RMD256::new(), DigestData::RMD256)
+        }
+        DigestKind::RMD320 => {
+            boxed(rmd320::RMD320::new(), DigestData::RMD320)
+        }
+    };
+    Some(digest)
+}
+
+/// Create a MD2 digest, if the `md2` feature is enabled and OpenSSL
+/// supplies it.
+fn inline_md2() -> Option<Box<dyn InlineDigest>> {
+    #[cfg(feature = "md2")]
+    if md2::MD2::available() {
+        return Some(boxed(md2::MD2::new(), DigestData::MD2));
+    }
+    None
+}
+
+/// Create the `kind` of digest computed using libgcrypt, if the `gcrypt`
+/// feature is enabled.
+fn inline_gcrypt(kind: DigestKind) -> Option<Box<dyn InlineDigest>> {
+    #[cfg(feature = "gcrypt")]
+    {
+        let digest = match kind {
+            DigestKind::STREEBOG256 => boxed(
+                streebog256::STREEBOG256::new(),
+                DigestData::STREEBOG256,
+            ),
+            DigestKind::STREEBOG512 => boxed(
+                streebog512::STREEBOG512::new(),
+                DigestData::STREEBOG512,
+            ),
+            DigestKind::TIGER => {
+                boxed(tiger::TIGER::new(), DigestData::TIGER)
+            }
+            DigestKind::TIGER2 => {
+                boxed(tiger2::TIGER2::new(), DigestData::TIGER2)
+            }
+            _ => return None,
+        };
+        Some(digest)
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    {
+        let _ = kind;
+        None
+    }
+}
+
+/// A generator which computes the digest in the thread which appends the
+/// data to it, rather than in a background thread. For small inputs the
+/// round trips to a background thread take longer than the digest itself.
+pub(crate) struct InlineGenerator {
+    kind: DigestKind,
+    /// The digest is locked while it is updated, so that the generator can
+    /// be shared between threads.
+    digest: Mutex<Box<dyn InlineDigest>>,
+}
+
+impl InlineGenerator {
+    /// Create a new generator for the `kind` of digest, computed by
+    /// `digest`, boxed so that it can be used as any other generator.
+    pub(crate) fn boxed(
+        kind: DigestKind,
+        digest: Box<dyn InlineDigest>,
+    ) -> Box<dyn Generator> {
+        Box::new(Self {
+            kind,
+            digest: Mutex::new(digest),
+        })
+    }
+
+    /// Lock the digest, ignoring any panic while it was locked.
+    fn digest(&self) -> MutexGuard<'_, Box<dyn InlineDigest>> {
+        self.digest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Generator for InlineGenerator {
+    /// Add the given `data` to the digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.digest().update(&data);
+        Ok(())
+    }
+
+    /// Retrieve the digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(self.digest().finish())
+    }
+
+    /// Discard the data added to the digest so far.
+    fn reset(&self) {
+        self.digest().finish();
+    }
+
+    /// The name of the digest.
+    fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    /// The length of the digest, in bytes.
+    fn output_len(&self) -> usize {
+        self.kind.output_len()
+    }
+
+    /// Copy the current state of the digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Self::boxed(self.kind, self.digest().fork()))
+    }
+
+    /// The state of an inline digest isn't exported.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Create a generator for the `kind` of digest which is computed in the
+/// calling thread, or `None` if it is unavailable.
+pub(crate) fn inline_generator(
+    kind: DigestKind,
+) -> Option<Box<dyn Generator>> {
+    Some(InlineGenerator::boxed(kind, inline_digest(kind)?))
+}
+
+/// Create a BLAKE2B generator using the given key, salt, and
+/// personalization `params`, which is computed in the calling thread.
+/// `None` is returned if the parameters are too long, as for
+/// `blake2b_with()`.
+#[must_use]
+pub fn inline_blake2b_with(
+    params: &Blake2Params,
+) -> Option<Box<dyn Generator>> {
+    let blake2b = blake2b::CustomBLAKE2B::new(params)?;
+    let digest = boxed(blake2b, DigestData::BLAKE2B);
+    Some(InlineGenerator::boxed(DigestKind::BLAKE2B, digest))
+}
+
+/// Create a BLAKE2S generator using the given key, salt, and
+/// personalization `params`, which is computed in the calling thread.
+/// `None` is returned if the parameters are too long, as for
+/// `blake2s_with()`.
+#[must_use]
+pub fn inline_blake2s_with(
+    params: &Blake2Params,
+) -> Option<Box<dyn Generator>> {
+    let blake2s = blake2s::CustomBLAKE2S::new(params)?;
+    let digest = boxed(blake2s, DigestData::BLAKE2S);
+    Some(InlineGenerator::boxed(DigestKind::BLAKE2S, digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{digest, fixtures};
+
+    #[test]
+    fn inline_matches_oneshot() {
+        for kind in DigestKind::all() {
+            // Some of the digests may be unavailable in this build.
+            let Some(mut inline) = inline_digest(kind) else {
+                continue;
+            };
+            inline.update(&fixtures::RANDOM_11171[..0x1000]);
+            inline.update(&fixtures::RANDOM_11171[0x1000..]);
+            assert_eq!(
+                Some(inline.finish()),
+                digest(kind, &fixtures::RANDOM_11171)
+            );
+            assert_eq!(Some(inline.finish()), digest(kind, &[]));
+        }
+    }
+
+    #[test]
+    fn inline_generators() {
+        for kind in DigestKind::all() {
+            let Some(generator) = inline_generator(kind) else {
+                continue;
+            };
+            assert_eq!(generator.name(), kind.name());
+            assert_eq!(generator.output_len(), kind.output_len());
+            generator.append(Arc::from(&fixtures::RANDOM_11171[..0x1000]));
+            let forked = generator.fork();
+            generator.append(Arc::from(&fixtures::RANDOM_11171[0x1000..]));
+            forked.append(Arc::from(&fixtures::RANDOM_11171[0x1000..]));
+            let expected = digest(kind, &fixtures::RANDOM_11171);
+            assert_eq!(Some(generator.result()), expected);
+            assert_eq!(Some(forked.result()), expected);
+            generator.append(Arc::from(&fixtures::RANDOM_11171[..]));
+            generator.reset();
+            assert_eq!(Some(generator.result()), digest(kind, &[]));
+        }
+
+        let params = Blake2Params::default();
+        let generator = inline_blake2b_with(&params).unwrap();
+        assert_eq!(
+            generator.result(),
+            digest(DigestKind::BLAKE2B, &[]).unwrap()
+        );
+        let generator = inline_blake2s_with(&params).unwrap();
+        assert_eq!(
+            generator.result(),
+            digest(DigestKind::BLAKE2S, &[]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The number of bytes absorbed into the Keccak state for each
+/// permutation, for a 256-bit digest.
+const RATE: usize = 136;
+
+/// The constants combined with the state in each round of the Keccak-f
+/// permutation.
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// The rotation applied to each lane of the state, indexed by `x + 5 * y`.
+#[rustfmt::skip]
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27,
+    36, 44, 6, 55, 20,
+    3, 10, 43, 25, 39,
+    41, 45, 15, 21, 8,
+    18, 2, 61, 56, 14,
+];
+
+/// A structure used to generate a KECCAK256 digest. This is the original
+/// Keccak submission (as used by Ethereum), which differs from SHA3-256
+/// only in the padding applied to the message.
+#[derive(Clone)]
+pub struct KECCAK256 {
+    /// The Keccak state, as 25 little endian lanes.
+    state: [u64; 25],
+    /// Input which has not yet filled a full block.
+    buffer: [u8; RATE],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+}
+
+impl KECCAK256 {
+    /// The length of the KECCAK256 digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new KECCAK256 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: [0; 25],
+            buffer: [0; RATE],
+            buffered: 0,
+        }
+    }
+
+    /// Re-initialize the KECCAK256 structure.
+    fn reset(&mut self) {
+        self.state = [0; 25];
+        self.buffered = 0;
+    }
+
+    /// Absorb a full block of input into the state.
+    fn absorb(&mut self, block: &[u8]) {
+        for (lane, bytes) in self.state.iter_mut().zip(block.chunks_exact(8))
+        {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        permute(&mut self.state);
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for KECCAK256 {
+    /// Update the KECCAK256 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (RATE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < RATE {
+                return;
+            }
+            let buffer = self.buffer;
+            self.absorb(&buffer);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(RATE);
+        for block in &mut blocks {
+            self.absorb(block);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Finalize the KECCAK256 digest computation and return the result.
+    /// The KECCAK256 structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        // The original Keccak padding uses a leading 0x01, where SHA-3
+        // uses 0x06.
+        let mut buffer = self.buffer;
+        buffer[self.buffered..].fill(0);
+        buffer[self.buffered] = 0x01;
+        buffer[RATE - 1] |= 0x80;
+        self.absorb(&buffer);
+
+        let mut digest = [0u8; Self::LENGTH];
+        for (bytes, lane) in digest.chunks_exact_mut(8).zip(self.state) {
+            bytes.copy_from_slice(&lane.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+
+    /// Export the current state of the KECCAK256 digest.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u64s(&self.state);
+        state.bytes(&self.buffer);
+        state.usize(self.buffered);
+        Some(state.finish())
+    }
+
+    /// Create a KECCAK256 digest from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let keccak = state.u64s()?;
+        let buffer = state.bytes()?;
+        let buffered = state.usize(RATE - 1)?;
+        state.finish()?;
+        Some(Self {
+            state: keccak,
+            buffer,
+            buffered,
+        })
+    }
+}
+
+impl Default for KECCAK256 {
+    /// Create a default KECCAK256 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apply the Keccak-f[1600] permutation to the `state`.
+fn permute(state: &mut [u64; 25]) {
+    for constant in ROUND_CONSTANTS {
+        // θ: mix each column's parity into the neighbouring columns.
+        let parity: [u64; 5] = std::array::from_fn(|x| {
+            state[x]
+                ^ state[x + 5]
+                ^ state[x + 10]
+                ^ state[x + 15]
+                ^ state[x + 20]
+        });
+        for (i, lane) in state.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1);
+        }
+
+        // ρ and π: rotate each lane and move it to its new position.
+        let mut lanes = [0u64; 25];
+        for (i, lane) in state.iter().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            lanes[y + 5 * ((2 * x + 3 * y) % 5)] =
+                lane.rotate_left(ROTATIONS[i]);
+        }
+
+        // χ: combine each lane with the next two lanes in its row.
+        for (i, lane) in state.iter_mut().enumerate() {
+            let (x, row) = (i % 5, i - i % 5);
+            *lane = lanes[i]
+                ^ (!lanes[row + (x + 1) % 5] & lanes[row + (x + 2) % 5]);
+        }
+
+        // ι: break the symmetry between rounds.
+        state[0] ^= constant;
+    }
+}
+
+/// Structure used to compute a KECCAK256 digest in a separate thread.
+pub struct BackgroundKECCAK256 {
+    worker: Background<{ KECCAK256::LENGTH }>,
+}
+
+impl BackgroundKECCAK256 {
+    /// Create a new `BackgroundKECCAK256` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(KECCAK256::new),
+        }
+    }
+
+    /// Create a `BackgroundKECCAK256` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<KECCAK256>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundKECCAK256 {
+    /// Add the given `data` to the KECCAK256 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the KECCAK256 digest data, and reset the digest
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::KECCAK256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the KECCAK256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the KECCAK256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::KECCAK256.name()
+    }
+
+    /// The length of the KECCAK256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::KECCAK256.output_len()
+    }
+
+    /// Copy the current state of the KECCAK256 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the KECCAK256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut keccak256 = KECCAK256::new();
+        assert_eq!(keccak256.finish(), fixtures::keccak256::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut keccak256 = KECCAK256::new();
+        keccak256.update(&[0; 0x4000]);
+        keccak256.update(&[0; 0x0d]);
+        assert_eq!(keccak256.finish(), fixtures::keccak256::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut keccak256 = KECCAK256::new();
+        keccak256.update(&fixtures::RANDOM_11171);
+        assert_eq!(keccak256.finish(), fixtures::keccak256::RANDOM_11171);
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut keccak256 = KECCAK256::new();
+        for chunk in fixtures::RANDOM_11171.chunks(135) {
+            keccak256.update(chunk);
+        }
+        assert_eq!(keccak256.finish(), fixtures::keccak256::RANDOM_11171);
+    }
+
+    #[test]
+    fn check() {
+        let mut keccak256 = KECCAK256::new();
+        keccak256.update(b"abc");
+        assert_eq!(
+            DigestData::KECCAK256(keccak256.finish()).to_string(),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // A message which exactly fills a block is followed by a full
+        // block of padding.
+        keccak256.update(&fixtures::RANDOM_11171[..RATE]);
+        assert_eq!(
+            DigestData::KECCAK256(keccak256.finish()).to_string(),
+            "f5aba140f622c6c8f2f9a3b6573d703b1e917f900f168065c86eb05e496056f3"
+        );
+    }
+
+    #[test]
+    fn multiple() {
+        let mut keccak256 = KECCAK256::new();
+        assert_eq!(keccak256.finish(), fixtures::keccak256::EMPTY);
+        keccak256.update(&fixtures::ZERO_400D);
+        assert_eq!(keccak256.finish(), fixtures::keccak256::ZERO_400D);
+        keccak256.update(&fixtures::RANDOM_11171);
+        assert_eq!(keccak256.finish(), fixtures::keccak256::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let keccak256 = BackgroundKECCAK256::new();
+        assert_eq!(
+            keccak256.result(),
+            DigestData::KECCAK256(fixtures::keccak256::EMPTY)
+        );
+        keccak256.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            keccak256.result(),
+            DigestData::KECCAK256(fixtures::keccak256::ZERO_400D)
+        );
+        keccak256.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            keccak256.result(),
+            DigestData::KECCAK256(fixtures::keccak256::RANDOM_11171)
+        );
+    }
+}
@@ -0,0 +1,214 @@
+/// The kinds of digest which may be computed. Each kind has its own
+/// `DigestData` variant.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DigestKind {
+    CRC16_CCITT,
+    CRC16_MODBUS,
+    CRC16_XMODEM,
+    CRC32,
+    CRC64,
+    XXH32,
+    XXH64,
+    RAPIDHASH,
+    MD2,
+    MD4,
+    MD5,
+    SHA224,
+    SHA256,
+    SHA384,
+    SHA512,
+    SHA512_224,
+    SHA512_256,
+    SHA3_224,
+    SHA3_256,
+    SHA3_384,
+    SHA3_512,
+    KECCAK256,
+    BLAKE2B,
+    BLAKE2S,
+    STREEBOG256,
+    STREEBOG512,
+    TIGER,
+    TIGER2,
+    RMD160,
+    RMD256,
+    RMD320,
+}
+
+impl DigestKind {
+    /// Every kind of digest, in the order they are listed by `checksum`.
+    #[must_use]
+    pub const fn all() -> [DigestKind; 31] {
+        [
+            DigestKind::CRC16_CCITT,
+            DigestKind::CRC16_MODBUS,
+            DigestKind::CRC16_XMODEM,
+            DigestKind::CRC32,
+            DigestKind::CRC64,
+            DigestKind::XXH32,
+            DigestKind::XXH64,
+            DigestKind::RAPIDHASH,
+            DigestKind::MD2,
+            DigestKind::MD4,
+            DigestKind::MD5,
+            DigestKind::SHA224,
+            DigestKind::SHA256,
+            DigestKind::SHA384,
+            DigestKind::SHA512,
+            DigestKind::SHA512_224,
+            DigestKind::SHA512_256,
+            DigestKind::SHA3_224,
+            DigestKind::SHA3_256,
+            DigestKind::SHA3_384,
+            DigestKind::SHA3_512,
+            DigestKind::KECCAK256,
+            DigestKind::BLAKE2B,
+            DigestKind::BLAKE2S,
+            DigestKind::STREEBOG256,
+            DigestKind::STREEBOG512,
+            DigestKind::TIGER,
+            DigestKind::TIGER2,
+            DigestKind::RMD160,
+            DigestKind::RMD256,
+            DigestKind::RMD320,
+        ]
+    }
+
+    /// Map the `name` of an algorithm, as returned by `name()`, to the
+    /// kind of digest. The name is case sensitive.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The name of the algorithm, as output by `checksum` (and the BSD
+    /// style output of `sha256sum --tag`).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            DigestKind::CRC16_CCITT => "CRC16/CCITT",
+            DigestKind::CRC16_MODBUS => "CRC16/MODBUS",
+            DigestKind::CRC16_XMODEM => "CRC16/XMODEM",
+            DigestKind::CRC32 => "CRC32",
+            DigestKind::CRC64 => "CRC64",
+            DigestKind::XXH32 => "XXH32",
+            DigestKind::XXH64 => "XXH64",
+            DigestKind::RAPIDHASH => "RAPIDHASH",
+            DigestKind::MD2 => "MD2",
+            DigestKind::MD4 => "MD4",
+            DigestKind::MD5 => "MD5",
+            DigestKind::SHA224 => "SHA224",
+            DigestKind::SHA256 => "SHA256",
+            DigestKind::SHA384 => "SHA384",
+            DigestKind::SHA512 => "SHA512",
+            DigestKind::SHA512_224 => "SHA512/224",
+            DigestKind::SHA512_256 => "SHA512/256",
+            DigestKind::SHA3_224 => "SHA3-224",
+            DigestKind::SHA3_256 => "SHA3-256",
+            DigestKind::SHA3_384 => "SHA3-384",
+            DigestKind::SHA3_512 => "SHA3-512",
+            DigestKind::KECCAK256 => "Keccak-256",
+            DigestKind::BLAKE2B => "BLAKE2b",
+            DigestKind::BLAKE2S => "BLAKE2s",
+            DigestKind::STREEBOG256 => "STREEBOG256",
+            DigestKind::STREEBOG512 => "STREEBOG512",
+            DigestKind::TIGER => "TIGER",
+            DigestKind::TIGER2 => "TIGER2",
+            DigestKind::RMD160 => "RMD160",
+            DigestKind::RMD256 => "RMD256",
+            DigestKind::RMD320 => "RMD320",
+        }
+    }
+
+    /// The length of the digest, in bytes.
+    #[must_use]
+    pub const fn output_len(self) -> usize {
+        match self {
+            DigestKind::CRC16_CCITT
+            | DigestKind::CRC16_MODBUS
+            | DigestKind::CRC16_XMODEM => 2,
+            DigestKind::CRC32 | DigestKind::XXH32 => 4,
+            DigestKind::CRC64 | DigestKind::XXH64 | DigestKind::RAPIDHASH => {
+                8
+            }
+            DigestKind::MD2 | DigestKind::MD4 | DigestKind::MD5 => 16,
+            DigestKind::RMD160 => 20,
+            DigestKind::TIGER | DigestKind::TIGER2 => 24,
+            DigestKind::SHA224
+            | DigestKind::SHA512_224
+            | DigestKind::SHA3_224 => 28,
+            DigestKind::SHA256
+            | DigestKind::SHA512_256
+            | DigestKind::SHA3_256
+            | DigestKind::KECCAK256
+            | DigestKind::BLAKE2S
+            | DigestKind::STREEBOG256
+            | DigestKind::RMD256 => 32,
+            DigestKind::RMD320 => 40,
+            DigestKind::SHA384 | DigestKind::SHA3_384 => 48,
+            DigestKind::SHA512
+            | DigestKind::SHA3_512
+            | DigestKind::BLAKE2B
+            | DigestKind::STREEBOG512 => 64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DigestData;
+
+    #[test]
+    fn named_kinds() {
+        let digests = [
+            DigestData::CRC16_CCITT([0; 2]),
+            DigestData::CRC16_MODBUS([0; 2]),
+            DigestData::CRC16_XMODEM([0; 2]),
+            DigestData::CRC32([0; 4]),
+            DigestData::CRC64([0; 8]),
+            DigestData::XXH32([0; 4]),
+            DigestData::XXH64([0; 8]),
+            DigestData::RAPIDHASH([0; 8]),
+            DigestData::MD2([0; 16]),
+            DigestData::MD4([0; 16]),
+            DigestData::MD5([0; 16]),
+            DigestData::SHA224([0; 28]),
+            DigestData::SHA256([0; 32]),
+            DigestData::SHA384([0; 48]),
+            DigestData::SHA512([0; 64]),
+            DigestData::SHA512_224([0; 28]),
+            DigestData::SHA512_256([0; 32]),
+            DigestData::SHA3_224([0; 28]),
+            DigestData::SHA3_256([0; 32]),
+            DigestData::SHA3_384([0; 48]),
+            DigestData::SHA3_512([0; 64]),
+            DigestData::KECCAK256([0; 32]),
+            DigestData::BLAKE2B([0; 64]),
+            DigestData::BLAKE2S([0; 32]),
+            DigestData::STREEBOG256([0; 32]),
+            DigestData::STREEBOG512([0; 64]),
+            DigestData::TIGER([0; 24]),
+            DigestData::TIGER2([0; 24]),
+            DigestData::RMD160([0; 20]),
+            DigestData::RMD256([0; 32]),
+            DigestData::RMD320([0; 40]),
+        ];
+        // Every name which is output can be read back.
+        for digest in digests {
+            let kind = DigestKind::from_name(digest.name()).unwrap();
+            assert_eq!(kind.name(), digest.name());
+            assert_eq!(kind.output_len(), digest.len());
+        }
+        for kind in DigestKind::all() {
+            assert_eq!(DigestKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(
+            DigestKind::from_name("SHA3-256"),
+            Some(DigestKind::SHA3_256)
+        );
+        assert_eq!(DigestKind::from_name("SHA1"), None);
+        assert_eq!(DigestKind::from_name("md5"), None);
+    }
+}
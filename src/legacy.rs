@@ -0,0 +1,18 @@
+use std::ptr;
+use std::sync::Once;
+
+use openssl_sys::OSSL_PROVIDER_try_load;
+
+/// Load the OpenSSL legacy provider, which supplies older digests such as
+/// MD4 and MD2. The provider is only loaded the first time this is called,
+/// and stays loaded for the life of the process. Failure to load the
+/// provider is not an error here; the digests it would have supplied will
+/// simply be unavailable.
+pub fn load_provider() {
+    static LEGACY: Once = Once::new();
+    LEGACY.call_once(|| {
+        unsafe {
+            OSSL_PROVIDER_try_load(ptr::null_mut(), c"legacy".as_ptr(), 1)
+        };
+    });
+}
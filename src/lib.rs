@@ -9,39 +9,295 @@ pub use crate::digest::Digest;
 mod background;
 pub use background::Background;
 
+mod backend;
+pub use backend::{backend_named, Backend, OpenSSL, Rust, BACKENDS};
+
+mod blake2;
+pub use blake2::Blake2Params;
+
+mod cancel;
+pub use cancel::CancellationToken;
+
+mod encoding;
+pub use encoding::{decode_hex, Encoding};
+
+mod error;
+pub use error::Error;
+
+mod kind;
+pub use kind::DigestKind;
+
+mod registry;
+pub use registry::{
+    generator, generator_named, import_generator, register, Constructor,
+};
+
+mod hash;
+pub use hash::{hash_file, hash_reader};
+
+mod hasher;
+pub use hasher::{Hasher, HasherBuilder};
+
+mod hmac;
+pub use hmac::HMAC_LENGTH;
+
+pub mod cache;
+pub mod glob;
+pub mod manifest;
+pub mod walk;
+
+mod oneshot;
+pub use oneshot::{
+    blake2b_digest, blake2s_digest, crc16_ccitt_digest, crc16_modbus_digest,
+    crc16_xmodem_digest, crc32_digest, crc64_digest, digest,
+    keccak256_digest, md2_digest, md4_digest, md5_digest, rapidhash_digest,
+    rmd160_digest, rmd256_digest, rmd320_digest, sha224_digest,
+    sha256_digest, sha384_digest, sha3_224_digest, sha3_256_digest,
+    sha3_384_digest, sha3_512_digest, sha512_224_digest, sha512_256_digest,
+    sha512_digest, streebog256_digest, streebog512_digest, tiger2_digest,
+    tiger_digest, xxh32_digest, xxh64_digest,
+};
+
+mod parallel;
+pub use parallel::parallel_digest;
+
+mod reader;
+pub use reader::HashReader;
+
+mod writer;
+pub use writer::HashWriter;
+
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "async")]
+pub use async_io::{hash_async_read, HashStream};
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "async")]
+pub use stream::{DigestStream, HashSink};
+
+mod evp;
+#[cfg(feature = "gcrypt")]
+mod gcrypt;
+mod inline;
+pub use inline::{inline_blake2b_with, inline_blake2s_with};
+mod legacy;
+mod ripemd;
+mod rust;
+mod state;
+
+mod blake2b;
+mod blake2s;
+mod crc16;
 mod crc32;
+mod crc64;
+mod keccak256;
+#[cfg(feature = "md2")]
+mod md2;
+mod md4;
 mod md5;
+mod rapidhash;
 mod rmd160;
+mod rmd256;
+mod rmd320;
+mod sha224;
 mod sha256;
+mod sha384;
+mod sha3_224;
+mod sha3_256;
+mod sha3_384;
+mod sha3_512;
 mod sha512;
+mod sha512_224;
+mod sha512_256;
+#[cfg(feature = "gcrypt")]
+mod streebog256;
+#[cfg(feature = "gcrypt")]
+mod streebog512;
+#[cfg(feature = "gcrypt")]
+mod tiger;
+#[cfg(feature = "gcrypt")]
+mod tiger2;
+mod xxh32;
+mod xxh64;
 
+pub use blake2b::{CustomBLAKE2B, BLAKE2B};
+pub use blake2s::{CustomBLAKE2S, BLAKE2S};
+pub use crc16::{Variant as CRC16Variant, CRC16};
+pub use crc32::CRC32;
+pub use crc64::CRC64;
+pub use keccak256::KECCAK256;
+#[cfg(feature = "md2")]
+pub use md2::MD2;
+pub use md4::MD4;
 pub use md5::MD5;
+pub use rapidhash::RAPIDHASH;
+pub use rmd160::RMD160;
+pub use rmd256::RMD256;
+pub use rmd320::RMD320;
+pub use sha224::SHA224;
+pub use sha256::SHA256;
+pub use sha384::SHA384;
+pub use sha3_224::SHA3_224;
+pub use sha3_256::SHA3_256;
+pub use sha3_384::SHA3_384;
+pub use sha3_512::SHA3_512;
+pub use sha512::SHA512;
+pub use sha512_224::SHA512_224;
+pub use sha512_256::SHA512_256;
+#[cfg(feature = "gcrypt")]
+pub use streebog256::STREEBOG256;
+#[cfg(feature = "gcrypt")]
+pub use streebog512::STREEBOG512;
+#[cfg(feature = "gcrypt")]
+pub use tiger::TIGER;
+#[cfg(feature = "gcrypt")]
+pub use tiger2::TIGER2;
+pub use xxh32::XXH32;
+pub use xxh64::XXH64;
 
+#[allow(non_camel_case_types)]
 #[derive(Clone, Copy, Eq)]
 pub enum DigestData {
+    CRC16_CCITT([u8; 2]),
+    CRC16_MODBUS([u8; 2]),
+    CRC16_XMODEM([u8; 2]),
     CRC32([u8; 4]),
+    CRC64([u8; 8]),
+    XXH32([u8; 4]),
+    XXH64([u8; 8]),
+    RAPIDHASH([u8; 8]),
+    MD2([u8; 16]),
+    MD4([u8; 16]),
     MD5([u8; 16]),
+    SHA224([u8; 28]),
     SHA256([u8; 32]),
+    SHA384([u8; 48]),
     SHA512([u8; 64]),
+    SHA512_224([u8; 28]),
+    SHA512_256([u8; 32]),
+    SHA3_224([u8; 28]),
+    SHA3_256([u8; 32]),
+    SHA3_384([u8; 48]),
+    SHA3_512([u8; 64]),
+    KECCAK256([u8; 32]),
+    BLAKE2B([u8; 64]),
+    BLAKE2S([u8; 32]),
+    STREEBOG256([u8; 32]),
+    STREEBOG512([u8; 64]),
+    TIGER([u8; 24]),
+    TIGER2([u8; 24]),
     RMD160([u8; 20]),
+    RMD256([u8; 32]),
+    RMD320([u8; 40]),
+    /// The HMAC of a `kind` of digest, as computed by `hmac()`. Only the
+    /// first `kind.output_len()` bytes are used, and the rest are zero.
+    HMAC {
+        kind: DigestKind,
+        bytes: [u8; HMAC_LENGTH],
+    },
 }
 
 impl PartialEq for DigestData {
+    #[allow(clippy::match_same_arms)]
     fn eq(&self, other: &DigestData) -> bool {
         match (self, other) {
+            (
+                DigestData::CRC16_CCITT(left),
+                DigestData::CRC16_CCITT(right),
+            ) => left == right,
+            (
+                DigestData::CRC16_MODBUS(left),
+                DigestData::CRC16_MODBUS(right),
+            ) => left == right,
+            (
+                DigestData::CRC16_XMODEM(left),
+                DigestData::CRC16_XMODEM(right),
+            ) => left == right,
             (DigestData::CRC32(left), DigestData::CRC32(right)) => {
                 left == right
             }
+            (DigestData::CRC64(left), DigestData::CRC64(right)) => {
+                left == right
+            }
+            (DigestData::XXH32(left), DigestData::XXH32(right)) => {
+                left == right
+            }
+            (DigestData::XXH64(left), DigestData::XXH64(right)) => {
+                left == right
+            }
+            (DigestData::RAPIDHASH(left), DigestData::RAPIDHASH(right)) => {
+                left == right
+            }
+            (DigestData::MD2(left), DigestData::MD2(right)) => left == right,
+            (DigestData::MD4(left), DigestData::MD4(right)) => left == right,
             (DigestData::MD5(left), DigestData::MD5(right)) => left == right,
+            (DigestData::SHA224(left), DigestData::SHA224(right)) => {
+                left == right
+            }
             (DigestData::SHA256(left), DigestData::SHA256(right)) => {
                 left == right
             }
+            (DigestData::SHA384(left), DigestData::SHA384(right)) => {
+                left == right
+            }
             (DigestData::SHA512(left), DigestData::SHA512(right)) => {
                 left == right
             }
+            (DigestData::SHA512_224(left), DigestData::SHA512_224(right)) => {
+                left == right
+            }
+            (DigestData::SHA512_256(left), DigestData::SHA512_256(right)) => {
+                left == right
+            }
+            (DigestData::SHA3_224(left), DigestData::SHA3_224(right)) => {
+                left == right
+            }
+            (DigestData::SHA3_256(left), DigestData::SHA3_256(right)) => {
+                left == right
+            }
+            (DigestData::SHA3_384(left), DigestData::SHA3_384(right)) => {
+                left == right
+            }
+            (DigestData::SHA3_512(left), DigestData::SHA3_512(right)) => {
+                left == right
+            }
+            (DigestData::KECCAK256(left), DigestData::KECCAK256(right)) => {
+                left == right
+            }
+            (DigestData::BLAKE2B(left), DigestData::BLAKE2B(right)) => {
+                left == right
+            }
+            (DigestData::BLAKE2S(left), DigestData::BLAKE2S(right)) => {
+                left == right
+            }
+            (
+                DigestData::STREEBOG256(left),
+                DigestData::STREEBOG256(right),
+            ) => left == right,
+            (
+                DigestData::STREEBOG512(left),
+                DigestData::STREEBOG512(right),
+            ) => left == right,
+            (DigestData::TIGER(left), DigestData::TIGER(right)) => {
+                left == right
+            }
+            (DigestData::TIGER2(left), DigestData::TIGER2(right)) => {
+                left == right
+            }
+            (DigestData::RMD256(left), DigestData::RMD256(right)) => {
+                left == right
+            }
+            (DigestData::RMD320(left), DigestData::RMD320(right)) => {
+                left == right
+            }
             (DigestData::RMD160(left), DigestData::RMD160(right)) => {
                 left == right
             }
+            (
+                DigestData::HMAC { kind: left, .. },
+                DigestData::HMAC { kind: right, .. },
+            ) => left == right && self.as_bytes() == other.as_bytes(),
             _ => false,
         }
     }
@@ -49,58 +305,432 @@ impl PartialEq for DigestData {
 
 impl fmt::Debug for DigestData {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            DigestData::CRC32(digest) => {
-                write!(f, "CRC32(")?;
-                format_bytes(f, digest)?;
-                write!(f, ")")
-            }
-            DigestData::MD5(digest) => {
-                write!(f, "MD5(")?;
-                format_bytes(f, digest)?;
-                write!(f, ")")
-            }
-            DigestData::SHA256(digest) => {
-                write!(f, "SHA256(")?;
-                format_bytes(f, digest)?;
-                write!(f, ")")
-            }
-            DigestData::SHA512(digest) => {
-                write!(f, "SHA512(")?;
-                format_bytes(f, digest)?;
-                write!(f, ")")
-            }
-            DigestData::RMD160(digest) => {
-                write!(f, "RMD160(")?;
-                format_bytes(f, digest)?;
-                write!(f, ")")
+        let (name, digest): (&str, &[u8]) = match self {
+            DigestData::CRC16_CCITT(digest) => ("CRC16_CCITT", digest),
+            DigestData::CRC16_MODBUS(digest) => ("CRC16_MODBUS", digest),
+            DigestData::CRC16_XMODEM(digest) => ("CRC16_XMODEM", digest),
+            DigestData::CRC32(digest) => ("CRC32", digest),
+            DigestData::CRC64(digest) => ("CRC64", digest),
+            DigestData::XXH32(digest) => ("XXH32", digest),
+            DigestData::XXH64(digest) => ("XXH64", digest),
+            DigestData::RAPIDHASH(digest) => ("RAPIDHASH", digest),
+            DigestData::MD2(digest) => ("MD2", digest),
+            DigestData::MD4(digest) => ("MD4", digest),
+            DigestData::MD5(digest) => ("MD5", digest),
+            DigestData::SHA224(digest) => ("SHA224", digest),
+            DigestData::SHA256(digest) => ("SHA256", digest),
+            DigestData::SHA384(digest) => ("SHA384", digest),
+            DigestData::SHA512(digest) => ("SHA512", digest),
+            DigestData::SHA512_224(digest) => ("SHA512_224", digest),
+            DigestData::SHA512_256(digest) => ("SHA512_256", digest),
+            DigestData::SHA3_224(digest) => ("SHA3_224", digest),
+            DigestData::SHA3_256(digest) => ("SHA3_256", digest),
+            DigestData::SHA3_384(digest) => ("SHA3_384", digest),
+            DigestData::SHA3_512(digest) => ("SHA3_512", digest),
+            DigestData::KECCAK256(digest) => ("KECCAK256", digest),
+            DigestData::BLAKE2B(digest) => ("BLAKE2B", digest),
+            DigestData::BLAKE2S(digest) => ("BLAKE2S", digest),
+            DigestData::STREEBOG256(digest) => ("STREEBOG256", digest),
+            DigestData::STREEBOG512(digest) => ("STREEBOG512", digest),
+            DigestData::TIGER(digest) => ("TIGER", digest),
+            DigestData::TIGER2(digest) => ("TIGER2", digest),
+            DigestData::RMD160(digest) => ("RMD160", digest),
+            DigestData::RMD256(digest) => ("RMD256", digest),
+            DigestData::RMD320(digest) => ("RMD320", digest),
+            DigestData::HMAC { kind, .. } => {
+                write!(f, "HMAC({kind:?}, ")?;
+                format_bytes(f, self.as_bytes())?;
+                return write!(f, ")");
+            }
+        };
+        write!(f, "{name}(")?;
+        format_bytes(f, digest)?;
+        write!(f, ")")
+    }
+}
+
+impl DigestData {
+    /// Create the `kind` of digest from its raw `bytes`, such as a digest
+    /// read back from a database.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::InvalidDigest` is returned if the `bytes` are the wrong
+    /// length for the `kind` of digest.
+    pub fn from_bytes(kind: DigestKind, bytes: &[u8]) -> Result<Self, Error> {
+        Ok(match kind {
+            DigestKind::CRC16_CCITT => {
+                DigestData::CRC16_CCITT(array(kind, bytes)?)
+            }
+            DigestKind::CRC16_MODBUS => {
+                DigestData::CRC16_MODBUS(array(kind, bytes)?)
+            }
+            DigestKind::CRC16_XMODEM => {
+                DigestData::CRC16_XMODEM(array(kind, bytes)?)
+            }
+            DigestKind::CRC32 => DigestData::CRC32(array(kind, bytes)?),
+            DigestKind::CRC64 => DigestData::CRC64(array(kind, bytes)?),
+            DigestKind::XXH32 => DigestData::XXH32(array(kind, bytes)?),
+            DigestKind::XXH64 => DigestData::XXH64(array(kind, bytes)?),
+            DigestKind::RAPIDHASH => {
+                DigestData::RAPIDHASH(array(kind, bytes)?)
             }
+            DigestKind::MD2 => DigestData::MD2(array(kind, bytes)?),
+            DigestKind::MD4 => DigestData::MD4(array(kind, bytes)?),
+            DigestKind::MD5 => DigestData::MD5(array(kind, bytes)?),
+            DigestKind::SHA224 => DigestData::SHA224(array(kind, bytes)?),
+            DigestKind::SHA256 => DigestData::SHA256(array(kind, bytes)?),
+            DigestKind::SHA384 => DigestData::SHA384(array(kind, bytes)?),
+            DigestKind::SHA512 => DigestData::SHA512(array(kind, bytes)?),
+            DigestKind::SHA512_224 => {
+                DigestData::SHA512_224(array(kind, bytes)?)
+            }
+            DigestKind::SHA512_256 => {
+                DigestData::SHA512_256(array(kind, bytes)?)
+            }
+            DigestKind::SHA3_224 => DigestData::SHA3_224(array(kind, bytes)?),
+            DigestKind::SHA3_256 => DigestData::SHA3_256(array(kind, bytes)?),
+            DigestKind::SHA3_384 => DigestData::SHA3_384(array(kind, bytes)?),
+            DigestKind::SHA3_512 => DigestData::SHA3_512(array(kind, bytes)?),
+            DigestKind::KECCAK256 => {
+                DigestData::KECCAK256(array(kind, bytes)?)
+            }
+            DigestKind::BLAKE2B => DigestData::BLAKE2B(array(kind, bytes)?),
+            DigestKind::BLAKE2S => DigestData::BLAKE2S(array(kind, bytes)?),
+            DigestKind::STREEBOG256 => {
+                DigestData::STREEBOG256(array(kind, bytes)?)
+            }
+            DigestKind::STREEBOG512 => {
+                DigestData::STREEBOG512(array(kind, bytes)?)
+            }
+            DigestKind::TIGER => DigestData::TIGER(array(kind, bytes)?),
+            DigestKind::TIGER2 => DigestData::TIGER2(array(kind, bytes)?),
+            DigestKind::RMD160 => DigestData::RMD160(array(kind, bytes)?),
+            DigestKind::RMD256 => DigestData::RMD256(array(kind, bytes)?),
+            DigestKind::RMD320 => DigestData::RMD320(array(kind, bytes)?),
+        })
+    }
+
+    /// The kind of digest. For an HMAC this is the kind of digest it was
+    /// computed with.
+    #[must_use]
+    pub fn kind(&self) -> DigestKind {
+        match self {
+            DigestData::CRC16_CCITT(_) => DigestKind::CRC16_CCITT,
+            DigestData::CRC16_MODBUS(_) => DigestKind::CRC16_MODBUS,
+            DigestData::CRC16_XMODEM(_) => DigestKind::CRC16_XMODEM,
+            DigestData::CRC32(_) => DigestKind::CRC32,
+            DigestData::CRC64(_) => DigestKind::CRC64,
+            DigestData::XXH32(_) => DigestKind::XXH32,
+            DigestData::XXH64(_) => DigestKind::XXH64,
+            DigestData::RAPIDHASH(_) => DigestKind::RAPIDHASH,
+            DigestData::MD2(_) => DigestKind::MD2,
+            DigestData::MD4(_) => DigestKind::MD4,
+            DigestData::MD5(_) => DigestKind::MD5,
+            DigestData::SHA224(_) => DigestKind::SHA224,
+            DigestData::SHA256(_) => DigestKind::SHA256,
+            DigestData::SHA384(_) => DigestKind::SHA384,
+            DigestData::SHA512(_) => DigestKind::SHA512,
+            DigestData::SHA512_224(_) => DigestKind::SHA512_224,
+            DigestData::SHA512_256(_) => DigestKind::SHA512_256,
+            DigestData::SHA3_224(_) => DigestKind::SHA3_224,
+            DigestData::SHA3_256(_) => DigestKind::SHA3_256,
+            DigestData::SHA3_384(_) => DigestKind::SHA3_384,
+            DigestData::SHA3_512(_) => DigestKind::SHA3_512,
+            DigestData::KECCAK256(_) => DigestKind::KECCAK256,
+            DigestData::BLAKE2B(_) => DigestKind::BLAKE2B,
+            DigestData::BLAKE2S(_) => DigestKind::BLAKE2S,
+            DigestData::STREEBOG256(_) => DigestKind::STREEBOG256,
+            DigestData::STREEBOG512(_) => DigestKind::STREEBOG512,
+            DigestData::TIGER(_) => DigestKind::TIGER,
+            DigestData::TIGER2(_) => DigestKind::TIGER2,
+            DigestData::RMD160(_) => DigestKind::RMD160,
+            DigestData::RMD256(_) => DigestKind::RMD256,
+            DigestData::RMD320(_) => DigestKind::RMD320,
+            DigestData::HMAC { kind, .. } => *kind,
+        }
+    }
+
+    /// The name of the algorithm used to compute the digest, as shown in
+    /// the output of `checksum`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            DigestData::CRC16_CCITT(_) => "CRC16/CCITT",
+            DigestData::CRC16_MODBUS(_) => "CRC16/MODBUS",
+            DigestData::CRC16_XMODEM(_) => "CRC16/XMODEM",
+            DigestData::CRC32(_) => "CRC32",
+            DigestData::CRC64(_) => "CRC64",
+            DigestData::XXH32(_) => "XXH32",
+            DigestData::XXH64(_) => "XXH64",
+            DigestData::RAPIDHASH(_) => "RAPIDHASH",
+            DigestData::MD2(_) => "MD2",
+            DigestData::MD4(_) => "MD4",
+            DigestData::MD5(_) => "MD5",
+            DigestData::SHA224(_) => "SHA224",
+            DigestData::SHA256(_) => "SHA256",
+            DigestData::SHA384(_) => "SHA384",
+            DigestData::SHA512(_) => "SHA512",
+            DigestData::SHA512_224(_) => "SHA512/224",
+            DigestData::SHA512_256(_) => "SHA512/256",
+            DigestData::SHA3_224(_) => "SHA3-224",
+            DigestData::SHA3_256(_) => "SHA3-256",
+            DigestData::SHA3_384(_) => "SHA3-384",
+            DigestData::SHA3_512(_) => "SHA3-512",
+            DigestData::KECCAK256(_) => "Keccak-256",
+            DigestData::BLAKE2B(_) => "BLAKE2b",
+            DigestData::BLAKE2S(_) => "BLAKE2s",
+            DigestData::STREEBOG256(_) => "STREEBOG256",
+            DigestData::STREEBOG512(_) => "STREEBOG512",
+            DigestData::TIGER(_) => "TIGER",
+            DigestData::TIGER2(_) => "TIGER2",
+            DigestData::RMD160(_) => "RMD160",
+            DigestData::RMD256(_) => "RMD256",
+            DigestData::RMD320(_) => "RMD320",
+            DigestData::HMAC { kind, .. } => hmac::name(*kind),
+        }
+    }
+
+    /// The length of the digest, in bytes.
+    #[must_use]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// The raw bytes of the digest.
+    #[must_use]
+    #[allow(clippy::match_same_arms)]
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            DigestData::CRC16_CCITT(digest) => digest,
+            DigestData::CRC16_MODBUS(digest) => digest,
+            DigestData::CRC16_XMODEM(digest) => digest,
+            DigestData::CRC32(digest) => digest,
+            DigestData::CRC64(digest) => digest,
+            DigestData::XXH32(digest) => digest,
+            DigestData::XXH64(digest) => digest,
+            DigestData::RAPIDHASH(digest) => digest,
+            DigestData::MD2(digest) => digest,
+            DigestData::MD4(digest) => digest,
+            DigestData::MD5(digest) => digest,
+            DigestData::SHA224(digest) => digest,
+            DigestData::SHA256(digest) => digest,
+            DigestData::SHA384(digest) => digest,
+            DigestData::SHA512(digest) => digest,
+            DigestData::SHA512_224(digest) => digest,
+            DigestData::SHA512_256(digest) => digest,
+            DigestData::SHA3_224(digest) => digest,
+            DigestData::SHA3_256(digest) => digest,
+            DigestData::SHA3_384(digest) => digest,
+            DigestData::SHA3_512(digest) => digest,
+            DigestData::KECCAK256(digest) => digest,
+            DigestData::BLAKE2B(digest) => digest,
+            DigestData::BLAKE2S(digest) => digest,
+            DigestData::STREEBOG256(digest) => digest,
+            DigestData::STREEBOG512(digest) => digest,
+            DigestData::TIGER(digest) => digest,
+            DigestData::TIGER2(digest) => digest,
+            DigestData::RMD160(digest) => digest,
+            DigestData::RMD256(digest) => digest,
+            DigestData::RMD320(digest) => digest,
+            DigestData::HMAC { kind, bytes } => &bytes[..kind.output_len()],
         }
     }
+
+    /// Encode the digest as base32, using the RFC 4648 alphabet. If
+    /// `padding` is set then the result is padded with `=` to a multiple
+    /// of 8 characters.
+    #[must_use]
+    pub fn to_base32(&self, padding: bool) -> String {
+        encoding::base32(self.as_bytes(), padding)
+    }
+
+    /// Encode the digest as a string, using the given `encoding`.
+    #[must_use]
+    pub fn encode(&self, encoding: Encoding) -> String {
+        encoding.encode(self.as_bytes())
+    }
 }
 
 impl fmt::Display for DigestData {
+    #[allow(clippy::match_same_arms)]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            DigestData::CRC16_CCITT(digest) => format_bytes(f, digest),
+            DigestData::CRC16_MODBUS(digest) => format_bytes(f, digest),
+            DigestData::CRC16_XMODEM(digest) => format_bytes(f, digest),
             DigestData::CRC32(digest) => format_bytes(f, digest),
+            DigestData::CRC64(digest) => format_bytes(f, digest),
+            DigestData::XXH32(digest) => format_bytes(f, digest),
+            DigestData::XXH64(digest) => format_bytes(f, digest),
+            DigestData::RAPIDHASH(digest) => format_bytes(f, digest),
+            DigestData::MD2(digest) => format_bytes(f, digest),
+            DigestData::MD4(digest) => format_bytes(f, digest),
             DigestData::MD5(digest) => format_bytes(f, digest),
+            DigestData::SHA224(digest) => format_bytes(f, digest),
             DigestData::SHA256(digest) => format_bytes(f, digest),
+            DigestData::SHA384(digest) => format_bytes(f, digest),
             DigestData::SHA512(digest) => format_bytes(f, digest),
+            DigestData::SHA512_224(digest) => format_bytes(f, digest),
+            DigestData::SHA512_256(digest) => format_bytes(f, digest),
+            DigestData::SHA3_224(digest) => format_bytes(f, digest),
+            DigestData::SHA3_256(digest) => format_bytes(f, digest),
+            DigestData::SHA3_384(digest) => format_bytes(f, digest),
+            DigestData::SHA3_512(digest) => format_bytes(f, digest),
+            DigestData::KECCAK256(digest) => format_bytes(f, digest),
+            DigestData::BLAKE2B(digest) => format_bytes(f, digest),
+            DigestData::BLAKE2S(digest) => format_bytes(f, digest),
+            DigestData::STREEBOG256(digest) => format_bytes(f, digest),
+            DigestData::STREEBOG512(digest) => format_bytes(f, digest),
+            DigestData::TIGER(digest) => format_bytes(f, digest),
+            DigestData::TIGER2(digest) => format_bytes(f, digest),
             DigestData::RMD160(digest) => format_bytes(f, digest),
+            DigestData::RMD256(digest) => format_bytes(f, digest),
+            DigestData::RMD320(digest) => format_bytes(f, digest),
+            DigestData::HMAC { .. } => format_bytes(f, self.as_bytes()),
+        }
+    }
+}
+
+/// Format the digest as lower case hexadecimal, the same as `Display`. The
+/// alternate form (`{:#x}`) adds a `0x` prefix.
+impl fmt::LowerHex for DigestData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
         }
+        format_bytes(f, self.as_bytes())
     }
 }
 
+/// Format the digest as upper case hexadecimal. The alternate form
+/// (`{:#X}`) adds a `0x` prefix.
+impl fmt::UpperHex for DigestData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        for byte in self.as_bytes() {
+            write!(f, "{byte:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<(DigestKind, &[u8])> for DigestData {
+    type Error = Error;
+
+    /// Create a digest from its kind and raw bytes, as for `from_bytes()`.
+    fn try_from((kind, bytes): (DigestKind, &[u8])) -> Result<Self, Error> {
+        DigestData::from_bytes(kind, bytes)
+    }
+}
+
+/// Copy `bytes` into an array for the `kind` of digest, if they are the
+/// right length.
+fn array<const N: usize>(
+    kind: DigestKind,
+    bytes: &[u8],
+) -> Result<[u8; N], Error> {
+    bytes.try_into().map_err(|_err| Error::InvalidDigest {
+        kind,
+        len: bytes.len(),
+    })
+}
+
 fn format_bytes(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
     for byte in bytes {
-        write!(f, "{:02x}", byte)?;
+        write!(f, "{byte:02x}")?;
     }
     Ok(())
 }
 
-pub trait Generator {
-    fn append(&self, data: Arc<[u8]>);
-    fn result(&self) -> DigestData;
+/// An interface to compute a digest of data appended to it over time. A
+/// generator is `Send` and `Sync`, so that it can be moved into another
+/// thread, or shared between threads behind an `Arc`.
+pub trait Generator: Send + Sync {
+    /// Append `data` to the digest.
+    ///
+    /// ## Panics
+    ///
+    /// A panic will occur if the digest can't be computed, such as when
+    /// its background thread has exited. Use `try_append()` to handle the
+    /// error instead.
+    fn append(&self, data: Arc<[u8]>) {
+        self.try_append(data).expect("unable to append to digest");
+    }
+    /// Append `data` to the digest.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest can't be computed,
+    /// such as when its background thread has exited.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error>;
+    /// Retrieve the digest of the data appended so far, and reset the
+    /// digest so that further data starts a new one.
+    ///
+    /// ## Panics
+    ///
+    /// A panic will occur if the digest can't be computed. Use
+    /// `try_result()` to handle the error instead.
+    fn result(&self) -> DigestData {
+        self.try_result().expect("unable to retrieve digest")
+    }
+    /// Retrieve the digest of the data appended so far, as for `result()`.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest can't be computed,
+    /// and `Error::TimedOut` if it isn't computed within 5 seconds.
+    fn try_result(&self) -> Result<DigestData, Error>;
+    /// Discard any data appended so far, without computing the digest,
+    /// such as when a read error means the digest would be meaningless.
+    fn reset(&self);
+    /// The name of the digest, as returned by `DigestData::name()`.
+    fn name(&self) -> &'static str;
+    /// The length of the digest, in bytes.
+    fn output_len(&self) -> usize;
+    /// Create a new generator with a copy of the current state, so that
+    /// the digest of the data so far can be computed while this one
+    /// continues to have data appended.
+    ///
+    /// ## Panics
+    ///
+    /// A panic will occur if the digest can't be copied. Use `try_fork()`
+    /// to handle the error instead.
+    fn fork(&self) -> Box<dyn Generator> {
+        self.try_fork().expect("unable to fork digest")
+    }
+    /// Create a new generator with a copy of the current state, as for
+    /// `fork()`.
+    ///
+    /// ## Errors
+    ///
+    /// `Error::WorkerFailed` is returned if the digest can't be copied.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error>;
+    /// Export the current state of the digest, so that it can be resumed
+    /// later with `import_generator()`, or `None` if the state of this
+    /// kind of digest can't be exported.
+    fn export_state(&self) -> Option<Vec<u8>>;
+}
+
+#[must_use]
+pub fn crc16_ccitt() -> Box<dyn Generator> {
+    let crc16_ccitt = crc16::BackgroundCRC16::new(crc16::Variant::CCITT);
+    Box::new(crc16_ccitt)
+}
+
+#[must_use]
+pub fn crc16_modbus() -> Box<dyn Generator> {
+    let crc16_modbus = crc16::BackgroundCRC16::new(crc16::Variant::MODBUS);
+    Box::new(crc16_modbus)
+}
+
+#[must_use]
+pub fn crc16_xmodem() -> Box<dyn Generator> {
+    let crc16_xmodem = crc16::BackgroundCRC16::new(crc16::Variant::XMODEM);
+    Box::new(crc16_xmodem)
 }
 
 #[must_use]
@@ -109,24 +739,238 @@ pub fn crc32() -> Box<dyn Generator> {
     Box::new(crc32)
 }
 
+#[must_use]
+pub fn crc64() -> Box<dyn Generator> {
+    let crc64 = crc64::BackgroundCRC64::new();
+    Box::new(crc64)
+}
+
+#[must_use]
+pub fn xxh32() -> Box<dyn Generator> {
+    let xxh32 = xxh32::BackgroundXXH32::new();
+    Box::new(xxh32)
+}
+
+#[must_use]
+pub fn xxh64() -> Box<dyn Generator> {
+    let xxh64 = xxh64::BackgroundXXH64::new();
+    Box::new(xxh64)
+}
+
+/// Create a MD2 generator. MD2 support must be enabled using the `md2`
+/// feature, and is omitted from most builds of OpenSSL, so `None` is
+/// returned if either is missing.
+#[must_use]
+pub fn md2() -> Option<Box<dyn Generator>> {
+    #[cfg(feature = "md2")]
+    if md2::MD2::available() {
+        let md2 = md2::BackgroundMD2::new();
+        return Some(Box::new(md2));
+    }
+    None
+}
+
+/// Create a MD4 generator. MD4 is supplied by the OpenSSL legacy
+/// provider, so `None` is returned if that provider is not installed.
+#[must_use]
+pub fn md4() -> Option<Box<dyn Generator>> {
+    if !md4::MD4::available() {
+        return None;
+    }
+    let md4 = md4::BackgroundMD4::new();
+    Some(Box::new(md4))
+}
+
+/// Create a rapidhash generator, computing the 64-bit rapidhash (version
+/// 3) with the reference seed.
+#[must_use]
+pub fn rapidhash() -> Box<dyn Generator> {
+    let rapidhash = rapidhash::BackgroundRAPIDHASH::new();
+    Box::new(rapidhash)
+}
+
 #[must_use]
 pub fn md5() -> Box<dyn Generator> {
     let md5 = md5::BackgroundMD5::new();
     Box::new(md5)
 }
 
+#[must_use]
+pub fn sha224() -> Box<dyn Generator> {
+    let sha224 = sha224::BackgroundSHA224::new();
+    Box::new(sha224)
+}
+
 #[must_use]
 pub fn sha256() -> Box<dyn Generator> {
     let sha256 = sha256::BackgroundSHA256::new();
     Box::new(sha256)
 }
 
+#[must_use]
+pub fn sha384() -> Box<dyn Generator> {
+    let sha384 = sha384::BackgroundSHA384::new();
+    Box::new(sha384)
+}
+
 #[must_use]
 pub fn sha512() -> Box<dyn Generator> {
     let sha512 = sha512::BackgroundSHA512::new();
     Box::new(sha512)
 }
 
+#[must_use]
+pub fn sha512_224() -> Box<dyn Generator> {
+    let sha512_224 = sha512_224::BackgroundSHA512_224::new();
+    Box::new(sha512_224)
+}
+
+#[must_use]
+pub fn sha512_256() -> Box<dyn Generator> {
+    let sha512_256 = sha512_256::BackgroundSHA512_256::new();
+    Box::new(sha512_256)
+}
+
+#[must_use]
+pub fn sha3_224() -> Box<dyn Generator> {
+    let sha3_224 = sha3_224::BackgroundSHA3_224::new();
+    Box::new(sha3_224)
+}
+
+#[must_use]
+pub fn sha3_256() -> Box<dyn Generator> {
+    let sha3_256 = sha3_256::BackgroundSHA3_256::new();
+    Box::new(sha3_256)
+}
+
+#[must_use]
+pub fn sha3_384() -> Box<dyn Generator> {
+    let sha3_384 = sha3_384::BackgroundSHA3_384::new();
+    Box::new(sha3_384)
+}
+
+#[must_use]
+pub fn sha3_512() -> Box<dyn Generator> {
+    let sha3_512 = sha3_512::BackgroundSHA3_512::new();
+    Box::new(sha3_512)
+}
+
+#[must_use]
+pub fn keccak256() -> Box<dyn Generator> {
+    let keccak256 = keccak256::BackgroundKECCAK256::new();
+    Box::new(keccak256)
+}
+
+#[must_use]
+pub fn blake2b() -> Box<dyn Generator> {
+    let blake2b = blake2b::BackgroundBLAKE2B::new();
+    Box::new(blake2b)
+}
+
+/// Create a BLAKE2B generator using the given key, salt, and
+/// personalization `params`. `None` is returned if the key is longer than
+/// 64 bytes, or the salt or personalization are longer than 16 bytes.
+#[must_use]
+pub fn blake2b_with(params: &Blake2Params) -> Option<Box<dyn Generator>> {
+    let blake2b = blake2b::BackgroundCustomBLAKE2B::new(params)?;
+    Some(Box::new(blake2b))
+}
+
+#[must_use]
+pub fn blake2s() -> Box<dyn Generator> {
+    let blake2s = blake2s::BackgroundBLAKE2S::new();
+    Box::new(blake2s)
+}
+
+/// Create a BLAKE2S generator using the given key, salt, and
+/// personalization `params`. `None` is returned if the key is longer than
+/// 32 bytes, or the salt or personalization are longer than 8 bytes.
+#[must_use]
+pub fn blake2s_with(params: &Blake2Params) -> Option<Box<dyn Generator>> {
+    let blake2s = blake2s::BackgroundCustomBLAKE2S::new(params)?;
+    Some(Box::new(blake2s))
+}
+
+/// Create a generator for the HMAC (RFC 2104) of the `kind` of digest,
+/// using `key`. The results are `DigestData::HMAC`, tagged with the `kind`.
+///
+/// ## Errors
+///
+/// `Error::Unsupported` is returned if the `kind` is a checksum, such as
+/// CRC32, rather than a cryptographic digest, or if it is unavailable.
+pub fn hmac(
+    kind: DigestKind,
+    key: &[u8],
+) -> Result<Box<dyn Generator>, Error> {
+    let hmac = hmac::BackgroundHMAC::new(kind, key)
+        .ok_or(Error::Unsupported(kind))?;
+    Ok(Box::new(hmac))
+}
+
+/// Create a Streebog-256 generator. Streebog is computed using
+/// libgcrypt, so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn streebog256() -> Option<Box<dyn Generator>> {
+    #[cfg(feature = "gcrypt")]
+    {
+        let streebog256 = streebog256::BackgroundSTREEBOG256::new();
+        Some(Box::new(streebog256))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    None
+}
+
+/// Create a Streebog-512 generator. Streebog is computed using
+/// libgcrypt, so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn streebog512() -> Option<Box<dyn Generator>> {
+    #[cfg(feature = "gcrypt")]
+    {
+        let streebog512 = streebog512::BackgroundSTREEBOG512::new();
+        Some(Box::new(streebog512))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    None
+}
+
+/// Create a Tiger generator. Tiger is computed using libgcrypt, so `None`
+/// is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn tiger() -> Option<Box<dyn Generator>> {
+    #[cfg(feature = "gcrypt")]
+    {
+        let tiger = tiger::BackgroundTIGER::new();
+        Some(Box::new(tiger))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    None
+}
+
+/// Create a Tiger2 generator. Tiger2 is computed using libgcrypt, so `None`
+/// is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn tiger2() -> Option<Box<dyn Generator>> {
+    #[cfg(feature = "gcrypt")]
+    {
+        let tiger2 = tiger2::BackgroundTIGER2::new();
+        Some(Box::new(tiger2))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    None
+}
+
+#[must_use]
+pub fn rmd256() -> Box<dyn Generator> {
+    let rmd256 = rmd256::BackgroundRMD256::new();
+    Box::new(rmd256)
+}
+
+#[must_use]
+pub fn rmd320() -> Box<dyn Generator> {
+    let rmd320 = rmd320::BackgroundRMD320::new();
+    Box::new(rmd320)
+}
+
 #[must_use]
 pub fn rmd160() -> Box<dyn Generator> {
     let rmd160 = rmd160::BackgroundRMD160::new();
@@ -143,12 +987,38 @@ mod tests {
 
     #[test]
     fn digest_data_eq() {
-        const DIGESTS: [DigestData; 5] = [
+        const DIGESTS: [DigestData; 31] = [
             DigestData::CRC32(fixtures::crc32::EMPTY),
             DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::SHA224(fixtures::sha224::EMPTY),
             DigestData::SHA256(fixtures::sha256::EMPTY),
             DigestData::SHA512(fixtures::sha512::EMPTY),
             DigestData::RMD160(fixtures::rmd160::EMPTY),
+            DigestData::SHA384(fixtures::sha384::EMPTY),
+            DigestData::SHA3_224(fixtures::sha3_224::EMPTY),
+            DigestData::SHA3_256(fixtures::sha3_256::EMPTY),
+            DigestData::SHA3_384(fixtures::sha3_384::EMPTY),
+            DigestData::SHA3_512(fixtures::sha3_512::EMPTY),
+            DigestData::BLAKE2B(fixtures::blake2b::EMPTY),
+            DigestData::BLAKE2S(fixtures::blake2s::EMPTY),
+            DigestData::XXH32(fixtures::xxh32::EMPTY),
+            DigestData::XXH64(fixtures::xxh64::EMPTY),
+            DigestData::CRC64(fixtures::crc64::EMPTY),
+            DigestData::MD4(fixtures::md4::EMPTY),
+            DigestData::MD2(fixtures::md2::EMPTY),
+            DigestData::STREEBOG256(fixtures::streebog256::EMPTY),
+            DigestData::STREEBOG512(fixtures::streebog512::EMPTY),
+            DigestData::TIGER(fixtures::tiger::EMPTY),
+            DigestData::TIGER2(fixtures::tiger2::EMPTY),
+            DigestData::RMD256(fixtures::rmd256::EMPTY),
+            DigestData::RMD320(fixtures::rmd320::EMPTY),
+            DigestData::KECCAK256(fixtures::keccak256::EMPTY),
+            DigestData::SHA512_224(fixtures::sha512_224::EMPTY),
+            DigestData::SHA512_256(fixtures::sha512_256::EMPTY),
+            DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY),
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::EMPTY),
+            DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::EMPTY),
         ];
         for (i, left) in DIGESTS.iter().enumerate() {
             for (j, right) in DIGESTS.iter().enumerate() {
@@ -159,26 +1029,228 @@ mod tests {
                 }
             }
         }
+    }
 
-        assert_ne!(
+    #[test]
+    fn digest_data_value_ne() {
+        // The CRC16/XMODEM checksum of zeros is zero, the same as for no
+        // data, so it is left out here.
+        let empty = [
             DigestData::CRC32(fixtures::crc32::EMPTY),
-            DigestData::CRC32(fixtures::crc32::ZERO_400D)
-        );
-        assert_ne!(
+            DigestData::CRC64(fixtures::crc64::EMPTY),
+            DigestData::XXH32(fixtures::xxh32::EMPTY),
+            DigestData::XXH64(fixtures::xxh64::EMPTY),
+            DigestData::MD2(fixtures::md2::EMPTY),
+            DigestData::MD4(fixtures::md4::EMPTY),
             DigestData::MD5(fixtures::md5::EMPTY),
+            DigestData::SHA224(fixtures::sha224::EMPTY),
+            DigestData::SHA256(fixtures::sha256::EMPTY),
+            DigestData::SHA384(fixtures::sha384::EMPTY),
+            DigestData::SHA512(fixtures::sha512::EMPTY),
+            DigestData::SHA512_224(fixtures::sha512_224::EMPTY),
+            DigestData::SHA512_256(fixtures::sha512_256::EMPTY),
+            DigestData::SHA3_224(fixtures::sha3_224::EMPTY),
+            DigestData::SHA3_256(fixtures::sha3_256::EMPTY),
+            DigestData::SHA3_384(fixtures::sha3_384::EMPTY),
+            DigestData::SHA3_512(fixtures::sha3_512::EMPTY),
+            DigestData::KECCAK256(fixtures::keccak256::EMPTY),
+            DigestData::BLAKE2B(fixtures::blake2b::EMPTY),
+            DigestData::BLAKE2S(fixtures::blake2s::EMPTY),
+            DigestData::STREEBOG256(fixtures::streebog256::EMPTY),
+            DigestData::STREEBOG512(fixtures::streebog512::EMPTY),
+            DigestData::TIGER(fixtures::tiger::EMPTY),
+            DigestData::TIGER2(fixtures::tiger2::EMPTY),
+            DigestData::RMD256(fixtures::rmd256::EMPTY),
+            DigestData::RMD320(fixtures::rmd320::EMPTY),
+            DigestData::RMD160(fixtures::rmd160::EMPTY),
+            DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY),
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::EMPTY),
+        ];
+        let zero = [
+            DigestData::CRC32(fixtures::crc32::ZERO_400D),
+            DigestData::CRC64(fixtures::crc64::ZERO_400D),
+            DigestData::XXH32(fixtures::xxh32::ZERO_400D),
+            DigestData::XXH64(fixtures::xxh64::ZERO_400D),
+            DigestData::MD2(fixtures::md2::ZERO_400D),
+            DigestData::MD4(fixtures::md4::ZERO_400D),
+            DigestData::MD5(fixtures::md5::ZERO_400D),
+            DigestData::SHA224(fixtures::sha224::ZERO_400D),
+            DigestData::SHA256(fixtures::sha256::ZERO_400D),
+            DigestData::SHA384(fixtures::sha384::ZERO_400D),
+            DigestData::SHA512(fixtures::sha512::ZERO_400D),
+            DigestData::SHA512_224(fixtures::sha512_224::ZERO_400D),
+            DigestData::SHA512_256(fixtures::sha512_256::ZERO_400D),
+            DigestData::SHA3_224(fixtures::sha3_224::ZERO_400D),
+            DigestData::SHA3_256(fixtures::sha3_256::ZERO_400D),
+            DigestData::SHA3_384(fixtures::sha3_384::ZERO_400D),
+            DigestData::SHA3_512(fixtures::sha3_512::ZERO_400D),
+            DigestData::KECCAK256(fixtures::keccak256::ZERO_400D),
+            DigestData::BLAKE2B(fixtures::blake2b::ZERO_400D),
+            DigestData::BLAKE2S(fixtures::blake2s::ZERO_400D),
+            DigestData::STREEBOG256(fixtures::streebog256::ZERO_400D),
+            DigestData::STREEBOG512(fixtures::streebog512::ZERO_400D),
+            DigestData::TIGER(fixtures::tiger::ZERO_400D),
+            DigestData::TIGER2(fixtures::tiger2::ZERO_400D),
+            DigestData::RMD256(fixtures::rmd256::ZERO_400D),
+            DigestData::RMD320(fixtures::rmd320::ZERO_400D),
+            DigestData::RMD160(fixtures::rmd160::ZERO_400D),
+            DigestData::RAPIDHASH(fixtures::rapidhash::ZERO_400D),
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::ZERO_400D),
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::ZERO_400D),
+        ];
+        for (empty, zero) in empty.iter().zip(&zero) {
+            assert_ne!(empty, zero);
+        }
+    }
+
+    #[test]
+    fn digest_data_as_bytes() {
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(digest.as_bytes(), fixtures::md5::EMPTY);
+        let digest = DigestData::RMD320(fixtures::rmd320::RANDOM_11171);
+        assert_eq!(digest.as_bytes(), fixtures::rmd320::RANDOM_11171);
+    }
+
+    #[test]
+    fn digest_data_name_len() {
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(digest.name(), "MD5");
+        assert_eq!(digest.len(), 16);
+        let digest = DigestData::SHA512_256(fixtures::sha512_256::EMPTY);
+        assert_eq!(digest.name(), "SHA512/256");
+        assert_eq!(digest.len(), 32);
+        let digest = DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::EMPTY);
+        assert_eq!(digest.name(), "CRC16/XMODEM");
+        assert_eq!(digest.len(), 2);
+    }
+
+    #[test]
+    fn generator_shared() {
+        let generator: Arc<dyn Generator> = Arc::from(md5());
+        let appenders: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = Arc::clone(&generator);
+                std::thread::spawn(move || {
+                    generator.append(Arc::from([0; 0x1000]));
+                })
+            })
+            .collect();
+        for appender in appenders {
+            appender.join().unwrap();
+        }
+        generator.append(Arc::from([0; 0x0d]));
+        assert_eq!(
+            generator.result(),
             DigestData::MD5(fixtures::md5::ZERO_400D)
         );
-        assert_ne!(
-            DigestData::SHA256(fixtures::sha256::EMPTY),
-            DigestData::SHA256(fixtures::sha256::ZERO_400D)
+    }
+
+    #[test]
+    fn digest_data_from_bytes() {
+        for kind in DigestKind::all() {
+            let bytes = vec![0xa5; kind.output_len()];
+            let digest = DigestData::from_bytes(kind, &bytes).unwrap();
+            assert_eq!(digest.kind(), kind);
+            assert_eq!(digest.as_bytes(), bytes);
+            let converted = DigestData::try_from((kind, &bytes[..]));
+            assert_eq!(converted.unwrap(), digest);
+        }
+        let digest = DigestData::from_bytes(DigestKind::SHA256, &[0; 31]);
+        assert!(matches!(
+            digest,
+            Err(Error::InvalidDigest {
+                kind: DigestKind::SHA256,
+                len: 31
+            })
+        ));
+        let hmac = DigestData::HMAC {
+            kind: DigestKind::MD5,
+            bytes: [0; HMAC_LENGTH],
+        };
+        assert_eq!(hmac.kind(), DigestKind::MD5);
+    }
+
+    #[test]
+    fn digest_data_hex() {
+        let digest = DigestData::CRC32([0x0a, 0x1b, 0xc2, 0xfd]);
+        assert_eq!(format!("{digest:x}"), "0a1bc2fd");
+        assert_eq!(format!("{digest:X}"), "0A1BC2FD");
+        assert_eq!(format!("{digest:#x}"), "0x0a1bc2fd");
+        assert_eq!(format!("{digest:#X}"), "0x0A1BC2FD");
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(format!("{digest:x}"), digest.to_string());
+        assert_eq!(format!("{digest:X}"), digest.encode(Encoding::UpperHex));
+    }
+
+    #[test]
+    fn digest_data_to_base32() {
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(
+            digest.to_base32(true),
+            "2QOYZWMPACZAJ2MABGMOZ6CCPY======"
         );
-        assert_ne!(
-            DigestData::SHA512(fixtures::sha512::EMPTY),
-            DigestData::SHA512(fixtures::sha512::ZERO_400D)
+        assert_eq!(digest.to_base32(false), "2QOYZWMPACZAJ2MABGMOZ6CCPY");
+        let digest = DigestData::CRC32(fixtures::crc32::EMPTY);
+        assert_eq!(digest.to_base32(true), "AAAAAAA=");
+    }
+
+    #[test]
+    fn digest_data_encode() {
+        let digest = DigestData::MD5(fixtures::md5::EMPTY);
+        assert_eq!(digest.encode(Encoding::Hex), digest.to_string());
+        assert_eq!(
+            digest.encode(Encoding::UpperHex),
+            "D41D8CD98F00B204E9800998ECF8427E"
         );
-        assert_ne!(
-            DigestData::RMD160(fixtures::rmd160::EMPTY),
-            DigestData::RMD160(fixtures::rmd160::ZERO_400D)
+        assert_eq!(
+            digest.encode(Encoding::Base32 { padding: true }),
+            digest.to_base32(true)
+        );
+        assert_eq!(
+            digest.encode(Encoding::Base58Btc),
+            "TCByYo9r1su7nMQP3WHDFK"
+        );
+        assert_eq!(
+            digest.encode(Encoding::Base64 { padding: true }),
+            "1B2M2Y8AsgTpgAmY7PhCfg=="
+        );
+        assert_eq!(
+            digest.encode(Encoding::Base64Url { padding: false }),
+            "1B2M2Y8AsgTpgAmY7PhCfg"
+        );
+    }
+
+    #[test]
+    fn crc16_ccitt_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY)
+            ),
+            "ffff"
+        );
+    }
+
+    #[test]
+    fn crc16_modbus_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::CRC16_MODBUS(fixtures::crc16_modbus::EMPTY)
+            ),
+            "ffff"
+        );
+    }
+
+    #[test]
+    fn crc16_xmodem_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::EMPTY)
+            ),
+            "0000"
         );
     }
 
@@ -190,6 +1262,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn crc64_format() {
+        assert_eq!(
+            format!("{}", DigestData::CRC64(fixtures::crc64::EMPTY)),
+            "0000000000000000"
+        );
+    }
+
+    #[test]
+    fn xxh32_format() {
+        assert_eq!(
+            format!("{}", DigestData::XXH32(fixtures::xxh32::EMPTY)),
+            "02cc5d05"
+        );
+    }
+
+    #[test]
+    fn xxh64_format() {
+        assert_eq!(
+            format!("{}", DigestData::XXH64(fixtures::xxh64::EMPTY)),
+            "ef46db3751d8e999"
+        );
+    }
+
+    #[test]
+    fn rapidhash_format() {
+        assert_eq!(
+            format!("{}", DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY)),
+            "0338dc4be2cecdae"
+        );
+    }
+
+    #[test]
+    fn md2_format() {
+        assert_eq!(
+            format!("{}", DigestData::MD2(fixtures::md2::EMPTY)),
+            "8350e5a3e24c153df2275c9f80692773"
+        );
+    }
+
+    #[test]
+    fn md4_format() {
+        assert_eq!(
+            format!("{}", DigestData::MD4(fixtures::md4::EMPTY)),
+            "31d6cfe0d16ae931b73c59d7e0c089c0"
+        );
+    }
+
     #[test]
     fn md5_format() {
         assert_eq!(
@@ -198,6 +1318,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sha224_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA224(fixtures::sha224::EMPTY)),
+            "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"
+        );
+    }
+
     #[test]
     fn sha256_format() {
         assert_eq!(
@@ -209,6 +1337,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sha384_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA384(fixtures::sha384::EMPTY)),
+            concat!(
+                "38b060a751ac96384cd9327eb1b1e36a",
+                "21fdb71114be07434c0cc7bf63f6e1da",
+                "274edebfe76f65fbd51ad2f14898b95b"
+            )
+        );
+    }
+
     #[test]
     fn sha512_format() {
         assert_eq!(
@@ -222,6 +1362,179 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sha512_224_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::SHA512_224(fixtures::sha512_224::EMPTY)
+            ),
+            "6ed0dd02806fa89e25de060c19d3ac86cabb87d6a0ddd05c333b84f4"
+        );
+    }
+
+    #[test]
+    fn sha512_256_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::SHA512_256(fixtures::sha512_256::EMPTY)
+            ),
+            concat!(
+                "c672b8d1ef56ed28ab87c3622c511406",
+                "9bdd3ad7b8f9737498d0c01ecef0967a"
+            )
+        );
+    }
+
+    #[test]
+    fn sha3_224_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA3_224(fixtures::sha3_224::EMPTY)),
+            "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"
+        );
+    }
+
+    #[test]
+    fn sha3_256_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA3_256(fixtures::sha3_256::EMPTY)),
+            concat!(
+                "a7ffc6f8bf1ed76651c14756a061d662",
+                "f580ff4de43b49fa82d80a4b80f8434a"
+            )
+        );
+    }
+
+    #[test]
+    fn sha3_384_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA3_384(fixtures::sha3_384::EMPTY)),
+            concat!(
+                "0c63a75b845e4f7d01107d852e4c2485",
+                "c51a50aaaa94fc61995e71bbee983a2a",
+                "c3713831264adb47fb6bd1e058d5f004"
+            )
+        );
+    }
+
+    #[test]
+    fn sha3_512_format() {
+        assert_eq!(
+            format!("{}", DigestData::SHA3_512(fixtures::sha3_512::EMPTY)),
+            concat!(
+                "a69f73cca23a9ac5c8b567dc185a756e",
+                "97c982164fe25859e0d1dcc1475c80a6",
+                "15b2123af1f5f94c11e3e9402c3ac558",
+                "f500199d95b6d3e301758586281dcd26"
+            )
+        );
+    }
+
+    #[test]
+    fn keccak256_format() {
+        assert_eq!(
+            format!("{}", DigestData::KECCAK256(fixtures::keccak256::EMPTY)),
+            concat!(
+                "c5d2460186f7233c927e7db2dcc703c0",
+                "e500b653ca82273b7bfad8045d85a470"
+            )
+        );
+    }
+
+    #[test]
+    fn blake2b_format() {
+        assert_eq!(
+            format!("{}", DigestData::BLAKE2B(fixtures::blake2b::EMPTY)),
+            concat!(
+                "786a02f742015903c6c6fd852552d272",
+                "912f4740e15847618a86e217f71f5419",
+                "d25e1031afee585313896444934eb04b",
+                "903a685b1448b755d56f701afe9be2ce"
+            )
+        );
+    }
+
+    #[test]
+    fn blake2s_format() {
+        assert_eq!(
+            format!("{}", DigestData::BLAKE2S(fixtures::blake2s::EMPTY)),
+            concat!(
+                "69217a3079908094e11121d042354a7c",
+                "1f55b6482ca1a51e1b250dfd1ed0eef9"
+            )
+        );
+    }
+
+    #[test]
+    fn streebog256_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::STREEBOG256(fixtures::streebog256::EMPTY)
+            ),
+            concat!(
+                "3f539a213e97c802cc229d474c6aa32a",
+                "825a360b2a933a949fd925208d9ce1bb"
+            )
+        );
+    }
+
+    #[test]
+    fn streebog512_format() {
+        assert_eq!(
+            format!(
+                "{}",
+                DigestData::STREEBOG512(fixtures::streebog512::EMPTY)
+            ),
+            concat!(
+                "8e945da209aa869f0455928529bcae46",
+                "79e9873ab707b55315f56ceb98bef0a7",
+                "362f715528356ee83cda5f2aac4c6ad2",
+                "ba3a715c1bcd81cb8e9f90bf4c1c1a8a"
+            )
+        );
+    }
+
+    #[test]
+    fn tiger_format() {
+        assert_eq!(
+            format!("{}", DigestData::TIGER(fixtures::tiger::EMPTY)),
+            "3293ac630c13f0245f92bbb1766e16167a4e58492dde73f3"
+        );
+    }
+
+    #[test]
+    fn tiger2_format() {
+        assert_eq!(
+            format!("{}", DigestData::TIGER2(fixtures::tiger2::EMPTY)),
+            "4441be75f6018773c206c22745374b924aa8313fef919f41"
+        );
+    }
+
+    #[test]
+    fn rmd256_format() {
+        assert_eq!(
+            format!("{}", DigestData::RMD256(fixtures::rmd256::EMPTY)),
+            concat!(
+                "02ba4c4e5f8ecd1877fc52d64d30e37a",
+                "2d9774fb1e5d026380ae0168e3c5522d"
+            )
+        );
+    }
+
+    #[test]
+    fn rmd320_format() {
+        assert_eq!(
+            format!("{}", DigestData::RMD320(fixtures::rmd320::EMPTY)),
+            concat!(
+                "22d65d5661536cdc75c1fdf5c6de7b41",
+                "b9f27325ebc61e8557177d705a0ec880",
+                "151c3a32a00899b8"
+            )
+        );
+    }
+
     #[test]
     fn rmd160_format() {
         assert_eq!(
@@ -230,6 +1543,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn crc16_ccitt_generator() {
+        let crc16_ccitt = crc16_ccitt();
+        let digest = crc16_ccitt.result();
+        assert_eq!(
+            digest,
+            DigestData::CRC16_CCITT(fixtures::crc16_ccitt::EMPTY)
+        );
+    }
+
+    #[test]
+    fn crc16_modbus_generator() {
+        let crc16_modbus = crc16_modbus();
+        let digest = crc16_modbus.result();
+        assert_eq!(
+            digest,
+            DigestData::CRC16_MODBUS(fixtures::crc16_modbus::EMPTY)
+        );
+    }
+
+    #[test]
+    fn crc16_xmodem_generator() {
+        let crc16_xmodem = crc16_xmodem();
+        let digest = crc16_xmodem.result();
+        assert_eq!(
+            digest,
+            DigestData::CRC16_XMODEM(fixtures::crc16_xmodem::EMPTY)
+        );
+    }
+
     #[test]
     fn crc32_generator() {
         let crc32 = crc32();
@@ -237,6 +1580,50 @@ mod tests {
         assert_eq!(digest, DigestData::CRC32(fixtures::crc32::EMPTY));
     }
 
+    #[test]
+    fn crc64_generator() {
+        let crc64 = crc64();
+        let digest = crc64.result();
+        assert_eq!(digest, DigestData::CRC64(fixtures::crc64::EMPTY));
+    }
+
+    #[test]
+    fn xxh32_generator() {
+        let xxh32 = xxh32();
+        let digest = xxh32.result();
+        assert_eq!(digest, DigestData::XXH32(fixtures::xxh32::EMPTY));
+    }
+
+    #[test]
+    fn xxh64_generator() {
+        let xxh64 = xxh64();
+        let digest = xxh64.result();
+        assert_eq!(digest, DigestData::XXH64(fixtures::xxh64::EMPTY));
+    }
+
+    #[test]
+    fn rapidhash_generator() {
+        let rapidhash = rapidhash();
+        let digest = rapidhash.result();
+        assert_eq!(digest, DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY));
+    }
+
+    #[test]
+    fn md2_generator() {
+        if let Some(md2) = md2() {
+            let digest = md2.result();
+            assert_eq!(digest, DigestData::MD2(fixtures::md2::EMPTY));
+        }
+    }
+
+    #[test]
+    fn md4_generator() {
+        if let Some(md4) = md4() {
+            let digest = md4.result();
+            assert_eq!(digest, DigestData::MD4(fixtures::md4::EMPTY));
+        }
+    }
+
     #[test]
     fn md5_generator() {
         let md5 = md5();
@@ -244,6 +1631,13 @@ mod tests {
         assert_eq!(digest, DigestData::MD5(fixtures::md5::EMPTY));
     }
 
+    #[test]
+    fn sha224_generator() {
+        let sha224 = sha224();
+        let digest = sha224.result();
+        assert_eq!(digest, DigestData::SHA224(fixtures::sha224::EMPTY));
+    }
+
     #[test]
     fn sha256_generator() {
         let sha256 = sha256();
@@ -251,6 +1645,13 @@ mod tests {
         assert_eq!(digest, DigestData::SHA256(fixtures::sha256::EMPTY));
     }
 
+    #[test]
+    fn sha384_generator() {
+        let sha384 = sha384();
+        let digest = sha384.result();
+        assert_eq!(digest, DigestData::SHA384(fixtures::sha384::EMPTY));
+    }
+
     #[test]
     fn sha512_generator() {
         let sha512 = sha512();
@@ -258,6 +1659,168 @@ mod tests {
         assert_eq!(digest, DigestData::SHA512(fixtures::sha512::EMPTY));
     }
 
+    #[test]
+    fn sha512_224_generator() {
+        let sha512_224 = sha512_224();
+        let digest = sha512_224.result();
+        assert_eq!(
+            digest,
+            DigestData::SHA512_224(fixtures::sha512_224::EMPTY)
+        );
+    }
+
+    #[test]
+    fn sha512_256_generator() {
+        let sha512_256 = sha512_256();
+        let digest = sha512_256.result();
+        assert_eq!(
+            digest,
+            DigestData::SHA512_256(fixtures::sha512_256::EMPTY)
+        );
+    }
+
+    #[test]
+    fn sha3_224_generator() {
+        let sha3_224 = sha3_224();
+        let digest = sha3_224.result();
+        assert_eq!(digest, DigestData::SHA3_224(fixtures::sha3_224::EMPTY));
+    }
+
+    #[test]
+    fn sha3_256_generator() {
+        let sha3_256 = sha3_256();
+        let digest = sha3_256.result();
+        assert_eq!(digest, DigestData::SHA3_256(fixtures::sha3_256::EMPTY));
+    }
+
+    #[test]
+    fn sha3_384_generator() {
+        let sha3_384 = sha3_384();
+        let digest = sha3_384.result();
+        assert_eq!(digest, DigestData::SHA3_384(fixtures::sha3_384::EMPTY));
+    }
+
+    #[test]
+    fn sha3_512_generator() {
+        let sha3_512 = sha3_512();
+        let digest = sha3_512.result();
+        assert_eq!(digest, DigestData::SHA3_512(fixtures::sha3_512::EMPTY));
+    }
+
+    #[test]
+    fn keccak256_generator() {
+        let keccak256 = keccak256();
+        let digest = keccak256.result();
+        assert_eq!(digest, DigestData::KECCAK256(fixtures::keccak256::EMPTY));
+    }
+
+    #[test]
+    fn blake2b_generator() {
+        let blake2b = blake2b();
+        let digest = blake2b.result();
+        assert_eq!(digest, DigestData::BLAKE2B(fixtures::blake2b::EMPTY));
+    }
+
+    #[test]
+    fn blake2b_with_generator() {
+        let blake2b = blake2b_with(&Blake2Params::default()).unwrap();
+        let digest = blake2b.result();
+        assert_eq!(digest, DigestData::BLAKE2B(fixtures::blake2b::EMPTY));
+        let params = Blake2Params {
+            salt: vec![0; 17],
+            ..Blake2Params::default()
+        };
+        assert!(blake2b_with(&params).is_none());
+    }
+
+    #[test]
+    fn blake2s_generator() {
+        let blake2s = blake2s();
+        let digest = blake2s.result();
+        assert_eq!(digest, DigestData::BLAKE2S(fixtures::blake2s::EMPTY));
+    }
+
+    #[test]
+    fn blake2s_with_generator() {
+        let blake2s = blake2s_with(&Blake2Params::default()).unwrap();
+        let digest = blake2s.result();
+        assert_eq!(digest, DigestData::BLAKE2S(fixtures::blake2s::EMPTY));
+        let params = Blake2Params {
+            salt: vec![0; 9],
+            ..Blake2Params::default()
+        };
+        assert!(blake2s_with(&params).is_none());
+    }
+
+    #[test]
+    fn blake2_params_wipe() {
+        let mut params = Blake2Params {
+            key: b"key".to_vec(),
+            salt: b"salt".to_vec(),
+            ..Blake2Params::default()
+        };
+        params.wipe();
+        assert_eq!(
+            params,
+            Blake2Params {
+                salt: b"salt".to_vec(),
+                ..Blake2Params::default()
+            }
+        );
+    }
+
+    #[test]
+    fn streebog256_generator() {
+        if let Some(streebog256) = streebog256() {
+            let digest = streebog256.result();
+            assert_eq!(
+                digest,
+                DigestData::STREEBOG256(fixtures::streebog256::EMPTY)
+            );
+        }
+    }
+
+    #[test]
+    fn streebog512_generator() {
+        if let Some(streebog512) = streebog512() {
+            let digest = streebog512.result();
+            assert_eq!(
+                digest,
+                DigestData::STREEBOG512(fixtures::streebog512::EMPTY)
+            );
+        }
+    }
+
+    #[test]
+    fn tiger_generator() {
+        if let Some(tiger) = tiger() {
+            let digest = tiger.result();
+            assert_eq!(digest, DigestData::TIGER(fixtures::tiger::EMPTY));
+        }
+    }
+
+    #[test]
+    fn tiger2_generator() {
+        if let Some(tiger2) = tiger2() {
+            let digest = tiger2.result();
+            assert_eq!(digest, DigestData::TIGER2(fixtures::tiger2::EMPTY));
+        }
+    }
+
+    #[test]
+    fn rmd256_generator() {
+        let rmd256 = rmd256();
+        let digest = rmd256.result();
+        assert_eq!(digest, DigestData::RMD256(fixtures::rmd256::EMPTY));
+    }
+
+    #[test]
+    fn rmd320_generator() {
+        let rmd320 = rmd320();
+        let digest = rmd320.result();
+        assert_eq!(digest, DigestData::RMD320(fixtures::rmd320::EMPTY));
+    }
+
     #[test]
     fn rmd160_generator() {
         let rmd160 = rmd160();
@@ -0,0 +1,691 @@
+use std::borrow::Cow;
+use std::error;
+use std::fmt::{self, Write as _};
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::{decode_hex, DigestKind, Encoding};
+
+/// The digests listed in a checksum manifest, as read by `parse()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// The entries, in the order they are listed.
+    pub entries: Vec<Entry>,
+}
+
+/// An entry in a manifest, giving the expected digest of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The path of the file, as it is listed.
+    pub path: PathBuf,
+    /// The kind of digest, if the entry names it. SFV entries are always
+    /// CRC32, while `sha256sum` style entries leave it to the reader to
+    /// pick a kind based on the length of the digest.
+    pub kind: Option<DigestKind>,
+    /// The expected digest.
+    pub digest: Vec<u8>,
+    /// The size of the file, from a `size=` column of a BSD style entry.
+    pub size: Option<u64>,
+    /// The modification time of the file, from an `mtime=` column of a BSD
+    /// style entry.
+    pub mtime: Option<String>,
+}
+
+impl Entry {
+    /// Parse a single `line` of a manifest, in any of the forms read by
+    /// `parse()`. The line number of any error is 1.
+    ///
+    /// ## Errors
+    ///
+    /// A `ParseError` is returned if the line isn't a valid entry.
+    pub fn parse(line: &str) -> Result<Self, ParseError> {
+        Self::parse_with_format(line).map(|(entry, _)| entry)
+    }
+
+    /// Parse a single `line` of a manifest as for `Entry::parse()`, along
+    /// with the `Format` the line is in, so that it can be written again
+    /// in the same form.
+    ///
+    /// ## Errors
+    ///
+    /// A `ParseError` is returned if the line isn't a valid entry.
+    pub fn parse_with_format(
+        line: &str,
+    ) -> Result<(Self, Format), ParseError> {
+        parse_entry(line).map_err(|(span, reason)| ParseError {
+            line: 1,
+            span,
+            reason,
+        })
+    }
+}
+
+/// An error in a manifest, which locates the part of the line at fault.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line number, counting from 1.
+    pub line: usize,
+    /// The range of bytes within the line which are in error.
+    pub span: Range<usize>,
+    /// A description of the error.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line,
+            self.span.start + 1,
+            self.reason
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parse the `text` of a checksum manifest. Each line is in one of these
+/// forms:
+///
+/// - a `sha256sum` style line, `HEXDIGEST  filename`, or `HEXDIGEST
+///   *filename` for files read in binary mode;
+/// - a BSD style line, `ALGORITHM (filename) = HEXDIGEST`, as output by
+///   `checksum` or `sha256sum --tag`, which may be followed by `size=` and
+///   `mtime=` columns;
+/// - an SFV line, `filename HEXDIGEST`, where the digest is a CRC32.
+///
+/// If a line starts with a `\` then the filename has any backslashes and
+/// line breaks escaped, as `\\`, `\n`, and `\r`. Blank lines are skipped,
+/// along with comments starting with `#` or `;`.
+///
+/// ## Errors
+///
+/// A `ParseError` is returned for the first line which isn't in any of
+/// these forms.
+pub fn parse(text: &str) -> Result<Manifest, ParseError> {
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        let (entry, _) =
+            parse_entry(line).map_err(|(span, reason)| ParseError {
+                line: number + 1,
+                span,
+                reason,
+            })?;
+        entries.push(entry);
+    }
+    Ok(Manifest { entries })
+}
+
+/// The forms of manifest line which may be written by a `Writer`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The `sha256sum` style `HEXDIGEST  filename` format.
+    GNU,
+    /// The BSD style `ALGORITHM (filename) = HEXDIGEST` format, as output
+    /// by `checksum`.
+    BSD,
+    /// The Simple File Verification (SFV) `filename CRC32` format, as
+    /// output by `checksum --sfv`.
+    SFV,
+}
+
+impl Format {
+    /// The line written at the start of a manifest, if any. SFV manifests
+    /// start with a comment naming the program which generated them.
+    #[must_use]
+    pub fn header(self) -> Option<String> {
+        match self {
+            Format::GNU | Format::BSD => None,
+            Format::SFV => Some(format!(
+                "; Generated by checksum {}",
+                env!("CARGO_PKG_VERSION")
+            )),
+        }
+    }
+
+    /// Format the `entry` as a line of a manifest, without a line ending.
+    /// The digest is written in the given `encoding`, although only
+    /// hexadecimal digests can be read by `parse()`, and SFV digests are
+    /// always upper case hexadecimal. A filename containing a backslash or
+    /// line break is escaped, with the line starting with a `\`. The size
+    /// and modification time of the entry are only written to BSD style
+    /// lines.
+    ///
+    /// ## Errors
+    ///
+    /// An error of kind `InvalidInput` is returned if the entry can't be
+    /// written in this format: if the path is empty or isn't valid UTF-8,
+    /// if a BSD style entry has no kind, or if an SFV entry isn't a CRC32
+    /// or has a filename which can't be written without escaping.
+    pub fn line(
+        self,
+        entry: &Entry,
+        encoding: Encoding,
+    ) -> io::Result<String> {
+        let invalid =
+            |reason| io::Error::new(io::ErrorKind::InvalidInput, reason);
+        let path = entry
+            .path
+            .to_str()
+            .ok_or_else(|| invalid("file name isn't valid UTF-8"))?;
+        if path.is_empty() {
+            return Err(invalid("missing file name"));
+        }
+        let (escape, path) = escape_path(path);
+        match self {
+            Format::GNU => {
+                let digest = encoding.encode(&entry.digest);
+                Ok(format!("{escape}{digest}  {path}"))
+            }
+            Format::BSD => {
+                let kind = entry
+                    .kind
+                    .ok_or_else(|| invalid("missing digest kind"))?;
+                let name = kind.name();
+                let digest = encoding.encode(&entry.digest);
+                let mut line = format!("{escape}{name} ({path}) = {digest}");
+                if let Some(size) = entry.size {
+                    write!(line, " size={size}").unwrap();
+                }
+                if let Some(mtime) = &entry.mtime {
+                    write!(line, " mtime={mtime}").unwrap();
+                }
+                Ok(line)
+            }
+            Format::SFV => {
+                if entry.kind.is_some_and(|kind| kind != DigestKind::CRC32)
+                    || entry.digest.len() != DigestKind::CRC32.output_len()
+                {
+                    return Err(invalid("SFV entries must be CRC32"));
+                }
+                if !escape.is_empty() || path.starts_with(['#', ';']) {
+                    return Err(invalid("file name can't be written as SFV"));
+                }
+                let digest = Encoding::UpperHex.encode(&entry.digest);
+                Ok(format!("{path} {digest}"))
+            }
+        }
+    }
+}
+
+/// Writes the entries of a manifest to an inner writer in one of the
+/// `Format`s, producing the same lines as `checksum` itself, so that the
+/// manifest can be read by `parse()` or checked with `checksum --check`.
+///
+/// The header of the format, if any, is always written first, and then
+/// the entries in the order they are given. Each line is written with a
+/// single write, so an error leaves no partial entry behind other than
+/// what the inner writer itself has written.
+pub struct Writer<W: io::Write> {
+    inner: W,
+    format: Format,
+    encoding: Encoding,
+    /// Whether the header has been written.
+    started: bool,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Create a new `Writer` which writes lines in the given `format` to
+    /// `inner`, with the digests in the given `encoding`.
+    pub fn new(inner: W, format: Format, encoding: Encoding) -> Self {
+        Self {
+            inner,
+            format,
+            encoding,
+            started: false,
+        }
+    }
+
+    /// Write the `entry`, after the header if it hasn't been written yet.
+    ///
+    /// ## Errors
+    ///
+    /// Any error writing to the inner writer is returned, along with the
+    /// errors returned by `Format::line()`, in which case nothing is
+    /// written for the entry.
+    pub fn write_entry(&mut self, entry: &Entry) -> io::Result<()> {
+        let mut line = self.format.line(entry, self.encoding)?;
+        line.push('\n');
+        self.start()?;
+        self.inner.write_all(line.as_bytes())
+    }
+
+    /// Write all the entries of the `manifest`, in order.
+    ///
+    /// ## Errors
+    ///
+    /// The first error returned by `write_entry()` is returned, and none
+    /// of the following entries are written.
+    pub fn write_manifest(&mut self, manifest: &Manifest) -> io::Result<()> {
+        self.start()?;
+        for entry in &manifest.entries {
+            self.write_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Write the header if no entries were written, and flush the inner
+    /// writer, returning it.
+    ///
+    /// ## Errors
+    ///
+    /// Any error writing to or flushing the inner writer is returned.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.start()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Write the header, if it hasn't been written yet.
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            if let Some(header) = self.format.header() {
+                self.inner.write_all(format!("{header}\n").as_bytes())?;
+            }
+            self.started = true;
+        }
+        Ok(())
+    }
+}
+
+/// Escape a `path` for a manifest line, returning the prefix for the line
+/// along with the escaped path. If the path contains no backslashes or
+/// line breaks then it is returned as it is, with no prefix.
+fn escape_path(path: &str) -> (&'static str, Cow<'_, str>) {
+    if !path.contains(['\\', '\n', '\r']) {
+        return ("", Cow::Borrowed(path));
+    }
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    ("\\", Cow::Owned(escaped))
+}
+
+/// The span and description of an error within a line.
+type Failure = (Range<usize>, &'static str);
+
+/// Parse a `line` of a manifest, trying each of the forms in turn, and
+/// returning the form it is in along with the entry.
+fn parse_entry(line: &str) -> Result<(Entry, Format), Failure> {
+    let (escaped, rest) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    if let Some((name, path, digest)) = split_bsd(rest) {
+        let Some(kind) = DigestKind::from_name(name) else {
+            return Err((span(line, name), "unknown digest algorithm"));
+        };
+        let (hex, columns) = digest.split_once(' ').unwrap_or((digest, ""));
+        let digest = parse_digest(line, hex)?;
+        if digest.len() != kind.output_len() {
+            return Err((
+                span(line, hex),
+                "digest is the wrong length for the algorithm",
+            ));
+        }
+        let (size, mtime) = parse_columns(line, columns)?;
+        let entry = Entry {
+            path: parse_path(line, path, escaped)?,
+            kind: Some(kind),
+            digest,
+            size,
+            mtime,
+        };
+        return Ok((entry, Format::BSD));
+    }
+    let sfv = split_sfv(rest);
+    if let Some((hex, path)) = split_gnu(rest) {
+        // A line such as `name  name 01234567` isn't a valid `sha256sum`
+        // style line, so is read as an SFV line instead.
+        if hex.bytes().all(|byte| byte.is_ascii_hexdigit()) || sfv.is_none() {
+            let entry = Entry {
+                digest: parse_digest(line, hex)?,
+                path: parse_path(line, path, escaped)?,
+                kind: None,
+                size: None,
+                mtime: None,
+            };
+            return Ok((entry, Format::GNU));
+        }
+    }
+    if let Some((path, hex)) = sfv {
+        let entry = Entry {
+            digest: parse_digest(line, hex)?,
+            path: parse_path(line, path, escaped)?,
+            kind: Some(DigestKind::CRC32),
+            size: None,
+            mtime: None,
+        };
+        return Ok((entry, Format::SFV));
+    }
+    Err((0..line.len(), "unrecognized line"))
+}
+
+/// Split a BSD style `line` into the name of the algorithm, the filename,
+/// and the digest along with any columns following it.
+fn split_bsd(line: &str) -> Option<(&str, &str, &str)> {
+    let (name, rest) = line.split_once(" (")?;
+    if name.is_empty() || name.contains(' ') {
+        return None;
+    }
+    let (path, digest) = rest.rsplit_once(") = ")?;
+    Some((name, path, digest))
+}
+
+/// Split a `sha256sum` style `line` into the digest and the filename.
+fn split_gnu(line: &str) -> Option<(&str, &str)> {
+    let (hex, path) = line.split_once(' ')?;
+    let path = path.strip_prefix([' ', '*'])?;
+    (!hex.is_empty()).then_some((hex, path))
+}
+
+/// Split an SFV `line` into the filename and the digest, which must be
+/// eight hexadecimal digits.
+fn split_sfv(line: &str) -> Option<(&str, &str)> {
+    let (path, hex) = line.rsplit_once(' ')?;
+    let is_crc32 =
+        hex.len() == 8 && hex.bytes().all(|byte| byte.is_ascii_hexdigit());
+    (is_crc32 && !path.is_empty()).then_some((path, hex))
+}
+
+/// Parse the `hex` digest found on the `line`.
+fn parse_digest(line: &str, hex: &str) -> Result<Vec<u8>, Failure> {
+    if hex.is_empty() {
+        return Err((span(line, hex), "missing digest"));
+    }
+    decode_hex(hex)
+        .ok_or_else(|| (span(line, hex), "invalid hexadecimal digest"))
+}
+
+/// Parse the `size=` and `mtime=` columns following a BSD style digest on
+/// the `line`.
+fn parse_columns(
+    line: &str,
+    columns: &str,
+) -> Result<(Option<u64>, Option<String>), Failure> {
+    let mut size = None;
+    let mut mtime = None;
+    for column in columns.split(' ').filter(|column| !column.is_empty()) {
+        if let Some(value) = column.strip_prefix("size=") {
+            let value = value
+                .parse()
+                .map_err(|_| (span(line, column), "invalid size"))?;
+            size = Some(value);
+        } else if let Some(value) = column.strip_prefix("mtime=") {
+            mtime = Some(value.to_string());
+        } else {
+            return Err((span(line, column), "unexpected column"));
+        }
+    }
+    Ok((size, mtime))
+}
+
+/// Parse the filename found on the `line`, undoing the escaping of it if
+/// the line is `escaped`. In an escaped filename `\\` is a backslash, `\n`
+/// is a newline, and `\r` is a carriage return, and any other escape
+/// sequence is an error.
+fn parse_path(
+    line: &str,
+    path: &str,
+    escaped: bool,
+) -> Result<PathBuf, Failure> {
+    if path.is_empty() {
+        return Err((span(line, path), "missing file name"));
+    }
+    if !escaped {
+        return Ok(PathBuf::from(path));
+    }
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some((_, '\\')) => unescaped.push('\\'),
+            Some((_, 'n')) => unescaped.push('\n'),
+            Some((_, 'r')) => unescaped.push('\r'),
+            next => {
+                let end = next.map_or(path.len(), |(i, c)| i + c.len_utf8());
+                let start = span(line, path).start;
+                return Err((start + index..start + end, "invalid escape"));
+            }
+        }
+    }
+    Ok(PathBuf::from(unescaped))
+}
+
+/// The range of bytes of the `line` covered by `part`, which must be a
+/// slice of it.
+fn span(line: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - line.as_ptr() as usize;
+    start..start + part.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn parse_gnu_entry() {
+        let line = "d41d8cd98f00b204e9800998ecf8427e  foo bar";
+        assert_eq!(
+            Entry::parse(line),
+            Ok(Entry {
+                path: PathBuf::from("foo bar"),
+                kind: None,
+                digest: fixtures::md5::EMPTY.to_vec(),
+                size: None,
+                mtime: None,
+            })
+        );
+        let line = "D41D8CD98F00B204E9800998ECF8427E *foo";
+        assert_eq!(Entry::parse(line).unwrap().digest, fixtures::md5::EMPTY);
+        let line = "\\d41d8cd98f00b204e9800998ecf8427e  a\\\\b\\nc";
+        assert_eq!(
+            Entry::parse(line).unwrap().path,
+            PathBuf::from("a\\b\nc")
+        );
+    }
+
+    #[test]
+    fn parse_bsd_entry() {
+        let line = "MD5 (foo (1)) = d41d8cd98f00b204e9800998ecf8427e";
+        let entry = Entry::parse(line).unwrap();
+        assert_eq!(entry.kind, Some(DigestKind::MD5));
+        assert_eq!(entry.path, PathBuf::from("foo (1)"));
+        let line = "CRC32 (foo) = 00000000 size=0 mtime=2023-11-14T22:13:20Z";
+        assert_eq!(
+            Entry::parse(line),
+            Ok(Entry {
+                path: PathBuf::from("foo"),
+                kind: Some(DigestKind::CRC32),
+                digest: fixtures::crc32::EMPTY.to_vec(),
+                size: Some(0),
+                mtime: Some(String::from("2023-11-14T22:13:20Z")),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_sfv_entry() {
+        let line = "foo bar.txt 0A1B2C3D";
+        assert_eq!(
+            Entry::parse(line),
+            Ok(Entry {
+                path: PathBuf::from("foo bar.txt"),
+                kind: Some(DigestKind::CRC32),
+                digest: vec![0x0a, 0x1b, 0x2c, 0x3d],
+                size: None,
+                mtime: None,
+            })
+        );
+        let line = "foo  bar 00000000";
+        assert_eq!(
+            Entry::parse(line).unwrap().path,
+            PathBuf::from("foo  bar")
+        );
+    }
+
+    #[test]
+    fn parse_entry_format() {
+        let format = |line| Entry::parse_with_format(line).unwrap().1;
+        assert_eq!(format("00000000  foo"), Format::GNU);
+        assert_eq!(format("CRC32 (foo) = 00000000"), Format::BSD);
+        assert_eq!(format("foo 00000000"), Format::SFV);
+    }
+
+    #[test]
+    fn parse_manifest() {
+        let text = "; generated by cksfv\r\n\
+                    \n\
+                    # comment\n\
+                    d41d8cd98f00b204e9800998ecf8427e  foo\n\
+                    CRC32 (bar) = 00000000\r\n\
+                    baz 00000000\n";
+        let manifest = parse(text).unwrap();
+        let paths: Vec<_> = manifest
+            .entries
+            .iter()
+            .map(|entry| entry.path.to_str().unwrap())
+            .collect();
+        assert_eq!(paths, ["foo", "bar", "baz"]);
+        assert_eq!(parse(""), Ok(Manifest::default()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        fn error(line: &str) -> (&str, &'static str) {
+            let error = Entry::parse(line).unwrap_err();
+            (&line[error.span], error.reason)
+        }
+        assert_eq!(
+            error("SHA1 (foo) = da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+            ("SHA1", "unknown digest algorithm")
+        );
+        assert_eq!(
+            error("MD5 (foo) = 00000000"),
+            ("00000000", "digest is the wrong length for the algorithm")
+        );
+        assert_eq!(
+            error("CRC32 (foo) = 00000000 size=x"),
+            ("size=x", "invalid size")
+        );
+        assert_eq!(
+            error("CRC32 (foo) = 00000000 extra"),
+            ("extra", "unexpected column")
+        );
+        assert_eq!(error("CRC32 (foo) = "), ("", "missing digest"));
+        assert_eq!(
+            error("d41d8cd98f00b204e9800998ecf8427x  foo"),
+            (
+                "d41d8cd98f00b204e9800998ecf8427x",
+                "invalid hexadecimal digest"
+            )
+        );
+        assert_eq!(error("00000000  "), ("", "missing file name"));
+        assert_eq!(error("\\00000000  a\\b"), ("\\b", "invalid escape"));
+        assert_eq!(error("\\00000000  a\\"), ("\\", "invalid escape"));
+        assert_eq!(
+            error("MD5 = 00000000x"),
+            ("MD5 = 00000000x", "unrecognized line")
+        );
+    }
+
+    #[test]
+    fn format_lines() {
+        let entry = Entry {
+            path: PathBuf::from("foo"),
+            kind: Some(DigestKind::CRC32),
+            digest: fixtures::crc32::RANDOM_11171.to_vec(),
+            size: Some(0x11171),
+            mtime: Some(String::from("2023-11-14T22:13:20Z")),
+        };
+        let line =
+            |format: Format| format.line(&entry, Encoding::Hex).unwrap();
+        assert_eq!(line(Format::GNU), "ff70a8ee  foo");
+        assert_eq!(
+            line(Format::BSD),
+            "CRC32 (foo) = ff70a8ee size=70001 mtime=2023-11-14T22:13:20Z"
+        );
+        assert_eq!(line(Format::SFV), "foo FF70A8EE");
+        assert_eq!(
+            Format::SFV.header(),
+            Some(format!(
+                "; Generated by checksum {}",
+                env!("CARGO_PKG_VERSION")
+            ))
+        );
+        assert_eq!(Format::BSD.header(), None);
+    }
+
+    #[test]
+    fn format_escaped_lines() {
+        let entry = Entry {
+            path: PathBuf::from("a\\b\nc\rd"),
+            kind: Some(DigestKind::MD5),
+            digest: fixtures::md5::EMPTY.to_vec(),
+            size: None,
+            mtime: None,
+        };
+        for format in [Format::GNU, Format::BSD] {
+            let line = format.line(&entry, Encoding::Hex).unwrap();
+            assert!(line.starts_with('\\'));
+            assert!(!line.contains(['\n', '\r']));
+            assert_eq!(Entry::parse(&line).unwrap().path, entry.path);
+        }
+        let kind = io::ErrorKind::InvalidInput;
+        let error = Format::SFV.line(&entry, Encoding::Hex).unwrap_err();
+        assert_eq!(error.kind(), kind);
+        let entry = Entry {
+            path: PathBuf::from("a\nb"),
+            kind: None,
+            digest: fixtures::crc32::EMPTY.to_vec(),
+            ..entry
+        };
+        let error = Format::SFV.line(&entry, Encoding::Hex).unwrap_err();
+        assert_eq!(error.kind(), kind);
+        let error = Format::BSD.line(&entry, Encoding::Hex).unwrap_err();
+        assert_eq!(error.kind(), kind);
+    }
+
+    #[test]
+    fn write_manifest() {
+        let text = "; Generated by cksfv\n\
+                    foo 0A1B2C3D\n\
+                    bar baz 00000000\n";
+        let manifest = parse(text).unwrap();
+        let mut writer = Writer::new(Vec::new(), Format::SFV, Encoding::Hex);
+        writer.write_manifest(&manifest).unwrap();
+        let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let (header, entries) = written.split_once('\n').unwrap();
+        assert_eq!(Some(String::from(header)), Format::SFV.header());
+        assert_eq!(entries, "foo 0A1B2C3D\nbar baz 00000000\n");
+        assert_eq!(parse(&written), Ok(manifest));
+
+        let writer = Writer::new(Vec::new(), Format::SFV, Encoding::Hex);
+        let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(written, format!("{}\n", Format::SFV.header().unwrap()));
+        let writer = Writer::new(Vec::new(), Format::GNU, Encoding::Hex);
+        assert!(writer.finish().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_error_lines() {
+        let text = "00000000  foo\n\n00000000 *\n";
+        let error = parse(text).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.span, 10..10);
+        assert_eq!(error.to_string(), "line 3, column 11: missing file name");
+    }
+}
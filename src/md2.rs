@@ -0,0 +1,211 @@
+use std::ptr;
+use std::sync::Arc;
+
+use openssl_sys::{EVP_MD_fetch, EVP_MD_free, EVP_MD};
+
+use crate::{
+    evp, legacy, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
+
+/// A structure used to generate a MD2 digest.
+#[derive(Clone)]
+pub struct MD2 {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl MD2 {
+    /// The length of the MD2 digest, in bytes.
+    pub const LENGTH: usize = 16;
+
+    /// Create a new MD2 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. In particular, MD2 is
+    /// omitted from most OpenSSL builds, and when present is supplied by
+    /// the legacy provider. Use `MD2::available()` to check for it
+    /// beforehand.
+    #[must_use]
+    pub fn new() -> Self {
+        let md2 = fetch();
+        assert!(!md2.is_null(), "the MD2 digest is not available");
+        let ctx = evp::Context::new(md2);
+        // The context keeps its own reference to the algorithm.
+        unsafe { EVP_MD_free(md2) };
+        Self { ctx }
+    }
+
+    /// Check whether OpenSSL is able to provide the MD2 digest.
+    #[must_use]
+    pub fn available() -> bool {
+        let md2 = fetch();
+        if md2.is_null() {
+            false
+        } else {
+            unsafe { EVP_MD_free(md2) };
+            true
+        }
+    }
+}
+
+/// Fetch the MD2 digest algorithm from OpenSSL, loading the legacy
+/// provider the first time through. A null pointer is returned if the
+/// algorithm is not available.
+fn fetch() -> *mut EVP_MD {
+    legacy::load_provider();
+    unsafe { EVP_MD_fetch(ptr::null_mut(), c"MD2".as_ptr(), ptr::null()) }
+}
+
+impl Digest<{ Self::LENGTH }> for MD2 {
+    /// Update the MD2 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the MD2 digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the MD2 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        let md2 = fetch();
+        assert!(!md2.is_null(), "the MD2 digest is not available");
+        let digest = evp::digest(md2, data);
+        unsafe { EVP_MD_free(md2) };
+        digest
+    }
+}
+
+impl Default for MD2 {
+    /// Create a default MD2 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute an MD2 digest in a separate thread.
+pub struct BackgroundMD2 {
+    worker: Background<{ MD2::LENGTH }>,
+}
+
+impl BackgroundMD2 {
+    /// Create a new `BackgroundMD2` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(MD2::new),
+        }
+    }
+}
+
+impl Generator for BackgroundMD2 {
+    /// Add the given `data` to the MD2 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the MD2 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD2(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD2 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD2 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD2.name()
+    }
+
+    /// The length of the MD2 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD2.output_len()
+    }
+
+    /// Copy the current state of the MD2 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD2 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    // The MD2 digest is frequently omitted from OpenSSL builds, so each
+    // of these tests is skipped if it is missing.
+
+    #[test]
+    fn empty() {
+        if !MD2::available() {
+            return;
+        }
+        let mut md2 = MD2::new();
+        assert_eq!(md2.finish(), fixtures::md2::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        if !MD2::available() {
+            return;
+        }
+        let mut md2 = MD2::new();
+        md2.update(&[0; 0x4000]);
+        md2.update(&[0; 0x0d]);
+        assert_eq!(md2.finish(), fixtures::md2::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        if !MD2::available() {
+            return;
+        }
+        let mut md2 = MD2::new();
+        md2.update(&fixtures::RANDOM_11171);
+        assert_eq!(md2.finish(), fixtures::md2::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        if !MD2::available() {
+            return;
+        }
+        let mut md2 = MD2::new();
+        assert_eq!(md2.finish(), fixtures::md2::EMPTY);
+        md2.update(&fixtures::ZERO_400D);
+        assert_eq!(md2.finish(), fixtures::md2::ZERO_400D);
+        md2.update(&fixtures::RANDOM_11171);
+        assert_eq!(md2.finish(), fixtures::md2::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        if !MD2::available() {
+            return;
+        }
+        let md2 = BackgroundMD2::new();
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::EMPTY));
+        md2.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::ZERO_400D));
+        md2.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            md2.result(),
+            DigestData::MD2(fixtures::md2::RANDOM_11171)
+        );
+    }
+}
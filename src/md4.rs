@@ -0,0 +1,210 @@
+use std::ptr;
+use std::sync::Arc;
+
+use openssl_sys::{EVP_MD_fetch, EVP_MD_free, EVP_MD};
+
+use crate::{
+    evp, legacy, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
+
+/// A structure used to generate a MD4 digest.
+#[derive(Clone)]
+pub struct MD4 {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl MD4 {
+    /// The length of the MD4 digest, in bytes.
+    pub const LENGTH: usize = 16;
+
+    /// Create a new MD4 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. In particular, MD4 is
+    /// provided by the OpenSSL legacy provider, which is not always
+    /// installed. Use `MD4::available()` to check for it beforehand.
+    #[must_use]
+    pub fn new() -> Self {
+        let md4 = fetch();
+        assert!(!md4.is_null(), "the MD4 digest is not available");
+        let ctx = evp::Context::new(md4);
+        // The context keeps its own reference to the algorithm.
+        unsafe { EVP_MD_free(md4) };
+        Self { ctx }
+    }
+
+    /// Check whether OpenSSL is able to provide the MD4 digest.
+    #[must_use]
+    pub fn available() -> bool {
+        let md4 = fetch();
+        if md4.is_null() {
+            false
+        } else {
+            unsafe { EVP_MD_free(md4) };
+            true
+        }
+    }
+}
+
+/// Fetch the MD4 digest algorithm from OpenSSL, loading the legacy
+/// provider the first time through. A null pointer is returned if the
+/// algorithm is not available.
+fn fetch() -> *mut EVP_MD {
+    legacy::load_provider();
+    unsafe { EVP_MD_fetch(ptr::null_mut(), c"MD4".as_ptr(), ptr::null()) }
+}
+
+impl Digest<{ Self::LENGTH }> for MD4 {
+    /// Update the MD4 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the MD4 digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the MD4 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        let md4 = fetch();
+        assert!(!md4.is_null(), "the MD4 digest is not available");
+        let digest = evp::digest(md4, data);
+        unsafe { EVP_MD_free(md4) };
+        digest
+    }
+}
+
+impl Default for MD4 {
+    /// Create a default MD4 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute an MD4 digest in a separate thread.
+pub struct BackgroundMD4 {
+    worker: Background<{ MD4::LENGTH }>,
+}
+
+impl BackgroundMD4 {
+    /// Create a new `BackgroundMD4` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(MD4::new),
+        }
+    }
+}
+
+impl Generator for BackgroundMD4 {
+    /// Add the given `data` to the MD4 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the MD4 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD4(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD4 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD4 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD4.name()
+    }
+
+    /// The length of the MD4 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD4.output_len()
+    }
+
+    /// Copy the current state of the MD4 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD4 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    // The MD4 digest depends on the OpenSSL legacy provider being
+    // installed, so each of these tests is skipped if it is missing.
+
+    #[test]
+    fn empty() {
+        if !MD4::available() {
+            return;
+        }
+        let mut md4 = MD4::new();
+        assert_eq!(md4.finish(), fixtures::md4::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        if !MD4::available() {
+            return;
+        }
+        let mut md4 = MD4::new();
+        md4.update(&[0; 0x4000]);
+        md4.update(&[0; 0x0d]);
+        assert_eq!(md4.finish(), fixtures::md4::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        if !MD4::available() {
+            return;
+        }
+        let mut md4 = MD4::new();
+        md4.update(&fixtures::RANDOM_11171);
+        assert_eq!(md4.finish(), fixtures::md4::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        if !MD4::available() {
+            return;
+        }
+        let mut md4 = MD4::new();
+        assert_eq!(md4.finish(), fixtures::md4::EMPTY);
+        md4.update(&fixtures::ZERO_400D);
+        assert_eq!(md4.finish(), fixtures::md4::ZERO_400D);
+        md4.update(&fixtures::RANDOM_11171);
+        assert_eq!(md4.finish(), fixtures::md4::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        if !MD4::available() {
+            return;
+        }
+        let md4 = BackgroundMD4::new();
+        assert_eq!(md4.result(), DigestData::MD4(fixtures::md4::EMPTY));
+        md4.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(md4.result(), DigestData::MD4(fixtures::md4::ZERO_400D));
+        md4.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            md4.result(),
+            DigestData::MD4(fixtures::md4::RANDOM_11171)
+        );
+    }
+}
@@ -1,18 +1,16 @@
 use std::sync::Arc;
 
-use openssl_sys::{
-    EVP_DigestFinal, EVP_DigestInit, EVP_DigestUpdate, EVP_MD_CTX_free,
-    EVP_MD_CTX_new, EVP_md5, EVP_MAX_MD_SIZE, EVP_MD, EVP_MD_CTX,
-};
+use openssl_sys::EVP_md5;
 
-use crate::{Background, Digest, DigestData, Generator};
+use crate::{
+    evp, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
 
 /// A structure used to generate a MD5 digest.
+#[derive(Clone)]
 pub struct MD5 {
     /// The OpenSSL context used to generate the digest.
-    ctx: *mut EVP_MD_CTX,
-    /// The OpenSSL MD5 digest algorithm.
-    md5: *const EVP_MD,
+    ctx: evp::Context,
 }
 
 impl MD5 {
@@ -28,38 +26,28 @@ impl MD5 {
     /// unless the OpenSSL API has fallen out of sync.
     #[must_use]
     pub fn new() -> Self {
-        let ctx = unsafe { EVP_MD_CTX_new() };
-        assert!(!ctx.is_null());
-        let md5 = unsafe { EVP_md5() };
-        assert!(!md5.is_null());
-        let mut this = Self { ctx, md5 };
-        this.reset();
-        this
-    }
-
-    /// Initialize the OpenSSL context for use computing an MD5 digest.
-    fn reset(&mut self) {
-        unsafe { EVP_DigestInit(self.ctx, self.md5) };
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_md5() }),
+        }
     }
 }
 
 impl Digest<{ Self::LENGTH }> for MD5 {
     /// Update the MD5 digest using the given `data`.
     fn update(&mut self, data: &[u8]) {
-        unsafe {
-            EVP_DigestUpdate(self.ctx, data.as_ptr().cast(), data.len());
-        }
+        self.ctx.update(data);
     }
 
     /// Finalize the MD5 digest computation and return the result. The
     /// OpenSSL context is reset so that it can be reused.
     fn finish(&mut self) -> [u8; Self::LENGTH] {
-        let mut len = 0;
-        let mut buffer = [0u8; EVP_MAX_MD_SIZE as usize];
-        unsafe { EVP_DigestFinal(self.ctx, buffer.as_mut_ptr(), &mut len) };
-        assert!(Self::LENGTH == len as usize);
-        self.reset();
-        buffer[..Self::LENGTH].try_into().unwrap()
+        self.ctx.finish()
+    }
+
+    /// Compute the MD5 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_md5() }, data)
     }
 }
 
@@ -70,13 +58,6 @@ impl Default for MD5 {
     }
 }
 
-impl Drop for MD5 {
-    /// Clean up the OpenSSL context.
-    fn drop(&mut self) {
-        unsafe { EVP_MD_CTX_free(self.ctx) };
-    }
-}
-
 /// Structure used to compute an MD5 digest in a separate thread.
 pub struct BackgroundMD5 {
     worker: Background<{ MD5::LENGTH }>,
@@ -93,13 +74,41 @@ impl BackgroundMD5 {
 
 impl Generator for BackgroundMD5 {
     /// Add the given `data` to the MD5 digest.
-    fn append(&self, data: Arc<[u8]>) {
-        self.worker.update(data);
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
     }
 
     /// Retrieve the MD5 digest data, and reset the digest computation.
-    fn result(&self) -> DigestData {
-        DigestData::MD5(self.worker.finish())
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD5(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD5 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD5 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD5.name()
+    }
+
+    /// The length of the MD5 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD5.output_len()
+    }
+
+    /// Copy the current state of the MD5 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD5 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
     }
 }
 
@@ -139,6 +148,15 @@ mod tests {
         assert_eq!(md5.finish(), fixtures::md5::RANDOM_11171);
     }
 
+    #[test]
+    fn oneshot() {
+        assert_eq!(MD5::digest(&[]), fixtures::md5::EMPTY);
+        assert_eq!(
+            MD5::digest(&fixtures::ZERO_400D),
+            fixtures::md5::ZERO_400D
+        );
+    }
+
     #[test]
     fn background() {
         let md5 = BackgroundMD5::new();
@@ -151,4 +169,11 @@ mod tests {
             DigestData::MD5(fixtures::md5::RANDOM_11171)
         );
     }
+    #[test]
+    fn background_reset() {
+        let md5 = BackgroundMD5::new();
+        md5.append(Arc::from(fixtures::ZERO_400D));
+        md5.reset();
+        assert_eq!(md5.result(), DigestData::MD5(fixtures::md5::EMPTY));
+    }
 }
@@ -0,0 +1,328 @@
+#[cfg(feature = "md2")]
+use crate::md2;
+use crate::{
+    blake2b, blake2s, crc16, crc32, crc64, keccak256, md4, md5, rapidhash,
+    rmd160, rmd256, rmd320, sha224, sha256, sha384, sha3_224, sha3_256,
+    sha3_384, sha3_512, sha512, sha512_224, sha512_256, xxh32, xxh64,
+};
+#[cfg(feature = "gcrypt")]
+use crate::{streebog256, streebog512, tiger, tiger2};
+use crate::{Digest, DigestData, DigestKind};
+
+/// Compute the digest of `data` in the current thread, using a new
+/// `digest` which is dropped afterwards.
+fn once<const N: usize>(mut digest: impl Digest<N>, data: &[u8]) -> [u8; N] {
+    digest.update(data);
+    digest.finish()
+}
+
+/// Compute the `kind` of digest of `data` in the current thread. This
+/// avoids starting a background thread, as a `Generator` does, which is
+/// wasteful for small buffers which are already in memory. `None` is
+/// returned if the digest is unavailable, as described for `md2()`,
+/// `md4()`, `streebog256()`, and so on.
+#[must_use]
+pub fn digest(kind: DigestKind, data: &[u8]) -> Option<DigestData> {
+    let digest = match kind {
+        DigestKind::CRC16_CCITT => crc16_ccitt_digest(data),
+        DigestKind::CRC16_MODBUS => crc16_modbus_digest(data),
+        DigestKind::CRC16_XMODEM => crc16_xmodem_digest(data),
+        DigestKind::CRC32 => crc32_digest(data),
+        DigestKind::CRC64 => crc64_digest(data),
+        DigestKind::XXH32 => xxh32_digest(data),
+        DigestKind::XXH64 => xxh64_digest(data),
+        DigestKind::RAPIDHASH => rapidhash_digest(data),
+        DigestKind::MD2 => return md2_digest(data),
+        DigestKind::MD4 => return md4_digest(data),
+        DigestKind::MD5 => md5_digest(data),
+        DigestKind::SHA224 => sha224_digest(data),
+        DigestKind::SHA256 => sha256_digest(data),
+        DigestKind::SHA384 => sha384_digest(data),
+        DigestKind::SHA512 => sha512_digest(data),
+        DigestKind::SHA512_224 => sha512_224_digest(data),
+        DigestKind::SHA512_256 => sha512_256_digest(data),
+        DigestKind::SHA3_224 => sha3_224_digest(data),
+        DigestKind::SHA3_256 => sha3_256_digest(data),
+        DigestKind::SHA3_384 => sha3_384_digest(data),
+        DigestKind::SHA3_512 => sha3_512_digest(data),
+        DigestKind::KECCAK256 => keccak256_digest(data),
+        DigestKind::BLAKE2B => blake2b_digest(data),
+        DigestKind::BLAKE2S => blake2s_digest(data),
+        DigestKind::STREEBOG256 => return streebog256_digest(data),
+        DigestKind::STREEBOG512 => return streebog512_digest(data),
+        DigestKind::TIGER => return tiger_digest(data),
+        DigestKind::TIGER2 => return tiger2_digest(data),
+        DigestKind::RMD160 => rmd160_digest(data),
+        DigestKind::RMD256 => rmd256_digest(data),
+        DigestKind::RMD320 => rmd320_digest(data),
+    };
+    Some(digest)
+}
+
+#[must_use]
+pub fn crc16_ccitt_digest(data: &[u8]) -> DigestData {
+    let crc16 = crc16::CRC16::new(crc16::Variant::CCITT);
+    DigestData::CRC16_CCITT(once(crc16, data))
+}
+
+#[must_use]
+pub fn crc16_modbus_digest(data: &[u8]) -> DigestData {
+    let crc16 = crc16::CRC16::new(crc16::Variant::MODBUS);
+    DigestData::CRC16_MODBUS(once(crc16, data))
+}
+
+#[must_use]
+pub fn crc16_xmodem_digest(data: &[u8]) -> DigestData {
+    let crc16 = crc16::CRC16::new(crc16::Variant::XMODEM);
+    DigestData::CRC16_XMODEM(once(crc16, data))
+}
+
+#[must_use]
+pub fn crc32_digest(data: &[u8]) -> DigestData {
+    DigestData::CRC32(crc32::CRC32::digest(data))
+}
+
+#[must_use]
+pub fn crc64_digest(data: &[u8]) -> DigestData {
+    DigestData::CRC64(crc64::CRC64::digest(data))
+}
+
+#[must_use]
+pub fn xxh32_digest(data: &[u8]) -> DigestData {
+    DigestData::XXH32(xxh32::XXH32::digest(data))
+}
+
+#[must_use]
+pub fn xxh64_digest(data: &[u8]) -> DigestData {
+    DigestData::XXH64(xxh64::XXH64::digest(data))
+}
+
+#[must_use]
+pub fn rapidhash_digest(data: &[u8]) -> DigestData {
+    DigestData::RAPIDHASH(rapidhash::RAPIDHASH::digest(data))
+}
+
+/// Compute a MD2 digest. MD2 support must be enabled using the `md2`
+/// feature, and is omitted from most builds of OpenSSL, so `None` is
+/// returned if either is missing.
+#[must_use]
+pub fn md2_digest(data: &[u8]) -> Option<DigestData> {
+    #[cfg(feature = "md2")]
+    if md2::MD2::available() {
+        return Some(DigestData::MD2(md2::MD2::digest(data)));
+    }
+    let _ = data;
+    None
+}
+
+/// Compute a MD4 digest. MD4 is supplied by the OpenSSL legacy provider,
+/// so `None` is returned if that provider is not installed.
+#[must_use]
+pub fn md4_digest(data: &[u8]) -> Option<DigestData> {
+    if !md4::MD4::available() {
+        return None;
+    }
+    Some(DigestData::MD4(md4::MD4::digest(data)))
+}
+
+#[must_use]
+pub fn md5_digest(data: &[u8]) -> DigestData {
+    DigestData::MD5(md5::MD5::digest(data))
+}
+
+#[must_use]
+pub fn sha224_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA224(sha224::SHA224::digest(data))
+}
+
+#[must_use]
+pub fn sha256_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA256(sha256::SHA256::digest(data))
+}
+
+#[must_use]
+pub fn sha384_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA384(sha384::SHA384::digest(data))
+}
+
+#[must_use]
+pub fn sha512_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA512(sha512::SHA512::digest(data))
+}
+
+#[must_use]
+pub fn sha512_224_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA512_224(sha512_224::SHA512_224::digest(data))
+}
+
+#[must_use]
+pub fn sha512_256_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA512_256(sha512_256::SHA512_256::digest(data))
+}
+
+#[must_use]
+pub fn sha3_224_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA3_224(sha3_224::SHA3_224::digest(data))
+}
+
+#[must_use]
+pub fn sha3_256_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA3_256(sha3_256::SHA3_256::digest(data))
+}
+
+#[must_use]
+pub fn sha3_384_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA3_384(sha3_384::SHA3_384::digest(data))
+}
+
+#[must_use]
+pub fn sha3_512_digest(data: &[u8]) -> DigestData {
+    DigestData::SHA3_512(sha3_512::SHA3_512::digest(data))
+}
+
+#[must_use]
+pub fn keccak256_digest(data: &[u8]) -> DigestData {
+    DigestData::KECCAK256(keccak256::KECCAK256::digest(data))
+}
+
+#[must_use]
+pub fn blake2b_digest(data: &[u8]) -> DigestData {
+    DigestData::BLAKE2B(blake2b::BLAKE2B::digest(data))
+}
+
+#[must_use]
+pub fn blake2s_digest(data: &[u8]) -> DigestData {
+    DigestData::BLAKE2S(blake2s::BLAKE2S::digest(data))
+}
+
+/// Compute a Streebog-256 digest. Streebog is computed using libgcrypt,
+/// so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn streebog256_digest(data: &[u8]) -> Option<DigestData> {
+    #[cfg(feature = "gcrypt")]
+    {
+        Some(DigestData::STREEBOG256(streebog256::STREEBOG256::digest(
+            data,
+        )))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    {
+        let _ = data;
+        None
+    }
+}
+
+/// Compute a Streebog-512 digest. Streebog is computed using libgcrypt,
+/// so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn streebog512_digest(data: &[u8]) -> Option<DigestData> {
+    #[cfg(feature = "gcrypt")]
+    {
+        Some(DigestData::STREEBOG512(streebog512::STREEBOG512::digest(
+            data,
+        )))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    {
+        let _ = data;
+        None
+    }
+}
+
+/// Compute a Tiger digest. Tiger is computed using libgcrypt,
+/// so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn tiger_digest(data: &[u8]) -> Option<DigestData> {
+    #[cfg(feature = "gcrypt")]
+    {
+        Some(DigestData::TIGER(tiger::TIGER::digest(data)))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    {
+        let _ = data;
+        None
+    }
+}
+
+/// Compute a Tiger2 digest. Tiger2 is computed using libgcrypt,
+/// so `None` is returned if the `gcrypt` feature is disabled.
+#[must_use]
+pub fn tiger2_digest(data: &[u8]) -> Option<DigestData> {
+    #[cfg(feature = "gcrypt")]
+    {
+        Some(DigestData::TIGER2(tiger2::TIGER2::digest(data)))
+    }
+    #[cfg(not(feature = "gcrypt"))]
+    {
+        let _ = data;
+        None
+    }
+}
+
+#[must_use]
+pub fn rmd160_digest(data: &[u8]) -> DigestData {
+    DigestData::RMD160(rmd160::RMD160::digest(data))
+}
+
+#[must_use]
+pub fn rmd256_digest(data: &[u8]) -> DigestData {
+    DigestData::RMD256(rmd256::RMD256::digest(data))
+}
+
+#[must_use]
+pub fn rmd320_digest(data: &[u8]) -> DigestData {
+    DigestData::RMD320(rmd320::RMD320::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn oneshot_digest() {
+        assert_eq!(
+            sha256_digest(&[]),
+            DigestData::SHA256(fixtures::sha256::EMPTY)
+        );
+        assert_eq!(
+            crc32_digest(&fixtures::ZERO_400D),
+            DigestData::CRC32(fixtures::crc32::ZERO_400D)
+        );
+        assert_eq!(
+            digest(DigestKind::MD5, &fixtures::RANDOM_11171),
+            Some(DigestData::MD5(fixtures::md5::RANDOM_11171))
+        );
+        assert_eq!(
+            digest(DigestKind::CRC16_MODBUS, &fixtures::RANDOM_11171),
+            Some(DigestData::CRC16_MODBUS(
+                fixtures::crc16_modbus::RANDOM_11171
+            ))
+        );
+        assert_eq!(
+            digest(DigestKind::RMD320, &fixtures::ZERO_400D),
+            Some(DigestData::RMD320(fixtures::rmd320::ZERO_400D))
+        );
+    }
+
+    #[test]
+    fn oneshot_matches_generator() {
+        // The digest computed in the current thread is the same as the one
+        // computed in the background.
+        let generator = crate::blake2b();
+        generator.append(std::sync::Arc::from(&fixtures::RANDOM_11171[..]));
+        assert_eq!(
+            digest(DigestKind::BLAKE2B, &fixtures::RANDOM_11171),
+            Some(generator.result())
+        );
+    }
+
+    #[cfg(feature = "gcrypt")]
+    #[test]
+    fn oneshot_gcrypt() {
+        assert_eq!(
+            digest(DigestKind::TIGER, &fixtures::ZERO_400D),
+            Some(DigestData::TIGER(fixtures::tiger::ZERO_400D))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::num::NonZeroUsize;
+use std::thread;
+
+use libz_sys::{crc32_combine, z_off_t};
+
+use crate::{crc32, digest, Digest, DigestData, DigestKind};
+
+/// The smallest chunk of data hashed by each thread. Smaller chunks aren't
+/// worth the cost of starting a thread.
+const MIN_CHUNK: usize = 0x10_0000;
+
+/// The largest slice passed to a single `CRC32::update()`, which is
+/// limited to `u32::MAX` bytes.
+const MAX_UPDATE: usize = 0x4000_0000;
+
+/// Compute the `kind` of digest of `data`, splitting it into chunks which
+/// are hashed in parallel for the kinds of digest which can be combined
+/// afterwards. Currently this is only CRC32, using `crc32_combine()` from
+/// zlib. Other kinds are computed in the current thread, as for
+/// `digest()`, and `None` is returned if the digest is unavailable.
+#[must_use]
+pub fn parallel_digest(kind: DigestKind, data: &[u8]) -> Option<DigestData> {
+    match kind {
+        DigestKind::CRC32 => {
+            let threads =
+                thread::available_parallelism().map_or(1, NonZeroUsize::get);
+            let chunk = data.len().div_ceil(threads).max(MIN_CHUNK);
+            Some(DigestData::CRC32(parallel_crc32(data, chunk)))
+        }
+        _ => digest(kind, data),
+    }
+}
+
+/// Compute the CRC32 checksum of `data`, hashing each `chunk` bytes of it
+/// in a separate thread, and then combining the checksums.
+fn parallel_crc32(data: &[u8], chunk: usize) -> [u8; crc32::CRC32::LENGTH] {
+    if data.len() <= chunk {
+        return crc32_of(data).to_be_bytes();
+    }
+    let crcs: Vec<(u32, usize)> = thread::scope(|scope| {
+        let workers: Vec<_> = data
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(move || (crc32_of(chunk), chunk.len())))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("CRC32 thread panicked"))
+            .collect()
+    });
+    let crc = crcs
+        .into_iter()
+        .map(|(crc, len)| {
+            let len = z_off_t::try_from(len).expect("chunk is too large");
+            (crc.into(), len)
+        })
+        .reduce(|(crc1, len1), (crc2, len2)| {
+            // SAFETY: `crc32_combine` only operates on the values passed.
+            (unsafe { crc32_combine(crc1, crc2, len2) }, len1 + len2)
+        })
+        .map_or(0, |(crc, _)| crc);
+    u32::try_from(crc)
+        .expect("unexpected CRC32 value > u32::MAX")
+        .to_be_bytes()
+}
+
+/// Compute the CRC32 checksum of `data` in the current thread.
+fn crc32_of(data: &[u8]) -> u32 {
+    let mut crc32 = crc32::CRC32::new();
+    for data in data.chunks(MAX_UPDATE) {
+        crc32.update(data);
+    }
+    u32::from_be_bytes(crc32.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn parallel_crc32_chunks() {
+        for chunk in [0x100, 0x1000, fixtures::RANDOM_11171.len()] {
+            assert_eq!(
+                parallel_crc32(&fixtures::RANDOM_11171, chunk),
+                fixtures::crc32::RANDOM_11171
+            );
+        }
+        assert_eq!(parallel_crc32(&[], 0x100), fixtures::crc32::EMPTY);
+    }
+
+    #[test]
+    fn parallel_digests() {
+        for kind in [DigestKind::CRC32, DigestKind::MD5] {
+            assert_eq!(
+                parallel_digest(kind, &fixtures::RANDOM_11171),
+                digest(kind, &fixtures::RANDOM_11171)
+            );
+        }
+        let zero = vec![0; MIN_CHUNK * 3 + 0x0d];
+        assert_eq!(
+            parallel_digest(DigestKind::CRC32, &zero),
+            digest(DigestKind::CRC32, &zero)
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The default secrets used by the reference rapidhash implementation.
+const SECRETS: [u64; 7] = [
+    0x2d35_8dcc_aa6c_78a5,
+    0x8bb8_4b93_962e_acc9,
+    0x4b33_a62e_d433_d4a3,
+    0x4d5a_2da5_1de1_aa47,
+    0xa076_1d64_78bd_642f,
+    0xe703_7ed1_a0b4_28db,
+    0x90ed_1765_281c_388c,
+];
+
+/// The seed used by the RAPIDHASH checksum. This is the reference seed of
+/// 0, premixed with the secrets.
+const SEED: u64 = mix(SECRETS[2], SECRETS[1]);
+
+/// The secrets used for each 16 bytes of input remaining after the last
+/// full chunk.
+const TAIL_SECRETS: [usize; 6] = [2, 2, 1, 1, 2, 1];
+
+/// The number of bytes consumed by each round of the RAPIDHASH checksum.
+const CHUNK: usize = 112;
+
+/// The number of bytes from the previous chunk which may be needed when
+/// finishing the checksum.
+const PREVIOUS: usize = 16;
+
+/// A structure used to generate a RAPIDHASH checksum, using version 3 of
+/// the rapidhash algorithm.
+#[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct RAPIDHASH {
+    /// The seven lanes used while processing full chunks.
+    seeds: [u64; 7],
+    /// The tail of the last processed chunk, followed by input which has
+    /// not yet been processed.
+    buffer: [u8; PREVIOUS + CHUNK],
+    /// The number of unprocessed bytes used in `buffer`.
+    buffered: usize,
+    /// Whether any full chunks have been processed.
+    processed: bool,
+}
+
+impl RAPIDHASH {
+    /// The length of the RAPIDHASH checksum, in bytes.
+    pub const LENGTH: usize = 8;
+
+    /// Create a new RAPIDHASH structure to generate a checksum.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            seeds: [SEED; 7],
+            buffer: [0; PREVIOUS + CHUNK],
+            buffered: 0,
+            processed: false,
+        }
+    }
+
+    /// Re-initialize the RAPIDHASH structure.
+    fn reset(&mut self) {
+        self.seeds = [SEED; 7];
+        self.buffered = 0;
+        self.processed = false;
+    }
+
+    /// Process the buffered chunk of input, keeping its tail in case it is
+    /// needed by `finish()`.
+    fn chunk(&mut self) {
+        let chunk = &self.buffer[PREVIOUS..];
+        for (lane, (seed, secret)) in
+            self.seeds.iter_mut().zip(SECRETS).enumerate()
+        {
+            *seed = mix(
+                read_u64(chunk, 16 * lane) ^ secret,
+                read_u64(chunk, 16 * lane + 8) ^ *seed,
+            );
+        }
+        self.buffer.copy_within(CHUNK.., 0);
+        self.buffered = 0;
+        self.processed = true;
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for RAPIDHASH {
+    /// Update the RAPIDHASH checksum using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            // A full chunk is only processed once we know more input
+            // follows it, since the final chunk is handled by `finish()`.
+            if self.buffered == CHUNK {
+                self.chunk();
+            }
+            let start = PREVIOUS + self.buffered;
+            let count = (CHUNK - self.buffered).min(data.len());
+            self.buffer[start..start + count].copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+        }
+    }
+
+    /// Return the RAPIDHASH checksum. The RAPIDHASH checksum is reset so
+    /// that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let data = &self.buffer[PREVIOUS..PREVIOUS + self.buffered];
+        let mut seed = SEED;
+        let (a, b) = if !self.processed && data.len() <= 16 {
+            let len = data.len();
+            if len >= 4 {
+                seed ^= len as u64;
+                if len >= 8 {
+                    (read_u64(data, 0), read_u64(data, len - 8))
+                } else {
+                    (read_u32(data, 0), read_u32(data, len - 4))
+                }
+            } else if len > 0 {
+                (
+                    (u64::from(data[0]) << 45) | u64::from(data[len - 1]),
+                    u64::from(data[len >> 1]),
+                )
+            } else {
+                (0, 0)
+            }
+        } else {
+            if self.processed {
+                seed = self.seeds.iter().fold(0, |seed, lane| seed ^ lane);
+            }
+            for (i, secret) in TAIL_SECRETS.into_iter().enumerate() {
+                if data.len() <= 16 * (i + 1) {
+                    break;
+                }
+                seed = mix(
+                    read_u64(data, 16 * i) ^ SECRETS[secret],
+                    read_u64(data, 16 * i + 8) ^ seed,
+                );
+            }
+            // The final 16 bytes may overlap the previous chunk.
+            let tail = &self.buffer[..PREVIOUS + self.buffered];
+            let end = tail.len();
+            (
+                read_u64(tail, end - 16) ^ data.len() as u64,
+                read_u64(tail, end - 8),
+            )
+        };
+
+        let (a, b) = mum(a ^ SECRETS[1], b ^ seed);
+        let hash = mix(
+            a ^ 0xaaaa_aaaa_aaaa_aaaa,
+            b ^ SECRETS[1] ^ self.buffered as u64,
+        );
+
+        self.reset();
+        hash.to_be_bytes()
+    }
+
+    /// Export the current state of the RAPIDHASH checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u64s(&self.seeds);
+        state.bytes(&self.buffer);
+        state.usize(self.buffered);
+        state.bool(self.processed);
+        Some(state.finish())
+    }
+
+    /// Create a RAPIDHASH checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let seeds = state.u64s()?;
+        let buffer = state.bytes()?;
+        let buffered = state.usize(CHUNK)?;
+        let processed = state.bool()?;
+        state.finish()?;
+        Some(Self {
+            seeds,
+            buffer,
+            buffered,
+            processed,
+        })
+    }
+}
+
+impl Default for RAPIDHASH {
+    /// Create a default RAPIDHASH structure to generate a checksum.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Multiply `a` and `b`, returning the low and high halves of the result.
+#[allow(clippy::cast_possible_truncation)]
+const fn mum(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+    (r as u64, (r >> 64) as u64)
+}
+
+/// Multiply `a` and `b`, folding the high and low halves of the result.
+const fn mix(a: u64, b: u64) -> u64 {
+    let (low, high) = mum(a, b);
+    low ^ high
+}
+
+/// Read a little endian `u64` from `bytes` at the given `offset`.
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Read a little endian `u32` from `bytes` at the given `offset`.
+fn read_u32(bytes: &[u8], offset: usize) -> u64 {
+    let word =
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    u64::from(word)
+}
+
+/// Structure used to compute a RAPIDHASH checksum in a separate thread.
+pub struct BackgroundRAPIDHASH {
+    worker: Background<{ RAPIDHASH::LENGTH }>,
+}
+
+impl BackgroundRAPIDHASH {
+    /// Create a new `BackgroundRAPIDHASH` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(RAPIDHASH::new),
+        }
+    }
+
+    /// Create a `BackgroundRAPIDHASH` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<RAPIDHASH>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundRAPIDHASH {
+    /// Add the given `data` to the RAPIDHASH checksum.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the RAPIDHASH checksum, and reset the checksum
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::RAPIDHASH(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the RAPIDHASH checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the RAPIDHASH checksum.
+    fn name(&self) -> &'static str {
+        DigestKind::RAPIDHASH.name()
+    }
+
+    /// The length of the RAPIDHASH checksum, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::RAPIDHASH.output_len()
+    }
+
+    /// Copy the current state of the RAPIDHASH checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the RAPIDHASH checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// Reference checksums for prefixes of `fixtures::RANDOM_11171`.
+    const SHORT: [(usize, u64); 16] = [
+        (1, 0x119f_7ce0_b6bf_c8fc),
+        (3, 0xa15c_2c74_6179_83a4),
+        (4, 0x36d9_d37a_6dff_9d05),
+        (7, 0x89a7_567d_bfad_2e4c),
+        (8, 0x9a31_4cb5_70b9_41ac),
+        (16, 0x55fc_2934_f7ac_7249),
+        (17, 0x80c0_5a5f_e7a3_20f4),
+        (33, 0x3fc1_2259_4446_e2fa),
+        (49, 0xc2e9_3d26_3204_b6fc),
+        (65, 0x3209_4fa5_1b4c_1cab),
+        (81, 0xc2fd_0be8_8544_77ad),
+        (97, 0xcb5d_8cfd_f943_a5e0),
+        (112, 0x4fd4_85a7_f194_d80f),
+        (113, 0xb18a_b2d5_b45b_5d7e),
+        (224, 0xf404_bf72_bd1a_26e3),
+        (225, 0xb721_b2de_c989_4d23),
+    ];
+
+    #[test]
+    fn empty() {
+        let mut rapidhash = RAPIDHASH::new();
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut rapidhash = RAPIDHASH::new();
+        rapidhash.update(&[0; 0x4000]);
+        rapidhash.update(&[0; 0x0d]);
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut rapidhash = RAPIDHASH::new();
+        rapidhash.update(&fixtures::RANDOM_11171);
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::RANDOM_11171);
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut rapidhash = RAPIDHASH::new();
+        for chunk in fixtures::RANDOM_11171.chunks(29) {
+            rapidhash.update(chunk);
+        }
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::RANDOM_11171);
+    }
+
+    #[test]
+    fn short() {
+        // Inputs of up to a full chunk are each handled differently.
+        for (len, expected) in SHORT {
+            let mut rapidhash = RAPIDHASH::new();
+            rapidhash.update(&fixtures::RANDOM_11171[..len]);
+            assert_eq!(rapidhash.finish(), expected.to_be_bytes(), "{len}");
+        }
+    }
+
+    #[test]
+    fn multiple() {
+        let mut rapidhash = RAPIDHASH::new();
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::EMPTY);
+        rapidhash.update(&fixtures::ZERO_400D);
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::ZERO_400D);
+        rapidhash.update(&fixtures::RANDOM_11171);
+        assert_eq!(rapidhash.finish(), fixtures::rapidhash::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let rapidhash = BackgroundRAPIDHASH::new();
+        assert_eq!(
+            rapidhash.result(),
+            DigestData::RAPIDHASH(fixtures::rapidhash::EMPTY)
+        );
+        rapidhash.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            rapidhash.result(),
+            DigestData::RAPIDHASH(fixtures::rapidhash::ZERO_400D)
+        );
+        rapidhash.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            rapidhash.result(),
+            DigestData::RAPIDHASH(fixtures::rapidhash::RANDOM_11171)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::io;
+use std::sync::Arc;
+
+use crate::{DigestData, Generator};
+
+/// A reader which passes on everything read from an inner reader, while
+/// appending the same data to one or more `Generator`s. This lets the
+/// digests of data be checked as it is consumed, such as while it is
+/// downloaded, without reading it a second time.
+pub struct HashReader<R> {
+    inner: R,
+    generators: Vec<Box<dyn Generator>>,
+}
+
+impl<R: io::Read> HashReader<R> {
+    /// Create a new `HashReader`, which reads from `inner` and appends the
+    /// data read to each of the `generators`.
+    pub fn new(inner: R, generators: Vec<Box<dyn Generator>>) -> Self {
+        Self { inner, generators }
+    }
+
+    /// A reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Retrieve the digests of the data read so far, in the same order as
+    /// the generators were given. The generators are reset, so further
+    /// reads start new digests.
+    #[must_use]
+    pub fn result(&self) -> Vec<DigestData> {
+        self.generators
+            .iter()
+            .map(|generator| generator.result())
+            .collect()
+    }
+
+    /// Return the inner reader, along with the digests of all the data
+    /// read from it.
+    #[must_use]
+    pub fn finish(self) -> (R, Vec<DigestData>) {
+        let digests = self.result();
+        (self.inner, digests)
+    }
+}
+
+impl<R: io::Read> io::Read for HashReader<R> {
+    /// Read into `buf` from the inner reader, and append the data read to
+    /// each of the generators.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        if count > 0 {
+            let data: Arc<[u8]> = Arc::from(&buf[..count]);
+            for generator in &self.generators {
+                generator.try_append(Arc::clone(&data))?;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use io::Read;
+
+    #[test]
+    fn hash_reader() {
+        let input = &fixtures::RANDOM_11171[..];
+        let mut reader = HashReader::new(input, vec![crate::md5()]);
+        assert_eq!(reader.result(), [DigestData::MD5(fixtures::md5::EMPTY)]);
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, fixtures::RANDOM_11171);
+        let (rest, digests) = reader.finish();
+        assert!(rest.is_empty());
+        assert_eq!(digests, [DigestData::MD5(fixtures::md5::RANDOM_11171)]);
+    }
+
+    #[test]
+    fn hash_reader_multiple() {
+        let generators = vec![crate::crc32(), crate::sha256()];
+        let mut reader =
+            HashReader::new(&fixtures::ZERO_400D[..], generators);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        let (_, digests) = reader.finish();
+        assert_eq!(
+            digests,
+            [
+                DigestData::CRC32(fixtures::crc32::ZERO_400D),
+                DigestData::SHA256(fixtures::sha256::ZERO_400D),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_reader_partial() {
+        // Only the bytes which have been read are digested.
+        let input = &fixtures::RANDOM_11171[..];
+        let mut reader = HashReader::new(input, vec![crate::md5()]);
+        let mut buffer = vec![0; 0x400d];
+        reader.read_exact(&mut buffer).unwrap();
+        let (rest, digests) = reader.finish();
+        assert_eq!(rest.len(), fixtures::RANDOM_11171.len() - 0x400d);
+        let expected = crate::md5();
+        expected.append(Arc::from(&buffer[..]));
+        assert_eq!(digests, [expected.result()]);
+    }
+}
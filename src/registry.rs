@@ -0,0 +1,239 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::crc16::{BackgroundCRC16, Variant};
+use crate::crc32::BackgroundCRC32;
+use crate::crc64::BackgroundCRC64;
+use crate::keccak256::BackgroundKECCAK256;
+use crate::rapidhash::BackgroundRAPIDHASH;
+use crate::rmd256::BackgroundRMD256;
+use crate::rmd320::BackgroundRMD320;
+use crate::xxh32::BackgroundXXH32;
+use crate::xxh64::BackgroundXXH64;
+use crate::{
+    blake2b, blake2s, crc16_ccitt, crc16_modbus, crc16_xmodem, crc32, crc64,
+    keccak256, md2, md4, md5, rapidhash, rmd160, rmd256, rmd320, sha224,
+    sha256, sha384, sha3_224, sha3_256, sha3_384, sha3_512, sha512,
+    sha512_224, sha512_256, streebog256, streebog512, tiger, tiger2, xxh32,
+    xxh64, DigestKind, Generator,
+};
+
+/// A function which creates a new generator.
+pub type Constructor = fn() -> Box<dyn Generator>;
+
+/// The generators which have been added with `register()`, along with the
+/// names they were registered under.
+static REGISTERED: Mutex<Vec<(String, Constructor)>> = Mutex::new(Vec::new());
+
+/// Create a generator for the `kind` of digest, or `None` if it is
+/// unavailable, as described for `md2()`, `md4()`, `streebog256()`, and so
+/// on.
+#[must_use]
+pub fn generator(kind: DigestKind) -> Option<Box<dyn Generator>> {
+    let generator = match kind {
+        DigestKind::CRC16_CCITT => crc16_ccitt(),
+        DigestKind::CRC16_MODBUS => crc16_modbus(),
+        DigestKind::CRC16_XMODEM => crc16_xmodem(),
+        DigestKind::CRC32 => crc32(),
+        DigestKind::CRC64 => crc64(),
+        DigestKind::XXH32 => xxh32(),
+        DigestKind::XXH64 => xxh64(),
+        DigestKind::RAPIDHASH => rapidhash(),
+        DigestKind::MD2 => return md2(),
+        DigestKind::MD4 => return md4(),
+        DigestKind::MD5 => md5(),
+        DigestKind::SHA224 => sha224(),
+        DigestKind::SHA256 => sha256(),
+        DigestKind::SHA384 => sha384(),
+        DigestKind::SHA512 => sha512(),
+        DigestKind::SHA512_224 => sha512_224(),
+        DigestKind::SHA512_256 => sha512_256(),
+        DigestKind::SHA3_224 => sha3_224(),
+        DigestKind::SHA3_256 => sha3_256(),
+        DigestKind::SHA3_384 => sha3_384(),
+        DigestKind::SHA3_512 => sha3_512(),
+        DigestKind::KECCAK256 => keccak256(),
+        DigestKind::BLAKE2B => blake2b(),
+        DigestKind::BLAKE2S => blake2s(),
+        DigestKind::STREEBOG256 => return streebog256(),
+        DigestKind::STREEBOG512 => return streebog512(),
+        DigestKind::TIGER => return tiger(),
+        DigestKind::TIGER2 => return tiger2(),
+        DigestKind::RMD160 => rmd160(),
+        DigestKind::RMD256 => rmd256(),
+        DigestKind::RMD320 => rmd320(),
+    };
+    Some(generator)
+}
+
+/// Create a generator for the `kind` of digest, which resumes from the
+/// `state` returned by `Generator::export_state()`. `None` is returned if
+/// the `state` wasn't exported by the same kind of digest, or the state of
+/// the `kind` of digest can't be imported.
+#[must_use]
+pub fn import_generator(
+    kind: DigestKind,
+    state: &[u8],
+) -> Option<Box<dyn Generator>> {
+    let generator: Box<dyn Generator> = match kind {
+        DigestKind::CRC16_CCITT => {
+            Box::new(BackgroundCRC16::import(Variant::CCITT, state)?)
+        }
+        DigestKind::CRC16_MODBUS => {
+            Box::new(BackgroundCRC16::import(Variant::MODBUS, state)?)
+        }
+        DigestKind::CRC16_XMODEM => {
+            Box::new(BackgroundCRC16::import(Variant::XMODEM, state)?)
+        }
+        DigestKind::CRC32 => Box::new(BackgroundCRC32::import(state)?),
+        DigestKind::CRC64 => Box::new(BackgroundCRC64::import(state)?),
+        DigestKind::XXH32 => Box::new(BackgroundXXH32::import(state)?),
+        DigestKind::XXH64 => Box::new(BackgroundXXH64::import(state)?),
+        DigestKind::RAPIDHASH => {
+            Box::new(BackgroundRAPIDHASH::import(state)?)
+        }
+        DigestKind::KECCAK256 => {
+            Box::new(BackgroundKECCAK256::import(state)?)
+        }
+        DigestKind::RMD256 => Box::new(BackgroundRMD256::import(state)?),
+        DigestKind::RMD320 => Box::new(BackgroundRMD320::import(state)?),
+        _ => return None,
+    };
+    Some(generator)
+}
+
+/// Register a `constructor` for generators under `name`, so that they can
+/// be created by `generator_named()`. A registered name takes precedence
+/// over the name of a built in digest, and registering a name again
+/// replaces the earlier constructor.
+pub fn register(name: &str, constructor: Constructor) {
+    let mut registered =
+        REGISTERED.lock().unwrap_or_else(PoisonError::into_inner);
+    match registered.iter_mut().find(|(known, _)| known == name) {
+        Some(entry) => entry.1 = constructor,
+        None => registered.push((name.to_string(), constructor)),
+    }
+}
+
+/// Create a generator by `name`. Any generator registered under the name
+/// is used, or else the built in digest with that name, as returned by
+/// `DigestKind::name()`. `None` is returned if there is no such digest,
+/// or it is unavailable.
+#[must_use]
+pub fn generator_named(name: &str) -> Option<Box<dyn Generator>> {
+    let constructor = REGISTERED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(known, _)| known == name)
+        .map(|(_, constructor)| *constructor);
+    match constructor {
+        Some(constructor) => Some(constructor()),
+        None => generator(DigestKind::from_name(name)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, DigestData};
+    use std::sync::Arc;
+
+    #[test]
+    fn generator_kinds() {
+        for kind in DigestKind::all() {
+            // Some of the digests may be unavailable in this build.
+            if let Some(generator) = generator(kind) {
+                assert_eq!(generator.name(), kind.name());
+                assert_eq!(generator.output_len(), kind.output_len());
+                assert_eq!(generator.result().len(), kind.output_len());
+            }
+        }
+        let generator = generator(DigestKind::SHA256).unwrap();
+        assert_eq!(
+            generator.result(),
+            DigestData::SHA256(fixtures::sha256::EMPTY)
+        );
+    }
+
+    #[test]
+    fn generator_fork() {
+        let both =
+            [&fixtures::ZERO_400D[..], &fixtures::RANDOM_11171].concat();
+        for kind in DigestKind::all() {
+            let Some(generator) = generator(kind) else {
+                continue;
+            };
+            generator.append(Arc::from(&fixtures::ZERO_400D[..]));
+            let forked = generator.fork();
+            generator.append(Arc::from(&fixtures::RANDOM_11171[..]));
+            assert_eq!(
+                Some(forked.result()),
+                crate::digest(kind, &fixtures::ZERO_400D)
+            );
+            assert_eq!(Some(generator.result()), crate::digest(kind, &both));
+        }
+    }
+
+    #[test]
+    fn generator_state() {
+        let both =
+            [&fixtures::ZERO_400D[..], &fixtures::RANDOM_11171].concat();
+        let (head, tail) = both.split_at(0x4123);
+        for kind in DigestKind::all() {
+            let Some(generator) = generator(kind) else {
+                continue;
+            };
+            generator.append(Arc::from(head));
+            let Some(state) = generator.export_state() else {
+                assert!(import_generator(kind, &[]).is_none());
+                continue;
+            };
+            let resumed = import_generator(kind, &state).unwrap();
+            generator.append(Arc::from(tail));
+            resumed.append(Arc::from(tail));
+            let expected = crate::digest(kind, &both);
+            assert_eq!(Some(resumed.result()), expected);
+            assert_eq!(Some(generator.result()), expected);
+
+            assert!(import_generator(kind, &state[1..]).is_none());
+            assert!(import_generator(kind, &[&state[..], &[0]].concat())
+                .is_none());
+        }
+
+        for kind in [
+            DigestKind::CRC32,
+            DigestKind::XXH64,
+            DigestKind::RAPIDHASH,
+            DigestKind::KECCAK256,
+            DigestKind::RMD320,
+        ] {
+            assert!(generator(kind).unwrap().export_state().is_some());
+        }
+        assert!(crate::sha256().export_state().is_none());
+
+        let state = crate::crc16_ccitt().export_state().unwrap();
+        assert!(import_generator(DigestKind::CRC16_CCITT, &state).is_some());
+        assert!(import_generator(DigestKind::CRC16_MODBUS, &state).is_none());
+    }
+
+    #[test]
+    fn generator_registry() {
+        let generator = generator_named("SHA3-256").unwrap();
+        assert_eq!(
+            generator.result(),
+            DigestData::SHA3_256(fixtures::sha3_256::EMPTY)
+        );
+        assert!(generator_named("test-registry").is_none());
+        assert!(generator_named("sha3-256").is_none());
+
+        register("test-registry", crate::md5);
+        let generator = generator_named("test-registry").unwrap();
+        assert_eq!(generator.result(), DigestData::MD5(fixtures::md5::EMPTY));
+        register("test-registry", crate::crc32);
+        let generator = generator_named("test-registry").unwrap();
+        assert_eq!(
+            generator.result(),
+            DigestData::CRC32(fixtures::crc32::EMPTY)
+        );
+    }
+}
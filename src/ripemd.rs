@@ -0,0 +1,169 @@
+//! Tables and block handling shared by the RIPEMD-256 and RIPEMD-320
+//! digests, which OpenSSL does not provide. The block handling is also
+//! used by the Rust MD4 and MD5 digests, which are padded the same way.
+
+use crate::state::{Exporter, Importer};
+
+/// The number of bytes processed by each compression of a RIPEMD digest.
+pub const BLOCK: usize = 64;
+
+/// The order in which message words are used by the left line.
+#[rustfmt::skip]
+pub const R_LEFT: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8,
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12,
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2,
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// The order in which message words are used by the right line.
+#[rustfmt::skip]
+pub const R_RIGHT: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12,
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2,
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13,
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14,
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// The rotation applied at each step of the left line.
+#[rustfmt::skip]
+pub const S_LEFT: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8,
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12,
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5,
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12,
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// The rotation applied at each step of the right line.
+#[rustfmt::skip]
+pub const S_RIGHT: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6,
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11,
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5,
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8,
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+/// The constant added in each round of the left line.
+pub const K_LEFT: [u32; 5] = [
+    0x0000_0000,
+    0x5a82_7999,
+    0x6ed9_eba1,
+    0x8f1b_bcdc,
+    0xa953_fd4e,
+];
+
+/// The boolean function used in the given `round`.
+pub fn f(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+/// Input which is split into blocks for compression, along with the
+/// MD4 style padding applied at the end of the message.
+#[derive(Clone)]
+pub struct Blocks {
+    /// Input which has not yet filled a full block.
+    buffer: [u8; BLOCK],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes processed.
+    total: u64,
+}
+
+impl Blocks {
+    /// Create a new, empty, `Blocks` structure.
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; BLOCK],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Discard any buffered input.
+    pub fn reset(&mut self) {
+        self.buffered = 0;
+        self.total = 0;
+    }
+
+    /// Write the buffered input and message length to the `state`.
+    pub fn export(&self, state: &mut Exporter) {
+        state.bytes(&self.buffer);
+        state.usize(self.buffered);
+        state.u64(self.total);
+    }
+
+    /// Read the buffered input and message length written by `export()`.
+    pub fn import(state: &mut Importer) -> Option<Self> {
+        Some(Self {
+            buffer: state.bytes()?,
+            buffered: state.usize(BLOCK - 1)?,
+            total: state.u64()?,
+        })
+    }
+
+    /// Add `data` to the message, passing each full block to `compress`.
+    pub fn update(
+        &mut self,
+        data: &[u8],
+        mut compress: impl FnMut(&[u32; 16]),
+    ) {
+        self.total = self.total.wrapping_add(data.len() as u64);
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (BLOCK - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < BLOCK {
+                return;
+            }
+            compress(&words(&self.buffer));
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK);
+        for block in &mut blocks {
+            compress(&words(block));
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Pad the message, passing the final blocks to `compress`, and reset
+    /// the structure so that it can be reused.
+    pub fn finish(&mut self, mut compress: impl FnMut(&[u32; 16])) {
+        let bits = self.total.wrapping_mul(8);
+        let mut padding = [0; BLOCK];
+        padding[0] = 0x80;
+        let count = if self.buffered < BLOCK - 8 {
+            BLOCK - 8 - self.buffered
+        } else {
+            2 * BLOCK - 8 - self.buffered
+        };
+        self.update(&padding[..count], &mut compress);
+        self.update(&bits.to_le_bytes(), &mut compress);
+        self.reset();
+    }
+}
+
+/// Split a block into little endian words.
+fn words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    words
+}
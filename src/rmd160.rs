@@ -1,18 +1,16 @@
 use std::sync::Arc;
 
-use openssl_sys::{
-    EVP_DigestFinal, EVP_DigestInit, EVP_DigestUpdate, EVP_MD_CTX_free,
-    EVP_MD_CTX_new, EVP_ripemd160, EVP_MAX_MD_SIZE, EVP_MD, EVP_MD_CTX,
-};
+use openssl_sys::EVP_ripemd160;
 
-use crate::{Background, Digest, DigestData, Generator};
+use crate::{
+    evp, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
 
 /// A structure used to generate a RMD160 digest.
+#[derive(Clone)]
 pub struct RMD160 {
     /// The OpenSSL context used to generate the digest.
-    ctx: *mut EVP_MD_CTX,
-    /// The OpenSSL RMD160 digest algorithm.
-    rmd160: *const EVP_MD,
+    ctx: evp::Context,
 }
 
 impl RMD160 {
@@ -28,38 +26,28 @@ impl RMD160 {
     /// unless the OpenSSL API has fallen out of sync.
     #[must_use]
     pub fn new() -> Self {
-        let ctx = unsafe { EVP_MD_CTX_new() };
-        assert!(!ctx.is_null());
-        let rmd160 = unsafe { EVP_ripemd160() };
-        assert!(!rmd160.is_null());
-        let mut this = Self { ctx, rmd160 };
-        this.reset();
-        this
-    }
-
-    /// Initialize the OpenSSL context for use computing an RMD160 digest.
-    fn reset(&mut self) {
-        unsafe { EVP_DigestInit(self.ctx, self.rmd160) };
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_ripemd160() }),
+        }
     }
 }
 
 impl Digest<{ Self::LENGTH }> for RMD160 {
     /// Update the RMD160 digest using the given `data`.
     fn update(&mut self, data: &[u8]) {
-        unsafe {
-            EVP_DigestUpdate(self.ctx, data.as_ptr().cast(), data.len());
-        }
+        self.ctx.update(data);
     }
 
     /// Finalize the RMD160 digest computation and return the result. The
     /// OpenSSL context is reset so that it can be reused.
     fn finish(&mut self) -> [u8; Self::LENGTH] {
-        let mut len = 0;
-        let mut buffer = [0u8; EVP_MAX_MD_SIZE as usize];
-        unsafe { EVP_DigestFinal(self.ctx, buffer.as_mut_ptr(), &mut len) };
-        assert!(Self::LENGTH == len as usize);
-        self.reset();
-        buffer[..Self::LENGTH].try_into().unwrap()
+        self.ctx.finish()
+    }
+
+    /// Compute the RMD160 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_ripemd160() }, data)
     }
 }
 
@@ -70,13 +58,6 @@ impl Default for RMD160 {
     }
 }
 
-impl Drop for RMD160 {
-    /// Clean up the OpenSSL context.
-    fn drop(&mut self) {
-        unsafe { EVP_MD_CTX_free(self.ctx) };
-    }
-}
-
 /// Structure used to compute a RMD160 digest in a separate thread.
 pub struct BackgroundRMD160 {
     worker: Background<{ RMD160::LENGTH }>,
@@ -93,13 +74,41 @@ impl BackgroundRMD160 {
 
 impl Generator for BackgroundRMD160 {
     /// Add the given `data` to the RMD160 digest.
-    fn append(&self, data: Arc<[u8]>) {
-        self.worker.update(data);
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
     }
 
     /// Retrieve the RMD160 digest data, and reset the digest computation.
-    fn result(&self) -> DigestData {
-        DigestData::RMD160(self.worker.finish())
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::RMD160(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the RMD160 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the RMD160 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::RMD160.name()
+    }
+
+    /// The length of the RMD160 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::RMD160.output_len()
+    }
+
+    /// Copy the current state of the RMD160 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the RMD160 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
     }
 }
 
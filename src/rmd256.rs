@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use crate::ripemd::{f, Blocks, K_LEFT, R_LEFT, R_RIGHT, S_LEFT, S_RIGHT};
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The constant added in each round of the right line.
+const K_RIGHT: [u32; 4] =
+    [0x50a2_8be6, 0x5c4d_d124, 0x6d70_3ef3, 0x0000_0000];
+
+/// The initial state of the RMD256 digest.
+const INITIAL: [u32; 8] = [
+    0x6745_2301,
+    0xefcd_ab89,
+    0x98ba_dcfe,
+    0x1032_5476,
+    0x7654_3210,
+    0xfedc_ba98,
+    0x89ab_cdef,
+    0x0123_4567,
+];
+
+/// A structure used to generate a RMD256 digest.
+#[derive(Clone)]
+pub struct RMD256 {
+    /// The chaining state, with the left line followed by the right line.
+    state: [u32; 8],
+    /// The input which is split into blocks for compression.
+    blocks: Blocks,
+}
+
+impl RMD256 {
+    /// The length of the RMD256 digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new RMD256 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL,
+            blocks: Blocks::new(),
+        }
+    }
+
+    /// Re-initialize the RMD256 structure.
+    fn reset(&mut self) {
+        self.state = INITIAL;
+        self.blocks.reset();
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for RMD256 {
+    /// Update the RMD256 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// Finalize the RMD256 digest computation and return the result. The
+    /// RMD256 structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let state = &mut self.state;
+        self.blocks.finish(|block| compress(state, block));
+        let mut digest = [0u8; Self::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+
+    /// Export the current state of the RMD256 digest.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u32s(&self.state);
+        self.blocks.export(&mut state);
+        Some(state.finish())
+    }
+
+    /// Create a RMD256 digest from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let words = state.u32s()?;
+        let blocks = Blocks::import(&mut state)?;
+        state.finish()?;
+        Some(Self {
+            state: words,
+            blocks,
+        })
+    }
+}
+
+impl Default for RMD256 {
+    /// Create a default RMD256 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress a single `block` into the `state`. RIPEMD-256 uses the
+/// RIPEMD-128 compression function, but keeps the two lines separate and
+/// exchanges a register between them after each round.
+fn compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    let mut left: [u32; 4] = state[..4].try_into().unwrap();
+    let mut right: [u32; 4] = state[4..].try_into().unwrap();
+    for round in 0..4 {
+        for i in round * 16..(round + 1) * 16 {
+            left = step(
+                left,
+                f(round, left[1], left[2], left[3]),
+                block[R_LEFT[i]].wrapping_add(K_LEFT[round]),
+                S_LEFT[i],
+            );
+            right = step(
+                right,
+                f(3 - round, right[1], right[2], right[3]),
+                block[R_RIGHT[i]].wrapping_add(K_RIGHT[round]),
+                S_RIGHT[i],
+            );
+        }
+        std::mem::swap(&mut left[round], &mut right[round]);
+    }
+    for (word, value) in state.iter_mut().zip(left.into_iter().chain(right)) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// Perform a single step of one line, given the result of the boolean
+/// function `f`, the message word `x` (with the round constant added) and
+/// the rotation `s`. The register names follow the RIPEMD specification.
+#[allow(clippy::many_single_char_names)]
+fn step([a, b, c, d]: [u32; 4], f: u32, x: u32, s: u32) -> [u32; 4] {
+    let t = a.wrapping_add(f).wrapping_add(x).rotate_left(s);
+    [d, t, b, c]
+}
+
+/// Structure used to compute an RMD256 digest in a separate thread.
+pub struct BackgroundRMD256 {
+    worker: Background<{ RMD256::LENGTH }>,
+}
+
+impl BackgroundRMD256 {
+    /// Create a new `BackgroundRMD256` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(RMD256::new),
+        }
+    }
+
+    /// Create a `BackgroundRMD256` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<RMD256>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundRMD256 {
+    /// Add the given `data` to the RMD256 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the RMD256 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::RMD256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the RMD256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the RMD256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::RMD256.name()
+    }
+
+    /// The length of the RMD256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::RMD256.output_len()
+    }
+
+    /// Copy the current state of the RMD256 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the RMD256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut rmd256 = RMD256::new();
+        assert_eq!(rmd256.finish(), fixtures::rmd256::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut rmd256 = RMD256::new();
+        rmd256.update(&[0; 0x4000]);
+        rmd256.update(&[0; 0x0d]);
+        assert_eq!(rmd256.finish(), fixtures::rmd256::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut rmd256 = RMD256::new();
+        rmd256.update(&fixtures::RANDOM_11171);
+        assert_eq!(rmd256.finish(), fixtures::rmd256::RANDOM_11171);
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut rmd256 = RMD256::new();
+        for chunk in fixtures::RANDOM_11171.chunks(55) {
+            rmd256.update(chunk);
+        }
+        assert_eq!(rmd256.finish(), fixtures::rmd256::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut rmd256 = RMD256::new();
+        assert_eq!(rmd256.finish(), fixtures::rmd256::EMPTY);
+        rmd256.update(&fixtures::ZERO_400D);
+        assert_eq!(rmd256.finish(), fixtures::rmd256::ZERO_400D);
+        rmd256.update(&fixtures::RANDOM_11171);
+        assert_eq!(rmd256.finish(), fixtures::rmd256::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let rmd256 = BackgroundRMD256::new();
+        assert_eq!(
+            rmd256.result(),
+            DigestData::RMD256(fixtures::rmd256::EMPTY)
+        );
+        rmd256.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            rmd256.result(),
+            DigestData::RMD256(fixtures::rmd256::ZERO_400D)
+        );
+        rmd256.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            rmd256.result(),
+            DigestData::RMD256(fixtures::rmd256::RANDOM_11171)
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The number of bytes processed by each compression of the MD2 digest.
+const BLOCK: usize = 16;
+
+/// The permutation of the bytes derived from the digits of pi, as given
+/// in RFC 1319.
+const PI_SUBST: [u8; 256] = [
+    0x29, 0x2e, 0x43, 0xc9, 0xa2, 0xd8, 0x7c, 0x01, 0x3d, 0x36, 0x54, 0xa1,
+    0xec, 0xf0, 0x06, 0x13, 0x62, 0xa7, 0x05, 0xf3, 0xc0, 0xc7, 0x73, 0x8c,
+    0x98, 0x93, 0x2b, 0xd9, 0xbc, 0x4c, 0x82, 0xca, 0x1e, 0x9b, 0x57, 0x3c,
+    0xfd, 0xd4, 0xe0, 0x16, 0x67, 0x42, 0x6f, 0x18, 0x8a, 0x17, 0xe5, 0x12,
+    0xbe, 0x4e, 0xc4, 0xd6, 0xda, 0x9e, 0xde, 0x49, 0xa0, 0xfb, 0xf5, 0x8e,
+    0xbb, 0x2f, 0xee, 0x7a, 0xa9, 0x68, 0x79, 0x91, 0x15, 0xb2, 0x07, 0x3f,
+    0x94, 0xc2, 0x10, 0x89, 0x0b, 0x22, 0x5f, 0x21, 0x80, 0x7f, 0x5d, 0x9a,
+    0x5a, 0x90, 0x32, 0x27, 0x35, 0x3e, 0xcc, 0xe7, 0xbf, 0xf7, 0x97, 0x03,
+    0xff, 0x19, 0x30, 0xb3, 0x48, 0xa5, 0xb5, 0xd1, 0xd7, 0x5e, 0x92, 0x2a,
+    0xac, 0x56, 0xaa, 0xc6, 0x4f, 0xb8, 0x38, 0xd2, 0x96, 0xa4, 0x7d, 0xb6,
+    0x76, 0xfc, 0x6b, 0xe2, 0x9c, 0x74, 0x04, 0xf1, 0x45, 0x9d, 0x70, 0x59,
+    0x64, 0x71, 0x87, 0x20, 0x86, 0x5b, 0xcf, 0x65, 0xe6, 0x2d, 0xa8, 0x02,
+    0x1b, 0x60, 0x25, 0xad, 0xae, 0xb0, 0xb9, 0xf6, 0x1c, 0x46, 0x61, 0x69,
+    0x34, 0x40, 0x7e, 0x0f, 0x55, 0x47, 0xa3, 0x23, 0xdd, 0x51, 0xaf, 0x3a,
+    0xc3, 0x5c, 0xf9, 0xce, 0xba, 0xc5, 0xea, 0x26, 0x2c, 0x53, 0x0d, 0x6e,
+    0x85, 0x28, 0x84, 0x09, 0xd3, 0xdf, 0xcd, 0xf4, 0x41, 0x81, 0x4d, 0x52,
+    0x6a, 0xdc, 0x37, 0xc8, 0x6c, 0xc1, 0xab, 0xfa, 0x24, 0xe1, 0x7b, 0x08,
+    0x0c, 0xbd, 0xb1, 0x4a, 0x78, 0x88, 0x95, 0x8b, 0xe3, 0x63, 0xe8, 0x6d,
+    0xe9, 0xcb, 0xd5, 0xfe, 0x3b, 0x00, 0x1d, 0x39, 0xf2, 0xef, 0xb7, 0x0e,
+    0x66, 0x58, 0xd0, 0xe4, 0xa6, 0x77, 0x72, 0xf8, 0xeb, 0x75, 0x4b, 0x0a,
+    0x31, 0x44, 0x50, 0xb4, 0x8f, 0xed, 0x1f, 0x1a, 0xdb, 0x99, 0x8d, 0x33,
+    0x9f, 0x11, 0x83, 0x14,
+];
+
+/// A structure used to generate a MD2 digest without OpenSSL.
+#[derive(Clone)]
+pub struct MD2 {
+    /// The 48 byte state, of which the first 16 bytes are the digest.
+    state: [u8; 48],
+    /// The running checksum of the message, which is appended to it.
+    checksum: [u8; BLOCK],
+    /// Input which has not yet filled a full block.
+    buffer: [u8; BLOCK],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+}
+
+impl MD2 {
+    /// The length of the MD2 digest, in bytes.
+    pub const LENGTH: usize = 16;
+
+    /// Create a new MD2 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: [0; 48],
+            checksum: [0; BLOCK],
+            buffer: [0; BLOCK],
+            buffered: 0,
+        }
+    }
+
+    /// Re-initialize the MD2 structure.
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Process a single `block` of the message, updating both the state
+    /// and the checksum.
+    fn compress(&mut self, block: &[u8]) {
+        let mut last = self.checksum[BLOCK - 1];
+        for (sum, byte) in self.checksum.iter_mut().zip(block) {
+            *sum ^= PI_SUBST[usize::from(byte ^ last)];
+            last = *sum;
+        }
+        self.transform(block);
+    }
+
+    /// Mix a single `block` into the state, without updating the
+    /// checksum, as is done for the checksum itself at the end.
+    fn transform(&mut self, block: &[u8]) {
+        for (i, byte) in block.iter().enumerate() {
+            self.state[BLOCK + i] = *byte;
+            self.state[2 * BLOCK + i] = byte ^ self.state[i];
+        }
+        let mut t = 0u8;
+        for round in 0..18u8 {
+            for byte in &mut self.state {
+                *byte ^= PI_SUBST[usize::from(t)];
+                t = *byte;
+            }
+            t = t.wrapping_add(round);
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for MD2 {
+    /// Update the MD2 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (BLOCK - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < BLOCK {
+                return;
+            }
+            let buffer = self.buffer;
+            self.compress(&buffer);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Finalize the MD2 digest computation and return the result. The MD2
+    /// structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let count = BLOCK - self.buffered;
+        let padding = [u8::try_from(count).unwrap_or_default(); BLOCK];
+        self.update(&padding[..count]);
+        let checksum = self.checksum;
+        self.transform(&checksum);
+        let mut digest = [0u8; Self::LENGTH];
+        digest.copy_from_slice(&self.state[..Self::LENGTH]);
+        self.reset();
+        digest
+    }
+}
+
+impl Default for MD2 {
+    /// Create a default MD2 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute a MD2 digest in a separate thread, without
+/// OpenSSL.
+pub struct BackgroundMD2 {
+    worker: Background<{ MD2::LENGTH }>,
+}
+
+impl BackgroundMD2 {
+    /// Create a new `BackgroundMD2` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(MD2::new),
+        }
+    }
+}
+
+impl Generator for BackgroundMD2 {
+    /// Add the given `data` to the MD2 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the MD2 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD2(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD2 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD2 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD2.name()
+    }
+
+    /// The length of the MD2 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD2.output_len()
+    }
+
+    /// Copy the current state of the MD2 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD2 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut md2 = MD2::new();
+        assert_eq!(md2.finish(), fixtures::md2::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut md2 = MD2::new();
+        md2.update(&[0; 0x4000]);
+        md2.update(&[0; 0x0d]);
+        assert_eq!(md2.finish(), fixtures::md2::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut md2 = MD2::new();
+        md2.update(&fixtures::RANDOM_11171);
+        assert_eq!(md2.finish(), fixtures::md2::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut md2 = MD2::new();
+        assert_eq!(md2.finish(), fixtures::md2::EMPTY);
+        md2.update(&fixtures::ZERO_400D);
+        assert_eq!(md2.finish(), fixtures::md2::ZERO_400D);
+        md2.update(&fixtures::RANDOM_11171);
+        assert_eq!(md2.finish(), fixtures::md2::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let md2 = BackgroundMD2::new();
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::EMPTY));
+        md2.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(md2.result(), DigestData::MD2(fixtures::md2::ZERO_400D));
+    }
+}
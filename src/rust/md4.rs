@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use crate::ripemd::Blocks;
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The initial state of the MD4 digest.
+const INITIAL: [u32; 4] =
+    [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+/// The constant added in each round.
+const K: [u32; 3] = [0x0000_0000, 0x5a82_7999, 0x6ed9_eba1];
+
+/// The order in which message words are used in each round.
+#[rustfmt::skip]
+const R: [[usize; 16]; 3] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+];
+
+/// The rotations applied in each round, which repeat every four steps.
+const S: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+/// A structure used to generate a MD4 digest without OpenSSL.
+#[derive(Clone)]
+pub struct MD4 {
+    /// The chaining state.
+    state: [u32; 4],
+    /// The input which is split into blocks for compression.
+    blocks: Blocks,
+}
+
+impl MD4 {
+    /// The length of the MD4 digest, in bytes.
+    pub const LENGTH: usize = 16;
+
+    /// Create a new MD4 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL,
+            blocks: Blocks::new(),
+        }
+    }
+
+    /// Re-initialize the MD4 structure.
+    fn reset(&mut self) {
+        self.state = INITIAL;
+        self.blocks.reset();
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for MD4 {
+    /// Update the MD4 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// Finalize the MD4 digest computation and return the result. The MD4
+    /// structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let state = &mut self.state;
+        self.blocks.finish(|block| compress(state, block));
+        let mut digest = [0u8; Self::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+}
+
+impl Default for MD4 {
+    /// Create a default MD4 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress a single `block` into the `state`.
+#[allow(clippy::many_single_char_names)]
+fn compress(state: &mut [u32; 4], block: &[u32; 16]) {
+    let [mut a, mut b, mut c, mut d] = *state;
+    for round in 0..3 {
+        for i in 0..16 {
+            let f = match round {
+                0 => (b & c) | (!b & d),
+                1 => (b & c) | (b & d) | (c & d),
+                _ => b ^ c ^ d,
+            };
+            let t = a
+                .wrapping_add(f)
+                .wrapping_add(block[R[round][i]])
+                .wrapping_add(K[round])
+                .rotate_left(S[round][i % 4]);
+            (a, b, c, d) = (d, t, b, c);
+        }
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// Structure used to compute a MD4 digest in a separate thread, without
+/// OpenSSL.
+pub struct BackgroundMD4 {
+    worker: Background<{ MD4::LENGTH }>,
+}
+
+impl BackgroundMD4 {
+    /// Create a new `BackgroundMD4` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(MD4::new),
+        }
+    }
+}
+
+impl Generator for BackgroundMD4 {
+    /// Add the given `data` to the MD4 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the MD4 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD4(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD4 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD4 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD4.name()
+    }
+
+    /// The length of the MD4 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD4.output_len()
+    }
+
+    /// Copy the current state of the MD4 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD4 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut md4 = MD4::new();
+        assert_eq!(md4.finish(), fixtures::md4::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut md4 = MD4::new();
+        md4.update(&[0; 0x4000]);
+        md4.update(&[0; 0x0d]);
+        assert_eq!(md4.finish(), fixtures::md4::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut md4 = MD4::new();
+        md4.update(&fixtures::RANDOM_11171);
+        assert_eq!(md4.finish(), fixtures::md4::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut md4 = MD4::new();
+        assert_eq!(md4.finish(), fixtures::md4::EMPTY);
+        md4.update(&fixtures::ZERO_400D);
+        assert_eq!(md4.finish(), fixtures::md4::ZERO_400D);
+        md4.update(&fixtures::RANDOM_11171);
+        assert_eq!(md4.finish(), fixtures::md4::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let md4 = BackgroundMD4::new();
+        assert_eq!(md4.result(), DigestData::MD4(fixtures::md4::EMPTY));
+        md4.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(md4.result(), DigestData::MD4(fixtures::md4::ZERO_400D));
+    }
+}
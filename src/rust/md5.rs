@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use crate::ripemd::Blocks;
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The initial state of the MD5 digest.
+const INITIAL: [u32; 4] =
+    [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+/// The constant added at each step, which is the integer part of
+/// `abs(sin(i + 1)) * 2^32`.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0xd76a_a478, 0xe8c7_b756, 0x2420_70db, 0xc1bd_ceee,
+    0xf57c_0faf, 0x4787_c62a, 0xa830_4613, 0xfd46_9501,
+    0x6980_98d8, 0x8b44_f7af, 0xffff_5bb1, 0x895c_d7be,
+    0x6b90_1122, 0xfd98_7193, 0xa679_438e, 0x49b4_0821,
+    0xf61e_2562, 0xc040_b340, 0x265e_5a51, 0xe9b6_c7aa,
+    0xd62f_105d, 0x0244_1453, 0xd8a1_e681, 0xe7d3_fbc8,
+    0x21e1_cde6, 0xc337_07d6, 0xf4d5_0d87, 0x455a_14ed,
+    0xa9e3_e905, 0xfcef_a3f8, 0x676f_02d9, 0x8d2a_4c8a,
+    0xfffa_3942, 0x8771_f681, 0x6d9d_6122, 0xfde5_380c,
+    0xa4be_ea44, 0x4bde_cfa9, 0xf6bb_4b60, 0xbebf_bc70,
+    0x289b_7ec6, 0xeaa1_27fa, 0xd4ef_3085, 0x0488_1d05,
+    0xd9d4_d039, 0xe6db_99e5, 0x1fa2_7cf8, 0xc4ac_5665,
+    0xf429_2244, 0x432a_ff97, 0xab94_23a7, 0xfc93_a039,
+    0x655b_59c3, 0x8f0c_cc92, 0xffef_f47d, 0x8584_5dd1,
+    0x6fa8_7e4f, 0xfe2c_e6e0, 0xa301_4314, 0x4e08_11a1,
+    0xf753_7e82, 0xbd3a_f235, 0x2ad7_d2bb, 0xeb86_d391,
+];
+
+/// The rotations applied in each round, which repeat every four steps.
+const S: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+/// A structure used to generate a MD5 digest without OpenSSL.
+#[derive(Clone)]
+pub struct MD5 {
+    /// The chaining state.
+    state: [u32; 4],
+    /// The input which is split into blocks for compression.
+    blocks: Blocks,
+}
+
+impl MD5 {
+    /// The length of the MD5 digest, in bytes.
+    pub const LENGTH: usize = 16;
+
+    /// Create a new MD5 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL,
+            blocks: Blocks::new(),
+        }
+    }
+
+    /// Re-initialize the MD5 structure.
+    fn reset(&mut self) {
+        self.state = INITIAL;
+        self.blocks.reset();
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for MD5 {
+    /// Update the MD5 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| compress(state, block));
+    }
+
+    /// Finalize the MD5 digest computation and return the result. The MD5
+    /// structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let state = &mut self.state;
+        self.blocks.finish(|block| compress(state, block));
+        let mut digest = [0u8; Self::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.reset();
+        digest
+    }
+}
+
+impl Default for MD5 {
+    /// Create a default MD5 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress a single `block` into the `state`.
+#[allow(clippy::many_single_char_names)]
+fn compress(state: &mut [u32; 4], block: &[u32; 16]) {
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let round = i / 16;
+        let (f, g) = match round {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((b & d) | (c & !d), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let t = a
+            .wrapping_add(f)
+            .wrapping_add(block[g])
+            .wrapping_add(K[i])
+            .rotate_left(S[round][i % 4]);
+        (a, b, c, d) = (d, b.wrapping_add(t), b, c);
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// Structure used to compute a MD5 digest in a separate thread, without
+/// OpenSSL.
+pub struct BackgroundMD5 {
+    worker: Background<{ MD5::LENGTH }>,
+}
+
+impl BackgroundMD5 {
+    /// Create a new `BackgroundMD5` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(MD5::new),
+        }
+    }
+}
+
+impl Generator for BackgroundMD5 {
+    /// Add the given `data` to the MD5 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the MD5 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::MD5(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the MD5 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the MD5 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::MD5.name()
+    }
+
+    /// The length of the MD5 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::MD5.output_len()
+    }
+
+    /// Copy the current state of the MD5 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the MD5 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut md5 = MD5::new();
+        assert_eq!(md5.finish(), fixtures::md5::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut md5 = MD5::new();
+        md5.update(&[0; 0x4000]);
+        md5.update(&[0; 0x0d]);
+        assert_eq!(md5.finish(), fixtures::md5::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut md5 = MD5::new();
+        md5.update(&fixtures::RANDOM_11171);
+        assert_eq!(md5.finish(), fixtures::md5::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut md5 = MD5::new();
+        assert_eq!(md5.finish(), fixtures::md5::EMPTY);
+        md5.update(&fixtures::ZERO_400D);
+        assert_eq!(md5.finish(), fixtures::md5::ZERO_400D);
+        md5.update(&fixtures::RANDOM_11171);
+        assert_eq!(md5.finish(), fixtures::md5::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let md5 = BackgroundMD5::new();
+        assert_eq!(md5.result(), DigestData::MD5(fixtures::md5::EMPTY));
+        md5.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(md5.result(), DigestData::MD5(fixtures::md5::ZERO_400D));
+    }
+}
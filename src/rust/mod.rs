@@ -0,0 +1,9 @@
+//! Pure Rust implementations of digests which are otherwise computed by
+//! OpenSSL, so that the `Rust` backend can be used where OpenSSL lacks
+//! them (MD2 is omitted from most builds, and MD4 needs the legacy
+//! provider), and compared against OpenSSL by `checksum bench`.
+
+pub mod md2;
+pub mod md4;
+pub mod md5;
+pub mod sha256;
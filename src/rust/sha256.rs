@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// The number of bytes processed by each compression of the SHA256 digest.
+const BLOCK: usize = 64;
+
+/// The initial state of the SHA256 digest.
+const INITIAL: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// The constant added in each round, which is the fractional part of the
+/// cube root of each of the first 64 primes.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5,
+    0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3,
+    0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc,
+    0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7,
+    0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13,
+    0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3,
+    0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5,
+    0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208,
+    0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+/// A structure used to generate a SHA256 digest without OpenSSL.
+#[derive(Clone)]
+pub struct SHA256 {
+    /// The chaining state.
+    state: [u32; 8],
+    /// Input which has not yet filled a full block.
+    buffer: [u8; BLOCK],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes processed.
+    total: u64,
+}
+
+impl SHA256 {
+    /// The length of the SHA256 digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new SHA256 structure to generate a digest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL,
+            buffer: [0; BLOCK],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    /// Re-initialize the SHA256 structure.
+    fn reset(&mut self) {
+        self.state = INITIAL;
+        self.buffered = 0;
+        self.total = 0;
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for SHA256 {
+    /// Update the SHA256 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.total = self.total.wrapping_add(data.len() as u64);
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (BLOCK - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < BLOCK {
+                return;
+            }
+            compress(&mut self.state, &self.buffer);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK);
+        for block in &mut blocks {
+            compress(&mut self.state, block);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Finalize the SHA256 digest computation and return the result. The
+    /// SHA256 structure is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let bits = self.total.wrapping_mul(8);
+        let mut padding = [0; BLOCK];
+        padding[0] = 0x80;
+        let count = if self.buffered < BLOCK - 8 {
+            BLOCK - 8 - self.buffered
+        } else {
+            2 * BLOCK - 8 - self.buffered
+        };
+        self.update(&padding[..count]);
+        self.update(&bits.to_be_bytes());
+        let mut digest = [0u8; Self::LENGTH];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        self.reset();
+        digest
+    }
+}
+
+impl Default for SHA256 {
+    /// Create a default SHA256 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress a single 64 byte `block` into the `state`.
+#[allow(clippy::many_single_char_names)]
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7)
+            ^ w[i - 15].rotate_right(18)
+            ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17)
+            ^ w[i - 2].rotate_right(19)
+            ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in K.iter().zip(w) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        (h, g, f, e) = (g, f, e, d.wrapping_add(t1));
+        (d, c, b, a) = (c, b, a, t1.wrapping_add(t2));
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// Structure used to compute a SHA256 digest in a separate thread, without
+/// OpenSSL.
+pub struct BackgroundSHA256 {
+    worker: Background<{ SHA256::LENGTH }>,
+}
+
+impl BackgroundSHA256 {
+    /// Create a new `BackgroundSHA256` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(SHA256::new),
+        }
+    }
+}
+
+impl Generator for BackgroundSHA256 {
+    /// Add the given `data` to the SHA256 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the SHA256 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::SHA256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the SHA256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the SHA256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::SHA256.name()
+    }
+
+    /// The length of the SHA256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::SHA256.output_len()
+    }
+
+    /// Copy the current state of the SHA256 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the SHA256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut sha256 = SHA256::new();
+        assert_eq!(sha256.finish(), fixtures::sha256::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut sha256 = SHA256::new();
+        sha256.update(&[0; 0x4000]);
+        sha256.update(&[0; 0x0d]);
+        assert_eq!(sha256.finish(), fixtures::sha256::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut sha256 = SHA256::new();
+        sha256.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha256.finish(), fixtures::sha256::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut sha256 = SHA256::new();
+        assert_eq!(sha256.finish(), fixtures::sha256::EMPTY);
+        sha256.update(&fixtures::ZERO_400D);
+        assert_eq!(sha256.finish(), fixtures::sha256::ZERO_400D);
+        sha256.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha256.finish(), fixtures::sha256::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let sha256 = BackgroundSHA256::new();
+        assert_eq!(
+            sha256.result(),
+            DigestData::SHA256(fixtures::sha256::EMPTY)
+        );
+        sha256.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            sha256.result(),
+            DigestData::SHA256(fixtures::sha256::ZERO_400D)
+        );
+    }
+}
@@ -1,18 +1,16 @@
 use std::sync::Arc;
 
-use openssl_sys::{
-    EVP_DigestFinal, EVP_DigestInit, EVP_DigestUpdate, EVP_MD_CTX_free,
-    EVP_MD_CTX_new, EVP_sha256, EVP_MAX_MD_SIZE, EVP_MD, EVP_MD_CTX,
-};
+use openssl_sys::EVP_sha256;
 
-use crate::{Background, Digest, DigestData, Generator};
+use crate::{
+    evp, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
 
 /// A structure used to generate a SHA256 digest.
+#[derive(Clone)]
 pub struct SHA256 {
     /// The OpenSSL context used to generate the digest.
-    ctx: *mut EVP_MD_CTX,
-    /// The OpenSSL SHA256 digest algorithm.
-    sha256: *const EVP_MD,
+    ctx: evp::Context,
 }
 
 impl SHA256 {
@@ -28,38 +26,28 @@ impl SHA256 {
     /// unless the OpenSSL API has fallen out of sync.
     #[must_use]
     pub fn new() -> Self {
-        let ctx = unsafe { EVP_MD_CTX_new() };
-        assert!(!ctx.is_null());
-        let sha256 = unsafe { EVP_sha256() };
-        assert!(!sha256.is_null());
-        let mut this = Self { ctx, sha256 };
-        this.reset();
-        this
-    }
-
-    /// Initialize the OpenSSL context for use computing an SHA256 digest.
-    fn reset(&mut self) {
-        unsafe { EVP_DigestInit(self.ctx, self.sha256) };
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_sha256() }),
+        }
     }
 }
 
 impl Digest<{ Self::LENGTH }> for SHA256 {
     /// Update the SHA256 digest using the given `data`.
     fn update(&mut self, data: &[u8]) {
-        unsafe {
-            EVP_DigestUpdate(self.ctx, data.as_ptr().cast(), data.len());
-        }
+        self.ctx.update(data);
     }
 
     /// Finalize the SHA256 digest computation and return the result. The
     /// OpenSSL context is reset so that it can be reused.
     fn finish(&mut self) -> [u8; Self::LENGTH] {
-        let mut len = 0;
-        let mut buffer = [0u8; EVP_MAX_MD_SIZE as usize];
-        unsafe { EVP_DigestFinal(self.ctx, buffer.as_mut_ptr(), &mut len) };
-        assert!(Self::LENGTH == len as usize);
-        self.reset();
-        buffer[..Self::LENGTH].try_into().unwrap()
+        self.ctx.finish()
+    }
+
+    /// Compute the SHA256 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_sha256() }, data)
     }
 }
 
@@ -70,13 +58,6 @@ impl Default for SHA256 {
     }
 }
 
-impl Drop for SHA256 {
-    /// Clean up the OpenSSL context.
-    fn drop(&mut self) {
-        unsafe { EVP_MD_CTX_free(self.ctx) };
-    }
-}
-
 /// Structure used to compute an SHA256 digest in a separate thread.
 pub struct BackgroundSHA256 {
     worker: Background<{ SHA256::LENGTH }>,
@@ -93,13 +74,41 @@ impl BackgroundSHA256 {
 
 impl Generator for BackgroundSHA256 {
     /// Add the given `data` to the SHA256 digest.
-    fn append(&self, data: Arc<[u8]>) {
-        self.worker.update(data);
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
     }
 
     /// Retrieve the SHA256 digest data, and reset the digest computation.
-    fn result(&self) -> DigestData {
-        DigestData::SHA256(self.worker.finish())
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::SHA256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the SHA256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the SHA256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::SHA256.name()
+    }
+
+    /// The length of the SHA256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::SHA256.output_len()
+    }
+
+    /// Copy the current state of the SHA256 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the SHA256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
     }
 }
 
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use openssl_sys::EVP_sha3_224;
+
+use crate::{
+    evp, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
+
+/// A structure used to generate a SHA3-224 digest.
+#[derive(Clone)]
+pub struct SHA3_224 {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl SHA3_224 {
+    /// The length of the SHA3-224 digest, in bytes.
+    pub const LENGTH: usize = 28;
+
+    /// Create a new SHA3-224 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the OpenSSL API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_sha3_224() }),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for SHA3_224 {
+    /// Update the SHA3-224 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the SHA3-224 digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the SHA3-224 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_sha3_224() }, data)
+    }
+}
+
+impl Default for SHA3_224 {
+    /// Create a default SHA3-224 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute an SHA3-224 digest in a separate thread.
+pub struct BackgroundSHA3_224 {
+    worker: Background<{ SHA3_224::LENGTH }>,
+}
+
+impl BackgroundSHA3_224 {
+    /// Create a new `BackgroundSHA3_224` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(SHA3_224::new),
+        }
+    }
+}
+
+impl Generator for BackgroundSHA3_224 {
+    /// Add the given `data` to the SHA3-224 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the SHA3-224 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::SHA3_224(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the SHA3-224 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the SHA3-224 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::SHA3_224.name()
+    }
+
+    /// The length of the SHA3-224 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::SHA3_224.output_len()
+    }
+
+    /// Copy the current state of the SHA3-224 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the SHA3-224 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut sha3_224 = SHA3_224::new();
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut sha3_224 = SHA3_224::new();
+        sha3_224.update(&[0; 0x4000]);
+        sha3_224.update(&[0; 0x0d]);
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut sha3_224 = SHA3_224::new();
+        sha3_224.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut sha3_224 = SHA3_224::new();
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::EMPTY);
+        sha3_224.update(&fixtures::ZERO_400D);
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::ZERO_400D);
+        sha3_224.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha3_224.finish(), fixtures::sha3_224::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let sha3_224 = BackgroundSHA3_224::new();
+        assert_eq!(
+            sha3_224.result(),
+            DigestData::SHA3_224(fixtures::sha3_224::EMPTY)
+        );
+        sha3_224.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            sha3_224.result(),
+            DigestData::SHA3_224(fixtures::sha3_224::ZERO_400D)
+        );
+        sha3_224.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            sha3_224.result(),
+            DigestData::SHA3_224(fixtures::sha3_224::RANDOM_11171)
+        );
+    }
+}
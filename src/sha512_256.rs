@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use openssl_sys::EVP_MD;
+
+use crate::{
+    evp, Background, Digest, DigestData, DigestKind, Error, Generator,
+};
+
+// The truncated SHA-512 digests are not exposed by `openssl_sys`, so we
+// need to declare the function we use ourselves.
+extern "C" {
+    fn EVP_sha512_256() -> *const EVP_MD;
+}
+
+/// A structure used to generate a SHA512/256 digest.
+#[derive(Clone)]
+pub struct SHA512_256 {
+    /// The OpenSSL context used to generate the digest.
+    ctx: evp::Context,
+}
+
+impl SHA512_256 {
+    /// The length of the SHA512/256 digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new SHA512/256 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the OpenSSL structures we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the OpenSSL API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: evp::Context::new(unsafe { EVP_sha512_256() }),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for SHA512_256 {
+    /// Update the SHA512/256 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the SHA512/256 digest computation and return the result. The
+    /// OpenSSL context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+
+    /// Compute the SHA512/256 digest of `data` with a single OpenSSL call,
+    /// rather than creating a context which can be reused.
+    fn digest(data: &[u8]) -> [u8; Self::LENGTH] {
+        evp::digest(unsafe { EVP_sha512_256() }, data)
+    }
+}
+
+impl Default for SHA512_256 {
+    /// Create a default SHA512/256 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute an SHA512/256 digest in a separate thread.
+pub struct BackgroundSHA512_256 {
+    worker: Background<{ SHA512_256::LENGTH }>,
+}
+
+impl BackgroundSHA512_256 {
+    /// Create a new `BackgroundSHA512_256` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(SHA512_256::new),
+        }
+    }
+}
+
+impl Generator for BackgroundSHA512_256 {
+    /// Add the given `data` to the SHA512/256 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the SHA512/256 digest data, and reset the digest computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::SHA512_256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the SHA512/256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the SHA512/256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::SHA512_256.name()
+    }
+
+    /// The length of the SHA512/256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::SHA512_256.output_len()
+    }
+
+    /// Copy the current state of the SHA512/256 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the SHA512/256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut sha512_256 = SHA512_256::new();
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut sha512_256 = SHA512_256::new();
+        sha512_256.update(&[0; 0x4000]);
+        sha512_256.update(&[0; 0x0d]);
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut sha512_256 = SHA512_256::new();
+        sha512_256.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut sha512_256 = SHA512_256::new();
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::EMPTY);
+        sha512_256.update(&fixtures::ZERO_400D);
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::ZERO_400D);
+        sha512_256.update(&fixtures::RANDOM_11171);
+        assert_eq!(sha512_256.finish(), fixtures::sha512_256::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let sha512_256 = BackgroundSHA512_256::new();
+        assert_eq!(
+            sha512_256.result(),
+            DigestData::SHA512_256(fixtures::sha512_256::EMPTY)
+        );
+        sha512_256.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            sha512_256.result(),
+            DigestData::SHA512_256(fixtures::sha512_256::ZERO_400D)
+        );
+        sha512_256.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            sha512_256.result(),
+            DigestData::SHA512_256(fixtures::sha512_256::RANDOM_11171)
+        );
+    }
+}
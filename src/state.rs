@@ -0,0 +1,195 @@
+//! Serialization of the state of the digests implemented in this crate,
+//! for `Digest::export_state()` and `Digest::import_state()`.
+
+/// Builds the state exported by `Digest::export_state()`. The values are
+/// written one after another, with integers in little endian order, so
+/// that the state can be imported on any platform.
+pub struct Exporter {
+    state: Vec<u8>,
+}
+
+impl Exporter {
+    /// Create a new `Exporter`, with no values written.
+    pub fn new() -> Self {
+        Self { state: Vec::new() }
+    }
+
+    /// Write a single byte.
+    pub fn u8(&mut self, value: u8) {
+        self.state.push(value);
+    }
+
+    /// Write a 16-bit value.
+    pub fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    /// Write a 32-bit value.
+    pub fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    /// Write a 64-bit value.
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    /// Write a length or count, which is always written as 64 bits.
+    pub fn usize(&mut self, value: usize) {
+        self.u64(value as u64);
+    }
+
+    /// Write a flag, as a single byte.
+    pub fn bool(&mut self, value: bool) {
+        self.u8(u8::from(value));
+    }
+
+    /// Write the `bytes` as they are.
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.state.extend_from_slice(bytes);
+    }
+
+    /// Write each of the 32-bit `values`.
+    pub fn u32s(&mut self, values: &[u32]) {
+        for value in values {
+            self.u32(*value);
+        }
+    }
+
+    /// Write each of the 64-bit `values`.
+    pub fn u64s(&mut self, values: &[u64]) {
+        for value in values {
+            self.u64(*value);
+        }
+    }
+
+    /// Return the exported state.
+    pub fn finish(self) -> Vec<u8> {
+        self.state
+    }
+}
+
+/// Reads back the state written by an `Exporter`. Each method returns
+/// `None` if the state is too short, so that truncated state is rejected
+/// rather than causing a panic.
+pub struct Importer<'a> {
+    state: &'a [u8],
+}
+
+impl<'a> Importer<'a> {
+    /// Create a new `Importer` which reads the given `state`.
+    pub fn new(state: &'a [u8]) -> Self {
+        Self { state }
+    }
+
+    /// Read a single byte.
+    pub fn u8(&mut self) -> Option<u8> {
+        let [value] = self.bytes()?;
+        Some(value)
+    }
+
+    /// Read a 16-bit value.
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes()?))
+    }
+
+    /// Read a 32-bit value.
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes()?))
+    }
+
+    /// Read a 64-bit value.
+    pub fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes()?))
+    }
+
+    /// Read a length or count, which must be no more than `limit`.
+    pub fn usize(&mut self, limit: usize) -> Option<usize> {
+        usize::try_from(self.u64()?)
+            .ok()
+            .filter(|value| *value <= limit)
+    }
+
+    /// Read a flag, which must be either zero or one.
+    pub fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Read `L` bytes as they are.
+    pub fn bytes<const L: usize>(&mut self) -> Option<[u8; L]> {
+        if self.state.len() < L {
+            return None;
+        }
+        let (bytes, rest) = self.state.split_at(L);
+        self.state = rest;
+        bytes.try_into().ok()
+    }
+
+    /// Read `L` 32-bit values.
+    pub fn u32s<const L: usize>(&mut self) -> Option<[u32; L]> {
+        let mut values = [0; L];
+        for value in &mut values {
+            *value = self.u32()?;
+        }
+        Some(values)
+    }
+
+    /// Read `L` 64-bit values.
+    pub fn u64s<const L: usize>(&mut self) -> Option<[u64; L]> {
+        let mut values = [0; L];
+        for value in &mut values {
+            *value = self.u64()?;
+        }
+        Some(values)
+    }
+
+    /// Check that all of the state has been read, as any left over means
+    /// it wasn't exported by the same kind of digest.
+    pub fn finish(self) -> Option<()> {
+        self.state.is_empty().then_some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_round_trip() {
+        let mut exporter = Exporter::new();
+        exporter.u8(0xa5);
+        exporter.u16(0x1234);
+        exporter.usize(7);
+        exporter.bool(true);
+        exporter.u32s(&[1, 2]);
+        exporter.bytes(b"abc");
+        let state = exporter.finish();
+        assert_eq!(state.len(), 1 + 2 + 8 + 1 + 8 + 3);
+        assert_eq!(state[1..3], [0x34, 0x12]);
+
+        let mut importer = Importer::new(&state);
+        assert_eq!(importer.u8(), Some(0xa5));
+        assert_eq!(importer.u16(), Some(0x1234));
+        assert_eq!(importer.usize(7), Some(7));
+        assert_eq!(importer.bool(), Some(true));
+        assert_eq!(importer.u32s(), Some([1, 2]));
+        assert_eq!(importer.bytes(), Some(*b"abc"));
+        assert_eq!(importer.finish(), Some(()));
+    }
+
+    #[test]
+    fn state_invalid() {
+        let state = [2, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(Importer::new(&state).bool(), None);
+        assert_eq!(Importer::new(&state[..4]).u64(), None);
+        assert_eq!(Importer::new(&state).usize(1), None);
+        assert_eq!(Importer::new(&state).usize(2), Some(2));
+        let mut importer = Importer::new(&state);
+        assert_eq!(importer.u16(), Some(2));
+        assert_eq!(importer.finish(), None);
+    }
+}
@@ -0,0 +1,233 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::vec;
+
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::{DigestData, Generator};
+
+/// A `Sink` which appends each chunk of bytes sent to it to one or more
+/// `Generator`s, so that the digests of data produced by a stream
+/// pipeline can be computed by forwarding the stream into it.
+pub struct HashSink {
+    generators: Vec<Box<dyn Generator>>,
+}
+
+impl HashSink {
+    /// Create a new `HashSink`, which appends the chunks sent to it to
+    /// each of the `generators`.
+    #[must_use]
+    pub fn new(generators: Vec<Box<dyn Generator>>) -> Self {
+        Self { generators }
+    }
+
+    /// Retrieve the digests of the chunks sent so far, in the same order
+    /// as the generators were given. The generators are reset, so further
+    /// chunks start new digests.
+    #[must_use]
+    pub fn result(&self) -> Vec<DigestData> {
+        self.generators
+            .iter()
+            .map(|generator| generator.result())
+            .collect()
+    }
+}
+
+impl<B: AsRef<[u8]>> Sink<B> for HashSink {
+    type Error = io::Error;
+
+    /// The sink is always ready, as the generators queue the chunks for
+    /// their background threads.
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Append the `chunk` to each of the generators.
+    fn start_send(self: Pin<&mut Self>, chunk: B) -> io::Result<()> {
+        let chunk = chunk.as_ref();
+        if !chunk.is_empty() {
+            let data: Arc<[u8]> = Arc::from(chunk);
+            for generator in &self.generators {
+                generator.try_append(Arc::clone(&data))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A `Stream` which consumes an inner stream of byte chunks, appending
+/// them to one or more `Generator`s, and then yields the digests of all
+/// the chunks, in the same order as the generators were given.
+///
+/// If the inner stream yields an error, the error is passed on and the
+/// stream ends without yielding any digests.
+pub struct DigestStream<S> {
+    inner: S,
+    generators: Vec<Box<dyn Generator>>,
+    /// The digests left to yield, once the inner stream has ended.
+    digests: Option<vec::IntoIter<DigestData>>,
+}
+
+impl<S> DigestStream<S> {
+    /// Create a new `DigestStream`, which appends the chunks from `inner`
+    /// to each of the `generators`.
+    pub fn new(inner: S, generators: Vec<Box<dyn Generator>>) -> Self {
+        Self {
+            inner,
+            generators,
+            digests: None,
+        }
+    }
+}
+
+impl<S, B, E> Stream for DigestStream<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<DigestData, E>;
+
+    /// Append the chunks from the inner stream to the generators until it
+    /// ends, and then yield each of the digests.
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(digests) = &mut self.digests {
+                return Poll::Ready(digests.next().map(Ok));
+            }
+            match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+                Some(Ok(chunk)) => {
+                    let chunk = chunk.as_ref();
+                    if !chunk.is_empty() {
+                        let data: Arc<[u8]> = Arc::from(chunk);
+                        for generator in &self.generators {
+                            generator.append(Arc::clone(&data));
+                        }
+                    }
+                }
+                Some(Err(err)) => {
+                    for generator in &self.generators {
+                        generator.reset();
+                    }
+                    self.digests = Some(Vec::new().into_iter());
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => {
+                    let digests: Vec<_> = self
+                        .generators
+                        .iter()
+                        .map(|generator| generator.result())
+                        .collect();
+                    self.digests = Some(digests.into_iter());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::task::Waker;
+
+    /// A stream which yields each of a list of items.
+    struct Items<T>(vec::IntoIter<T>);
+
+    impl<T: Unpin> Stream for Items<T> {
+        type Item = T;
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<T>> {
+            Poll::Ready(self.0.next())
+        }
+    }
+
+    /// Poll the `stream` until it ends, collecting the items it yields.
+    /// None of the streams used here ever return `Pending`.
+    fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(item)) =
+            Pin::new(&mut stream).poll_next(&mut cx)
+        {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn hash_sink() {
+        let mut sink = HashSink::new(vec![crate::md5(), crate::crc32()]);
+        let mut cx = Context::from_waker(Waker::noop());
+        let (head, tail) = fixtures::RANDOM_11171.split_at(0x1000);
+        for chunk in [head, &[], tail] {
+            let mut sink = Pin::new(&mut sink);
+            assert!(matches!(
+                Sink::<&[u8]>::poll_ready(sink.as_mut(), &mut cx),
+                Poll::Ready(Ok(()))
+            ));
+            sink.start_send(chunk).unwrap();
+        }
+        assert!(matches!(
+            Sink::<&[u8]>::poll_close(Pin::new(&mut sink), &mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(
+            sink.result(),
+            [
+                DigestData::MD5(fixtures::md5::RANDOM_11171),
+                DigestData::CRC32(fixtures::crc32::RANDOM_11171),
+            ]
+        );
+    }
+
+    #[test]
+    fn digest_stream() {
+        let chunks: Vec<Result<_, ()>> =
+            fixtures::RANDOM_11171.chunks(0x1000).map(Ok).collect();
+        let stream = DigestStream::new(
+            Items(chunks.into_iter()),
+            vec![crate::sha256(), crate::xxh64()],
+        );
+        assert_eq!(
+            collect(stream),
+            [
+                Ok(DigestData::SHA256(fixtures::sha256::RANDOM_11171)),
+                Ok(DigestData::XXH64(fixtures::xxh64::RANDOM_11171)),
+            ]
+        );
+    }
+
+    #[test]
+    fn digest_stream_error() {
+        let chunks = vec![Ok(&fixtures::ZERO_400D[..]), Err("failed")];
+        let stream =
+            DigestStream::new(Items(chunks.into_iter()), vec![crate::md5()]);
+        assert_eq!(collect(stream), [Err("failed")]);
+    }
+}
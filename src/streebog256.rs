@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use crate::gcrypt::{self, GCRY_MD_STRIBOG256};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// A structure used to generate a Streebog-256 digest.
+#[derive(Clone)]
+pub struct STREEBOG256 {
+    /// The libgcrypt context used to generate the digest.
+    ctx: gcrypt::Context,
+}
+
+impl STREEBOG256 {
+    /// The length of the Streebog-256 digest, in bytes.
+    pub const LENGTH: usize = 32;
+
+    /// Create a new Streebog-256 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the libgcrypt context we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the libgcrypt API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: gcrypt::Context::new(GCRY_MD_STRIBOG256),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for STREEBOG256 {
+    /// Update the Streebog-256 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the Streebog-256 digest computation and return the result.
+    /// The libgcrypt context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+}
+
+impl Default for STREEBOG256 {
+    /// Create a default Streebog-256 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute a Streebog-256 digest in a separate thread.
+pub struct BackgroundSTREEBOG256 {
+    worker: Background<{ STREEBOG256::LENGTH }>,
+}
+
+impl BackgroundSTREEBOG256 {
+    /// Create a new `BackgroundSTREEBOG256` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(STREEBOG256::new),
+        }
+    }
+}
+
+impl Generator for BackgroundSTREEBOG256 {
+    /// Add the given `data` to the Streebog-256 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the Streebog-256 digest data, and reset the digest
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::STREEBOG256(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the Streebog-256 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the Streebog-256 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::STREEBOG256.name()
+    }
+
+    /// The length of the Streebog-256 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::STREEBOG256.output_len()
+    }
+
+    /// Copy the current state of the Streebog-256 digest into a new
+    /// generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the Streebog-256 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut streebog256 = STREEBOG256::new();
+        assert_eq!(streebog256.finish(), fixtures::streebog256::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut streebog256 = STREEBOG256::new();
+        streebog256.update(&[0; 0x4000]);
+        streebog256.update(&[0; 0x0d]);
+        assert_eq!(streebog256.finish(), fixtures::streebog256::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut streebog256 = STREEBOG256::new();
+        streebog256.update(&fixtures::RANDOM_11171);
+        assert_eq!(streebog256.finish(), fixtures::streebog256::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut streebog256 = STREEBOG256::new();
+        assert_eq!(streebog256.finish(), fixtures::streebog256::EMPTY);
+        streebog256.update(&fixtures::ZERO_400D);
+        assert_eq!(streebog256.finish(), fixtures::streebog256::ZERO_400D);
+        streebog256.update(&fixtures::RANDOM_11171);
+        assert_eq!(streebog256.finish(), fixtures::streebog256::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let streebog256 = BackgroundSTREEBOG256::new();
+        assert_eq!(
+            streebog256.result(),
+            DigestData::STREEBOG256(fixtures::streebog256::EMPTY)
+        );
+        streebog256.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            streebog256.result(),
+            DigestData::STREEBOG256(fixtures::streebog256::ZERO_400D)
+        );
+        streebog256.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            streebog256.result(),
+            DigestData::STREEBOG256(fixtures::streebog256::RANDOM_11171)
+        );
+    }
+}
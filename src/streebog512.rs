@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use crate::gcrypt::{self, GCRY_MD_STRIBOG512};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// A structure used to generate a Streebog-512 digest.
+#[derive(Clone)]
+pub struct STREEBOG512 {
+    /// The libgcrypt context used to generate the digest.
+    ctx: gcrypt::Context,
+}
+
+impl STREEBOG512 {
+    /// The length of the Streebog-512 digest, in bytes.
+    pub const LENGTH: usize = 64;
+
+    /// Create a new Streebog-512 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the libgcrypt context we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the libgcrypt API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: gcrypt::Context::new(GCRY_MD_STRIBOG512),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for STREEBOG512 {
+    /// Update the Streebog-512 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the Streebog-512 digest computation and return the result.
+    /// The libgcrypt context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+}
+
+impl Default for STREEBOG512 {
+    /// Create a default Streebog-512 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute a Streebog-512 digest in a separate thread.
+pub struct BackgroundSTREEBOG512 {
+    worker: Background<{ STREEBOG512::LENGTH }>,
+}
+
+impl BackgroundSTREEBOG512 {
+    /// Create a new `BackgroundSTREEBOG512` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(STREEBOG512::new),
+        }
+    }
+}
+
+impl Generator for BackgroundSTREEBOG512 {
+    /// Add the given `data` to the Streebog-512 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the Streebog-512 digest data, and reset the digest
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::STREEBOG512(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the Streebog-512 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the Streebog-512 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::STREEBOG512.name()
+    }
+
+    /// The length of the Streebog-512 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::STREEBOG512.output_len()
+    }
+
+    /// Copy the current state of the Streebog-512 digest into a new
+    /// generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the Streebog-512 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut streebog512 = STREEBOG512::new();
+        assert_eq!(streebog512.finish(), fixtures::streebog512::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut streebog512 = STREEBOG512::new();
+        streebog512.update(&[0; 0x4000]);
+        streebog512.update(&[0; 0x0d]);
+        assert_eq!(streebog512.finish(), fixtures::streebog512::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut streebog512 = STREEBOG512::new();
+        streebog512.update(&fixtures::RANDOM_11171);
+        assert_eq!(streebog512.finish(), fixtures::streebog512::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut streebog512 = STREEBOG512::new();
+        assert_eq!(streebog512.finish(), fixtures::streebog512::EMPTY);
+        streebog512.update(&fixtures::ZERO_400D);
+        assert_eq!(streebog512.finish(), fixtures::streebog512::ZERO_400D);
+        streebog512.update(&fixtures::RANDOM_11171);
+        assert_eq!(streebog512.finish(), fixtures::streebog512::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let streebog512 = BackgroundSTREEBOG512::new();
+        assert_eq!(
+            streebog512.result(),
+            DigestData::STREEBOG512(fixtures::streebog512::EMPTY)
+        );
+        streebog512.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            streebog512.result(),
+            DigestData::STREEBOG512(fixtures::streebog512::ZERO_400D)
+        );
+        streebog512.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            streebog512.result(),
+            DigestData::STREEBOG512(fixtures::streebog512::RANDOM_11171)
+        );
+    }
+}
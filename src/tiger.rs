@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::gcrypt::{self, GCRY_MD_TIGER1};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// A structure used to generate a Tiger digest.
+#[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct TIGER {
+    /// The libgcrypt context used to generate the digest.
+    ctx: gcrypt::Context,
+}
+
+impl TIGER {
+    /// The length of the Tiger digest, in bytes.
+    pub const LENGTH: usize = 24;
+
+    /// Create a new Tiger structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the libgcrypt context we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the libgcrypt API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: gcrypt::Context::new(GCRY_MD_TIGER1),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for TIGER {
+    /// Update the Tiger digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the Tiger digest computation and return the result.
+    /// The libgcrypt context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+}
+
+impl Default for TIGER {
+    /// Create a default Tiger structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute a Tiger digest in a separate thread.
+pub struct BackgroundTIGER {
+    worker: Background<{ TIGER::LENGTH }>,
+}
+
+impl BackgroundTIGER {
+    /// Create a new `BackgroundTIGER` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(TIGER::new),
+        }
+    }
+}
+
+impl Generator for BackgroundTIGER {
+    /// Add the given `data` to the Tiger digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the Tiger digest data, and reset the digest
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::TIGER(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the Tiger digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the Tiger digest.
+    fn name(&self) -> &'static str {
+        DigestKind::TIGER.name()
+    }
+
+    /// The length of the Tiger digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::TIGER.output_len()
+    }
+
+    /// Copy the current state of the Tiger digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the Tiger digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut tiger = TIGER::new();
+        assert_eq!(tiger.finish(), fixtures::tiger::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut tiger = TIGER::new();
+        tiger.update(&[0; 0x4000]);
+        tiger.update(&[0; 0x0d]);
+        assert_eq!(tiger.finish(), fixtures::tiger::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut tiger = TIGER::new();
+        tiger.update(&fixtures::RANDOM_11171);
+        assert_eq!(tiger.finish(), fixtures::tiger::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut tiger = TIGER::new();
+        assert_eq!(tiger.finish(), fixtures::tiger::EMPTY);
+        tiger.update(&fixtures::ZERO_400D);
+        assert_eq!(tiger.finish(), fixtures::tiger::ZERO_400D);
+        tiger.update(&fixtures::RANDOM_11171);
+        assert_eq!(tiger.finish(), fixtures::tiger::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let tiger = BackgroundTIGER::new();
+        assert_eq!(tiger.result(), DigestData::TIGER(fixtures::tiger::EMPTY));
+        tiger.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            tiger.result(),
+            DigestData::TIGER(fixtures::tiger::ZERO_400D)
+        );
+        tiger.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            tiger.result(),
+            DigestData::TIGER(fixtures::tiger::RANDOM_11171)
+        );
+    }
+}
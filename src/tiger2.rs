@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use crate::gcrypt::{self, GCRY_MD_TIGER2};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+/// A structure used to generate a Tiger2 digest.
+#[derive(Clone)]
+pub struct TIGER2 {
+    /// The libgcrypt context used to generate the digest.
+    ctx: gcrypt::Context,
+}
+
+impl TIGER2 {
+    /// The length of the Tiger2 digest, in bytes.
+    pub const LENGTH: usize = 24;
+
+    /// Create a new Tiger2 structure to generate a digest.
+    ///
+    /// ## Panics
+    ///
+    /// If we are unable to initialize the libgcrypt context we use to
+    /// compute the digest, a panic will occur. This should not occur
+    /// unless the libgcrypt API has fallen out of sync.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ctx: gcrypt::Context::new(GCRY_MD_TIGER2),
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for TIGER2 {
+    /// Update the Tiger2 digest using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.ctx.update(data);
+    }
+
+    /// Finalize the Tiger2 digest computation and return the result.
+    /// The libgcrypt context is reset so that it can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        self.ctx.finish()
+    }
+}
+
+impl Default for TIGER2 {
+    /// Create a default Tiger2 structure to generate a digest.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure used to compute a Tiger2 digest in a separate thread.
+pub struct BackgroundTIGER2 {
+    worker: Background<{ TIGER2::LENGTH }>,
+}
+
+impl BackgroundTIGER2 {
+    /// Create a new `BackgroundTIGER2` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(TIGER2::new),
+        }
+    }
+}
+
+impl Generator for BackgroundTIGER2 {
+    /// Add the given `data` to the Tiger2 digest.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the Tiger2 digest data, and reset the digest
+    /// computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::TIGER2(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the Tiger2 digest so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the Tiger2 digest.
+    fn name(&self) -> &'static str {
+        DigestKind::TIGER2.name()
+    }
+
+    /// The length of the Tiger2 digest, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::TIGER2.output_len()
+    }
+
+    /// Copy the current state of the Tiger2 digest into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the Tiger2 digest, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut tiger2 = TIGER2::new();
+        assert_eq!(tiger2.finish(), fixtures::tiger2::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut tiger2 = TIGER2::new();
+        tiger2.update(&[0; 0x4000]);
+        tiger2.update(&[0; 0x0d]);
+        assert_eq!(tiger2.finish(), fixtures::tiger2::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut tiger2 = TIGER2::new();
+        tiger2.update(&fixtures::RANDOM_11171);
+        assert_eq!(tiger2.finish(), fixtures::tiger2::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut tiger2 = TIGER2::new();
+        assert_eq!(tiger2.finish(), fixtures::tiger2::EMPTY);
+        tiger2.update(&fixtures::ZERO_400D);
+        assert_eq!(tiger2.finish(), fixtures::tiger2::ZERO_400D);
+        tiger2.update(&fixtures::RANDOM_11171);
+        assert_eq!(tiger2.finish(), fixtures::tiger2::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let tiger2 = BackgroundTIGER2::new();
+        assert_eq!(
+            tiger2.result(),
+            DigestData::TIGER2(fixtures::tiger2::EMPTY)
+        );
+        tiger2.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            tiger2.result(),
+            DigestData::TIGER2(fixtures::tiger2::ZERO_400D)
+        );
+        tiger2.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            tiger2.result(),
+            DigestData::TIGER2(fixtures::tiger2::RANDOM_11171)
+        );
+    }
+}
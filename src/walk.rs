@@ -0,0 +1,595 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, FileType};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::glob;
+
+/// How symbolic links are treated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Symlinks {
+    /// Links given as arguments, and links to files found while walking a
+    /// tree, are followed. Links to directories in a tree are not.
+    #[default]
+    Files,
+    /// Every link is followed, although a directory which has already been
+    /// walked is skipped, so a link can't cause a loop.
+    Follow,
+    /// No link is followed, so links are skipped.
+    NoFollow,
+    /// No link is followed, but every link is included as a file, with the
+    /// path it points to digested in place of the contents of its target.
+    Literal,
+}
+
+/// The options controlling which files are found while walking a tree.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options<'a> {
+    /// The patterns for files and directories to skip.
+    pub exclude: &'a [String],
+    /// Skip the files and directories ignored by the `.gitignore` files in
+    /// the tree, along with any `.git` directory.
+    pub gitignore: bool,
+    /// How symbolic links in the tree are treated.
+    pub symlinks: Symlinks,
+    /// How many levels of directories below the root are walked, if
+    /// limited. The files directly in the root are at a depth of 1.
+    pub max_depth: Option<usize>,
+    /// Called with each file or directory which is skipped, and why, if
+    /// set.
+    pub log: Option<fn(&Path, Skip)>,
+}
+
+/// Why a file or directory was skipped while walking a tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Skip {
+    /// It matched one of the exclude patterns.
+    Excluded,
+    /// It is ignored by git.
+    Ignored,
+    /// It is a symbolic link which isn't followed, or can't be.
+    Symlink,
+    /// It is neither a regular file nor a directory, such as a socket.
+    Special,
+    /// It is a directory deeper than the maximum depth.
+    TooDeep,
+    /// It is a directory which has already been walked, through a link.
+    Walked,
+}
+
+impl fmt::Display for Skip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Skip::Excluded => "excluded",
+            Skip::Ignored => "ignored by git",
+            Skip::Symlink => "symbolic link not followed",
+            Skip::Special => "not a regular file",
+            Skip::TooDeep => "deeper than the maximum depth",
+            Skip::Walked => "already searched",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// An iterator over the regular files in the tree under a root directory,
+/// in the order of their paths, as used by `checksum` to find the files in
+/// a directory. Symbolic links are followed as set in the `Options`. Files
+/// and directories which match any of the exclude patterns in the options
+/// (or are ignored by git) are skipped, along with everything under them,
+/// as are any files deeper than the maximum depth. Each one skipped is
+/// passed to the log in the options, if set.
+///
+/// The tree is walked lazily, reading each directory as it is reached. If
+/// a directory can't be read then its path is yielded as an error, and the
+/// walk carries on with the rest of the tree.
+pub struct Walker<'a> {
+    root: PathBuf,
+    options: Options<'a>,
+    /// The directories being walked, with the innermost last.
+    dirs: Vec<Dir>,
+    /// The directories which have been walked, when following links.
+    walked: HashSet<PathBuf>,
+    /// Whether the root directory has been read.
+    started: bool,
+}
+
+/// A directory being walked by a `Walker`.
+struct Dir {
+    /// The entries which are still to be visited, in reverse order.
+    entries: Vec<fs::DirEntry>,
+    /// The depth of the directory below the root.
+    depth: usize,
+    /// The `.gitignore` patterns which apply to the entries.
+    ignores: Vec<Ignore>,
+}
+
+impl<'a> Walker<'a> {
+    /// Create a new `Walker` for the tree under `root`, finding the files
+    /// as set by the `options`.
+    pub fn new(root: impl Into<PathBuf>, options: Options<'a>) -> Self {
+        Self {
+            root: root.into(),
+            options,
+            dirs: Vec::new(),
+            walked: HashSet::new(),
+            started: false,
+        }
+    }
+
+    /// Start walking the directory `dir`, at the given `depth` below the
+    /// root, where the `ignores` apply. If the directory can't be read
+    /// then its path is returned as the error.
+    fn enter(
+        &mut self,
+        dir: &Path,
+        depth: usize,
+        mut ignores: Vec<Ignore>,
+    ) -> Result<(), PathBuf> {
+        if self.options.symlinks == Symlinks::Follow {
+            let walking = fs::canonicalize(dir).unwrap_or(dir.to_path_buf());
+            if !self.walked.insert(walking) {
+                log_skip(self.options, dir, Skip::Walked);
+                return Ok(());
+            }
+        }
+        if self.options.gitignore {
+            let base = dir.strip_prefix(&self.root).unwrap_or(dir);
+            ignores.extend(read_gitignore(dir, base));
+        }
+        let mut entries = fs::read_dir(dir)
+            .and_then(Iterator::collect::<io::Result<Vec<_>>>)
+            .map_err(|_err| dir.to_path_buf())?;
+        // Sorting each directory by name, and walking the tree depth
+        // first, visits the files in the order of their paths.
+        entries.sort_by_key(|entry| Reverse(entry.file_name()));
+        self.dirs.push(Dir {
+            entries,
+            depth,
+            ignores,
+        });
+        Ok(())
+    }
+}
+
+impl Iterator for Walker<'_> {
+    type Item = Result<PathBuf, PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            let root = self.root.clone();
+            if let Err(dir) = self.enter(&root, 0, Vec::new()) {
+                return Some(Err(dir));
+            }
+        }
+        loop {
+            let dir = self.dirs.last_mut()?;
+            let Some(entry) = dir.entries.pop() else {
+                self.dirs.pop();
+                continue;
+            };
+            let (depth, symlinks) = (dir.depth, self.options.symlinks);
+            let path = entry.path();
+            let Some(kind) = entry_kind(&entry, symlinks) else {
+                log_skip(self.options, &path, Skip::Symlink);
+                continue;
+            };
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+            let is_dir = kind.is_dir();
+            if is_excluded(relative, self.options.exclude) {
+                log_skip(self.options, &path, Skip::Excluded);
+            } else if self.options.gitignore
+                && is_ignored(relative, is_dir, &dir.ignores)
+            {
+                log_skip(self.options, &path, Skip::Ignored);
+            } else if is_dir {
+                if self.options.max_depth.is_none_or(|max| depth + 1 < max) {
+                    let ignores = dir.ignores.clone();
+                    if let Err(dir) = self.enter(&path, depth + 1, ignores) {
+                        return Some(Err(dir));
+                    }
+                } else {
+                    log_skip(self.options, &path, Skip::TooDeep);
+                }
+            } else if kind.is_file() || kind.is_symlink() {
+                return Some(Ok(path));
+            } else {
+                log_skip(self.options, &path, Skip::Special);
+            }
+        }
+    }
+}
+
+/// Pass the `path` which was skipped, and why, to the log in the
+/// `options`, if set.
+fn log_skip(options: Options, path: &Path, skip: Skip) {
+    if let Some(log) = options.log {
+        log(path, skip);
+    }
+}
+
+/// List the regular files in the tree under `root`, sorted by path, as
+/// found by a `Walker` with the given `options`.
+///
+/// ## Errors
+///
+/// If a directory can't be read then its path is returned as the error.
+pub fn files(root: &Path, options: Options) -> Result<Vec<PathBuf>, PathBuf> {
+    Walker::new(root, options).collect()
+}
+
+/// The type of the file for a directory `entry`, following it if it is a
+/// symbolic link which the `symlinks` policy follows. A link is left as
+/// it is if it is taken literally. If the entry is a link which isn't
+/// followed, or can't be followed, then `None` is returned.
+fn entry_kind(entry: &fs::DirEntry, symlinks: Symlinks) -> Option<FileType> {
+    let kind = entry.file_type().ok()?;
+    if !kind.is_symlink() || symlinks == Symlinks::Literal {
+        return Some(kind);
+    }
+    let target = fs::metadata(entry.path()).ok()?.file_type();
+    match symlinks {
+        Symlinks::Follow => Some(target),
+        Symlinks::Files if target.is_file() => Some(target),
+        Symlinks::Files | Symlinks::NoFollow | Symlinks::Literal => None,
+    }
+}
+
+/// Check if a `path` given as an argument is a symbolic link which the
+/// `symlinks` policy doesn't follow.
+#[must_use]
+pub fn is_unfollowed(path: &Path, symlinks: Symlinks) -> bool {
+    symlinks == Symlinks::NoFollow
+        && fs::symlink_metadata(path).is_ok_and(|meta| meta.is_symlink())
+}
+
+/// Check if the path of a file or directory, `relative` to the root of the
+/// tree, matches any of the `exclude` patterns. A pattern containing a `/`
+/// is matched against the whole relative path, while any other pattern is
+/// matched against the name alone (so `.git` or `*.tmp` match at any
+/// depth).
+fn is_excluded(relative: &Path, exclude: &[String]) -> bool {
+    let Some(path) = relative.to_str() else {
+        return false;
+    };
+    let name = path.rsplit('/').next().unwrap_or(path);
+    exclude.iter().any(|pattern| {
+        if pattern.contains('/') {
+            glob::matches(pattern.trim_start_matches('/'), path)
+        } else {
+            glob::matches(pattern, name)
+        }
+    })
+}
+
+/// A pattern read from a `.gitignore` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ignore {
+    /// The directory holding the `.gitignore`, relative to the root of the
+    /// tree, which an anchored pattern is matched from.
+    base: PathBuf,
+    /// The glob pattern, without any `!` prefix or trailing `/`.
+    pattern: String,
+    /// The pattern started with a `!`, so it re-includes what it matches.
+    negated: bool,
+    /// The pattern ended with a `/`, so it only matches directories.
+    dir_only: bool,
+    /// The pattern contained a `/`, so it is matched against the path from
+    /// the base, rather than the name alone.
+    anchored: bool,
+}
+
+impl Ignore {
+    /// Parse a `line` of a `.gitignore` file in the `base` directory. Blank
+    /// lines and comments give `None`.
+    fn parse(line: &str, base: &Path) -> Option<Self> {
+        let line = line.trim_end_matches('\r').trim_end_matches(' ');
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        if line.is_empty() {
+            return None;
+        }
+        Some(Ignore {
+            base: base.to_path_buf(),
+            pattern: line.trim_start_matches('/').to_string(),
+            negated,
+            dir_only,
+            anchored: line.contains('/'),
+        })
+    }
+
+    /// Check if the pattern matches the file or directory at the path
+    /// `relative` to the root of the tree.
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Some(path) = relative
+            .strip_prefix(&self.base)
+            .ok()
+            .and_then(Path::to_str)
+        else {
+            return false;
+        };
+        if self.anchored {
+            glob::matches(&self.pattern, path)
+        } else {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            glob::matches(&self.pattern, name)
+        }
+    }
+}
+
+/// Read the patterns from the `.gitignore` file in `dir`, which is the
+/// `base` directory relative to the root of the tree. A missing or
+/// unreadable `.gitignore` has no patterns.
+fn read_gitignore(dir: &Path, base: &Path) -> Vec<Ignore> {
+    fs::read_to_string(dir.join(".gitignore"))
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| Ignore::parse(line, base))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check if the file or directory at the path `relative` to the root of
+/// the tree is ignored by git, given the `ignores` which apply to it. The
+/// last pattern which matches decides, as a negated pattern re-includes
+/// the path. Any `.git` directory is always ignored.
+fn is_ignored(relative: &Path, is_dir: bool, ignores: &[Ignore]) -> bool {
+    if is_dir && relative.file_name().is_some_and(|name| name == ".git") {
+        return true;
+    }
+    ignores
+        .iter()
+        .rev()
+        .find(|ignore| ignore.matches(relative, is_dir))
+        .is_some_and(|ignore| !ignore.negated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn walk_tree() {
+        let dir = fixtures::ScratchDir::new("walk");
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        for file in
+            ["b", "a.md5", "sub/c", "sub/c.tmp", "sub/deeper/d", ".git/e"]
+        {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        assert_eq!(
+            files(&dir, Options::default()).unwrap(),
+            [".git/e", "a.md5", "b", "sub/c", "sub/c.tmp", "sub/deeper/d"]
+                .map(|file| dir.join(file))
+        );
+        let exclude = [".git", "*.tmp", "sub/deeper"].map(String::from);
+        let options = Options {
+            exclude: &exclude,
+            ..Options::default()
+        };
+        assert_eq!(
+            files(&dir, options).unwrap(),
+            ["a.md5", "b", "sub/c"].map(|file| dir.join(file))
+        );
+        assert_eq!(
+            files(&dir.join("missing"), Options::default()),
+            Err(dir.join("missing"))
+        );
+
+        fs::write(dir.join(".gitignore"), "*.md5\n/deeper/\n").unwrap();
+        fs::write(dir.join("sub/.gitignore"), "*.tmp\n/deeper\n!a.md5\n")
+            .unwrap();
+        fs::write(dir.join("sub/a.md5"), "").unwrap();
+        let options = Options {
+            gitignore: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            files(&dir, options).unwrap(),
+            [".gitignore", "b", "sub/.gitignore", "sub/a.md5", "sub/c"]
+                .map(|file| dir.join(file))
+        );
+    }
+
+    #[test]
+    fn walker_order() {
+        let dir = fixtures::ScratchDir::new("walker");
+        fs::create_dir_all(dir.join("a")).unwrap();
+        for file in ["a.txt", "a/b", "a-b", "c"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let mut walker = Walker::new(dir.to_path_buf(), Options::default());
+        assert_eq!(walker.next(), Some(Ok(dir.join("a/b"))));
+        assert_eq!(
+            walker.collect::<Vec<_>>(),
+            ["a-b", "a.txt", "c"].map(|file| Ok(dir.join(file)))
+        );
+
+        let mut walker = Walker::new(dir.join("missing"), Options::default());
+        assert_eq!(walker.next(), Some(Err(dir.join("missing"))));
+        assert_eq!(walker.next(), None);
+    }
+
+    #[test]
+    fn walk_max_depth() {
+        let dir = fixtures::ScratchDir::new("depth");
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in ["a", "sub/b", "sub/deeper/c"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let walk = |max_depth| {
+            let options = Options {
+                max_depth,
+                ..Options::default()
+            };
+            files(&dir, options).unwrap()
+        };
+        assert_eq!(walk(Some(1)), [dir.join("a")]);
+        assert_eq!(walk(Some(2)), ["a", "sub/b"].map(|file| dir.join(file)));
+        assert_eq!(
+            walk(Some(3)),
+            ["a", "sub/b", "sub/deeper/c"].map(|file| dir.join(file))
+        );
+        assert_eq!(walk(Some(3)), walk(None));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = fixtures::ScratchDir::new("symlinks");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/a"), "").unwrap();
+        symlink("a", dir.join("sub/file")).unwrap();
+        symlink("missing", dir.join("sub/broken")).unwrap();
+        // A link back up the tree would loop forever if it was followed
+        // blindly.
+        symlink("..", dir.join("sub/loop")).unwrap();
+        let walk = |symlinks| {
+            let options = Options {
+                symlinks,
+                ..Options::default()
+            };
+            files(&dir, options).unwrap()
+        };
+
+        assert_eq!(
+            walk(Symlinks::Files),
+            ["sub/a", "sub/file"].map(|file| dir.join(file))
+        );
+        assert_eq!(walk(Symlinks::NoFollow), [dir.join("sub/a")]);
+        assert_eq!(
+            walk(Symlinks::Follow),
+            ["sub/a", "sub/file"].map(|file| dir.join(file))
+        );
+        assert_eq!(
+            walk(Symlinks::Literal),
+            ["sub/a", "sub/broken", "sub/file", "sub/loop"]
+                .map(|file| dir.join(file))
+        );
+
+        assert!(is_unfollowed(&dir.join("sub/file"), Symlinks::NoFollow));
+        assert!(!is_unfollowed(&dir.join("sub/file"), Symlinks::Files));
+        assert!(!is_unfollowed(&dir.join("sub/a"), Symlinks::NoFollow));
+        assert!(!is_unfollowed(&dir.join("sub/file"), Symlinks::Literal));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_skipped() {
+        use std::cell::RefCell;
+        use std::os::unix::{fs::symlink, net::UnixListener};
+
+        thread_local! {
+            static SKIPPED: RefCell<Vec<(PathBuf, Skip)>> =
+                const { RefCell::new(Vec::new()) };
+        }
+        fn log(path: &Path, skip: Skip) {
+            SKIPPED.with_borrow_mut(|skipped| {
+                skipped.push((path.to_path_buf(), skip));
+            });
+        }
+
+        let dir = fixtures::ScratchDir::new("skipped");
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in ["a", "b.tmp", "sub/deeper/c"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        symlink("sub", dir.join("link")).unwrap();
+        let _socket = UnixListener::bind(dir.join("socket")).unwrap();
+        let exclude = [String::from("*.tmp")];
+        let options = Options {
+            exclude: &exclude,
+            max_depth: Some(2),
+            log: Some(log),
+            ..Options::default()
+        };
+        assert_eq!(files(&dir, options).unwrap(), [dir.join("a")]);
+        let mut skipped = SKIPPED.take();
+        skipped.sort();
+        assert_eq!(
+            skipped,
+            [
+                (dir.join("b.tmp"), Skip::Excluded),
+                (dir.join("link"), Skip::Symlink),
+                (dir.join("socket"), Skip::Special),
+                (dir.join("sub/deeper"), Skip::TooDeep),
+            ]
+        );
+    }
+
+    #[test]
+    fn excluded_paths() {
+        let exclude =
+            ["node_modules", "*.tmp", "build/out"].map(String::from);
+        let excluded = |path| is_excluded(Path::new(path), &exclude);
+        assert!(excluded("node_modules"));
+        assert!(excluded("web/node_modules"));
+        assert!(excluded("foo.tmp"));
+        assert!(excluded("dir/foo.tmp"));
+        assert!(excluded("build/out"));
+        assert!(!excluded("src/build/out"));
+        assert!(!excluded("build"));
+        assert!(!excluded("foo.txt"));
+    }
+
+    #[test]
+    fn ignored_paths() {
+        let ignores: Vec<Ignore> = [
+            ("", "# A comment"),
+            ("", ""),
+            ("", "*.o"),
+            ("", "/target"),
+            ("", "build/"),
+            ("", "docs/**/*.html"),
+            ("", "!keep.o"),
+            ("sub", "local.txt"),
+            ("sub", "/only"),
+            ("", "\\#hash"),
+        ]
+        .iter()
+        .filter_map(|(base, line)| Ignore::parse(line, Path::new(base)))
+        .collect();
+        assert_eq!(ignores.len(), 8);
+        let is_match =
+            |path, is_dir| is_ignored(Path::new(path), is_dir, &ignores);
+        assert!(is_match("main.o", false));
+        assert!(is_match("src/main.o", false));
+        assert!(!is_match("keep.o", false));
+        assert!(is_match("target", true));
+        assert!(!is_match("src/target", true));
+        assert!(is_match("build", true));
+        assert!(is_match("src/build", true));
+        assert!(!is_match("build", false));
+        assert!(is_match("docs/api/index.html", false));
+        assert!(!is_match("index.html", false));
+        assert!(is_match("sub/local.txt", false));
+        assert!(is_match("sub/deeper/local.txt", false));
+        assert!(!is_match("local.txt", false));
+        assert!(is_match("sub/only", false));
+        assert!(!is_match("sub/deeper/only", false));
+        assert!(is_match("#hash", false));
+        assert!(is_match(".git", true));
+        assert!(is_match("sub/.git", true));
+        assert!(!is_match(".git", false));
+    }
+}
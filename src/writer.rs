@@ -0,0 +1,115 @@
+use std::io;
+use std::sync::Arc;
+
+use crate::{DigestData, Generator};
+
+/// A writer which passes everything written to it on to an inner writer,
+/// while appending the same data to one or more `Generator`s. This lets
+/// the digests of data be computed as it is written out, rather than
+/// reading it back again afterwards.
+pub struct HashWriter<W> {
+    inner: W,
+    generators: Vec<Box<dyn Generator>>,
+}
+
+impl<W: io::Write> HashWriter<W> {
+    /// Create a new `HashWriter`, which writes to `inner` and appends the
+    /// written data to each of the `generators`.
+    pub fn new(inner: W, generators: Vec<Box<dyn Generator>>) -> Self {
+        Self { inner, generators }
+    }
+
+    /// A reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Retrieve the digests of the data written so far, in the same order
+    /// as the generators were given. The generators are reset, so further
+    /// writes start new digests.
+    #[must_use]
+    pub fn result(&self) -> Vec<DigestData> {
+        self.generators
+            .iter()
+            .map(|generator| generator.result())
+            .collect()
+    }
+
+    /// Flush the inner writer, and return it along with the digests of
+    /// all the data written to it.
+    ///
+    /// ## Errors
+    ///
+    /// Any error flushing the inner writer is returned.
+    pub fn finish(mut self) -> io::Result<(W, Vec<DigestData>)> {
+        self.inner.flush()?;
+        let digests = self.result();
+        Ok((self.inner, digests))
+    }
+}
+
+impl<W: io::Write> io::Write for HashWriter<W> {
+    /// Write `buf` to the inner writer, and append the part of it which
+    /// was actually written to each of the generators.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let count = self.inner.write(buf)?;
+        if count > 0 {
+            let data: Arc<[u8]> = Arc::from(&buf[..count]);
+            for generator in &self.generators {
+                generator.try_append(Arc::clone(&data))?;
+            }
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use io::Write;
+
+    #[test]
+    fn hash_writer() {
+        let mut writer = HashWriter::new(Vec::new(), vec![crate::md5()]);
+        assert_eq!(writer.result(), [DigestData::MD5(fixtures::md5::EMPTY)]);
+
+        writer.write_all(&fixtures::RANDOM_11171).unwrap();
+        let (written, digests) = writer.finish().unwrap();
+        assert_eq!(written, fixtures::RANDOM_11171);
+        assert_eq!(digests, [DigestData::MD5(fixtures::md5::RANDOM_11171)]);
+    }
+
+    #[test]
+    fn hash_writer_multiple() {
+        let generators = vec![crate::crc32(), crate::sha256()];
+        let mut writer = HashWriter::new(io::sink(), generators);
+        writer.write_all(&fixtures::ZERO_400D).unwrap();
+        let (_, digests) = writer.finish().unwrap();
+        assert_eq!(
+            digests,
+            [
+                DigestData::CRC32(fixtures::crc32::ZERO_400D),
+                DigestData::SHA256(fixtures::sha256::ZERO_400D),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_writer_partial() {
+        // Only the bytes the inner writer accepts are digested.
+        let mut buffer = vec![0xff; fixtures::ZERO_400D.len()];
+        let mut writer = HashWriter::new(&mut buffer[..], vec![crate::md5()]);
+        let written = writer.write(&fixtures::RANDOM_11171).unwrap();
+        assert_eq!(written, fixtures::ZERO_400D.len());
+        assert_eq!(writer.write(&fixtures::RANDOM_11171).unwrap(), 0);
+        let (_, digests) = writer.finish().unwrap();
+        let expected = crate::md5();
+        expected.append(Arc::from(&fixtures::RANDOM_11171[..written]));
+        assert_eq!(digests, [expected.result()]);
+    }
+}
@@ -0,0 +1,304 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+const PRIME32_1: u32 = 0x9e37_79b1;
+const PRIME32_2: u32 = 0x85eb_ca77;
+const PRIME32_3: u32 = 0xc2b2_ae3d;
+const PRIME32_4: u32 = 0x27d4_eb2f;
+const PRIME32_5: u32 = 0x1656_67b1;
+
+/// The number of bytes consumed by each round of the XXH32 checksum.
+const STRIPE: usize = 16;
+
+/// A structure used to generate a XXH32 checksum.
+#[derive(Clone)]
+pub struct XXH32 {
+    /// The four accumulators used while processing full stripes.
+    acc: [u32; 4],
+    /// Input which has not yet filled a full stripe.
+    buffer: [u8; STRIPE],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes processed.
+    total: u64,
+}
+
+impl XXH32 {
+    /// The length of the XXH32 checksum, in bytes.
+    pub const LENGTH: usize = 4;
+
+    /// Create a new XXH32 structure to generate a checksum.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut this = Self {
+            acc: [0; 4],
+            buffer: [0; STRIPE],
+            buffered: 0,
+            total: 0,
+        };
+        this.reset();
+        this
+    }
+
+    /// Re-initialize the XXH32 structure, using a seed of 0.
+    fn reset(&mut self) {
+        self.acc = [
+            PRIME32_1.wrapping_add(PRIME32_2),
+            PRIME32_2,
+            0,
+            0u32.wrapping_sub(PRIME32_1),
+        ];
+        self.buffered = 0;
+        self.total = 0;
+    }
+
+    /// Process a full stripe of input.
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (acc, lane) in self.acc.iter_mut().zip(stripe.chunks_exact(4)) {
+            *acc = round(*acc, read_u32(lane));
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for XXH32 {
+    /// Update the XXH32 checksum using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.total += data.len() as u64;
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (STRIPE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < STRIPE {
+                return;
+            }
+            let buffer = self.buffer;
+            self.stripe(&buffer);
+            self.buffered = 0;
+        }
+
+        let mut stripes = data.chunks_exact(STRIPE);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        let remainder = stripes.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Return the XXH32 checksum. The XXH32 checksum is reset so that it
+    /// can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let mut hash = if self.total >= STRIPE as u64 {
+            self.acc[0]
+                .rotate_left(1)
+                .wrapping_add(self.acc[1].rotate_left(7))
+                .wrapping_add(self.acc[2].rotate_left(12))
+                .wrapping_add(self.acc[3].rotate_left(18))
+        } else {
+            PRIME32_5
+        };
+        // The algorithm only uses the low 32 bits of the length.
+        #[allow(clippy::cast_possible_truncation)]
+        let total = self.total as u32;
+        hash = hash.wrapping_add(total);
+
+        let mut words = self.buffer[..self.buffered].chunks_exact(4);
+        for word in &mut words {
+            hash = hash
+                .wrapping_add(read_u32(word).wrapping_mul(PRIME32_3))
+                .rotate_left(17)
+                .wrapping_mul(PRIME32_4);
+        }
+        for &byte in words.remainder() {
+            hash = hash
+                .wrapping_add(u32::from(byte).wrapping_mul(PRIME32_5))
+                .rotate_left(11)
+                .wrapping_mul(PRIME32_1);
+        }
+
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(PRIME32_2);
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(PRIME32_3);
+        hash ^= hash >> 16;
+
+        self.reset();
+        hash.to_be_bytes()
+    }
+
+    /// Export the current state of the XXH32 checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u32s(&self.acc);
+        state.bytes(&self.buffer);
+        state.usize(self.buffered);
+        state.u64(self.total);
+        Some(state.finish())
+    }
+
+    /// Create a XXH32 checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let acc = state.u32s()?;
+        let buffer = state.bytes()?;
+        let buffered = state.usize(STRIPE - 1)?;
+        let total = state.u64()?;
+        state.finish()?;
+        Some(Self {
+            acc,
+            buffer,
+            buffered,
+            total,
+        })
+    }
+}
+
+impl Default for XXH32 {
+    /// Create a default XXH32 structure to generate a checksum.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mix a single 32-bit lane of input into an accumulator.
+fn round(acc: u32, lane: u32) -> u32 {
+    acc.wrapping_add(lane.wrapping_mul(PRIME32_2))
+        .rotate_left(13)
+        .wrapping_mul(PRIME32_1)
+}
+
+/// Read a little endian `u32` from the start of `bytes`.
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+/// Structure used to compute an XXH32 checksum in a separate thread.
+pub struct BackgroundXXH32 {
+    worker: Background<{ XXH32::LENGTH }>,
+}
+
+impl BackgroundXXH32 {
+    /// Create a new `BackgroundXXH32` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(XXH32::new),
+        }
+    }
+
+    /// Create a `BackgroundXXH32` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<XXH32>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundXXH32 {
+    /// Add the given `data` to the XXH32 checksum.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the XXH32 checksum, and reset the checksum computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::XXH32(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the XXH32 checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the XXH32 checksum.
+    fn name(&self) -> &'static str {
+        DigestKind::XXH32.name()
+    }
+
+    /// The length of the XXH32 checksum, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::XXH32.output_len()
+    }
+
+    /// Copy the current state of the XXH32 checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the XXH32 checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut xxh32 = XXH32::new();
+        assert_eq!(xxh32.finish(), fixtures::xxh32::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut xxh32 = XXH32::new();
+        xxh32.update(&[0; 0x4000]);
+        xxh32.update(&[0; 0x0d]);
+        assert_eq!(xxh32.finish(), fixtures::xxh32::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut xxh32 = XXH32::new();
+        xxh32.update(&fixtures::RANDOM_11171);
+        assert_eq!(xxh32.finish(), fixtures::xxh32::RANDOM_11171);
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut xxh32 = XXH32::new();
+        for chunk in fixtures::RANDOM_11171.chunks(7) {
+            xxh32.update(chunk);
+        }
+        assert_eq!(xxh32.finish(), fixtures::xxh32::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut xxh32 = XXH32::new();
+        assert_eq!(xxh32.finish(), fixtures::xxh32::EMPTY);
+        xxh32.update(&fixtures::ZERO_400D);
+        assert_eq!(xxh32.finish(), fixtures::xxh32::ZERO_400D);
+        xxh32.update(&fixtures::RANDOM_11171);
+        assert_eq!(xxh32.finish(), fixtures::xxh32::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let xxh32 = BackgroundXXH32::new();
+        assert_eq!(xxh32.result(), DigestData::XXH32(fixtures::xxh32::EMPTY));
+        xxh32.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            xxh32.result(),
+            DigestData::XXH32(fixtures::xxh32::ZERO_400D)
+        );
+        xxh32.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            xxh32.result(),
+            DigestData::XXH32(fixtures::xxh32::RANDOM_11171)
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use crate::state::{Exporter, Importer};
+use crate::{Background, Digest, DigestData, DigestKind, Error, Generator};
+
+const PRIME64_1: u64 = 0x9e37_79b1_85eb_ca87;
+const PRIME64_2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const PRIME64_3: u64 = 0x1656_67b1_9e37_79f9;
+const PRIME64_4: u64 = 0x85eb_ca77_c2b2_ae63;
+const PRIME64_5: u64 = 0x27d4_eb2f_1656_67c5;
+
+/// The number of bytes consumed by each round of the XXH64 checksum.
+const STRIPE: usize = 32;
+
+/// A structure used to generate a XXH64 checksum.
+#[derive(Clone)]
+pub struct XXH64 {
+    /// The four accumulators used while processing full stripes.
+    acc: [u64; 4],
+    /// Input which has not yet filled a full stripe.
+    buffer: [u8; STRIPE],
+    /// The number of bytes used in `buffer`.
+    buffered: usize,
+    /// The total number of bytes processed.
+    total: u64,
+}
+
+impl XXH64 {
+    /// The length of the XXH64 checksum, in bytes.
+    pub const LENGTH: usize = 8;
+
+    /// Create a new XXH64 structure to generate a checksum.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut this = Self {
+            acc: [0; 4],
+            buffer: [0; STRIPE],
+            buffered: 0,
+            total: 0,
+        };
+        this.reset();
+        this
+    }
+
+    /// Re-initialize the XXH64 structure, using a seed of 0.
+    fn reset(&mut self) {
+        self.acc = [
+            PRIME64_1.wrapping_add(PRIME64_2),
+            PRIME64_2,
+            0,
+            0u64.wrapping_sub(PRIME64_1),
+        ];
+        self.buffered = 0;
+        self.total = 0;
+    }
+
+    /// Process a full stripe of input.
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (acc, lane) in self.acc.iter_mut().zip(stripe.chunks_exact(8)) {
+            *acc = round(*acc, read_u64(lane));
+        }
+    }
+}
+
+impl Digest<{ Self::LENGTH }> for XXH64 {
+    /// Update the XXH64 checksum using the given `data`.
+    fn update(&mut self, data: &[u8]) {
+        self.total += data.len() as u64;
+        let mut data = data;
+
+        if self.buffered > 0 {
+            let count = (STRIPE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count]
+                .copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < STRIPE {
+                return;
+            }
+            let buffer = self.buffer;
+            self.stripe(&buffer);
+            self.buffered = 0;
+        }
+
+        let mut stripes = data.chunks_exact(STRIPE);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        let remainder = stripes.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Return the XXH64 checksum. The XXH64 checksum is reset so that it
+    /// can be reused.
+    fn finish(&mut self) -> [u8; Self::LENGTH] {
+        let mut hash = if self.total >= STRIPE as u64 {
+            let mut hash = self.acc[0]
+                .rotate_left(1)
+                .wrapping_add(self.acc[1].rotate_left(7))
+                .wrapping_add(self.acc[2].rotate_left(12))
+                .wrapping_add(self.acc[3].rotate_left(18));
+            for acc in self.acc {
+                hash = merge(hash, acc);
+            }
+            hash
+        } else {
+            PRIME64_5
+        };
+        hash = hash.wrapping_add(self.total);
+
+        let mut words = self.buffer[..self.buffered].chunks_exact(8);
+        for word in &mut words {
+            hash ^= round(0, read_u64(word));
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+        }
+        let mut remainder = words.remainder();
+        if remainder.len() >= 4 {
+            let word = u32::from_le_bytes(remainder[..4].try_into().unwrap());
+            hash ^= u64::from(word).wrapping_mul(PRIME64_1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            remainder = &remainder[4..];
+        }
+        for &byte in remainder {
+            hash ^= u64::from(byte).wrapping_mul(PRIME64_5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME64_1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME64_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME64_3);
+        hash ^= hash >> 32;
+
+        self.reset();
+        hash.to_be_bytes()
+    }
+
+    /// Export the current state of the XXH64 checksum.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        let mut state = Exporter::new();
+        state.u64s(&self.acc);
+        state.bytes(&self.buffer);
+        state.usize(self.buffered);
+        state.u64(self.total);
+        Some(state.finish())
+    }
+
+    /// Create a XXH64 checksum from the state returned by
+    /// `export_state()`.
+    fn import_state(state: &[u8]) -> Option<Self> {
+        let mut state = Importer::new(state);
+        let acc = state.u64s()?;
+        let buffer = state.bytes()?;
+        let buffered = state.usize(STRIPE - 1)?;
+        let total = state.u64()?;
+        state.finish()?;
+        Some(Self {
+            acc,
+            buffer,
+            buffered,
+            total,
+        })
+    }
+}
+
+impl Default for XXH64 {
+    /// Create a default XXH64 structure to generate a checksum.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mix a single 64-bit lane of input into an accumulator.
+fn round(acc: u64, lane: u64) -> u64 {
+    acc.wrapping_add(lane.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+
+/// Merge an accumulator into the final hash value.
+fn merge(hash: u64, acc: u64) -> u64 {
+    (hash ^ round(0, acc))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(PRIME64_4)
+}
+
+/// Read a little endian `u64` from the start of `bytes`.
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// Structure used to compute an XXH64 checksum in a separate thread.
+pub struct BackgroundXXH64 {
+    worker: Background<{ XXH64::LENGTH }>,
+}
+
+impl BackgroundXXH64 {
+    /// Create a new `BackgroundXXH64` structure.
+    pub fn new() -> Self {
+        Self {
+            worker: Background::new(XXH64::new),
+        }
+    }
+
+    /// Create a `BackgroundXXH64` structure which resumes the state
+    /// returned by `export_state()`.
+    pub fn import(state: &[u8]) -> Option<Self> {
+        Some(Self {
+            worker: Background::import_state::<XXH64>(state)?,
+        })
+    }
+}
+
+impl Generator for BackgroundXXH64 {
+    /// Add the given `data` to the XXH64 checksum.
+    fn try_append(&self, data: Arc<[u8]>) -> Result<(), Error> {
+        self.worker.update(data)
+    }
+
+    /// Retrieve the XXH64 checksum, and reset the checksum computation.
+    fn try_result(&self) -> Result<DigestData, Error> {
+        Ok(DigestData::XXH64(self.worker.finish()?))
+    }
+
+    /// Discard the data added to the XXH64 checksum so far.
+    fn reset(&self) {
+        self.worker.reset();
+    }
+
+    /// The name of the XXH64 checksum.
+    fn name(&self) -> &'static str {
+        DigestKind::XXH64.name()
+    }
+
+    /// The length of the XXH64 checksum, in bytes.
+    fn output_len(&self) -> usize {
+        DigestKind::XXH64.output_len()
+    }
+
+    /// Copy the current state of the XXH64 checksum into a new generator.
+    fn try_fork(&self) -> Result<Box<dyn Generator>, Error> {
+        Ok(Box::new(Self {
+            worker: self.worker.fork()?,
+        }))
+    }
+
+    /// Export the current state of the XXH64 checksum, if it can be
+    /// resumed later.
+    fn export_state(&self) -> Option<Vec<u8>> {
+        self.worker.export_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn empty() {
+        let mut xxh64 = XXH64::new();
+        assert_eq!(xxh64.finish(), fixtures::xxh64::EMPTY);
+    }
+
+    #[test]
+    fn zero() {
+        let mut xxh64 = XXH64::new();
+        xxh64.update(&[0; 0x4000]);
+        xxh64.update(&[0; 0x0d]);
+        assert_eq!(xxh64.finish(), fixtures::xxh64::ZERO_400D);
+    }
+
+    #[test]
+    fn random() {
+        let mut xxh64 = XXH64::new();
+        xxh64.update(&fixtures::RANDOM_11171);
+        assert_eq!(xxh64.finish(), fixtures::xxh64::RANDOM_11171);
+    }
+
+    #[test]
+    fn unaligned() {
+        let mut xxh64 = XXH64::new();
+        for chunk in fixtures::RANDOM_11171.chunks(13) {
+            xxh64.update(chunk);
+        }
+        assert_eq!(xxh64.finish(), fixtures::xxh64::RANDOM_11171);
+    }
+
+    #[test]
+    fn multiple() {
+        let mut xxh64 = XXH64::new();
+        assert_eq!(xxh64.finish(), fixtures::xxh64::EMPTY);
+        xxh64.update(&fixtures::ZERO_400D);
+        assert_eq!(xxh64.finish(), fixtures::xxh64::ZERO_400D);
+        xxh64.update(&fixtures::RANDOM_11171);
+        assert_eq!(xxh64.finish(), fixtures::xxh64::RANDOM_11171);
+    }
+
+    #[test]
+    fn background() {
+        let xxh64 = BackgroundXXH64::new();
+        assert_eq!(xxh64.result(), DigestData::XXH64(fixtures::xxh64::EMPTY));
+        xxh64.append(Arc::from(fixtures::ZERO_400D));
+        assert_eq!(
+            xxh64.result(),
+            DigestData::XXH64(fixtures::xxh64::ZERO_400D)
+        );
+        xxh64.append(Arc::from(fixtures::RANDOM_11171));
+        assert_eq!(
+            xxh64.result(),
+            DigestData::XXH64(fixtures::xxh64::RANDOM_11171)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::fs;
 use std::io;
 use std::path;
 use std::process;
@@ -18,11 +19,77 @@ fn checksum_help() {
     let help_text = lines.join("\n");
     assert!(help_text.contains("checksum"));
     assert!(help_text.contains("--help"));
+    assert!(help_text.contains("--crc16=ccitt"));
+    assert!(help_text.contains("--crc16=modbus"));
+    assert!(help_text.contains("--crc16=xmodem"));
     assert!(help_text.contains("--crc32"));
+    assert!(help_text.contains("--crc64"));
+    assert!(help_text.contains("--xxh32"));
+    assert!(help_text.contains("--xxh64"));
+    assert!(help_text.contains("--rapidhash"));
+    assert!(help_text.contains("--md2"));
+    assert!(help_text.contains("--md4"));
     assert!(help_text.contains("--md5"));
+    assert!(help_text.contains("--sha224"));
     assert!(help_text.contains("--sha256"));
+    assert!(help_text.contains("--sha384"));
     assert!(help_text.contains("--sha512"));
+    assert!(help_text.contains("--sha512-224"));
+    assert!(help_text.contains("--sha512-256"));
+    assert!(help_text.contains("--sha3-224"));
+    assert!(help_text.contains("--sha3-256"));
+    assert!(help_text.contains("--sha3-384"));
+    assert!(help_text.contains("--sha3-512"));
+    assert!(help_text.contains("--keccak256"));
+    assert!(help_text.contains("--blake2b"));
+    assert!(help_text.contains("--blake2s"));
+    assert!(help_text.contains("--streebog256"));
+    assert!(help_text.contains("--streebog512"));
+    assert!(help_text.contains("--tiger"));
+    assert!(help_text.contains("--tiger2"));
+    assert!(help_text.contains("--rmd256"));
+    assert!(help_text.contains("--rmd320"));
     assert!(help_text.contains("--rmd160"));
+    assert!(help_text.contains("--sfv"));
+    assert!(help_text.contains("--csv"));
+    assert!(help_text.contains("--yaml"));
+    assert!(help_text.contains("--plain"));
+    assert!(help_text.contains("--encoding"));
+    assert!(help_text.contains("--base32"));
+    assert!(help_text.contains("--no-padding"));
+    assert!(help_text.contains("--with-size"));
+    assert!(help_text.contains("--with-mtime"));
+    assert!(help_text.contains("--output"));
+    assert!(help_text.contains("--check"));
+    assert!(help_text.contains("--quiet"));
+    assert!(help_text.contains("--status"));
+    assert!(help_text.contains("--warn"));
+    assert!(help_text.contains("--strict"));
+    assert!(help_text.contains("--json"));
+    assert!(help_text.contains("--expect"));
+    assert!(help_text.contains("--check-sidecars"));
+    assert!(help_text.contains("--base-dir"));
+    assert!(help_text.contains("--update"));
+    assert!(help_text.contains("--dry-run"));
+    assert!(help_text.contains("--glob"));
+    assert!(help_text.contains("--exclude"));
+    assert!(help_text.contains("--respect-gitignore"));
+    assert!(help_text.contains("--follow-symlinks"));
+    assert!(help_text.contains("--no-follow-symlinks"));
+    assert!(help_text.contains("--symlink-literal"));
+    assert!(help_text.contains("--max-depth"));
+    assert!(help_text.contains("--recursive"));
+    assert!(help_text.contains("--key-file"));
+    assert!(help_text.contains("--offset"));
+    assert!(help_text.contains("--size"));
+    assert!(help_text.contains("--combine"));
+    assert!(help_text.contains("--tree-digest"));
+    assert!(help_text.contains("--sidecar"));
+    assert!(help_text.contains("--force"));
+    assert!(help_text.contains("--sort"));
+    assert!(help_text.contains("--progress"));
+    assert!(help_text.contains("--verbose"));
+    assert!(help_text.contains("--jobs"));
 
     let lines =
         child_errlines(&mut child).expect("error reading checksum stderr");
@@ -57,6 +124,165 @@ fn checksum_stdin() {
     assert!(lines.is_empty());
 }
 
+#[test]
+fn checksum_tee() {
+    let mut child = run_checksum(&["--tee", "--md5", "--crc32"], &[]);
+
+    let count = child_write(&mut child, b"abc\ndef\n")
+        .expect("error writing to checksum stdin");
+    assert_eq!(count, 8);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    // The data is passed through unchanged, and the digests follow on
+    // stderr.
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["abc", "def"]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(
+        lines,
+        ["MD5 = f72fe788e136ba9e53518afa8b407eac", "CRC32 = 88b00b75"]
+    );
+}
+
+#[test]
+fn checksum_stdin_files() {
+    let mut child = run_checksum(&["--md5", "-"], &["empty"]);
+
+    let count = child_write(&mut child, &[0u8; 0x400d])
+        .expect("error writing to checksum stdin");
+    assert_eq!(count, 0x400d);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "MD5 (-) = 96f64e179f777e6eda0caa2d879356c9",
+            "MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e",
+        ]
+    );
+}
+
+#[test]
+fn checksum_end_of_options() {
+    let dir = std::env::temp_dir()
+        .join(format!("checksum-options-{}", process::id()));
+    fs::create_dir_all(&dir).expect("error creating directory");
+    fs::write(dir.join("-rf"), "").expect("error writing file");
+    fs::write(dir.join("--md5"), "").expect("error writing file");
+
+    // The files are in the temporary directory, so the command is run
+    // there to list them without a leading directory.
+    let checksum_path = fs::canonicalize("target/debug/checksum")
+        .expect("error finding checksum");
+    let mut child = process::Command::new(checksum_path)
+        .args(["--crc32", "--", "-rf", "--md5"])
+        .current_dir(&dir)
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn checksum");
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["CRC32 (-rf) = 00000000", "CRC32 (--md5) = 00000000"]
+    );
+
+    fs::remove_dir_all(&dir).expect("error removing directory");
+}
+
+#[test]
+fn checksum_range() {
+    // The zeros are surrounded by other bytes which shouldn't be digested.
+    let mut data = vec![0xff; 0x20];
+    data.extend([0; 0x400d]);
+    data.extend([0xff; 0x20]);
+    let path = std::env::temp_dir()
+        .join(format!("checksum-range-{}", process::id()));
+    fs::write(&path, &data).expect("error writing file");
+    let pathstr = path.to_str().unwrap();
+
+    let mut child = run_checksum(
+        &["--md5", "--offset", "32", "--size=16397", pathstr, "-"],
+        &[],
+    );
+    let count = child_write(&mut child, &data)
+        .expect("error writing to checksum stdin");
+    assert_eq!(count, data.len());
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            format!("MD5 ({pathstr}) = 96f64e179f777e6eda0caa2d879356c9"),
+            "MD5 (-) = 96f64e179f777e6eda0caa2d879356c9".to_string(),
+        ]
+    );
+
+    fs::remove_file(&path).expect("error removing file");
+
+    // Nothing is left to digest past the end of a file.
+    let mut child =
+        run_checksum(&["--crc32", "--offset", "70001"], &["random-11171"]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["CRC32 (tests/fixtures/random-11171) = 00000000"]);
+}
+
+#[test]
+fn checksum_sha2_sha3() {
+    let mut child = run_checksum(&["--sha256", "--sha3-256"], &[]);
+
+    let count = child_write(&mut child, &[0u8; 0x400d])
+        .expect("error writing to checksum stdin");
+    assert_eq!(count, 0x400d);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            concat!(
+                "SHA256 = ",
+                "10bb1dbb5bcfb203d5830e8a5bf4ff49ba1d0bd918697250f89471ab22f4a599"
+            ),
+            concat!(
+                "SHA3-256 = ",
+                "d7ecfed836b2dcff66c2d271d3b1c9b6a80743c27216f8b3932525783e53f947"
+            ),
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
 #[test]
 fn checksum_files() {
     let mut child = run_checksum(
@@ -84,6 +310,1482 @@ fn checksum_files() {
     assert!(lines.is_empty());
 }
 
+#[test]
+fn checksum_blake2_keyed() {
+    let mut child = run_checksum(
+        &["--blake2s", "--key", "6b6579", "--salt=73616c74"],
+        &["random-11171"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, [
+        "BLAKE2s (tests/fixtures/random-11171) = 464905a49ca7988add9608346878f1923f00ced4cf6690e8d7175c19daa73ed8",
+    ]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_blake2_key_file() {
+    let key =
+        std::env::temp_dir().join(format!("checksum-key-{}", process::id()));
+    fs::write(&key, b"key").expect("error writing key file");
+    let keystr = key.to_str().unwrap();
+
+    let mut child = run_checksum(
+        &["--blake2s", "--key-file", keystr, "--salt=73616c74"],
+        &["random-11171"],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, [
+        "BLAKE2s (tests/fixtures/random-11171) = 464905a49ca7988add9608346878f1923f00ced4cf6690e8d7175c19daa73ed8",
+    ]);
+
+    // A key which is too long is rejected, however long the file is.
+    fs::write(&key, [0x5a; 0x1000]).expect("error writing key file");
+    let mut child =
+        run_checksum(&["--blake2b", "--key-file", keystr], &["empty"]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["the BLAKE2 parameters are too long for BLAKE2b"]);
+
+    fs::remove_file(&key).expect("error removing key file");
+
+    let mut child =
+        run_checksum(&["--blake2b", "--key-file", keystr], &["empty"]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, [format!("unable to open '{keystr}'")]);
+}
+
+#[test]
+fn checksum_sfv() {
+    let mut child = run_checksum(&["--sfv"], &["zero-400d", "random-11171"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("; Generated by checksum"));
+    assert_eq!(
+        lines[1..],
+        [
+            "tests/fixtures/zero-400d 26A348BB",
+            "tests/fixtures/random-11171 FF70A8EE",
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_csv() {
+    let mut child =
+        run_checksum(&["--csv", "--md5", "--crc32"], &["random-11171"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, [
+        "path,algorithm,digest,size",
+        "tests/fixtures/random-11171,MD5,ff8ae3cf944cdddea7191c906afe0c81,70001",
+        "tests/fixtures/random-11171,CRC32,ff70a8ee,70001",
+    ]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_yaml() {
+    let mut child = run_checksum(
+        &["--yaml", "--md5", "--crc32"],
+        &["empty", "random-11171"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "\"tests/fixtures/empty\":",
+            "  MD5: \"d41d8cd98f00b204e9800998ecf8427e\"",
+            "  CRC32: \"00000000\"",
+            "\"tests/fixtures/random-11171\":",
+            "  MD5: \"ff8ae3cf944cdddea7191c906afe0c81\"",
+            "  CRC32: \"ff70a8ee\"",
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_plain() {
+    let mut child = run_checksum(
+        &["--plain", "--md5", "--crc32"],
+        &["empty", "random-11171"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "00000000",
+            "ff8ae3cf944cdddea7191c906afe0c81",
+            "ff70a8ee",
+        ]
+    );
+}
+
+#[test]
+fn checksum_base32() {
+    let mut child =
+        run_checksum(&["--base32", "--no-padding", "--md5"], &["empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/empty) = 2QOYZWMPACZAJ2MABGMOZ6CCPY"]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_encoding() {
+    let mut child =
+        run_checksum(&["--encoding", "base64", "--md5"], &["empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/empty) = 1B2M2Y8AsgTpgAmY7PhCfg=="]
+    );
+
+    let mut child = run_checksum(&["--encoding=base16"], &["empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["invalid value 'base16' for option '--encoding'"]);
+}
+
+#[test]
+fn checksum_with_size_mtime() {
+    let mut child = run_checksum(
+        &["--crc32", "--with-size", "--with-mtime"],
+        &["zero-400d"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 1);
+    let (digest, mtime) = lines[0]
+        .split_once(" mtime=")
+        .expect("missing mtime in output");
+    assert_eq!(
+        digest,
+        "CRC32 (tests/fixtures/zero-400d) = 26a348bb size=16397"
+    );
+    assert_eq!(mtime.len(), "1970-01-01T00:00:00Z".len());
+    assert!(mtime.ends_with('Z'));
+}
+
+#[test]
+fn checksum_output() {
+    let output = std::env::temp_dir()
+        .join(format!("checksum-output-{}", process::id()));
+    let outstr = output.to_str().unwrap();
+    fs::write(&output, "old").expect("error writing output file");
+
+    let mut child = run_checksum(&["--md5", "-o", outstr], &["empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert!(lines.is_empty());
+
+    let contents =
+        fs::read_to_string(&output).expect("error reading output file");
+    assert_eq!(
+        contents,
+        "MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e\n"
+    );
+
+    // The output file is left untouched if any checksum fails.
+    let mut child =
+        run_checksum(&["--md5", "-o", outstr], &["zero-400d", "missing"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let contents =
+        fs::read_to_string(&output).expect("error reading output file");
+    assert_eq!(
+        contents,
+        "MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e\n"
+    );
+    fs::remove_file(&output).expect("error removing output file");
+}
+
+#[test]
+fn checksum_output_update() {
+    let output = std::env::temp_dir()
+        .join(format!("checksum-update-{}", process::id()));
+    let outstr = output.to_str().unwrap();
+    let flags = ["--md5", "--with-size", "--with-mtime", "-o", outstr];
+    let files = ["empty", "zero-400d"];
+
+    let mut child = run_checksum(&flags, &files);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    // Change the listed digest of one file, which is reused as the file
+    // is unchanged, and the listed size of the other, which is digested
+    // again as the file looks to have changed.
+    let contents =
+        fs::read_to_string(&output).expect("error reading output file");
+    let contents = contents
+        .replace(
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "0123456789abcdef0123456789abcdef",
+        )
+        .replace("size=16397", "size=1");
+    fs::write(&output, &contents).expect("error writing output file");
+
+    let mut child = run_checksum(
+        &[&flags[..], &["--update", "--stats"]].concat(),
+        &files,
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("1 file, 16397 bytes"));
+
+    let lines: Vec<String> = fs::read_to_string(&output)
+        .expect("error reading output file")
+        .lines()
+        .map(|line| line.split(" mtime=").next().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "MD5 (tests/fixtures/empty) = 0123456789abcdef0123456789abcdef \
+             size=0",
+            "MD5 (tests/fixtures/zero-400d) = \
+             96f64e179f777e6eda0caa2d879356c9 size=16397",
+        ]
+    );
+    fs::remove_file(&output).expect("error removing output file");
+}
+
+#[test]
+fn checksum_sidecar() {
+    let dir = std::env::temp_dir()
+        .join(format!("checksum-sidecar-{}", process::id()));
+    fs::create_dir_all(&dir).expect("error creating sidecar directory");
+    let file = dir.join("empty");
+    fs::write(&file, "").expect("error writing sidecar input");
+    let filestr = file.to_str().unwrap();
+
+    let checksum = || {
+        let mut child =
+            run_checksum(&["--sidecar", "--md5", "--crc32", filestr], &[]);
+        let status =
+            child_run(&mut child).expect("error running checksum subprocess");
+        assert_eq!(status, 0);
+        let lines = child_readlines(&mut child)
+            .expect("error reading checksum stdout");
+        assert!(lines.is_empty());
+    };
+    checksum();
+
+    let md5 = dir.join("empty.md5");
+    let contents = fs::read_to_string(&md5).expect("error reading sidecar");
+    assert_eq!(contents, "MD5 (empty) = d41d8cd98f00b204e9800998ecf8427e\n");
+    let crc32 = dir.join("empty.crc32");
+    let contents = fs::read_to_string(&crc32).expect("error reading sidecar");
+    assert_eq!(contents, "CRC32 (empty) = 00000000\n");
+
+    // Current sidecars are left alone, but stale ones are rewritten.
+    fs::write(&md5, "current").expect("error writing sidecar");
+    fs::remove_file(&crc32).expect("error removing sidecar");
+    checksum();
+    let contents = fs::read_to_string(&md5).expect("error reading sidecar");
+    assert_eq!(contents, "current");
+    let contents = fs::read_to_string(&crc32).expect("error reading sidecar");
+    assert_eq!(contents, "CRC32 (empty) = 00000000\n");
+
+    // Using --force rewrites current sidecars.
+    let mut child =
+        run_checksum(&["--sidecar", "--force", "--md5", filestr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let contents = fs::read_to_string(&md5).expect("error reading sidecar");
+    assert_eq!(contents, "MD5 (empty) = d41d8cd98f00b204e9800998ecf8427e\n");
+
+    fs::remove_dir_all(&dir).expect("error removing sidecar directory");
+}
+
+#[test]
+fn checksum_glob() {
+    let mut child = run_checksum(
+        &[
+            "--md5",
+            "--glob",
+            "tests/**/e?p[st]y",
+            "--glob=tests/*/zero-400d",
+        ],
+        &["zero-400d"],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "MD5 (tests/fixtures/zero-400d) = 96f64e179f777e6eda0caa2d879356c9",
+            "MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e",
+            "MD5 (tests/fixtures/zero-400d) = 96f64e179f777e6eda0caa2d879356c9",
+        ]
+    );
+
+    let mut child = run_checksum(
+        &[
+            "--md5",
+            "--glob=tests/fixtures/*-*",
+            "--exclude=*.*",
+            "--exclude",
+            "random-*",
+        ],
+        &[],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/zero-400d) = 96f64e179f777e6eda0caa2d879356c9"]
+    );
+
+    let mut child = run_checksum(&["--glob", "tests/fixtures/*.zip"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["no files match 'tests/fixtures/*.zip'"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn checksum_symlinks() {
+    let link = std::env::temp_dir()
+        .join(format!("checksum-symlink-{}", process::id()));
+    let target = fs::canonicalize("tests/fixtures/empty")
+        .expect("error finding symlink target");
+    std::os::unix::fs::symlink(target, &link)
+        .expect("error creating symlink");
+    let linkstr = link.to_str().unwrap();
+
+    let mut child = run_checksum(&["--md5", "-L", linkstr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [format!(
+            "MD5 ({linkstr}) = d41d8cd98f00b204e9800998ecf8427e"
+        )]
+    );
+
+    let mut child = run_checksum(&["--md5", "-P", linkstr], &["empty"]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e"]
+    );
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, [format!("skipping the symbolic link '{linkstr}'")]);
+
+    fs::remove_file(&link).expect("error removing symlink");
+
+    // The path the link points to is digested, rather than its target.
+    std::os::unix::fs::symlink("empty", &link)
+        .expect("error creating symlink");
+    let mut child =
+        run_checksum(&["--md5", "--symlink-literal", linkstr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [format!(
+            "MD5 ({linkstr}) = a2e4822a98337283e39f7b60acf85ec9"
+        )]
+    );
+
+    fs::remove_file(&link).expect("error removing symlink");
+}
+
+#[test]
+fn checksum_sort() {
+    let mut child =
+        run_checksum(&["--crc32", "--sort"], &["zero-400d", "empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "CRC32 (tests/fixtures/empty) = 00000000",
+            "CRC32 (tests/fixtures/zero-400d) = 26a348bb",
+        ]
+    );
+}
+
+#[test]
+fn checksum_recursive() {
+    let mut child = run_checksum(
+        &["--crc32", "-rj2", "--exclude", "*.*", "tests/fixtures"],
+        &[],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "CRC32 (tests/fixtures/empty) = 00000000",
+            "CRC32 (tests/fixtures/random-11171) = ff70a8ee",
+            "CRC32 (tests/fixtures/zero-400d) = 26a348bb",
+        ]
+    );
+}
+
+#[test]
+fn checksum_verbose() {
+    let mut child = run_checksum(
+        &[
+            "--crc32",
+            "-vv",
+            "--glob",
+            "tests/fixtures/emp?y",
+            "--exclude",
+            "empty.md5",
+        ],
+        &[],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["CRC32 (tests/fixtures/empty) = 00000000"]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(
+        lines,
+        [
+            "skipping 'tests/fixtures/empty.md5': excluded",
+            "opening 'tests/fixtures/empty'",
+        ]
+    );
+}
+
+#[test]
+fn checksum_jobs() {
+    let files = ["random-11171", "empty", "zero-400d", "missing", "empty"];
+    let mut child = run_checksum(&["--crc32", "--jobs", "3"], &files);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "CRC32 (tests/fixtures/random-11171) = ff70a8ee",
+            "CRC32 (tests/fixtures/empty) = 00000000",
+            "CRC32 (tests/fixtures/zero-400d) = 26a348bb",
+            "CRC32 (tests/fixtures/empty) = 00000000",
+        ]
+    );
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["unable to open 'tests/fixtures/missing'"]);
+}
+
+#[test]
+fn checksum_commands() {
+    let mut child = run_checksum(&["hash", "--md5"], &["empty"]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e"]
+    );
+
+    let mut child = run_checksum(&["check"], &[]);
+    child_write(
+        &mut child,
+        b"d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty\n",
+    )
+    .expect("error writing to checksum stdin");
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["tests/fixtures/empty: OK"]);
+
+    let mut child = run_checksum(&["list"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 31);
+    assert!(lines.contains(&format!("{:<16}{:<14}128 bits", "--md5", "MD5")));
+
+    let mut child = run_checksum(&["bench", "--crc32"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("CRC32 "));
+    assert!(lines[0].ends_with("/s"));
+
+    let mut child = run_checksum(&["check", "--help"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines[0], "usage: checksum check [option]... [manifest]");
+}
+
+#[test]
+fn checksum_dupes() {
+    let dir = std::env::temp_dir()
+        .join(format!("checksum-dupes-{}", process::id()));
+    fs::create_dir_all(dir.join("sub")).expect("error creating directory");
+    for (file, contents) in [
+        ("a", "abc"),
+        ("b", "xyz"),
+        ("c", "abcd"),
+        ("sub/a", "abc"),
+        ("sub/d", "abc"),
+        ("sub/e", ""),
+        ("sub/f", ""),
+    ] {
+        fs::write(dir.join(file), contents).expect("error writing file");
+    }
+    let dirstr = dir.to_str().unwrap();
+
+    let mut child = run_checksum(&["dupes", "--md5", dirstr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "MD5 = 900150983cd24fb0d6963f7d28e17f72, 3 files of 3 bytes"
+                .to_string(),
+            format!("    {dirstr}/a"),
+            format!("    {dirstr}/sub/a"),
+            format!("    {dirstr}/sub/d"),
+            String::new(),
+            "2 duplicate files, 6 bytes reclaimable".to_string(),
+        ]
+    );
+
+    let mut child =
+        run_checksum(&["dupes", "--json", "--exclude", "sub", dirstr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "{",
+            "  \"groups\": [],",
+            "  \"duplicates\": 0,",
+            "  \"reclaimable\": 0",
+            "}",
+        ]
+    );
+
+    fs::remove_dir_all(&dir).expect("error removing directory");
+}
+
+#[test]
+fn checksum_diff() {
+    let dir =
+        std::env::temp_dir().join(format!("checksum-diff-{}", process::id()));
+    for (file, contents) in [
+        ("left/same", "abc"),
+        ("left/size", "abc"),
+        ("left/contents", "abc"),
+        ("left/sub/left", "abc"),
+        ("right/same", "abc"),
+        ("right/size", "abcd"),
+        ("right/contents", "xyz"),
+        ("right/right", "abc"),
+    ] {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap())
+            .expect("error creating directory");
+        fs::write(path, contents).expect("error writing file");
+    }
+    let left = dir.join("left");
+    let right = dir.join("right");
+    let (left, right) = (left.to_str().unwrap(), right.to_str().unwrap());
+
+    let mut child = run_checksum(&["diff", "--md5", left, right], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "differs: contents".to_string(),
+            format!("only in {right}: right"),
+            "differs: size".to_string(),
+            format!("only in {left}: sub/left"),
+        ]
+    );
+
+    let mut child =
+        run_checksum(&["diff", "--exclude", "s*", left, left], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert!(lines.is_empty());
+
+    fs::remove_dir_all(&dir).expect("error removing directory");
+}
+
+#[test]
+fn checksum_progress() {
+    let mut child = run_checksum(&["--crc32", "--progress"], &["zero-400d"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["CRC32 (tests/fixtures/zero-400d) = 26a348bb"]);
+
+    // The progress line is redrawn in place, and cleared once the file has
+    // been read.
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(
+        "\rtests/fixtures/zero-400d: 100% | 16.3 KB of 16.3 KB (100%), "
+    ));
+    assert!(lines[0].ends_with("\r\x1b[K"));
+}
+
+#[test]
+fn checksum_throttle() {
+    let start = std::time::Instant::now();
+    let mut child = run_checksum(
+        &["--crc32", "--throttle", "32K"],
+        &["zero-400d", "zero-400d"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    // The files share the rate, so reading both takes about a second.
+    assert!(start.elapsed() >= std::time::Duration::from_millis(900));
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn checksum_background_priority() {
+    let mut child =
+        run_checksum(&["--md5", "--background-priority"], &["empty"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/empty) = d41d8cd98f00b204e9800998ecf8427e"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn checksum_interrupted() {
+    // Each file takes about a second to read, so the signal arrives while
+    // the second one is being read.
+    let mut child = run_checksum(
+        &["--md5", "--throttle", "16K"],
+        &["zero-400d", "zero-400d", "zero-400d", "zero-400d"],
+    );
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    let pid = i32::try_from(child.id()).unwrap();
+    // SAFETY: the signal is sent to the child, which traps it.
+    unsafe { libc::kill(pid, libc::SIGINT) };
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 128 + libc::SIGINT);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["MD5 (tests/fixtures/zero-400d) = 96f64e179f777e6eda0caa2d879356c9"]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["interrupted, skipping 3 remaining files"]);
+}
+
+#[test]
+fn checksum_fail_fast() {
+    let files = ["missing", "empty"];
+    let mut child = run_checksum(&["--md5", "--keep-going"], &files);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 1);
+
+    let mut child = run_checksum(&["--md5", "--fail-fast"], &files);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert!(lines.is_empty());
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["unable to open 'tests/fixtures/missing'"]);
+
+    let manifest = "\
+00000000000000000000000000000000  tests/fixtures/empty
+00000000000000000000000000000000  tests/fixtures/zero-400d
+";
+    let mut child = run_checksum(&["-c", "-", "--fail-fast"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["tests/fixtures/empty: FAILED"]);
+}
+
+#[test]
+fn checksum_stats() {
+    let mut child = run_checksum(
+        &["--crc32", "--stats"],
+        &["zero-400d", "random-11171", "missing"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines.len(), 2);
+
+    // The summary follows any errors.
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("2 files, 86398 bytes (86.3 KB) in "));
+    assert!(lines[1].ends_with(", 1 error"));
+}
+
+#[test]
+fn checksum_check() {
+    let manifest = "\
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+this line is ignored
+96f64e179f777e6eda0caa2d879356c9 *tests/fixtures/zero-400d
+";
+    let mut child = run_checksum(&["-c", "-"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["tests/fixtures/empty: OK", "tests/fixtures/zero-400d: OK"]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_check_bsd() {
+    let manifest = std::env::temp_dir()
+        .join(format!("checksum-check-bsd-{}", process::id()));
+    let manifeststr = manifest.to_str().unwrap();
+
+    let mut child = run_checksum(
+        &["--md5", "--sha3-256", "-o", manifeststr],
+        &["empty", "random-11171"],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let mut child = run_checksum(&["--check", manifeststr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "tests/fixtures/empty: OK",
+            "tests/fixtures/empty: OK",
+            "tests/fixtures/random-11171: OK",
+            "tests/fixtures/random-11171: OK",
+        ]
+    );
+
+    // Only the selected checksum is verified.
+    let mut child = run_checksum(&["--check", manifeststr, "--md5"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "tests/fixtures/empty: OK",
+            "tests/fixtures/random-11171: OK",
+        ]
+    );
+    fs::remove_file(&manifest).expect("error removing manifest");
+}
+
+#[test]
+fn checksum_check_mixed() {
+    let manifest = "\
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+9c1185a5c5e9fc54612808977ee8f548b2258d31  tests/fixtures/empty
+e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  tests/fixtures/empty
+";
+    let mut child = run_checksum(&["-c", "-"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["tests/fixtures/empty: OK"; 3]);
+}
+
+#[test]
+fn checksum_check_base_dir() {
+    let manifest = "\
+d41d8cd98f00b204e9800998ecf8427e  empty
+96f64e179f777e6eda0caa2d879356c9  zero-400d
+";
+    let mut child =
+        run_checksum(&["-c", "-", "--base-dir", "tests/fixtures"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["tests/fixtures/empty: OK", "tests/fixtures/zero-400d: OK"]
+    );
+}
+
+#[test]
+fn checksum_check_update() {
+    let manifest = std::env::temp_dir()
+        .join(format!("checksum-update-{}.md5", process::id()));
+    let contents = "\
+# Checksums
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+00000000000000000000000000000000  tests/fixtures/zero-400d
+MD5 (tests/fixtures/missing) = d41d8cd98f00b204e9800998ecf8427e
+";
+    fs::write(&manifest, contents).expect("error writing manifest");
+    let manifeststr = manifest.to_str().unwrap();
+
+    let mut child =
+        run_checksum(&["-c", manifeststr, "--update", "--dry-run"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "tests/fixtures/empty: OK",
+            "tests/fixtures/zero-400d: FAILED",
+            "tests/fixtures/missing: FAILED open or read",
+            "tests/fixtures/zero-400d: WOULD UPDATE",
+            "tests/fixtures/missing: WOULD REMOVE",
+        ]
+    );
+    let updated =
+        fs::read_to_string(&manifest).expect("error reading manifest");
+    assert_eq!(updated, contents);
+
+    let mut child =
+        run_checksum(&["-c", manifeststr, "--update", "--quiet"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "tests/fixtures/zero-400d: FAILED",
+            "tests/fixtures/missing: FAILED open or read",
+            "tests/fixtures/zero-400d: UPDATED",
+            "tests/fixtures/missing: REMOVED",
+        ]
+    );
+    let updated =
+        fs::read_to_string(&manifest).expect("error reading manifest");
+    assert_eq!(
+        updated,
+        "\
+# Checksums
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+96f64e179f777e6eda0caa2d879356c9  tests/fixtures/zero-400d
+"
+    );
+
+    // The updated manifest now matches every file.
+    let mut child = run_checksum(&["-c", manifeststr, "--update"], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    fs::remove_file(&manifest).expect("error removing manifest");
+}
+
+#[test]
+fn checksum_check_failed() {
+    let manifest = "\
+00000000000000000000000000000000  tests/fixtures/empty
+96f64e179f777e6eda0caa2d879356c9  tests/fixtures/zero-400d
+96f64e179f777e6eda0caa2d879356c9  tests/fixtures/missing
+";
+    let mut child = run_checksum(&["--check=-", "--md5"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "tests/fixtures/empty: FAILED",
+            "tests/fixtures/zero-400d: OK",
+            "tests/fixtures/missing: FAILED open or read",
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(
+        lines,
+        [
+            "unable to open 'tests/fixtures/missing'",
+            "WARNING: 1 listed file could not be read",
+            "WARNING: 1 computed checksum did NOT match",
+        ]
+    );
+}
+
+#[test]
+fn checksum_check_quiet_status() {
+    let manifest = "\
+00000000000000000000000000000000  tests/fixtures/empty
+96f64e179f777e6eda0caa2d879356c9  tests/fixtures/zero-400d
+";
+    let mut child = run_checksum(&["-c", "-", "--quiet"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["tests/fixtures/empty: FAILED"]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["WARNING: 1 computed checksum did NOT match"]);
+
+    let mut child = run_checksum(&["-c", "-", "--status"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert!(lines.is_empty());
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn checksum_check_warn_strict() {
+    let manifest = "\
+# A comment
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+d41d8cd98f00b204e9800998  tests/fixtures/empty
+";
+    let mut child = run_checksum(&["-c", "-", "--warn"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["tests/fixtures/empty: OK"]);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(
+        lines,
+        [
+            "'-': 3: improperly formatted checksum line",
+            "WARNING: 1 line is improperly formatted",
+        ]
+    );
+
+    let mut child = run_checksum(&["-c", "-", "--strict"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["WARNING: 1 line is improperly formatted"]);
+}
+
+#[test]
+fn checksum_check_json() {
+    let manifest = "\
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/empty
+00000000000000000000000000000000  tests/fixtures/zero-400d
+d41d8cd98f00b204e9800998ecf8427e  tests/fixtures/missing
+";
+    let mut child = run_checksum(&["-c", "-", "--json", "--quiet"], &[]);
+    child_write(&mut child, manifest.as_bytes())
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, [
+        "{",
+        "  \"files\": [",
+        "    {\"path\": \"tests/fixtures/zero-400d\", \"algorithm\": \"MD5\", \"status\": \"mismatch\", \"expected\": \"00000000000000000000000000000000\", \"actual\": \"96f64e179f777e6eda0caa2d879356c9\"},",
+        "    {\"path\": \"tests/fixtures/missing\", \"algorithm\": \"MD5\", \"status\": \"missing\", \"expected\": \"d41d8cd98f00b204e9800998ecf8427e\", \"actual\": null}",
+        "  ],",
+        "  \"summary\": {\"ok\": 1, \"mismatch\": 1, \"missing\": 1, \"read-error\": 0, \"improper\": 0}",
+        "}",
+    ]);
+}
+
+#[test]
+fn checksum_check_sidecars() {
+    let dir = std::env::temp_dir()
+        .join(format!("checksum-check-sidecars-{}", process::id()));
+    fs::create_dir_all(dir.join("sub"))
+        .expect("error creating sidecar directory");
+    for (path, contents) in [
+        ("empty", ""),
+        (
+            "empty.md5",
+            "MD5 (empty) = d41d8cd98f00b204e9800998ecf8427e\n",
+        ),
+        ("empty.crc32", "00000000  empty\n"),
+        ("sub/changed", "changed"),
+        (
+            "sub/changed.md5",
+            "d41d8cd98f00b204e9800998ecf8427e  changed\n",
+        ),
+        ("sub/gone.sha256", &format!("{}  gone\n", "0".repeat(64))),
+        ("unlisted", ""),
+    ] {
+        fs::write(dir.join(path), contents)
+            .expect("error writing sidecar input");
+    }
+    let dirstr = dir.to_str().unwrap();
+
+    let mut child = run_checksum(&["--check-sidecars", dirstr], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            format!("{dirstr}/empty (CRC32): OK"),
+            format!("{dirstr}/empty (MD5): OK"),
+            format!("{dirstr}/sub/changed (MD5): FAILED"),
+            format!("{dirstr}/sub/gone (SHA256): FAILED open or read"),
+            format!("{dirstr}/unlisted: NO SIDECAR"),
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(
+        lines,
+        [
+            format!("unable to open '{dirstr}/sub/gone'"),
+            String::from("WARNING: 1 file has no sidecar"),
+            String::from("WARNING: 1 listed file could not be read"),
+            String::from("WARNING: 1 computed checksum did NOT match"),
+        ]
+    );
+
+    // Only the sidecars for the selected digest are checked.
+    let mut child = run_checksum(
+        &["--check-sidecars", dirstr, "--crc32", "--quiet"],
+        &[],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            format!("{dirstr}/sub/changed: NO SIDECAR"),
+            format!("{dirstr}/unlisted: NO SIDECAR"),
+        ]
+    );
+
+    fs::remove_dir_all(&dir).expect("error removing sidecar directory");
+}
+
+#[test]
+fn checksum_combine() {
+    let mut child = run_checksum(
+        &["--crc32", "--md5", "--combine", "--with-size"],
+        &["random-11171", "empty", "zero-400d"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "CRC32 = 3f1cb280 size=86398",
+            "MD5 = 49cf722c475fcb581e5e8cf111b4361b size=86398",
+        ]
+    );
+
+    let mut child = run_checksum(
+        &[
+            "--combine",
+            "--expect",
+            "md5:49cf722c475fcb581e5e8cf111b4361b",
+        ],
+        &["random-11171", "zero-400d"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(lines, ["PASS: MD5 = 49cf722c475fcb581e5e8cf111b4361b"]);
+
+    // Any file which can't be read spoils the combined digest.
+    let mut child =
+        run_checksum(&["--md5", "--combine"], &["empty", "missing"]);
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert!(lines.is_empty());
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["unable to open 'tests/fixtures/missing'"]);
+}
+
+#[test]
+fn checksum_tree_digest() {
+    let root =
+        std::env::temp_dir().join(format!("checksum-tree-{}", process::id()));
+    // The same tree in two places has the same digest.
+    for tree in ["one", "two"] {
+        fs::create_dir_all(root.join(tree).join("sub"))
+            .expect("error creating directory");
+        fs::write(root.join(tree).join("a"), "").expect("error writing file");
+        fs::write(root.join(tree).join("sub/b"), "abc")
+            .expect("error writing file");
+    }
+    let one = root.join("one");
+    let two = root.join("two");
+    let (one, two) = (one.to_str().unwrap(), two.to_str().unwrap());
+
+    let mut child = run_checksum(
+        &["--md5", "--tree-digest", "--with-size", one, two],
+        &[],
+    );
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            format!("MD5 ({one}) = 4fa6e998a6bfaad6ce8caca3efcc6f8a size=3"),
+            format!("MD5 ({two}) = 4fa6e998a6bfaad6ce8caca3efcc6f8a size=3"),
+        ]
+    );
+
+    // Renaming a file changes the digest, even though the contents don't.
+    fs::rename(root.join("two/sub/b"), root.join("two/sub/c"))
+        .expect("error renaming file");
+    let mut child = run_checksum(&["--md5", "--tree-digest", two], &[]);
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [format!("MD5 ({two}) = 43abed731dd04c0485eb3977428a6fbe")]
+    );
+
+    fs::remove_dir_all(&root).expect("error removing directory");
+}
+
+#[test]
+fn checksum_expect() {
+    let mut child = run_checksum(
+        &["--expect", "96f64e179f777e6eda0caa2d879356c9"],
+        &["zero-400d"],
+    );
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 0);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        ["PASS: MD5 (tests/fixtures/zero-400d) = 96f64e179f777e6eda0caa2d879356c9"]
+    );
+
+    let mut child = run_checksum(
+        &["--expect=crc32:00000000", "--expect=crc32:26a348bb"],
+        &[],
+    );
+    child_write(&mut child, &[0u8; 0x400d])
+        .expect("error writing to checksum stdin");
+
+    let status =
+        child_run(&mut child).expect("error running checksum subprocess");
+    assert_eq!(status, 1);
+
+    let lines =
+        child_readlines(&mut child).expect("error reading checksum stdout");
+    assert_eq!(
+        lines,
+        [
+            "FAIL: CRC32 = 26a348bb, expected 00000000",
+            "PASS: CRC32 = 26a348bb",
+        ]
+    );
+
+    let lines =
+        child_errlines(&mut child).expect("error reading checksum stderr");
+    assert_eq!(lines, ["the computed checksum did NOT match"]);
+}
+
 #[test]
 fn checksum_invalid_option() {
     let mut child = run_checksum(&["--foo"], &[]);
@@ -1,6 +1,31 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
 pub const EMPTY: [u8; 0] = *include_bytes!("empty");
-pub const ZERO_400D: [u8; 0x400D] = *include_bytes!("zero-400d");
-pub const RANDOM_11171: [u8; 0x11171] = *include_bytes!("random-11171");
+pub static ZERO_400D: [u8; 0x400D] = *include_bytes!("zero-400d");
+pub static RANDOM_11171: [u8; 0x11171] = *include_bytes!("random-11171");
+
+pub mod crc16_ccitt {
+    pub const EMPTY: [u8; 2] = *include_bytes!("empty.crc16_ccitt");
+    pub const ZERO_400D: [u8; 2] = *include_bytes!("zero-400d.crc16_ccitt");
+    pub const RANDOM_11171: [u8; 2] =
+        *include_bytes!("random-11171.crc16_ccitt");
+}
+
+pub mod crc16_modbus {
+    pub const EMPTY: [u8; 2] = *include_bytes!("empty.crc16_modbus");
+    pub const ZERO_400D: [u8; 2] = *include_bytes!("zero-400d.crc16_modbus");
+    pub const RANDOM_11171: [u8; 2] =
+        *include_bytes!("random-11171.crc16_modbus");
+}
+
+pub mod crc16_xmodem {
+    pub const EMPTY: [u8; 2] = *include_bytes!("empty.crc16_xmodem");
+    pub const ZERO_400D: [u8; 2] = *include_bytes!("zero-400d.crc16_xmodem");
+    pub const RANDOM_11171: [u8; 2] =
+        *include_bytes!("random-11171.crc16_xmodem");
+}
 
 pub mod crc32 {
     pub const EMPTY: [u8; 4] = *include_bytes!("empty.crc32");
@@ -8,24 +33,174 @@ pub mod crc32 {
     pub const RANDOM_11171: [u8; 4] = *include_bytes!("random-11171.crc32");
 }
 
+pub mod crc64 {
+    pub const EMPTY: [u8; 8] = *include_bytes!("empty.crc64");
+    pub const ZERO_400D: [u8; 8] = *include_bytes!("zero-400d.crc64");
+    pub const RANDOM_11171: [u8; 8] = *include_bytes!("random-11171.crc64");
+}
+
+pub mod xxh32 {
+    pub const EMPTY: [u8; 4] = *include_bytes!("empty.xxh32");
+    pub const ZERO_400D: [u8; 4] = *include_bytes!("zero-400d.xxh32");
+    pub const RANDOM_11171: [u8; 4] = *include_bytes!("random-11171.xxh32");
+}
+
+pub mod xxh64 {
+    pub const EMPTY: [u8; 8] = *include_bytes!("empty.xxh64");
+    pub const ZERO_400D: [u8; 8] = *include_bytes!("zero-400d.xxh64");
+    pub const RANDOM_11171: [u8; 8] = *include_bytes!("random-11171.xxh64");
+}
+
+pub mod rapidhash {
+    pub const EMPTY: [u8; 8] = *include_bytes!("empty.rapidhash");
+    pub const ZERO_400D: [u8; 8] = *include_bytes!("zero-400d.rapidhash");
+    pub const RANDOM_11171: [u8; 8] =
+        *include_bytes!("random-11171.rapidhash");
+}
+
+pub mod md2 {
+    pub const EMPTY: [u8; 16] = *include_bytes!("empty.md2");
+    pub const ZERO_400D: [u8; 16] = *include_bytes!("zero-400d.md2");
+    pub const RANDOM_11171: [u8; 16] = *include_bytes!("random-11171.md2");
+}
+
+pub mod md4 {
+    pub const EMPTY: [u8; 16] = *include_bytes!("empty.md4");
+    pub const ZERO_400D: [u8; 16] = *include_bytes!("zero-400d.md4");
+    pub const RANDOM_11171: [u8; 16] = *include_bytes!("random-11171.md4");
+}
+
 pub mod md5 {
     pub const EMPTY: [u8; 16] = *include_bytes!("empty.md5");
     pub const ZERO_400D: [u8; 16] = *include_bytes!("zero-400d.md5");
     pub const RANDOM_11171: [u8; 16] = *include_bytes!("random-11171.md5");
 }
 
+pub mod sha224 {
+    pub const EMPTY: [u8; 28] = *include_bytes!("empty.sha224");
+    pub const ZERO_400D: [u8; 28] = *include_bytes!("zero-400d.sha224");
+    pub const RANDOM_11171: [u8; 28] = *include_bytes!("random-11171.sha224");
+}
+
 pub mod sha256 {
     pub const EMPTY: [u8; 32] = *include_bytes!("empty.sha256");
     pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.sha256");
     pub const RANDOM_11171: [u8; 32] = *include_bytes!("random-11171.sha256");
 }
 
+pub mod sha384 {
+    pub const EMPTY: [u8; 48] = *include_bytes!("empty.sha384");
+    pub const ZERO_400D: [u8; 48] = *include_bytes!("zero-400d.sha384");
+    pub const RANDOM_11171: [u8; 48] = *include_bytes!("random-11171.sha384");
+}
+
 pub mod sha512 {
     pub const EMPTY: [u8; 64] = *include_bytes!("empty.sha512");
     pub const ZERO_400D: [u8; 64] = *include_bytes!("zero-400d.sha512");
     pub const RANDOM_11171: [u8; 64] = *include_bytes!("random-11171.sha512");
 }
 
+pub mod sha512_224 {
+    pub const EMPTY: [u8; 28] = *include_bytes!("empty.sha512_224");
+    pub const ZERO_400D: [u8; 28] = *include_bytes!("zero-400d.sha512_224");
+    pub const RANDOM_11171: [u8; 28] =
+        *include_bytes!("random-11171.sha512_224");
+}
+
+pub mod sha512_256 {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.sha512_256");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.sha512_256");
+    pub const RANDOM_11171: [u8; 32] =
+        *include_bytes!("random-11171.sha512_256");
+}
+
+pub mod sha3_224 {
+    pub const EMPTY: [u8; 28] = *include_bytes!("empty.sha3_224");
+    pub const ZERO_400D: [u8; 28] = *include_bytes!("zero-400d.sha3_224");
+    pub const RANDOM_11171: [u8; 28] =
+        *include_bytes!("random-11171.sha3_224");
+}
+
+pub mod sha3_256 {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.sha3_256");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.sha3_256");
+    pub const RANDOM_11171: [u8; 32] =
+        *include_bytes!("random-11171.sha3_256");
+}
+
+pub mod sha3_384 {
+    pub const EMPTY: [u8; 48] = *include_bytes!("empty.sha3_384");
+    pub const ZERO_400D: [u8; 48] = *include_bytes!("zero-400d.sha3_384");
+    pub const RANDOM_11171: [u8; 48] =
+        *include_bytes!("random-11171.sha3_384");
+}
+
+pub mod sha3_512 {
+    pub const EMPTY: [u8; 64] = *include_bytes!("empty.sha3_512");
+    pub const ZERO_400D: [u8; 64] = *include_bytes!("zero-400d.sha3_512");
+    pub const RANDOM_11171: [u8; 64] =
+        *include_bytes!("random-11171.sha3_512");
+}
+
+pub mod keccak256 {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.keccak256");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.keccak256");
+    pub const RANDOM_11171: [u8; 32] =
+        *include_bytes!("random-11171.keccak256");
+}
+
+pub mod blake2b {
+    pub const EMPTY: [u8; 64] = *include_bytes!("empty.blake2b");
+    pub const ZERO_400D: [u8; 64] = *include_bytes!("zero-400d.blake2b");
+    pub const RANDOM_11171: [u8; 64] =
+        *include_bytes!("random-11171.blake2b");
+}
+
+pub mod blake2s {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.blake2s");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.blake2s");
+    pub const RANDOM_11171: [u8; 32] =
+        *include_bytes!("random-11171.blake2s");
+}
+
+pub mod streebog256 {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.streebog256");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.streebog256");
+    pub const RANDOM_11171: [u8; 32] =
+        *include_bytes!("random-11171.streebog256");
+}
+
+pub mod streebog512 {
+    pub const EMPTY: [u8; 64] = *include_bytes!("empty.streebog512");
+    pub const ZERO_400D: [u8; 64] = *include_bytes!("zero-400d.streebog512");
+    pub const RANDOM_11171: [u8; 64] =
+        *include_bytes!("random-11171.streebog512");
+}
+
+pub mod tiger {
+    pub const EMPTY: [u8; 24] = *include_bytes!("empty.tiger");
+    pub const ZERO_400D: [u8; 24] = *include_bytes!("zero-400d.tiger");
+    pub const RANDOM_11171: [u8; 24] = *include_bytes!("random-11171.tiger");
+}
+
+pub mod tiger2 {
+    pub const EMPTY: [u8; 24] = *include_bytes!("empty.tiger2");
+    pub const ZERO_400D: [u8; 24] = *include_bytes!("zero-400d.tiger2");
+    pub const RANDOM_11171: [u8; 24] = *include_bytes!("random-11171.tiger2");
+}
+
+pub mod rmd256 {
+    pub const EMPTY: [u8; 32] = *include_bytes!("empty.rmd256");
+    pub const ZERO_400D: [u8; 32] = *include_bytes!("zero-400d.rmd256");
+    pub const RANDOM_11171: [u8; 32] = *include_bytes!("random-11171.rmd256");
+}
+
+pub mod rmd320 {
+    pub const EMPTY: [u8; 40] = *include_bytes!("empty.rmd320");
+    pub const ZERO_400D: [u8; 40] = *include_bytes!("zero-400d.rmd320");
+    pub const RANDOM_11171: [u8; 40] = *include_bytes!("random-11171.rmd320");
+}
+
 pub mod rmd160 {
     pub const EMPTY: [u8; 20] = *include_bytes!("empty.rmd160");
     pub const ZERO_400D: [u8; 20] = *include_bytes!("zero-400d.rmd160");
@@ -43,3 +218,45 @@ pub mod xor {
     pub const ZERO_400D: [u8; 1] = [0x00];
     pub const RANDOM_11171: [u8; 1] = [0xac];
 }
+
+/// A directory for a test to work in, under the temporary directory and
+/// named for the process and the test. It is created empty, and removed
+/// along with everything in it when dropped, even if the test fails.
+pub struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    /// Create the scratch directory for the test called `name`.
+    ///
+    /// ## Panics
+    ///
+    /// A panic occurs if the directory can't be created.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir()
+            .join(format!("checksum-{}-{name}", std::process::id()));
+        // Clear out anything left behind by an earlier run.
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        ScratchDir(dir)
+    }
+}
+
+impl Deref for ScratchDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for ScratchDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}